@@ -5,6 +5,11 @@ use std::process::Command;
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let out_dir = "src/generated";
 
+    // the generated protos are only compiled with the babylon feature
+    if std::env::var_os("CARGO_FEATURE_BABYLON").is_none() {
+        return Ok(());
+    }
+
     let protoc_check = Command::new("protoc").arg("--version").output();
     if protoc_check.is_err() {
         println!("cargo:warning=protoc not found, skipping proto generation");
@@ -15,13 +20,26 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     fs::create_dir_all(out_dir)?;
 
     // compile proto files
-    tonic_build::configure().out_dir(out_dir).compile_protos(
-        &[
-            "proto/babylon/btclightclient/v1/query.proto",
-            "proto/babylon/btclightclient/v1/params.proto",
-        ],
-        &["proto", "proto/third_party"],
-    )?;
+    // google.api comments contain proto snippets that rustdoc tries to run as doctests
+    let mut config = prost_build::Config::new();
+    config.disable_comments([".google.api"]);
+
+    tonic_build::configure()
+        .out_dir(out_dir)
+        .compile_protos_with_config(
+            config,
+            &[
+                "proto/babylon/btclightclient/v1/query.proto",
+                "proto/babylon/btclightclient/v1/params.proto",
+                "proto/babylon/btclightclient/v1/tx.proto",
+                "proto/babylon/checkpointing/v1/query.proto",
+                "proto/babylon/btccheckpoint/v1/query.proto",
+                "proto/babylon/btcstaking/v1/query.proto",
+                "proto/babylon/epoching/v1/query.proto",
+                "proto/babylon/incentive/v1/query.proto",
+            ],
+            &["proto", "proto/third_party"],
+        )?;
 
     // generate mod.rs file
     let mod_content = r#"
@@ -33,6 +51,9 @@ pub mod cosmos {
                 include!("cosmos.base.query.v1beta1.rs");
             }
         }
+        pub mod v1beta1 {
+            include!("cosmos.base.v1beta1.rs");
+        }
     }
 }
 pub mod babylon {
@@ -41,6 +62,31 @@ pub mod babylon {
             include!("babylon.btclightclient.v1.rs");
         }
     }
+    pub mod checkpointing {
+        pub mod v1 {
+            include!("babylon.checkpointing.v1.rs");
+        }
+    }
+    pub mod btccheckpoint {
+        pub mod v1 {
+            include!("babylon.btccheckpoint.v1.rs");
+        }
+    }
+    pub mod btcstaking {
+        pub mod v1 {
+            include!("babylon.btcstaking.v1.rs");
+        }
+    }
+    pub mod epoching {
+        pub mod v1 {
+            include!("babylon.epoching.v1.rs");
+        }
+    }
+    pub mod incentive {
+        pub mod v1 {
+            include!("babylon.incentive.v1.rs");
+        }
+    }
 }
 pub mod cosmos_proto {
     include!("cosmos_proto.rs");
@@ -1,12 +1,10 @@
 #![allow(unused)]
-use anyhow;
 use cosmos_sdk_proto::cosmwasm::wasm::v1::{
     query_client::QueryClient as WasmQueryClient, QuerySmartContractStateRequest,
 };
 
 use cosmwasm_client_rs::{chain::ChainConfig, CosmWasmClient};
 use cosmwasm_std::{from_json, to_json_binary, Uint128};
-use dotenv;
 use std::path::Path;
 use tracing_subscriber::fmt;
 
@@ -44,9 +42,12 @@ async fn main() -> anyhow::Result<()> {
     let babylon_chain_config = ChainConfig {
         account_prefix: "bbn".to_string(),
         denom: "ubbn".to_string(),
-        fee_amount: 10000,
+        gas_price: "0.01ubbn".parse()?,
+        fallback_gas_prices: vec![],
         gas_limit: 1000000,
         chain_id: "bbn-test-5".to_string(),
+        grpc_url: Some(rpc_url.to_string()),
+        rpc_url: None,
     };
 
     let local_client = CosmWasmClient::new(
@@ -142,7 +143,7 @@ async fn main() -> anyhow::Result<()> {
 
     // query cw20 balance
 
-    let balance = query_cw20_balance(rpc_url, local_cw20_contract, &recipient).await?;
+    let balance = query_cw20_balance(rpc_url, local_cw20_contract, recipient).await?;
 
     println!("Cw20 balance: {:?}", balance);
 
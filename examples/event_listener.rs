@@ -1,8 +1,8 @@
-use anyhow;
 use cosmwasm_client_rs::{
-    events::{ContractEvent, PegInEvent, PegOutEvent},
+    events::{ContractEvent, PegInEvent, PegOutEvent, TxEvent},
     EventListener,
 };
+use std::time::Duration;
 use tokio::sync::mpsc;
 use tracing_subscriber::fmt;
 
@@ -19,14 +19,14 @@ async fn main() -> anyhow::Result<()> {
     let rpc_url = "https://babylon-testnet-rpc.nodes.guru";
     let contract_address = "bbn17p9rzwnnfxcjp32un9ug7yhhzgtkhvl9jfksztgw5uh69wac2pgs6spw0g";
 
-    let mut event_listener = EventListener::new(
-        rpc_url,
-        event_tx,
-        checkpoint_tx,
-        contract_address,
-        1329500, // Start from block height 1329500
-    )
-    .await?;
+    let mut event_listener = EventListener::builder(rpc_url)
+        .with_event_sender(event_tx)
+        .with_checkpoint_sender(checkpoint_tx)
+        .with_contract(contract_address)
+        .with_start_height(1329500) // Start from block height 1329500
+        // report progress at least every minute, even without contract events
+        .with_checkpoint_period(Duration::from_secs(60))
+        .build()?;
 
     tokio::spawn(async move {
         if let Err(e) = event_listener.start().await {
@@ -51,7 +51,7 @@ async fn main() -> anyhow::Result<()> {
         );
         tracing::info!("Processing events from block {}", block_events.height);
 
-        for (tx_hash, event) in block_events.events {
+        for TxEvent { tx_hash, event, .. } in block_events.events {
             match event {
                 ContractEvent::PegIn(PegInEvent {
                     msg_index,
@@ -85,6 +85,23 @@ async fn main() -> anyhow::Result<()> {
                         amount
                     );
                 }
+                ContractEvent::Cw20Transfer(_)
+                | ContractEvent::Cw20Mint(_)
+                | ContractEvent::Cw20Burn(_) => {
+                    tracing::info!("Received CW20 event tx_hash: {} {:?}", tx_hash, event);
+                }
+                ContractEvent::Instantiate(_)
+                | ContractEvent::Migrate(_)
+                | ContractEvent::UpdateAdmin(_) => {
+                    tracing::warn!(
+                        "Bridge contract lifecycle event tx_hash: {} {:?}",
+                        tx_hash,
+                        event
+                    );
+                }
+                ContractEvent::Raw(raw) => {
+                    tracing::info!("Received raw event tx_hash: {} {:?}", tx_hash, raw);
+                }
             }
         }
     }
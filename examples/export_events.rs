@@ -0,0 +1,38 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use cosmwasm_client_rs::export::{EventExporter, ExportFormat};
+use cosmwasm_client_rs::EventListener;
+
+const USAGE: &str =
+    "Usage: export_events <rpc url> <contract> <from height> <to height> [jsonl|csv] [output file]";
+
+/// Exports the peg ins and peg outs of a height range, e.g.
+/// `cargo run --example export_events -- https://rpc.example.com bbn1... 1000 2000 csv pegs.csv`
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .init();
+
+    let mut args = std::env::args().skip(1);
+    let mut next_arg = || args.next().ok_or_else(|| anyhow::anyhow!(USAGE));
+    let rpc_url = next_arg()?;
+    let contract = next_arg()?;
+    let from: u64 = next_arg()?.parse()?;
+    let to: u64 = next_arg()?.parse()?;
+    let format: ExportFormat = next_arg().unwrap_or_else(|_| "jsonl".to_string()).parse()?;
+    let writer: Box<dyn Write + Send> = match next_arg() {
+        Ok(path) => Box::new(BufWriter::new(File::create(path)?)),
+        Err(_) => Box::new(std::io::stdout()),
+    };
+
+    let listener = EventListener::builder(&rpc_url)
+        .with_contract(&contract)
+        .build()?;
+    let exporter = EventExporter::new(writer, format);
+    let written = listener.export(from, to, &exporter).await?;
+    tracing::info!("Exported {} records of {}..={}", written, from, to);
+
+    Ok(())
+}
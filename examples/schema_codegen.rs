@@ -0,0 +1,19 @@
+use cosmwasm_client_rs::codegen::ContractSchema;
+
+/// Generates typed bindings of a contract from its JSON schema, e.g.
+/// `cargo run --example schema_codegen -- ../bridge/schema src/bridge.rs`
+fn main() -> anyhow::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let schema_path = args
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Usage: schema_codegen <schema path> [output file]"))?;
+
+    // Accepts the `schema` directory of the contract or its `<contract>.json` file
+    let code = ContractSchema::load(&schema_path)?.generate()?;
+    match args.next() {
+        Some(out_path) => std::fs::write(out_path, code)?,
+        None => print!("{}", code),
+    }
+
+    Ok(())
+}
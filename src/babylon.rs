@@ -0,0 +1,784 @@
+use std::collections::HashMap;
+
+use cosmwasm_std::{Coin, Uint128};
+use tonic::transport::Channel;
+
+use crate::client::CosmWasmClient;
+use crate::error::ClientError;
+use crate::generated::babylon::{btccheckpoint, btcstaking, checkpointing, epoching, incentive};
+use crate::generated::cosmos::base::query::v1beta1::PageRequest;
+use crate::generated::cosmos::base::v1beta1::Coin as ProtoCoin;
+
+/// Status of a Babylon epoch checkpoint, ordered from least to most mature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CheckpointStatus {
+    /// Accumulating is awaiting BLS signatures
+    Accumulating,
+    /// Sealed has accumulated sufficient BLS signatures
+    Sealed,
+    /// Submitted is included on BTC
+    Submitted,
+    /// Confirmed is k-deep on BTC
+    Confirmed,
+    /// Finalized is w-deep on BTC
+    Finalized,
+}
+
+impl CheckpointStatus {
+    fn from_proto(status: i32) -> Result<Self, ClientError> {
+        let status = checkpointing::v1::CheckpointStatus::try_from(status)
+            .map_err(|_| ClientError::invalid("checkpoint status", status))?;
+
+        Ok(match status {
+            checkpointing::v1::CheckpointStatus::CkptStatusAccumulating => Self::Accumulating,
+            checkpointing::v1::CheckpointStatus::CkptStatusSealed => Self::Sealed,
+            checkpointing::v1::CheckpointStatus::CkptStatusSubmitted => Self::Submitted,
+            checkpointing::v1::CheckpointStatus::CkptStatusConfirmed => Self::Confirmed,
+            checkpointing::v1::CheckpointStatus::CkptStatusFinalized => Self::Finalized,
+        })
+    }
+
+    fn to_proto(self) -> checkpointing::v1::CheckpointStatus {
+        match self {
+            Self::Accumulating => checkpointing::v1::CheckpointStatus::CkptStatusAccumulating,
+            Self::Sealed => checkpointing::v1::CheckpointStatus::CkptStatusSealed,
+            Self::Submitted => checkpointing::v1::CheckpointStatus::CkptStatusSubmitted,
+            Self::Confirmed => checkpointing::v1::CheckpointStatus::CkptStatusConfirmed,
+            Self::Finalized => checkpointing::v1::CheckpointStatus::CkptStatusFinalized,
+        }
+    }
+}
+
+/// A state transition of an epoch checkpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckpointStateUpdate {
+    pub status: CheckpointStatus,
+    /// block_height is the Babylon height that triggered the transition
+    pub block_height: u64,
+    /// block_time is the unix timestamp of the Babylon block that triggered the transition
+    pub block_time: Option<i64>,
+}
+
+/// Raw checkpoint of a Babylon epoch together with its status
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawCheckpointInfo {
+    pub epoch_num: u64,
+    /// block_hash is the hash of the block the BLS signatures are signed on, in hex
+    pub block_hash: String,
+    pub status: CheckpointStatus,
+    /// power_sum is the accumulated voting power of the checkpoint
+    pub power_sum: u64,
+    pub lifecycle: Vec<CheckpointStateUpdate>,
+}
+
+/// Parameters of the Babylon btccheckpoint module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BtcCheckpointParams {
+    /// btc_confirmation_depth is the depth after which a checkpoint is confirmed (k)
+    pub btc_confirmation_depth: u32,
+    /// checkpoint_finalization_timeout is the depth after which a checkpoint is finalized (w)
+    pub checkpoint_finalization_timeout: u32,
+    pub checkpoint_tag: String,
+}
+
+/// Best BTC submission of an epoch checkpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BtcCheckpointInfo {
+    pub epoch_num: u64,
+    pub btc_block_height: u32,
+    pub btc_block_hash: String,
+    /// tx_hashes are the BTC transactions carrying the checkpoint
+    pub tx_hashes: Vec<String>,
+}
+
+/// Current Babylon epoch and the height of its last block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurrentEpoch {
+    pub epoch_num: u64,
+    /// boundary is the height of the last block of the epoch
+    pub boundary: u64,
+}
+
+/// Metadata of a Babylon epoch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpochInfo {
+    pub epoch_num: u64,
+    /// interval is the epoch interval in effect for this epoch
+    pub interval: u64,
+    pub first_block_height: u64,
+    /// last_block_time is the unix timestamp of the last block, unset while the epoch is ongoing
+    pub last_block_time: Option<i64>,
+    /// sealer_block_hash is the hash of the last block of the epoch in hex
+    pub sealer_block_hash: String,
+}
+
+impl EpochInfo {
+    /// Returns the height of the last block of the epoch
+    pub fn last_block_height(&self) -> u64 {
+        if self.epoch_num == 0 {
+            return 0;
+        }
+        self.first_block_height + self.interval - 1
+    }
+}
+
+/// Status of a BTC delegation in the btcstaking module
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BtcDelegationStatus {
+    /// Pending is waiting for covenant signatures
+    Pending,
+    /// Verified has covenant signatures but is not yet included in the BTC chain
+    Verified,
+    /// Active has voting power
+    Active,
+    /// Unbonded no longer has voting power after an unbonding tx
+    Unbonded,
+    /// Expired no longer has voting power after the staking timelock ended
+    Expired,
+    /// Any matches all of the above when querying
+    Any,
+}
+
+impl BtcDelegationStatus {
+    fn to_proto(self) -> btcstaking::v1::BtcDelegationStatus {
+        match self {
+            Self::Pending => btcstaking::v1::BtcDelegationStatus::Pending,
+            Self::Verified => btcstaking::v1::BtcDelegationStatus::Verified,
+            Self::Active => btcstaking::v1::BtcDelegationStatus::Active,
+            Self::Unbonded => btcstaking::v1::BtcDelegationStatus::Unbonded,
+            Self::Expired => btcstaking::v1::BtcDelegationStatus::Expired,
+            Self::Any => btcstaking::v1::BtcDelegationStatus::Any,
+        }
+    }
+}
+
+/// Finality provider registered in the Babylon btcstaking module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FinalityProvider {
+    /// address is the Babylon address receiving the commission
+    pub address: String,
+    /// btc_pk is the BIP-340 BTC public key in hex
+    pub btc_pk: String,
+    /// commission is the commission rate as a decimal string
+    pub commission: String,
+    /// slashed_babylon_height is the Babylon height of the slashing, 0 if not slashed
+    pub slashed_babylon_height: u64,
+    /// slashed_btc_height is the BTC height of the slashing, 0 if not slashed
+    pub slashed_btc_height: u32,
+    pub jailed: bool,
+    pub highest_voted_height: u32,
+}
+
+impl FinalityProvider {
+    /// Returns true if the finality provider is neither slashed nor jailed
+    pub fn is_active(&self) -> bool {
+        !self.jailed && self.slashed_babylon_height == 0 && self.slashed_btc_height == 0
+    }
+}
+
+impl From<btcstaking::v1::FinalityProviderResponse> for FinalityProvider {
+    fn from(fp: btcstaking::v1::FinalityProviderResponse) -> Self {
+        Self {
+            address: fp.addr,
+            btc_pk: hex::encode(fp.btc_pk),
+            commission: fp.commission,
+            slashed_babylon_height: fp.slashed_babylon_height,
+            slashed_btc_height: fp.slashed_btc_height,
+            jailed: fp.jailed,
+            highest_voted_height: fp.highest_voted_height,
+        }
+    }
+}
+
+/// BTC delegation to one or more finality providers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BtcDelegation {
+    /// staker_address is the Babylon address receiving the rewards
+    pub staker_address: String,
+    /// btc_pk is the BIP-340 BTC public key of the staker in hex
+    pub btc_pk: String,
+    /// finality_provider_btc_pks are the BIP-340 keys of the delegated finality providers in hex
+    pub finality_provider_btc_pks: Vec<String>,
+    /// start_height is the BTC height at which the staking timelock starts
+    pub start_height: u32,
+    /// end_height is the BTC height at which the delegation stops having voting power
+    pub end_height: u32,
+    /// total_sat is the staked amount in satoshi
+    pub total_sat: u64,
+    pub staking_tx_hex: String,
+    pub active: bool,
+    /// status is the status description reported by the module
+    pub status: String,
+}
+
+impl From<btcstaking::v1::BtcDelegationResponse> for BtcDelegation {
+    fn from(del: btcstaking::v1::BtcDelegationResponse) -> Self {
+        Self {
+            staker_address: del.staker_addr,
+            btc_pk: hex::encode(del.btc_pk),
+            finality_provider_btc_pks: del.fp_btc_pk_list.into_iter().map(hex::encode).collect(),
+            start_height: del.start_height,
+            end_height: del.end_height,
+            total_sat: del.total_sat,
+            staking_tx_hex: del.staking_tx_hex,
+            active: del.active,
+            status: del.status_desc,
+        }
+    }
+}
+
+/// Rewards distributed to a stakeholder in the incentive module
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RewardGauge {
+    /// coins are all rewards that have been accrued in the gauge
+    pub coins: Vec<Coin>,
+    /// withdrawn_coins are the rewards already withdrawn by the stakeholder
+    pub withdrawn_coins: Vec<Coin>,
+}
+
+impl RewardGauge {
+    /// Returns the rewards that have been accrued but not yet withdrawn
+    pub fn withdrawable(&self) -> Vec<Coin> {
+        self.coins
+            .iter()
+            .filter_map(|coin| {
+                let withdrawn = self
+                    .withdrawn_coins
+                    .iter()
+                    .filter(|w| w.denom == coin.denom)
+                    .fold(Uint128::zero(), |acc, w| acc + w.amount);
+                let amount = coin.amount.saturating_sub(withdrawn);
+                (!amount.is_zero()).then(|| Coin::new(amount, coin.denom.clone()))
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<incentive::v1::RewardGaugesResponse> for RewardGauge {
+    type Error = ClientError;
+
+    fn try_from(gauge: incentive::v1::RewardGaugesResponse) -> Result<Self, ClientError> {
+        Ok(Self {
+            coins: convert_coins(gauge.coins)?,
+            withdrawn_coins: convert_coins(gauge.withdrawn_coins)?,
+        })
+    }
+}
+
+fn convert_coins(coins: Vec<ProtoCoin>) -> Result<Vec<Coin>, ClientError> {
+    coins
+        .into_iter()
+        .map(|coin| {
+            let amount = coin
+                .amount
+                .parse::<u128>()
+                .map_err(|e| ClientError::invalid(format!("coin amount {}", coin.amount), e))?;
+            Ok(Coin::new(amount, coin.denom))
+        })
+        .collect()
+}
+
+/// Returns the epoch containing the given Babylon height.
+///
+/// Epoch 0 only contains the genesis block, epoch `n` covers heights
+/// `(n - 1) * epoch_interval + 1 ..= n * epoch_interval`.
+pub fn epoch_for_height(height: u64, epoch_interval: u64) -> u64 {
+    if height == 0 || epoch_interval == 0 {
+        return 0;
+    }
+    (height - 1) / epoch_interval + 1
+}
+
+impl CosmWasmClient {
+    async fn checkpointing_client(
+        &self,
+    ) -> Result<checkpointing::v1::query_client::QueryClient<Channel>, ClientError> {
+        checkpointing::v1::query_client::QueryClient::connect(self.grpc_url.clone())
+            .await
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))
+    }
+
+    async fn btccheckpoint_client(
+        &self,
+    ) -> Result<btccheckpoint::v1::query_client::QueryClient<Channel>, ClientError> {
+        btccheckpoint::v1::query_client::QueryClient::connect(self.grpc_url.clone())
+            .await
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))
+    }
+
+    /// Returns the checkpoint status of the given epoch
+    pub async fn query_epoch_status(
+        &self,
+        epoch_num: u64,
+    ) -> Result<CheckpointStatus, ClientError> {
+        let mut client = self.checkpointing_client().await?;
+
+        let resp = client
+            .epoch_status(checkpointing::v1::QueryEpochStatusRequest { epoch_num })
+            .await
+            .map_err(|e| ClientError::grpc("query epoch status", e))?;
+
+        CheckpointStatus::from_proto(resp.into_inner().status)
+    }
+
+    /// Returns the raw checkpoint of the given epoch including its lifecycle
+    pub async fn query_raw_checkpoint(
+        &self,
+        epoch_num: u64,
+    ) -> Result<RawCheckpointInfo, ClientError> {
+        let mut client = self.checkpointing_client().await?;
+
+        let resp = client
+            .raw_checkpoint(checkpointing::v1::QueryRawCheckpointRequest { epoch_num })
+            .await
+            .map_err(|e| ClientError::grpc("query raw checkpoint", e))?;
+
+        let checkpoint = resp
+            .into_inner()
+            .raw_checkpoint
+            .ok_or_else(|| ClientError::not_found(format!("checkpoint for epoch {}", epoch_num)))?;
+
+        let lifecycle = checkpoint
+            .lifecycle
+            .into_iter()
+            .map(|update| {
+                Ok(CheckpointStateUpdate {
+                    status: CheckpointStatus::from_proto(update.state)?,
+                    block_height: update.block_height,
+                    block_time: update.block_time.map(|t| t.seconds),
+                })
+            })
+            .collect::<Result<Vec<_>, ClientError>>()?;
+
+        Ok(RawCheckpointInfo {
+            epoch_num,
+            block_hash: checkpoint
+                .ckpt
+                .map(|c| c.block_hash_hex)
+                .unwrap_or_default(),
+            status: CheckpointStatus::from_proto(checkpoint.status)?,
+            power_sum: checkpoint.power_sum,
+            lifecycle,
+        })
+    }
+
+    /// Returns the last epoch whose checkpoint has reached at least the given status
+    pub async fn query_last_checkpointed_epoch(
+        &self,
+        status: CheckpointStatus,
+    ) -> Result<u64, ClientError> {
+        let mut client = self.checkpointing_client().await?;
+
+        let resp = client
+            .last_checkpoint_with_status(checkpointing::v1::QueryLastCheckpointWithStatusRequest {
+                status: status.to_proto() as i32,
+            })
+            .await
+            .map_err(|e| ClientError::grpc("query last checkpoint with status", e))?;
+
+        resp.into_inner()
+            .raw_checkpoint
+            .map(|c| c.epoch_num)
+            .ok_or_else(|| ClientError::not_found(format!("checkpoint with status {:?}", status)))
+    }
+
+    /// Returns the checkpoint status of the epoch containing the given Babylon height
+    pub async fn query_checkpoint_status_for_height(
+        &self,
+        height: u64,
+    ) -> Result<CheckpointStatus, ClientError> {
+        let epoch_num = self.query_epoch_for_height(height).await?;
+        self.query_epoch_status(epoch_num).await
+    }
+
+    /// Returns the parameters of the btccheckpoint module
+    pub async fn query_btc_checkpoint_params(&self) -> Result<BtcCheckpointParams, ClientError> {
+        let mut client = self.btccheckpoint_client().await?;
+
+        let resp = client
+            .params(btccheckpoint::v1::QueryParamsRequest {})
+            .await
+            .map_err(|e| ClientError::grpc("query btccheckpoint params", e))?;
+
+        let params = resp
+            .into_inner()
+            .params
+            .ok_or_else(|| ClientError::not_found("btccheckpoint params"))?;
+
+        Ok(BtcCheckpointParams {
+            btc_confirmation_depth: params.btc_confirmation_depth,
+            checkpoint_finalization_timeout: params.checkpoint_finalization_timeout,
+            checkpoint_tag: params.checkpoint_tag,
+        })
+    }
+
+    /// Returns the best BTC submission of the given epoch checkpoint
+    pub async fn query_btc_checkpoint_info(
+        &self,
+        epoch_num: u64,
+    ) -> Result<BtcCheckpointInfo, ClientError> {
+        let mut client = self.btccheckpoint_client().await?;
+
+        let resp = client
+            .btc_checkpoint_info(btccheckpoint::v1::QueryBtcCheckpointInfoRequest { epoch_num })
+            .await
+            .map_err(|e| ClientError::grpc("query btc checkpoint info", e))?;
+
+        let info = resp.into_inner().info.ok_or_else(|| {
+            ClientError::not_found(format!("BTC checkpoint for epoch {}", epoch_num))
+        })?;
+
+        Ok(BtcCheckpointInfo {
+            epoch_num: info.epoch_number,
+            btc_block_height: info.best_submission_btc_block_height,
+            btc_block_hash: info.best_submission_btc_block_hash,
+            tx_hashes: info
+                .best_submission_transactions
+                .into_iter()
+                .map(|tx| tx.hash)
+                .collect(),
+        })
+    }
+}
+
+impl CosmWasmClient {
+    async fn epoching_client(
+        &self,
+    ) -> Result<epoching::v1::query_client::QueryClient<Channel>, ClientError> {
+        epoching::v1::query_client::QueryClient::connect(self.grpc_url.clone())
+            .await
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))
+    }
+
+    /// Returns the number of blocks per epoch
+    pub async fn query_epoch_interval(&self) -> Result<u64, ClientError> {
+        let mut client = self.epoching_client().await?;
+
+        let resp = client
+            .params(epoching::v1::QueryParamsRequest {})
+            .await
+            .map_err(|e| ClientError::grpc("query epoching params", e))?;
+
+        resp.into_inner()
+            .params
+            .map(|p| p.epoch_interval)
+            .ok_or_else(|| ClientError::not_found("epoching params"))
+    }
+
+    /// Returns the current epoch and the height of its last block
+    pub async fn query_current_epoch(&self) -> Result<CurrentEpoch, ClientError> {
+        let mut client = self.epoching_client().await?;
+
+        let resp = client
+            .current_epoch(epoching::v1::QueryCurrentEpochRequest {})
+            .await
+            .map_err(|e| ClientError::grpc("query current epoch", e))?
+            .into_inner();
+
+        Ok(CurrentEpoch {
+            epoch_num: resp.current_epoch,
+            boundary: resp.epoch_boundary,
+        })
+    }
+
+    /// Returns the metadata of the given epoch
+    pub async fn query_epoch_info(&self, epoch_num: u64) -> Result<EpochInfo, ClientError> {
+        let mut client = self.epoching_client().await?;
+
+        let resp = client
+            .epoch_info(epoching::v1::QueryEpochInfoRequest { epoch_num })
+            .await
+            .map_err(|e| ClientError::grpc("query epoch info", e))?;
+
+        let epoch = resp
+            .into_inner()
+            .epoch
+            .ok_or_else(|| ClientError::not_found(format!("epoch {}", epoch_num)))?;
+
+        Ok(EpochInfo {
+            epoch_num: epoch.epoch_number,
+            interval: epoch.current_epoch_interval,
+            first_block_height: epoch.first_block_height,
+            last_block_time: epoch.last_block_time.map(|t| t.seconds),
+            sealer_block_hash: epoch.sealer_block_hash,
+        })
+    }
+
+    /// Returns the epoch containing the given Babylon height, based on the current epoch interval
+    pub async fn query_epoch_for_height(&self, height: u64) -> Result<u64, ClientError> {
+        let epoch_interval = self.query_epoch_interval().await?;
+        Ok(epoch_for_height(height, epoch_interval))
+    }
+}
+
+impl CosmWasmClient {
+    async fn btcstaking_client(
+        &self,
+    ) -> Result<btcstaking::v1::query_client::QueryClient<Channel>, ClientError> {
+        btcstaking::v1::query_client::QueryClient::connect(self.grpc_url.clone())
+            .await
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))
+    }
+
+    /// Returns all finality providers registered in the btcstaking module
+    pub async fn query_finality_providers(&self) -> Result<Vec<FinalityProvider>, ClientError> {
+        let mut client = self.btcstaking_client().await?;
+        let mut finality_providers = vec![];
+        let mut key = vec![];
+
+        loop {
+            let resp = client
+                .finality_providers(btcstaking::v1::QueryFinalityProvidersRequest {
+                    pagination: Some(PageRequest {
+                        key,
+                        ..Default::default()
+                    }),
+                })
+                .await
+                .map_err(|e| ClientError::grpc("query finality providers", e))?
+                .into_inner();
+
+            finality_providers.extend(resp.finality_providers.into_iter().map(Into::into));
+
+            key = resp.pagination.map(|p| p.next_key).unwrap_or_default();
+            if key.is_empty() {
+                break;
+            }
+        }
+
+        Ok(finality_providers)
+    }
+
+    /// Returns the finality provider with the given BTC public key in hex
+    pub async fn query_finality_provider(
+        &self,
+        fp_btc_pk_hex: &str,
+    ) -> Result<FinalityProvider, ClientError> {
+        let mut client = self.btcstaking_client().await?;
+
+        let resp = client
+            .finality_provider(btcstaking::v1::QueryFinalityProviderRequest {
+                fp_btc_pk_hex: fp_btc_pk_hex.to_string(),
+            })
+            .await
+            .map_err(|e| ClientError::grpc("query finality provider", e))?;
+
+        resp.into_inner()
+            .finality_provider
+            .map(Into::into)
+            .ok_or_else(|| ClientError::not_found(format!("finality provider {}", fp_btc_pk_hex)))
+    }
+
+    /// Returns all BTC delegations to the finality provider with the given BTC public key
+    pub async fn query_finality_provider_delegations(
+        &self,
+        fp_btc_pk_hex: &str,
+    ) -> Result<Vec<BtcDelegation>, ClientError> {
+        let mut client = self.btcstaking_client().await?;
+        let mut delegations = vec![];
+        let mut key = vec![];
+
+        loop {
+            let resp = client
+                .finality_provider_delegations(
+                    btcstaking::v1::QueryFinalityProviderDelegationsRequest {
+                        fp_btc_pk_hex: fp_btc_pk_hex.to_string(),
+                        pagination: Some(PageRequest {
+                            key,
+                            ..Default::default()
+                        }),
+                    },
+                )
+                .await
+                .map_err(|e| ClientError::grpc("query finality provider delegations", e))?
+                .into_inner();
+
+            delegations.extend(
+                resp.btc_delegator_delegations
+                    .into_iter()
+                    .flat_map(|d| d.dels)
+                    .map(Into::into),
+            );
+
+            key = resp.pagination.map(|p| p.next_key).unwrap_or_default();
+            if key.is_empty() {
+                break;
+            }
+        }
+
+        Ok(delegations)
+    }
+
+    /// Returns all BTC delegations with the given status
+    pub async fn query_btc_delegations(
+        &self,
+        status: BtcDelegationStatus,
+    ) -> Result<Vec<BtcDelegation>, ClientError> {
+        let mut client = self.btcstaking_client().await?;
+        let mut delegations = vec![];
+        let mut key = vec![];
+
+        loop {
+            let resp = client
+                .btc_delegations(btcstaking::v1::QueryBtcDelegationsRequest {
+                    status: status.to_proto() as i32,
+                    pagination: Some(PageRequest {
+                        key,
+                        ..Default::default()
+                    }),
+                })
+                .await
+                .map_err(|e| ClientError::grpc("query BTC delegations", e))?
+                .into_inner();
+
+            delegations.extend(resp.btc_delegations.into_iter().map(Into::into));
+
+            key = resp.pagination.map(|p| p.next_key).unwrap_or_default();
+            if key.is_empty() {
+                break;
+            }
+        }
+
+        Ok(delegations)
+    }
+
+    /// Returns the BTC delegation created by the given staking tx hash
+    pub async fn query_btc_delegation(
+        &self,
+        staking_tx_hash_hex: &str,
+    ) -> Result<BtcDelegation, ClientError> {
+        let mut client = self.btcstaking_client().await?;
+
+        let resp = client
+            .btc_delegation(btcstaking::v1::QueryBtcDelegationRequest {
+                staking_tx_hash_hex: staking_tx_hash_hex.to_string(),
+            })
+            .await
+            .map_err(|e| ClientError::grpc("query BTC delegation", e))?;
+
+        resp.into_inner()
+            .btc_delegation
+            .map(Into::into)
+            .ok_or_else(|| {
+                ClientError::not_found(format!("BTC delegation {}", staking_tx_hash_hex))
+            })
+    }
+}
+
+impl CosmWasmClient {
+    /// Returns the reward gauges of the given address, keyed by stakeholder type
+    /// (e.g. `finality_provider`, `btc_staker`)
+    pub async fn query_reward_gauges(
+        &self,
+        address: &str,
+    ) -> Result<HashMap<String, RewardGauge>, ClientError> {
+        let mut client = incentive::v1::query_client::QueryClient::connect(self.grpc_url.clone())
+            .await
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))?;
+
+        let resp = client
+            .reward_gauges(incentive::v1::QueryRewardGaugesRequest {
+                address: address.to_string(),
+            })
+            .await
+            .map_err(|e| ClientError::grpc("query reward gauges", e))?;
+
+        resp.into_inner()
+            .reward_gauges
+            .into_iter()
+            .map(|(stakeholder, gauge)| Ok((stakeholder, gauge.try_into()?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epoch_for_height() {
+        assert_eq!(epoch_for_height(0, 360), 0);
+        assert_eq!(epoch_for_height(1, 360), 1);
+        assert_eq!(epoch_for_height(360, 360), 1);
+        assert_eq!(epoch_for_height(361, 360), 2);
+        assert_eq!(epoch_for_height(100, 0), 0);
+    }
+
+    #[test]
+    fn test_epoch_last_block_height() {
+        let epoch = EpochInfo {
+            epoch_num: 2,
+            interval: 360,
+            first_block_height: 361,
+            last_block_time: None,
+            sealer_block_hash: String::new(),
+        };
+        assert_eq!(epoch.last_block_height(), 720);
+        assert_eq!(epoch_for_height(epoch.last_block_height(), 360), 2);
+    }
+
+    #[test]
+    fn test_checkpoint_status_order() {
+        assert!(CheckpointStatus::Finalized > CheckpointStatus::Confirmed);
+        assert!(CheckpointStatus::Sealed < CheckpointStatus::Submitted);
+        assert_eq!(
+            CheckpointStatus::from_proto(CheckpointStatus::Confirmed.to_proto() as i32).unwrap(),
+            CheckpointStatus::Confirmed
+        );
+        assert!(CheckpointStatus::from_proto(42).is_err());
+    }
+
+    #[test]
+    fn test_finality_provider_is_active() {
+        let fp = FinalityProvider::from(btcstaking::v1::FinalityProviderResponse {
+            addr: "bbn1ad2u30qd2vx6es4pmn28y23qtz6hea7708574y".to_string(),
+            btc_pk: vec![0xab; 32],
+            ..Default::default()
+        });
+        assert_eq!(fp.btc_pk, "ab".repeat(32));
+        assert!(fp.is_active());
+
+        let jailed = FinalityProvider {
+            jailed: true,
+            ..fp.clone()
+        };
+        assert!(!jailed.is_active());
+
+        let slashed = FinalityProvider {
+            slashed_babylon_height: 100,
+            ..fp
+        };
+        assert!(!slashed.is_active());
+    }
+
+    #[test]
+    fn test_reward_gauge_withdrawable() {
+        let gauge = RewardGauge::try_from(incentive::v1::RewardGaugesResponse {
+            coins: vec![
+                ProtoCoin {
+                    denom: "ubbn".to_string(),
+                    amount: "1000".to_string(),
+                },
+                ProtoCoin {
+                    denom: "ubtc".to_string(),
+                    amount: "5".to_string(),
+                },
+            ],
+            withdrawn_coins: vec![ProtoCoin {
+                denom: "ubtc".to_string(),
+                amount: "5".to_string(),
+            }],
+        })
+        .unwrap();
+        assert_eq!(gauge.withdrawable(), vec![Coin::new(1000u128, "ubbn")]);
+
+        assert!(RewardGauge::try_from(incentive::v1::RewardGaugesResponse {
+            coins: vec![ProtoCoin {
+                denom: "ubbn".to_string(),
+                amount: "abc".to_string(),
+            }],
+            withdrawn_coins: vec![],
+        })
+        .is_err());
+    }
+}
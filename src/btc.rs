@@ -0,0 +1,162 @@
+use std::str::FromStr;
+
+use bitcoin::consensus::encode::{deserialize, serialize_hex};
+use bitcoin::hashes::{sha256d, Hash, HashEngine};
+use bitcoin::{Block, Txid};
+
+use crate::btc_address::BtcNetwork;
+use crate::error::ClientError;
+use crate::transactions::PegInRequest;
+
+/// Peg in transaction and its inclusion proof, formatted as `ExecuteMsg::PegIn` expects
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PegInProof {
+    /// block_hash is the hash of the block containing the transaction, in display order
+    pub block_hash: String,
+    /// pegin_tx is the serialized peg in transaction in hex format
+    pub pegin_tx: String,
+    /// pegin_tx_idx is the index of the peg in transaction in the block
+    pub pegin_tx_idx: u32,
+    /// pegin_tx_merkle_proof are the sibling hashes from the leaf up to the merkle root,
+    /// in internal byte order
+    pub pegin_tx_merkle_proof: Vec<String>,
+}
+
+impl PegInProof {
+    /// Builds the peg in request for this proof
+    pub fn into_request(self, sender_btc_pk: &str, recipient: &str, amount: u128) -> PegInRequest {
+        PegInRequest {
+            sender_btc_pk: sender_btc_pk.to_string(),
+            recipient: recipient.to_string(),
+            amount,
+            block_hash: self.block_hash,
+            pegin_tx: self.pegin_tx,
+            pegin_tx_idx: self.pegin_tx_idx,
+            pegin_tx_merkle_proof: self.pegin_tx_merkle_proof,
+        }
+    }
+}
+
+impl From<BtcNetwork> for bitcoin::Network {
+    fn from(network: BtcNetwork) -> Self {
+        match network {
+            BtcNetwork::Mainnet => Self::Bitcoin,
+            BtcNetwork::Testnet => Self::Testnet,
+            BtcNetwork::Signet => Self::Signet,
+            BtcNetwork::Regtest => Self::Regtest,
+        }
+    }
+}
+
+/// Builds the peg in proof of the transaction with the given txid from a raw,
+/// consensus encoded Bitcoin block
+pub fn build_peg_in_proof(raw_block: &[u8], txid: &str) -> Result<PegInProof, ClientError> {
+    let block: Block = deserialize(raw_block)
+        .map_err(|e| ClientError::serialization("deserialize BTC block", e))?;
+    let txid = Txid::from_str(txid).map_err(|e| ClientError::invalid("txid", e))?;
+
+    let index = block
+        .txdata
+        .iter()
+        .position(|tx| tx.compute_txid() == txid)
+        .ok_or_else(|| ClientError::not_found(format!("transaction {} in block", txid)))?;
+
+    let leaves: Vec<sha256d::Hash> = block
+        .txdata
+        .iter()
+        .map(|tx| tx.compute_txid().to_raw_hash())
+        .collect();
+    let (root, branch) = merkle_branch(leaves, index);
+    if root != block.header.merkle_root.to_raw_hash() {
+        return Err(ClientError::invalid(
+            format!("BTC block {}", block.block_hash()),
+            "merkle root mismatch",
+        ));
+    }
+
+    Ok(PegInProof {
+        block_hash: block.block_hash().to_string(),
+        pegin_tx: serialize_hex(&block.txdata[index]),
+        pegin_tx_idx: index as u32,
+        pegin_tx_merkle_proof: branch
+            .iter()
+            .map(|hash| hex::encode(hash.as_byte_array()))
+            .collect(),
+    })
+}
+
+/// Returns the merkle root and the sibling hashes of the leaf at `index`.
+/// The last hash of an odd level is paired with itself, as in Bitcoin.
+fn merkle_branch(
+    mut level: Vec<sha256d::Hash>,
+    mut index: usize,
+) -> (sha256d::Hash, Vec<sha256d::Hash>) {
+    let mut branch = vec![];
+
+    while level.len() > 1 {
+        if level.len() % 2 == 1 {
+            level.push(*level.last().expect("non-empty level"));
+        }
+        branch.push(level[index ^ 1]);
+
+        level = level
+            .chunks(2)
+            .map(|pair| hash_pair(&pair[0], &pair[1]))
+            .collect();
+        index /= 2;
+    }
+
+    (level[0], branch)
+}
+
+fn hash_pair(left: &sha256d::Hash, right: &sha256d::Hash) -> sha256d::Hash {
+    let mut engine = sha256d::Hash::engine();
+    engine.input(left.as_byte_array());
+    engine.input(right.as_byte_array());
+    sha256d::Hash::from_engine(engine)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::blockdata::constants::genesis_block;
+    use bitcoin::consensus::encode::serialize;
+    use bitcoin::Network;
+
+    #[test]
+    fn test_build_peg_in_proof() {
+        let block = genesis_block(Network::Bitcoin);
+        let txid = block.txdata[0].compute_txid().to_string();
+
+        let proof = build_peg_in_proof(&serialize(&block), &txid).unwrap();
+        assert_eq!(
+            proof.block_hash,
+            "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+        );
+        assert_eq!(proof.pegin_tx_idx, 0);
+        assert!(proof.pegin_tx_merkle_proof.is_empty());
+
+        assert!(build_peg_in_proof(&serialize(&block), &"00".repeat(32)).is_err());
+    }
+
+    #[test]
+    fn test_merkle_branch() {
+        let leaves: Vec<sha256d::Hash> = (0u8..3).map(|i| sha256d::Hash::hash(&[i])).collect();
+        let (root, branch) = merkle_branch(leaves.clone(), 2);
+        assert_eq!(branch.len(), 2);
+        // odd levels pair the last leaf with itself
+        assert_eq!(branch[0], leaves[2]);
+
+        let mut hash = leaves[2];
+        let mut index = 2;
+        for sibling in branch {
+            hash = if index % 2 == 0 {
+                hash_pair(&hash, &sibling)
+            } else {
+                hash_pair(&sibling, &hash)
+            };
+            index /= 2;
+        }
+        assert_eq!(hash, root);
+    }
+}
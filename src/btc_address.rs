@@ -0,0 +1,134 @@
+use bech32::{segwit, Fe32, Hrp};
+
+use crate::error::ClientError;
+
+/// Length of the hash in a base58check P2PKH or P2SH address payload
+const BASE58_PAYLOAD_LEN: usize = 21;
+/// Length of a taproot output key
+const TAPROOT_PROGRAM_LEN: usize = 32;
+
+/// Bitcoin network the bridge operates on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BtcNetwork {
+    Mainnet,
+    Testnet,
+    Signet,
+    Regtest,
+}
+
+impl BtcNetwork {
+    /// Returns the bech32 human readable part of segwit addresses
+    fn hrp(self) -> &'static str {
+        match self {
+            Self::Mainnet => "bc",
+            Self::Testnet | Self::Signet => "tb",
+            Self::Regtest => "bcrt",
+        }
+    }
+
+    /// Returns the base58check version bytes of P2PKH and P2SH addresses
+    fn base58_versions(self) -> [u8; 2] {
+        match self {
+            Self::Mainnet => [0x00, 0x05],
+            Self::Testnet | Self::Signet | Self::Regtest => [0x6f, 0xc4],
+        }
+    }
+}
+
+const ALL_NETWORKS: [BtcNetwork; 4] = [
+    BtcNetwork::Mainnet,
+    BtcNetwork::Testnet,
+    BtcNetwork::Signet,
+    BtcNetwork::Regtest,
+];
+
+/// Checks that the address is a well-formed base58check, bech32 (segwit v0) or
+/// bech32m (segwit v1+) Bitcoin address. If a network is given, the address
+/// prefix must belong to it, otherwise any network is accepted.
+pub fn validate_btc_address(address: &str, network: Option<BtcNetwork>) -> Result<(), ClientError> {
+    let networks = match network {
+        Some(network) => vec![network],
+        None => ALL_NETWORKS.to_vec(),
+    };
+    let invalid = |reason: String| ClientError::InvalidBtcAddress {
+        address: address.to_string(),
+        reason,
+    };
+
+    if let Ok((hrp, version, program)) = segwit::decode(address) {
+        if !networks
+            .iter()
+            .any(|n| hrp == Hrp::parse_unchecked(n.hrp()))
+        {
+            return Err(invalid(format!("wrong network prefix {}", hrp)));
+        }
+        // other v1 program lengths are anyone-can-spend
+        if version == Fe32::P && program.len() != TAPROOT_PROGRAM_LEN {
+            return Err(invalid(format!(
+                "taproot program must be {} bytes, got {}",
+                TAPROOT_PROGRAM_LEN,
+                program.len()
+            )));
+        }
+        return Ok(());
+    }
+
+    let lowercase = address.to_lowercase();
+    if ALL_NETWORKS
+        .iter()
+        .any(|n| lowercase.starts_with(&format!("{}1", n.hrp())))
+    {
+        return Err(invalid("invalid bech32 encoding or checksum".to_string()));
+    }
+
+    let payload = bs58::decode(address)
+        .with_check(None)
+        .into_vec()
+        .map_err(|e| invalid(e.to_string()))?;
+    if payload.len() != BASE58_PAYLOAD_LEN {
+        return Err(invalid(format!("invalid payload length {}", payload.len())));
+    }
+    if !networks
+        .iter()
+        .any(|n| n.base58_versions().contains(&payload[0]))
+    {
+        return Err(invalid(format!(
+            "wrong network version byte {}",
+            payload[0]
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_btc_address() {
+        let taproot = "tb1pgx9vzuplwk87w587ekyh4tqecew0gxhttpfqk4jrz6euqgz3xpdsuzdp6g";
+        assert!(validate_btc_address(taproot, None).is_ok());
+        assert!(validate_btc_address(taproot, Some(BtcNetwork::Signet)).is_ok());
+        assert!(validate_btc_address(taproot, Some(BtcNetwork::Mainnet)).is_err());
+
+        let regtest = "bcrt1phcnl4zcl2fu047pv4wx6y058v8u0n02at6lthvm7pcf2wrvjm5tqatn90k";
+        assert!(validate_btc_address(regtest, Some(BtcNetwork::Regtest)).is_ok());
+
+        // segwit v0 and legacy mainnet addresses
+        let p2wpkh = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+        assert!(validate_btc_address(p2wpkh, Some(BtcNetwork::Mainnet)).is_ok());
+        let p2pkh = "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa";
+        assert!(validate_btc_address(p2pkh, Some(BtcNetwork::Mainnet)).is_ok());
+        assert!(validate_btc_address(p2pkh, Some(BtcNetwork::Testnet)).is_err());
+
+        // typo in the checksum
+        let typo = "tb1pgx9vzuplwk87w587ekyh4tqecew0gxhttpfqk4jrz6euqgz3xpdsuzdp6h";
+        assert!(matches!(
+            validate_btc_address(typo, None),
+            Err(ClientError::InvalidBtcAddress { .. })
+        ));
+        assert!(validate_btc_address("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb", None).is_err());
+        assert!(validate_btc_address("", None).is_err());
+    }
+}
@@ -1,10 +1,40 @@
-#[derive(Debug, Clone)]
+use std::path::Path;
+
+use cosmos_sdk_proto::cosmos::auth::v1beta1::{
+    query_client::QueryClient as AuthQueryClient, Bech32PrefixRequest,
+};
+use cosmos_sdk_proto::cosmos::base::tendermint::v1beta1::{
+    service_client::ServiceClient as TendermintServiceClient, GetNodeInfoRequest,
+};
+use cosmos_sdk_proto::cosmos::staking::v1beta1::{
+    query_client::QueryClient as StakingQueryClient, QueryParamsRequest,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::client::CosmWasmClient;
+use crate::error::{BoxError, ClientError};
+use crate::fee::{parse_gas_prices, GasPrice};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChainConfig {
     pub account_prefix: String,
     pub chain_id: String,
+    /// denom is the native staking denom of the chain
     pub denom: String,
     pub gas_limit: u64,
-    pub fee_amount: u128,
+    /// gas_price prices the gas of every tx, the fee is `gas × price` in its denom,
+    /// written as e.g. `0.002ubbn` in config files
+    pub gas_price: GasPrice,
+    /// fallback_gas_prices are other accepted fee tokens in order of preference, used by
+    /// `CosmWasmClient::select_fee_denom` when the wallet holds none of the `gas_price` denom
+    #[serde(default)]
+    pub fallback_gas_prices: Vec<GasPrice>,
+    /// grpc_url is the gRPC endpoint of the chain, e.g. `http://localhost:9090`
+    #[serde(default)]
+    pub grpc_url: Option<String>,
+    /// rpc_url is the Tendermint RPC endpoint of the chain, e.g. `http://localhost:26657`
+    #[serde(default)]
+    pub rpc_url: Option<String>,
 }
 
 impl ChainConfig {
@@ -13,14 +43,260 @@ impl ChainConfig {
         chain_id: String,
         denom: String,
         gas_limit: u64,
-        fee_amount: u128,
+        gas_price: GasPrice,
     ) -> Self {
         Self {
             account_prefix,
             chain_id,
             denom,
             gas_limit,
-            fee_amount,
+            gas_price,
+            fallback_gas_prices: vec![],
+            grpc_url: None,
+            rpc_url: None,
+        }
+    }
+
+    /// Loads the config from a TOML, YAML or JSON file, picked by its extension
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ClientError> {
+        let path = path.as_ref();
+        let invalid = |reason: &dyn std::fmt::Display| {
+            ClientError::invalid(format!("chain config {}", path.display()), reason)
+        };
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ClientError::io(format!("read chain config {}", path.display()), e))?;
+
+        let parse_error = |e: BoxError| {
+            ClientError::serialization(format!("parse chain config {}", path.display()), e)
+        };
+
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        match extension {
+            Some("toml") => toml::from_str(&content).map_err(|e| parse_error(e.into())),
+            Some("yaml" | "yml") => {
+                serde_yaml::from_str(&content).map_err(|e| parse_error(e.into()))
+            }
+            Some("json") => serde_json::from_str(&content).map_err(|e| parse_error(e.into())),
+            _ => Err(invalid(&format!("unsupported format {:?}", extension))),
+        }
+    }
+
+    /// Loads the config from environment variables named after the fields with the
+    /// given prefix, e.g. `BABYLON_CHAIN_ID` and `BABYLON_GRPC_URL` for `BABYLON`
+    pub fn from_env(prefix: &str) -> Result<Self, ClientError> {
+        Self::from_vars(prefix, |name| std::env::var(name).ok())
+    }
+
+    /// Loads the config from the variables returned by `lookup`, see `from_env`
+    fn from_vars(
+        prefix: &str,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, ClientError> {
+        let var = |name: &str| lookup(&format!("{}_{}", prefix, name));
+        let required = |name: &str| {
+            var(name).ok_or_else(|| ClientError::not_found(format!("{}_{}", prefix, name)))
+        };
+        let invalid = |name: &str, reason: &dyn std::fmt::Display| {
+            ClientError::invalid(format!("{}_{}", prefix, name), reason)
+        };
+
+        Ok(Self {
+            account_prefix: required("ACCOUNT_PREFIX")?,
+            chain_id: required("CHAIN_ID")?,
+            denom: required("DENOM")?,
+            gas_limit: required("GAS_LIMIT")?
+                .parse()
+                .map_err(|e| invalid("GAS_LIMIT", &e))?,
+            gas_price: required("GAS_PRICE")?
+                .parse()
+                .map_err(|e| invalid("GAS_PRICE", &e))?,
+            fallback_gas_prices: var("FALLBACK_GAS_PRICES")
+                .map(|prices| parse_gas_prices(&prices))
+                .transpose()
+                .map_err(|e| invalid("FALLBACK_GAS_PRICES", &e))?
+                .unwrap_or_default(),
+            grpc_url: var("GRPC_URL"),
+            rpc_url: var("RPC_URL"),
+        })
+    }
+
+    /// Returns the accepted gas prices in order of preference, `gas_price` first
+    pub fn gas_prices(&self) -> impl Iterator<Item = &GasPrice> {
+        std::iter::once(&self.gas_price).chain(&self.fallback_gas_prices)
+    }
+
+    /// Compares the config with the parameters reported by the node, returning
+    /// `ClientError::ChainMismatch` for the first field that differs
+    pub fn check(&self, params: &ChainParams) -> Result<(), ClientError> {
+        for (field, expected, actual) in [
+            ("chain_id", &self.chain_id, &params.chain_id),
+            (
+                "account_prefix",
+                &self.account_prefix,
+                &params.account_prefix,
+            ),
+            ("denom", &self.denom, &params.bond_denom),
+        ] {
+            if expected != actual {
+                return Err(ClientError::ChainMismatch {
+                    field: field.to_string(),
+                    expected: expected.clone(),
+                    actual: actual.clone(),
+                });
+            }
         }
+        Ok(())
+    }
+}
+
+/// Chain parameters reported by the node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainParams {
+    /// chain_id is the network of the node info
+    pub chain_id: String,
+    /// account_prefix is the bech32 prefix of the auth module
+    pub account_prefix: String,
+    /// bond_denom is the staking denom
+    pub bond_denom: String,
+}
+
+impl CosmWasmClient {
+    /// Queries the chain id, bech32 prefix and staking denom from the node
+    pub async fn query_chain_params(&self) -> Result<ChainParams, ClientError> {
+        let mut client = TendermintServiceClient::connect(self.grpc_url.clone())
+            .await
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))?;
+        let chain_id = client
+            .get_node_info(GetNodeInfoRequest {})
+            .await
+            .map_err(|e| ClientError::grpc("query node info", e))?
+            .into_inner()
+            .default_node_info
+            .ok_or_else(|| ClientError::not_found("node info"))?
+            .network;
+
+        let mut client = AuthQueryClient::connect(self.grpc_url.clone())
+            .await
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))?;
+        let account_prefix = client
+            .bech32_prefix(Bech32PrefixRequest {})
+            .await
+            .map_err(|e| ClientError::grpc("query bech32 prefix", e))?
+            .into_inner()
+            .bech32_prefix;
+
+        let mut client = StakingQueryClient::connect(self.grpc_url.clone())
+            .await
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))?;
+        let bond_denom = client
+            .params(QueryParamsRequest {})
+            .await
+            .map_err(|e| ClientError::grpc("query staking params", e))?
+            .into_inner()
+            .params
+            .ok_or_else(|| ClientError::not_found("staking params"))?
+            .bond_denom;
+
+        Ok(ChainParams {
+            chain_id,
+            account_prefix,
+            bond_denom,
+        })
+    }
+
+    /// Confirms the `ChainConfig` matches the chain the node belongs to, so a wrong
+    /// chain id, prefix or denom fails here instead of at signature verification
+    pub async fn validate_chain_config(&self) -> Result<(), ClientError> {
+        let params = self.query_chain_params().await?;
+        self.config.check(&params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_load_chain_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let toml_path = dir.path().join("chain.toml");
+        std::fs::write(
+            &toml_path,
+            r#"
+account_prefix = "bbn"
+chain_id = "bbn-test-5"
+denom = "ubbn"
+gas_limit = 1000000
+gas_price = "0.002ubbn"
+grpc_url = "http://localhost:9090"
+"#,
+        )
+        .unwrap();
+        let config = ChainConfig::from_file(&toml_path).unwrap();
+        assert_eq!(config.chain_id, "bbn-test-5");
+        assert_eq!(config.grpc_url.as_deref(), Some("http://localhost:9090"));
+        assert_eq!(config.rpc_url, None);
+
+        let yaml_path = dir.path().join("chain.yaml");
+        std::fs::write(&yaml_path, serde_yaml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(
+            ChainConfig::from_file(&yaml_path).unwrap().gas_limit,
+            1_000_000
+        );
+
+        assert!(matches!(
+            ChainConfig::from_file(dir.path().join("missing.toml")),
+            Err(ClientError::Io { .. })
+        ));
+
+        // the variables are looked up in a map, tests running in parallel share the
+        // process environment
+        let mut vars: HashMap<String, String> = [
+            ("BABYLON_ACCOUNT_PREFIX", "bbn"),
+            ("BABYLON_CHAIN_ID", "bbn-1"),
+            ("BABYLON_DENOM", "ubbn"),
+            ("BABYLON_GAS_LIMIT", "200000"),
+            ("BABYLON_GAS_PRICE", "0.002"),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+        assert!(ChainConfig::from_vars("BABYLON", |name| vars.get(name).cloned()).is_err());
+        vars.insert("BABYLON_GAS_PRICE".to_string(), "0.002ubbn".to_string());
+        vars.insert(
+            "BABYLON_FALLBACK_GAS_PRICES".to_string(),
+            "0.1uatom".to_string(),
+        );
+        let config = ChainConfig::from_vars("BABYLON", |name| vars.get(name).cloned()).unwrap();
+        assert_eq!(config.chain_id, "bbn-1");
+        assert_eq!(config.gas_price.to_string(), "0.002ubbn");
+        let denoms: Vec<_> = config.gas_prices().map(|p| p.denom.as_str()).collect();
+        assert_eq!(denoms, ["ubbn", "uatom"]);
+    }
+
+    #[test]
+    fn test_check_chain_params() {
+        let config = ChainConfig::new(
+            "bbn".to_string(),
+            "bbn-test-5".to_string(),
+            "ubbn".to_string(),
+            1_000_000,
+            "0.002ubbn".parse().unwrap(),
+        );
+        let mut params = ChainParams {
+            chain_id: "bbn-test-5".to_string(),
+            account_prefix: "bbn".to_string(),
+            bond_denom: "ubbn".to_string(),
+        };
+        assert!(config.check(&params).is_ok());
+
+        params.chain_id = "bbn-1".to_string();
+        assert!(matches!(
+            config.check(&params),
+            Err(ClientError::ChainMismatch { field, expected, actual })
+                if field == "chain_id" && expected == "bbn-test-5" && actual == "bbn-1"
+        ));
     }
 }
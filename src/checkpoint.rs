@@ -0,0 +1,209 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use async_trait::async_trait;
+
+/// Persists the last processed height of an `EventListener`, so a restarted
+/// listener resumes where it left off
+#[async_trait]
+pub trait CheckpointStore: Send + Sync {
+    /// Returns the last saved height, `None` if nothing has been saved yet
+    async fn load(&self) -> anyhow::Result<Option<u64>>;
+
+    /// Saves the last processed height
+    async fn save(&self, height: u64) -> anyhow::Result<()>;
+}
+
+/// Stores the height as plain text in a file
+#[derive(Debug, Clone)]
+pub struct FileCheckpointStore {
+    path: PathBuf,
+}
+
+impl FileCheckpointStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl CheckpointStore for FileCheckpointStore {
+    async fn load(&self) -> anyhow::Result<Option<u64>> {
+        let content = match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("Failed to read checkpoint file {}", self.path.display())
+                })
+            }
+        };
+
+        let height = content
+            .trim()
+            .parse::<u64>()
+            .with_context(|| format!("Invalid checkpoint in {}", self.path.display()))?;
+        Ok(Some(height))
+    }
+
+    async fn save(&self, height: u64) -> anyhow::Result<()> {
+        // write to a temporary file first so a crash never leaves a truncated checkpoint
+        let tmp_path = self.path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, height.to_string())
+            .await
+            .with_context(|| format!("Failed to write checkpoint file {}", tmp_path.display()))?;
+        tokio::fs::rename(&tmp_path, &self.path)
+            .await
+            .with_context(|| format!("Failed to write checkpoint file {}", self.path.display()))
+    }
+}
+
+/// Stores the height in a SQLite table, keyed by listener name so several
+/// listeners can share one database
+#[cfg(feature = "sqlite")]
+pub struct SqliteCheckpointStore {
+    conn: std::sync::Mutex<rusqlite::Connection>,
+    name: String,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteCheckpointStore {
+    pub fn open(path: impl AsRef<std::path::Path>, name: &str) -> anyhow::Result<Self> {
+        let conn = rusqlite::Connection::open(path).context("Failed to open SQLite database")?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS checkpoints (name TEXT PRIMARY KEY, height INTEGER NOT NULL)",
+            [],
+        )
+        .context("Failed to create checkpoints table")?;
+
+        Ok(Self {
+            conn: std::sync::Mutex::new(conn),
+            name: name.to_string(),
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[async_trait]
+impl CheckpointStore for SqliteCheckpointStore {
+    async fn load(&self) -> anyhow::Result<Option<u64>> {
+        use rusqlite::OptionalExtension;
+
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let height: Option<i64> = conn
+            .query_row(
+                "SELECT height FROM checkpoints WHERE name = ?1",
+                [&self.name],
+                |row| row.get(0),
+            )
+            .optional()
+            .context("Failed to load checkpoint")?;
+
+        Ok(height.map(|h| h as u64))
+    }
+
+    async fn save(&self, height: u64) -> anyhow::Result<()> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        conn.execute(
+            "INSERT INTO checkpoints (name, height) VALUES (?1, ?2)
+             ON CONFLICT(name) DO UPDATE SET height = excluded.height",
+            rusqlite::params![self.name, height as i64],
+        )
+        .context("Failed to save checkpoint")?;
+
+        Ok(())
+    }
+}
+
+/// Stores the height in a Postgres table, keyed by listener name
+#[cfg(feature = "postgres")]
+pub struct PostgresCheckpointStore {
+    client: tokio_postgres::Client,
+    name: String,
+}
+
+#[cfg(feature = "postgres")]
+impl PostgresCheckpointStore {
+    /// Connects to Postgres, e.g. `host=localhost user=postgres dbname=bridge`
+    pub async fn connect(config: &str, name: &str) -> anyhow::Result<Self> {
+        let (client, connection) = tokio_postgres::connect(config, tokio_postgres::NoTls)
+            .await
+            .context("Failed to connect to Postgres")?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::error!("Postgres connection error: {}", e);
+            }
+        });
+
+        client
+            .execute(
+                "CREATE TABLE IF NOT EXISTS checkpoints (name TEXT PRIMARY KEY, height BIGINT NOT NULL)",
+                &[],
+            )
+            .await
+            .context("Failed to create checkpoints table")?;
+
+        Ok(Self {
+            client,
+            name: name.to_string(),
+        })
+    }
+}
+
+#[cfg(feature = "postgres")]
+#[async_trait]
+impl CheckpointStore for PostgresCheckpointStore {
+    async fn load(&self) -> anyhow::Result<Option<u64>> {
+        let row = self
+            .client
+            .query_opt(
+                "SELECT height FROM checkpoints WHERE name = $1",
+                &[&self.name],
+            )
+            .await
+            .context("Failed to load checkpoint")?;
+
+        Ok(row.map(|row| row.get::<_, i64>(0) as u64))
+    }
+
+    async fn save(&self, height: u64) -> anyhow::Result<()> {
+        self.client
+            .execute(
+                "INSERT INTO checkpoints (name, height) VALUES ($1, $2)
+                 ON CONFLICT (name) DO UPDATE SET height = EXCLUDED.height",
+                &[&self.name, &(height as i64)],
+            )
+            .await
+            .context("Failed to save checkpoint")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_checkpoint_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileCheckpointStore::new(dir.path().join("checkpoint"));
+
+        assert_eq!(store.load().await.unwrap(), None);
+        store.save(42).await.unwrap();
+        store.save(100).await.unwrap();
+        assert_eq!(store.load().await.unwrap(), Some(100));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_sqlite_checkpoint_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SqliteCheckpointStore::open(dir.path().join("db.sqlite"), "bridge").unwrap();
+
+        assert_eq!(store.load().await.unwrap(), None);
+        store.save(42).await.unwrap();
+        store.save(100).await.unwrap();
+        assert_eq!(store.load().await.unwrap(), Some(100));
+    }
+}
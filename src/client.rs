@@ -1,25 +1,187 @@
+use std::future::Future;
 use std::str::FromStr;
+#[cfg(feature = "wallet")]
+use std::sync::Mutex;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
+#[cfg(feature = "babylon")]
 use crate::generated::babylon::btclightclient;
-use anyhow::Context;
+#[cfg(feature = "babylon")]
+use crate::generated::cosmos::base::query::v1beta1::PageRequest;
+use async_trait::async_trait;
 use cosmos_sdk_proto::cosmos::{
-    auth::v1beta1::{query_client::QueryClient, BaseAccount, QueryAccountRequest},
+    auth::v1beta1::{
+        query_client::QueryClient, BaseAccount, QueryAccountRequest, QueryAccountResponse,
+    },
+    base::abci::v1beta1::TxResponse,
+    base::tendermint::v1beta1::{
+        service_client::ServiceClient as TendermintServiceClient, GetLatestBlockRequest,
+    },
     tx::v1beta1::{
         service_client::ServiceClient, BroadcastMode, BroadcastTxRequest, BroadcastTxResponse,
         GetTxRequest, GetTxResponse,
     },
 };
+use cosmos_sdk_proto::cosmwasm::wasm::v1::{
+    query_client::QueryClient as WasmQueryClient, QueryCodeRequest, QueryRawContractStateRequest,
+    QueryRawContractStateResponse, QuerySmartContractStateRequest, QuerySmartContractStateResponse,
+};
 use cosmrs::AccountId;
+#[cfg(feature = "wallet")]
+use cosmrs::Any;
+use cosmwasm_std::Uint256;
+use futures::StreamExt;
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+#[cfg(feature = "babylon")]
+use tonic::transport::Channel;
 
+use crate::btc_address::BtcNetwork;
 use crate::chain::ChainConfig;
+use crate::error::{ClientError, TransactionError};
+use crate::fee::{GasPrice, TxPriority, DEFAULT_GAS_ADJUSTMENT};
+use crate::logging;
+#[cfg(feature = "rest")]
+use crate::rest::RestClient;
+#[cfg(feature = "events")]
+use crate::rpc::RpcClient;
+#[cfg(feature = "wallet")]
+use crate::transactions::{AccountCache, DEFAULT_ACCOUNT_CACHE_TTL};
+use crate::version::BridgeSchema;
 use crate::wallet::Wallet;
 
+/// gRPC query methods answered over ABCI query when the gRPC endpoint is unreachable
+const ACCOUNT_QUERY_PATH: &str = "/cosmos.auth.v1beta1.Query/Account";
+const SMART_QUERY_PATH: &str = "/cosmwasm.wasm.v1.Query/SmartContractState";
+const RAW_QUERY_PATH: &str = "/cosmwasm.wasm.v1.Query/RawContractState";
+#[cfg(feature = "babylon")]
+const CONTAINS_BYTES_QUERY_PATH: &str = "/babylon.btclightclient.v1.Query/ContainsBytes";
+
+/// gRPC metadata and REST header selecting the block height a query is answered at
+pub(crate) const BLOCK_HEIGHT_HEADER: &str = "x-cosmos-block-height";
+
+/// Default number of smart queries of `query_smart_many` in flight at once
+const DEFAULT_QUERY_CONCURRENCY: usize = 16;
+
+/// Number of headers requested per page when walking the BTC light client main chain
+#[cfg(feature = "babylon")]
+const MAIN_CHAIN_PAGE_LIMIT: u64 = 100;
+
+/// BTC header info as maintained by the Babylon btclightclient module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BtcHeaderInfo {
+    /// header_hex is the full 80-byte BTC header in hex format
+    pub header_hex: String,
+    /// hash is the block hash in hex format
+    pub hash: String,
+    /// height is the height of the header in the BTC chain
+    pub height: u32,
+    /// work is the total work accumulated up to this header
+    pub work: Uint256,
+}
+
+#[cfg(feature = "babylon")]
+impl TryFrom<btclightclient::v1::BtcHeaderInfoResponse> for BtcHeaderInfo {
+    type Error = ClientError;
+
+    fn try_from(header: btclightclient::v1::BtcHeaderInfoResponse) -> Result<Self, ClientError> {
+        let work = Uint256::from_str(&header.work)
+            .map_err(|e| ClientError::serialization("parse header work", e))?;
+
+        Ok(Self {
+            header_hex: header.header_hex,
+            hash: header.hash_hex,
+            height: header.height,
+            work,
+        })
+    }
+}
+
+/// Contract and chain queries of a `CosmWasmClient`, so code using the client can
+/// be tested against `mock::MockCosmWasmClient` instead of a node
+#[async_trait]
+pub trait CosmWasmApi: Send + Sync {
+    /// Returns the address signing the transactions, empty for a read-only client
+    fn sender(&self) -> String;
+
+    /// Executes the JSON message on the contract and returns the tx hash
+    async fn execute_json(
+        &self,
+        contract: &str,
+        msg: serde_json::Value,
+    ) -> Result<String, ClientError>;
+
+    /// Runs the JSON smart query against the contract
+    async fn query_json(
+        &self,
+        contract: &str,
+        msg: serde_json::Value,
+    ) -> Result<serde_json::Value, ClientError>;
+
+    /// Returns the raw value stored under `key` in the contract storage, if any
+    async fn query_raw(&self, contract: &str, key: &[u8]) -> Result<Option<Vec<u8>>, ClientError>;
+
+    async fn get_tx(&self, hash: &str) -> Result<GetTxResponse, ClientError>;
+
+    /// Returns the best header of the BTC light client
+    async fn query_tip(&self) -> Result<BtcHeaderInfo, ClientError>;
+
+    /// Returns the depth of the given block in the BTC light client main chain
+    async fn query_header_depth(&self, block_hash: &str) -> Result<u32, ClientError>;
+}
+
 #[derive(Clone)]
 pub struct CosmWasmClient {
-    grpc_url: String,
-    pub wallet: Wallet,
+    pub(crate) grpc_url: String,
+    /// archive_grpc_url serves the queries at heights pruned by the node
+    pub(crate) archive_grpc_url: Option<String>,
+    /// wallet signs the transactions, `None` for a read-only client
+    pub wallet: Option<Wallet>,
     pub contract: Option<AccountId>,
     pub config: ChainConfig,
+    /// peg_in_precheck queries the contract before every peg in and fails with
+    /// `ClientError::AlreadyPegged` if the BTC tx has already been processed
+    pub peg_in_precheck: bool,
+    /// btc_network restricts peg out addresses to the given network, any network if unset
+    pub btc_network: Option<BtcNetwork>,
+    /// gas_price is the gas price discovered from the node, shared between clones
+    pub(crate) gas_price: Arc<RwLock<Option<GasPrice>>>,
+    /// fee_gas_price is the configured gas price picked by `select_fee_denom`, shared
+    /// between clones
+    pub(crate) fee_gas_price: Arc<RwLock<Option<GasPrice>>>,
+    /// gas_adjustment is the multiplier applied to simulated gas by `estimate_fee`
+    pub gas_adjustment: f64,
+    /// priority raises the gas price of the transactions above the discovered or
+    /// configured one
+    pub priority: TxPriority,
+    /// query_concurrency is the number of smart queries `query_smart_many` runs at once
+    pub query_concurrency: usize,
+    /// bridge_schema is the message schema of the bridge contract detected by
+    /// `handshake`, shared between clones
+    pub(crate) bridge_schema: Arc<RwLock<BridgeSchema>>,
+    /// account_cache keeps the account number and sequence of the signer between
+    /// transactions, shared between clones
+    #[cfg(feature = "wallet")]
+    pub(crate) account_cache: Arc<Mutex<AccountCache>>,
+    /// account_cache_ttl is how long a fetched sequence is used for consecutive
+    /// transactions before querying the account again, zero disables the cache
+    #[cfg(feature = "wallet")]
+    pub account_cache_ttl: Duration,
+    /// extension_options are added to the body of every transaction, e.g. the
+    /// dynamic fee extension required by Ethermint based chains
+    #[cfg(feature = "wallet")]
+    pub extension_options: Vec<Any>,
+    /// non_critical_extension_options are added to the body of every transaction,
+    /// nodes that don't know them ignore them
+    #[cfg(feature = "wallet")]
+    pub non_critical_extension_options: Vec<Any>,
+    /// rpc is the Tendermint RPC endpoint shared with listeners created from the client
+    #[cfg(feature = "events")]
+    pub(crate) rpc: Option<RpcClient>,
+    /// rest replaces gRPC for account lookups, broadcasts and smart queries
+    #[cfg(feature = "rest")]
+    pub(crate) rest: Option<RestClient>,
 }
 
 impl CosmWasmClient {
@@ -28,22 +190,165 @@ impl CosmWasmClient {
         private_key: &str,
         contract: &str,
         config: ChainConfig,
-    ) -> anyhow::Result<Self> {
+    ) -> Result<Self, ClientError> {
         let wallet = Wallet::new(private_key, &config.account_prefix)?;
-        let contract = AccountId::from_str(contract).map_err(|e| anyhow::anyhow!(e));
+        let mut client = Self::new_readonly(grpc_url, contract, config)?;
+        client.wallet = Some(wallet);
+        Ok(client)
+    }
+
+    /// Creates a client without a wallet, for queries only. Transactions fail
+    /// with `ClientError::Wallet`.
+    pub fn new_readonly(
+        grpc_url: &str,
+        contract: &str,
+        config: ChainConfig,
+    ) -> Result<Self, ClientError> {
+        let contract = AccountId::from_str(contract)
+            .map_err(|e| ClientError::invalid(format!("contract address {}", contract), e))?;
 
         Ok(Self {
             grpc_url: grpc_url.to_string(),
-            wallet,
-            contract: Some(contract?),
+            archive_grpc_url: None,
+            wallet: None,
+            contract: Some(contract),
             config,
+            peg_in_precheck: false,
+            btc_network: None,
+            gas_price: Arc::new(RwLock::new(None)),
+            fee_gas_price: Arc::new(RwLock::new(None)),
+            gas_adjustment: DEFAULT_GAS_ADJUSTMENT,
+            priority: TxPriority::default(),
+            query_concurrency: DEFAULT_QUERY_CONCURRENCY,
+            bridge_schema: Arc::new(RwLock::new(BridgeSchema::default())),
+            #[cfg(feature = "wallet")]
+            account_cache: Arc::new(Mutex::new(AccountCache::default())),
+            #[cfg(feature = "wallet")]
+            account_cache_ttl: DEFAULT_ACCOUNT_CACHE_TTL,
+            #[cfg(feature = "wallet")]
+            extension_options: vec![],
+            #[cfg(feature = "wallet")]
+            non_critical_extension_options: vec![],
+            #[cfg(feature = "events")]
+            rpc: None,
+            #[cfg(feature = "rest")]
+            rest: None,
+        })
+    }
+
+    /// Creates the client and checks the `ChainConfig` against the node with
+    /// `validate_chain_config`
+    pub async fn new_validated(
+        grpc_url: &str,
+        private_key: &str,
+        contract: &str,
+        config: ChainConfig,
+    ) -> Result<Self, ClientError> {
+        let client = Self::new(grpc_url, private_key, contract, config)?;
+        client.validate_chain_config().await?;
+        Ok(client)
+    }
+
+    pub fn with_btc_network(mut self, btc_network: BtcNetwork) -> Self {
+        self.btc_network = Some(btc_network);
+        self
+    }
+
+    /// Sets the gRPC endpoint of an archive node, e.g. `http://archive:9090`, answering
+    /// `query_smart_at_height` and `query_raw_at_height` at heights pruned by the node
+    pub fn with_archive_grpc(mut self, archive_grpc_url: &str) -> Self {
+        self.archive_grpc_url = Some(archive_grpc_url.to_string());
+        self
+    }
+
+    /// Sets the number of smart queries `query_smart_many` runs at once
+    pub fn with_query_concurrency(mut self, query_concurrency: usize) -> Result<Self, ClientError> {
+        if query_concurrency == 0 {
+            return Err(ClientError::invalid(
+                "query concurrency",
+                "must be positive",
+            ));
+        }
+        self.query_concurrency = query_concurrency;
+        Ok(self)
+    }
+
+    /// Sets the Tendermint RPC endpoint, e.g. `http://localhost:26657`, queried by
+    /// `status`, `block`, `validators` and `tx` and shared with the event listeners
+    /// created by `EventListener::from_client`
+    #[cfg(feature = "events")]
+    pub fn with_rpc(mut self, rpc_url: &str) -> Result<Self, ClientError> {
+        self.rpc = Some(RpcClient::new(rpc_url)?);
+        Ok(self)
+    }
+
+    /// Sends account lookups, broadcasts and smart queries to the Cosmos REST API,
+    /// e.g. `http://localhost:1317`, instead of gRPC, for infrastructure where gRPC
+    /// is blocked by proxies
+    #[cfg(feature = "rest")]
+    pub fn with_rest(mut self, rest_url: &str) -> Result<Self, ClientError> {
+        self.rest = Some(RestClient::new(rest_url)?);
+        Ok(self)
+    }
+
+    /// Signs with another wallet, e.g. one of a `WalletPool`. The account cache isn't
+    /// shared with the clones signing with the previous wallet.
+    #[cfg(feature = "wallet")]
+    pub fn with_wallet(mut self, wallet: Wallet) -> Self {
+        self.wallet = Some(wallet);
+        self.account_cache = Arc::new(Mutex::new(AccountCache::default()));
+        self
+    }
+
+    /// Sets how long a fetched account sequence is reused, e.g. zero when other
+    /// processes sign with the same key
+    #[cfg(feature = "wallet")]
+    pub fn with_account_cache_ttl(mut self, account_cache_ttl: Duration) -> Self {
+        self.account_cache_ttl = account_cache_ttl;
+        self
+    }
+
+    /// Adds an extension option to the body of every transaction, for chains
+    /// rejecting transactions without it
+    #[cfg(feature = "wallet")]
+    pub fn with_extension_option(mut self, option: Any) -> Self {
+        self.extension_options.push(option);
+        self
+    }
+
+    /// Adds a non-critical extension option to the body of every transaction
+    #[cfg(feature = "wallet")]
+    pub fn with_non_critical_extension_option(mut self, option: Any) -> Self {
+        self.non_critical_extension_options.push(option);
+        self
+    }
+
+    pub fn with_peg_in_precheck(mut self, peg_in_precheck: bool) -> Self {
+        self.peg_in_precheck = peg_in_precheck;
+        self
+    }
+
+    /// Returns the wallet signing the transactions, failing for a read-only client
+    pub fn signer(&self) -> Result<&Wallet, ClientError> {
+        self.wallet.as_ref().ok_or_else(|| {
+            ClientError::wallet("sign transaction", "read-only client has no wallet")
         })
     }
 
-    pub async fn broadcast_tx(&self, tx_bytes: Vec<u8>) -> anyhow::Result<BroadcastTxResponse> {
+    pub async fn broadcast_tx(
+        &self,
+        tx_bytes: Vec<u8>,
+    ) -> Result<BroadcastTxResponse, ClientError> {
+        tracing::debug!("Broadcasting tx: {}", logging::bytes(&tx_bytes));
+
+        #[cfg(feature = "rest")]
+        if let Some(rest) = &self.rest {
+            return rest.broadcast_tx(&tx_bytes).await;
+        }
+
         let mut client = ServiceClient::connect(self.grpc_url.clone())
             .await
-            .context("Failed to connect to gRPC service")?;
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))?;
 
         let request = tonic::Request::new(BroadcastTxRequest {
             tx_bytes,
@@ -53,80 +358,427 @@ impl CosmWasmClient {
         let response = client
             .broadcast_tx(request)
             .await
-            .context("Failed to broadcast transaction")?;
+            .map_err(|e| ClientError::grpc("broadcast transaction", e))?;
 
         Ok(response.into_inner())
     }
 
-    pub async fn get_account_info(&self, address: String) -> anyhow::Result<BaseAccount> {
-        let mut client = QueryClient::connect(self.grpc_url.clone())
-            .await
-            .context("Failed to connect to gRPC service")?;
+    /// Falls back to the Tendermint RPC endpoint set by `with_rpc`, over ABCI query, when
+    /// the gRPC endpoint can't be reached, so nodes only exposing RPC can still be queried.
+    /// The query is answered at `height` if set, the latest height otherwise.
+    /// Returns `error` otherwise.
+    #[cfg_attr(not(feature = "events"), allow(unused_variables))]
+    async fn or_abci_query<Req, Resp>(
+        &self,
+        error: ClientError,
+        path: &str,
+        request: &Req,
+        height: Option<u64>,
+        action: &str,
+    ) -> Result<Resp, ClientError>
+    where
+        Req: prost::Message,
+        Resp: prost::Message + Default,
+    {
+        #[cfg(feature = "events")]
+        if matches!(error, ClientError::Connection { .. }) && self.rpc.is_some() {
+            tracing::debug!("{}, falling back to ABCI query {}", error, path);
+            return self.abci_query(path, request, height, action).await;
+        }
+        Err(error)
+    }
 
-        let resp = client
-            .account(QueryAccountRequest { address })
-            .await
-            .context("Failed to query account information")?;
+    pub async fn get_account_info(&self, address: String) -> Result<BaseAccount, ClientError> {
+        #[cfg(feature = "rest")]
+        if let Some(rest) = &self.rest {
+            return rest.account(&address).await;
+        }
+
+        let request = QueryAccountRequest { address };
+        let resp: QueryAccountResponse = match QueryClient::connect(self.grpc_url.clone()).await {
+            Ok(mut client) => client
+                .account(request)
+                .await
+                .map_err(|e| ClientError::grpc("query account information", e))?
+                .into_inner(),
+            Err(e) => {
+                let error = ClientError::connection(&self.grpc_url, e);
+                self.or_abci_query(
+                    error,
+                    ACCOUNT_QUERY_PATH,
+                    &request,
+                    None,
+                    "query account information",
+                )
+                .await?
+            }
+        };
 
         let account_info = resp
-            .get_ref()
-            .clone()
             .account
-            .ok_or_else(|| anyhow::anyhow!("No account data found"))?;
+            .ok_or_else(|| ClientError::not_found("account data"))?;
 
         let account = account_info
             .to_msg::<BaseAccount>()
-            .context("Failed to convert account info to BaseAccount")?;
+            .map_err(|e| ClientError::serialization("convert account info to BaseAccount", e))?;
 
         Ok(account)
     }
 
-    pub async fn get_tx(&self, hash: &str) -> anyhow::Result<GetTxResponse> {
+    pub async fn get_tx(&self, hash: &str) -> Result<GetTxResponse, ClientError> {
         let mut client = ServiceClient::connect(self.grpc_url.clone())
             .await
-            .context("Failed to connect to gRPC service")?;
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))?;
 
         let response = client
             .get_tx(GetTxRequest {
                 hash: hash.to_string(),
             })
             .await
-            .context("Failed to get transaction")?
+            .map_err(|e| ClientError::grpc("get transaction", e))?
             .into_inner();
 
         Ok(response)
     }
 
-    pub async fn query_header_contains(&self, block_hash: &str) -> anyhow::Result<bool> {
-        let mut client =
-            btclightclient::v1::query_client::QueryClient::connect(self.grpc_url.clone())
+    /// Polls `get_tx` until the transaction is included in a block, failing with
+    /// `ClientError::TxFailed` if its execution failed
+    pub async fn wait_for_tx(
+        &self,
+        hash: &str,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> Result<TxResponse, ClientError> {
+        self.wait_for_any_tx(&[hash], timeout, poll_interval)
+            .await?
+            .ok_or_else(|| ClientError::Timeout {
+                what: format!("Confirmation of transaction {}", hash),
+                timeout,
+            })
+    }
+
+    /// Polls `get_tx` until one of the transactions is included in a block, `None`
+    /// if none is by the timeout, e.g. while replacements of a tx are pending
+    pub(crate) async fn wait_for_any_tx(
+        &self,
+        hashes: &[&str],
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> Result<Option<TxResponse>, ClientError> {
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            for hash in hashes {
+                // the node returns an error until the tx is included in a block
+                if let Ok(Some(tx_response)) = self.get_tx(hash).await.map(|resp| resp.tx_response)
+                {
+                    if tx_response.code != 0 {
+                        return Err(ClientError::TxFailed(Box::new(TransactionError::from(
+                            tx_response,
+                        ))));
+                    }
+                    return Ok(Some(tx_response));
+                }
+            }
+
+            if tokio::time::Instant::now() >= deadline {
+                return Ok(None);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Runs a smart query against the given contract and deserializes the JSON response
+    pub async fn query_smart<M, R>(&self, contract: &str, msg: &M) -> Result<R, ClientError>
+    where
+        M: Serialize,
+        R: DeserializeOwned,
+    {
+        self.smart_query(contract, msg, None).await
+    }
+
+    /// Runs a smart query against the contract state at the given block height, so
+    /// several queries pinned to the same height, e.g. from `query_latest_height`, read
+    /// a consistent state. Heights pruned by the node are queried on the archive node
+    /// set by `with_archive_grpc`.
+    pub async fn query_smart_at_height<M, R>(
+        &self,
+        contract: &str,
+        msg: &M,
+        height: u64,
+    ) -> Result<R, ClientError>
+    where
+        M: Serialize,
+        R: DeserializeOwned,
+    {
+        self.smart_query(contract, msg, Some(height)).await
+    }
+
+    async fn smart_query<M, R>(
+        &self,
+        contract: &str,
+        msg: &M,
+        height: Option<u64>,
+    ) -> Result<R, ClientError>
+    where
+        M: Serialize,
+        R: DeserializeOwned,
+    {
+        let query_data = serde_json::to_vec(msg)
+            .map_err(|e| ClientError::serialization("serialize query message", e))?;
+        let request = QuerySmartContractStateRequest {
+            address: contract.to_string(),
+            query_data,
+        };
+
+        #[cfg(feature = "rest")]
+        if let Some(rest) = &self.rest {
+            let data = rest
+                .smart_query(contract, &request.query_data, height)
+                .await;
+            let data = self
+                .or_archive(data, |url| async move {
+                    Ok(smart_contract_state(&url, request, height).await?.data)
+                })
+                .await?;
+            return serde_json::from_slice(&data)
+                .map_err(|e| ClientError::serialization("deserialize query response", e));
+        }
+
+        let resp = match smart_contract_state(&self.grpc_url, request.clone(), height).await {
+            Err(error @ ClientError::Connection { .. }) => {
+                self.or_abci_query(
+                    error,
+                    SMART_QUERY_PATH,
+                    &request,
+                    height,
+                    "query contract state",
+                )
+                .await
+            }
+            resp => resp,
+        };
+        let resp = self
+            .or_archive(resp, |url| async move {
+                smart_contract_state(&url, request, height).await
+            })
+            .await?;
+
+        serde_json::from_slice(&resp.data)
+            .map_err(|e| ClientError::serialization("deserialize query response", e))
+    }
+
+    /// Runs the smart queries concurrently over one gRPC connection, at most
+    /// `query_concurrency` at once, and returns their results in the order of the queries
+    pub async fn query_smart_many<C, M, R>(
+        &self,
+        queries: Vec<(C, M)>,
+    ) -> Vec<Result<R, ClientError>>
+    where
+        C: AsRef<str>,
+        M: Serialize,
+        R: DeserializeOwned,
+    {
+        let concurrency = self.query_concurrency.max(1);
+
+        #[cfg(feature = "rest")]
+        let connect = self.rest.is_none();
+        #[cfg(not(feature = "rest"))]
+        let connect = true;
+        let shared = match connect {
+            true => WasmQueryClient::connect(self.grpc_url.clone())
                 .await
-                .context("Failed to connect to gRPC service")?;
-        let mut hash_bytes =
-            hex::decode(block_hash).context("Failed to decode block hash from hex")?;
-        hash_bytes.reverse();
+                .inspect_err(|e| {
+                    tracing::warn!(
+                        "Failed to connect to {}, running the queries one by one: {}",
+                        self.grpc_url,
+                        e
+                    )
+                })
+                .ok(),
+            false => None,
+        };
+
+        let Some(client) = shared else {
+            // each query falls back to REST or ABCI queries on its own
+            return futures::stream::iter(queries)
+                .map(|(contract, msg)| async move {
+                    self.query_smart(contract.as_ref(), &msg).await
+                })
+                .buffered(concurrency)
+                .collect()
+                .await;
+        };
+
+        futures::stream::iter(queries)
+            .map(|(contract, msg)| {
+                let mut client = client.clone();
+                async move {
+                    let query_data = serde_json::to_vec(&msg)
+                        .map_err(|e| ClientError::serialization("serialize query message", e))?;
+                    tracing::debug!(
+                        "Querying {} with msg: {}",
+                        logging::text(contract.as_ref()),
+                        logging::text(&String::from_utf8_lossy(&query_data))
+                    );
+                    let data = client
+                        .smart_contract_state(QuerySmartContractStateRequest {
+                            address: contract.as_ref().to_string(),
+                            query_data,
+                        })
+                        .await
+                        .map_err(|e| ClientError::grpc("query contract state", e))?
+                        .into_inner()
+                        .data;
+
+                    serde_json::from_slice(&data)
+                        .map_err(|e| ClientError::serialization("deserialize query response", e))
+                }
+            })
+            .buffered(concurrency)
+            .collect()
+            .await
+    }
+
+    /// Returns the result of a query, run again on the archive node set by
+    /// `with_archive_grpc` if it failed with `ClientError::Pruned`. Fails with
+    /// `ClientError::Pruned` without an archive node.
+    async fn or_archive<T, F, Fut>(
+        &self,
+        result: Result<T, ClientError>,
+        query: F,
+    ) -> Result<T, ClientError>
+    where
+        F: FnOnce(String) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        match (result, &self.archive_grpc_url) {
+            (Err(ClientError::Pruned { height }), Some(url)) => {
+                tracing::debug!(
+                    "State at height {} is pruned, querying the archive node",
+                    height
+                );
+                query(url.clone()).await
+            }
+            (result, _) => result,
+        }
+    }
+
+    /// Returns the height of the latest block of the node
+    pub async fn query_latest_height(&self) -> Result<u64, ClientError> {
+        let mut client = TendermintServiceClient::connect(self.grpc_url.clone())
+            .await
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))?;
+
+        let height = client
+            .get_latest_block(GetLatestBlockRequest {})
+            .await
+            .map_err(|e| ClientError::grpc("query latest block", e))?
+            .into_inner()
+            .block
+            .and_then(|block| block.header)
+            .ok_or_else(|| ClientError::not_found("latest block header"))?
+            .height;
+
+        u64::try_from(height).map_err(|e| ClientError::invalid("latest block height", e))
+    }
+
+    /// Returns the raw value stored under `key` in the contract storage, if any
+    pub async fn query_raw(
+        &self,
+        contract: &str,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, ClientError> {
+        self.raw_query(contract, key, None).await
+    }
+
+    /// Returns the raw value stored under `key` in the contract storage at the given
+    /// block height, if any. Heights pruned by the node are queried on the archive
+    /// node set by `with_archive_grpc`.
+    pub async fn query_raw_at_height(
+        &self,
+        contract: &str,
+        key: &[u8],
+        height: u64,
+    ) -> Result<Option<Vec<u8>>, ClientError> {
+        self.raw_query(contract, key, Some(height)).await
+    }
+
+    async fn raw_query(
+        &self,
+        contract: &str,
+        key: &[u8],
+        height: Option<u64>,
+    ) -> Result<Option<Vec<u8>>, ClientError> {
+        let request = QueryRawContractStateRequest {
+            address: contract.to_string(),
+            query_data: key.to_vec(),
+        };
+
+        let resp = match raw_contract_state(&self.grpc_url, request.clone(), height).await {
+            Err(error @ ClientError::Connection { .. }) => {
+                self.or_abci_query(
+                    error,
+                    RAW_QUERY_PATH,
+                    &request,
+                    height,
+                    "query raw contract state",
+                )
+                .await
+            }
+            resp => resp,
+        };
+        let data = self
+            .or_archive(resp, |url| async move {
+                raw_contract_state(&url, request, height).await
+            })
+            .await?
+            .data;
+        Ok((!data.is_empty()).then_some(data))
+    }
+
+    /// Returns the sha256 checksum of the wasm bytecode stored under `code_id`,
+    /// checked against the bytecode returned by the node
+    pub async fn query_code_checksum(&self, code_id: u64) -> Result<Vec<u8>, ClientError> {
+        let mut client = WasmQueryClient::connect(self.grpc_url.clone())
+            .await
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))?;
 
         let resp = client
-            .contains_bytes(btclightclient::v1::QueryContainsBytesRequest { hash: hash_bytes })
+            .code(QueryCodeRequest { code_id })
             .await
-            .context("Failed to query header contains")?;
+            .map_err(|e| ClientError::grpc("query code", e))?
+            .into_inner();
+        let checksum = resp
+            .code_info
+            .ok_or_else(|| ClientError::not_found(format!("code info of code {}", code_id)))?
+            .data_hash;
 
-        Ok(resp.into_inner().contains)
+        if Sha256::digest(&resp.data).as_slice() != checksum.as_slice() {
+            return Err(ClientError::invalid(
+                format!("bytecode of code {}", code_id),
+                "sha256 doesn't match the code info checksum",
+            ));
+        }
+        Ok(checksum)
     }
 
     pub fn validate_bech32_address(
         address: &str,
         expected_prefix: Option<&str>,
-    ) -> anyhow::Result<()> {
-        let account_id = AccountId::from_str(address)
-            .map_err(|e| anyhow::anyhow!("Invalid bech32 address: {}", e))?;
+    ) -> Result<(), ClientError> {
+        let account_id =
+            AccountId::from_str(address).map_err(|e| ClientError::invalid("bech32 address", e))?;
 
         if let Some(prefix) = expected_prefix {
             if account_id.prefix() != prefix {
-                return Err(anyhow::anyhow!(
-                    "Address has wrong prefix: expected {}, got {}",
-                    prefix,
-                    account_id.prefix()
+                return Err(ClientError::invalid(
+                    "bech32 address",
+                    format!(
+                        "wrong prefix: expected {}, got {}",
+                        prefix,
+                        account_id.prefix()
+                    ),
                 ));
             }
         }
@@ -135,10 +787,336 @@ impl CosmWasmClient {
     }
 }
 
+/// Queries of the Babylon btclightclient module
+#[cfg(feature = "babylon")]
+impl CosmWasmClient {
+    async fn btc_light_client(
+        &self,
+    ) -> Result<btclightclient::v1::query_client::QueryClient<Channel>, ClientError> {
+        btclightclient::v1::query_client::QueryClient::connect(self.grpc_url.clone())
+            .await
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))
+    }
+
+    pub async fn query_header_contains(&self, block_hash: &str) -> Result<bool, ClientError> {
+        let mut hash_bytes =
+            hex::decode(block_hash).map_err(|e| ClientError::invalid("block hash hex", e))?;
+        hash_bytes.reverse();
+        let request = btclightclient::v1::QueryContainsBytesRequest { hash: hash_bytes };
+
+        let resp: btclightclient::v1::QueryContainsBytesResponse =
+            match self.btc_light_client().await {
+                Ok(mut client) => client
+                    .contains_bytes(request)
+                    .await
+                    .map_err(|e| ClientError::grpc("query header contains", e))?
+                    .into_inner(),
+                Err(error) => {
+                    self.or_abci_query(
+                        error,
+                        CONTAINS_BYTES_QUERY_PATH,
+                        &request,
+                        None,
+                        "query header contains",
+                    )
+                    .await?
+                }
+            };
+
+        Ok(resp.contains)
+    }
+
+    /// Returns the depth of the given block (hex hash, as shown by block explorers) in the
+    /// BTC light client main chain. The tip has depth 0, so a block has `depth + 1` confirmations.
+    /// Fails if the block is unknown or on a fork.
+    pub async fn query_header_depth(&self, block_hash: &str) -> Result<u32, ClientError> {
+        let mut client = self.btc_light_client().await?;
+
+        let resp = client
+            .header_depth(btclightclient::v1::QueryHeaderDepthRequest {
+                hash: block_hash.to_string(),
+            })
+            .await
+            .map_err(|e| ClientError::grpc("query header depth", e))?;
+
+        Ok(resp.into_inner().depth)
+    }
+
+    /// Returns true once the given block is at least `confirmation_depth` deep in the
+    /// BTC light client main chain
+    pub async fn is_header_confirmed(
+        &self,
+        block_hash: &str,
+        confirmation_depth: u32,
+    ) -> Result<bool, ClientError> {
+        Ok(self.query_header_depth(block_hash).await? >= confirmation_depth)
+    }
+
+    /// Returns the best header on the canonical chain of the BTC light client
+    pub async fn query_tip(&self) -> Result<BtcHeaderInfo, ClientError> {
+        let mut client = self.btc_light_client().await?;
+
+        let resp = client
+            .tip(btclightclient::v1::QueryTipRequest {})
+            .await
+            .map_err(|e| ClientError::grpc("query tip", e))?;
+
+        resp.into_inner()
+            .header
+            .ok_or_else(|| ClientError::not_found("tip header"))?
+            .try_into()
+    }
+
+    /// Returns the base header of the BTC light client, defined at genesis
+    pub async fn query_base_header(&self) -> Result<BtcHeaderInfo, ClientError> {
+        let mut client = self.btc_light_client().await?;
+
+        let resp = client
+            .base_header(btclightclient::v1::QueryBaseHeaderRequest {})
+            .await
+            .map_err(|e| ClientError::grpc("query base header", e))?;
+
+        resp.into_inner()
+            .header
+            .ok_or_else(|| ClientError::not_found("base header"))?
+            .try_into()
+    }
+
+    /// Returns the main chain headers with heights in `[from, to]`, in ascending height order.
+    ///
+    /// The btclightclient module can only page the main chain from the tip downwards or
+    /// from the base header upwards, so the range is walked from whichever end is closer.
+    pub async fn query_main_chain(
+        &self,
+        from: u32,
+        to: u32,
+    ) -> Result<Vec<BtcHeaderInfo>, ClientError> {
+        if from > to {
+            return Err(ClientError::invalid(
+                "header range",
+                format!("{} > {}", from, to),
+            ));
+        }
+
+        let base = self.query_base_header().await?;
+        let tip = self.query_tip().await?;
+        if from < base.height || to > tip.height {
+            return Err(ClientError::invalid(
+                "header range",
+                format!(
+                    "{}..={} is outside of the light client main chain {}..={}",
+                    from, to, base.height, tip.height
+                ),
+            ));
+        }
+
+        let mut client = self.btc_light_client().await?;
+        // reverse pagination walks from the base header towards the tip
+        let reverse = to - base.height <= tip.height - from;
+        let mut headers = Vec::with_capacity((to - from + 1) as usize);
+        let mut key = vec![];
+
+        loop {
+            let resp = client
+                .main_chain(btclightclient::v1::QueryMainChainRequest {
+                    pagination: Some(PageRequest {
+                        key,
+                        limit: MAIN_CHAIN_PAGE_LIMIT,
+                        reverse,
+                        ..Default::default()
+                    }),
+                })
+                .await
+                .map_err(|e| ClientError::grpc("query main chain", e))?
+                .into_inner();
+
+            let mut done = resp.headers.is_empty();
+            for header in resp.headers {
+                let header = BtcHeaderInfo::try_from(header)?;
+                if (reverse && header.height >= to) || (!reverse && header.height <= from) {
+                    done = true;
+                }
+                if (from..=to).contains(&header.height) {
+                    headers.push(header);
+                }
+            }
+
+            key = resp.pagination.map(|p| p.next_key).unwrap_or_default();
+            if done || key.is_empty() {
+                break;
+            }
+        }
+
+        if !reverse {
+            headers.reverse();
+        }
+
+        Ok(headers)
+    }
+
+    /// Returns the main chain header at the given BTC height
+    pub async fn query_header_by_height(&self, height: u32) -> Result<BtcHeaderInfo, ClientError> {
+        self.query_main_chain(height, height)
+            .await?
+            .pop()
+            .ok_or_else(|| {
+                ClientError::not_found(format!("main chain header at height {}", height))
+            })
+    }
+}
+
+#[async_trait]
+impl CosmWasmApi for CosmWasmClient {
+    fn sender(&self) -> String {
+        self.wallet
+            .as_ref()
+            .map(|wallet| wallet.account_id.to_string())
+            .unwrap_or_default()
+    }
+
+    #[cfg(feature = "wallet")]
+    async fn execute_json(
+        &self,
+        contract: &str,
+        msg: serde_json::Value,
+    ) -> Result<String, ClientError> {
+        self.execute_contract_at(contract, &msg).await
+    }
+
+    #[cfg(not(feature = "wallet"))]
+    async fn execute_json(&self, _: &str, _: serde_json::Value) -> Result<String, ClientError> {
+        Err(disabled_feature("execute contract", "wallet"))
+    }
+
+    async fn query_json(
+        &self,
+        contract: &str,
+        msg: serde_json::Value,
+    ) -> Result<serde_json::Value, ClientError> {
+        self.query_smart(contract, &msg).await
+    }
+
+    async fn query_raw(&self, contract: &str, key: &[u8]) -> Result<Option<Vec<u8>>, ClientError> {
+        CosmWasmClient::query_raw(self, contract, key).await
+    }
+
+    async fn get_tx(&self, hash: &str) -> Result<GetTxResponse, ClientError> {
+        CosmWasmClient::get_tx(self, hash).await
+    }
+
+    #[cfg(feature = "babylon")]
+    async fn query_tip(&self) -> Result<BtcHeaderInfo, ClientError> {
+        CosmWasmClient::query_tip(self).await
+    }
+
+    #[cfg(feature = "babylon")]
+    async fn query_header_depth(&self, block_hash: &str) -> Result<u32, ClientError> {
+        CosmWasmClient::query_header_depth(self, block_hash).await
+    }
+
+    #[cfg(not(feature = "babylon"))]
+    async fn query_tip(&self) -> Result<BtcHeaderInfo, ClientError> {
+        Err(disabled_feature("query tip", "babylon"))
+    }
+
+    #[cfg(not(feature = "babylon"))]
+    async fn query_header_depth(&self, _: &str) -> Result<u32, ClientError> {
+        Err(disabled_feature("query header depth", "babylon"))
+    }
+}
+
+/// Error of a `CosmWasmApi` method whose implementation is compiled out
+#[cfg(any(not(feature = "wallet"), not(feature = "babylon")))]
+fn disabled_feature(action: &str, feature: &str) -> ClientError {
+    ClientError::invalid(action, format!("the {} feature is disabled", feature))
+}
+
+async fn smart_contract_state(
+    grpc_url: &str,
+    request: QuerySmartContractStateRequest,
+    height: Option<u64>,
+) -> Result<QuerySmartContractStateResponse, ClientError> {
+    let mut client = WasmQueryClient::connect(grpc_url.to_string())
+        .await
+        .map_err(|e| ClientError::connection(grpc_url, e))?;
+
+    Ok(client
+        .smart_contract_state(at_height(request, height)?)
+        .await
+        .map_err(|e| ClientError::grpc("query contract state", e).classify_pruned(height))?
+        .into_inner())
+}
+
+async fn raw_contract_state(
+    grpc_url: &str,
+    request: QueryRawContractStateRequest,
+    height: Option<u64>,
+) -> Result<QueryRawContractStateResponse, ClientError> {
+    let mut client = WasmQueryClient::connect(grpc_url.to_string())
+        .await
+        .map_err(|e| ClientError::connection(grpc_url, e))?;
+
+    Ok(client
+        .raw_contract_state(at_height(request, height)?)
+        .await
+        .map_err(|e| ClientError::grpc("query raw contract state", e).classify_pruned(height))?
+        .into_inner())
+}
+
+/// Wraps the gRPC request, asking the node to answer it at `height` if set
+pub(crate) fn at_height<T>(
+    request: T,
+    height: Option<u64>,
+) -> Result<tonic::Request<T>, ClientError> {
+    let mut request = tonic::Request::new(request);
+    if let Some(height) = height {
+        let value = height
+            .to_string()
+            .parse()
+            .map_err(|e| ClientError::invalid("block height", e))?;
+        request.metadata_mut().insert(BLOCK_HEIGHT_HEADER, value);
+    }
+    Ok(request)
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
 
+    /// Address of the bridge contract used by the test clients
+    pub(crate) const TEST_CONTRACT: &str =
+        "bbn1qwlgtx52gsdu7dtp0cekka5zehdl0uj3fhp9acg325fvgs8jdzksawp5cw";
+
+    fn test_config() -> ChainConfig {
+        ChainConfig::new(
+            "bbn".to_string(),
+            "bbn-test-5".to_string(),
+            "ubbn".to_string(),
+            200_000,
+            "0.002ubbn".parse().unwrap(),
+        )
+    }
+
+    /// Returns a client of `TEST_CONTRACT` without a wallet
+    pub(crate) fn readonly_client(grpc_url: &str) -> CosmWasmClient {
+        CosmWasmClient::new_readonly(grpc_url, TEST_CONTRACT, test_config()).unwrap()
+    }
+
+    /// Returns a client of `TEST_CONTRACT` signing with the `01..01` private key
+    #[cfg(feature = "wallet")]
+    pub(crate) fn test_client(grpc_url: &str) -> CosmWasmClient {
+        CosmWasmClient::new(grpc_url, &"01".repeat(32), TEST_CONTRACT, test_config()).unwrap()
+    }
+
+    #[test]
+    fn test_at_height() {
+        let request = at_height((), Some(42)).unwrap();
+        assert_eq!(request.metadata().get(BLOCK_HEIGHT_HEADER).unwrap(), "42");
+
+        let request = at_height((), None).unwrap();
+        assert!(request.metadata().get(BLOCK_HEIGHT_HEADER).is_none());
+    }
+
     #[test]
     fn test_validate_bech32_address() {
         // Valid address tests - using real valid addresses
@@ -200,6 +1178,30 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "babylon")]
+    #[test]
+    fn test_btc_header_info_conversion() {
+        let response = btclightclient::v1::BtcHeaderInfoResponse {
+            header_hex: "00".repeat(80),
+            hash_hex: "000001156c33b485db8a5d1de27f9d73e0e397ee0654ded0208acd3b6a10989c"
+                .to_string(),
+            height: 198432,
+            work: "340282366920938463463374607431768211456".to_string(),
+        };
+
+        let header = BtcHeaderInfo::try_from(response.clone()).expect("valid header");
+        assert_eq!(header.height, 198432);
+        assert_eq!(header.hash, response.hash_hex);
+        // work may exceed u128
+        assert_eq!(header.work, Uint256::from(u128::MAX) + Uint256::one());
+
+        let invalid = btclightclient::v1::BtcHeaderInfoResponse {
+            work: "not-a-number".to_string(),
+            ..response
+        };
+        assert!(BtcHeaderInfo::try_from(invalid).is_err());
+    }
+
     #[test]
     fn test_wallet_creation_and_validation() {
         // Test wallet creation with private key
@@ -218,4 +1220,12 @@ mod tests {
         let expected_address = wallet.account_id.to_string();
         assert_eq!(address, expected_address);
     }
+
+    #[test]
+    fn test_readonly_client() {
+        let client = readonly_client("http://localhost:9090");
+
+        assert_eq!(client.sender(), "");
+        assert!(matches!(client.signer(), Err(ClientError::Wallet { .. })));
+    }
 }
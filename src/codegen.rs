@@ -0,0 +1,844 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::ClientError;
+
+/// cosmwasm-std types referenced by name in contract schemas
+const STD_TYPES: &[&str] = &[
+    "Addr",
+    "Binary",
+    "Coin",
+    "Decimal",
+    "Decimal256",
+    "Empty",
+    "HexBinary",
+    "Int64",
+    "Int128",
+    "Int256",
+    "Timestamp",
+    "Uint64",
+    "Uint128",
+    "Uint256",
+    "Uint512",
+];
+
+/// Rust keywords that can't be used as field or method names without `r#`
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where",
+    "while", "yield",
+];
+
+/// Path keywords that can't be raw identifiers either, suffixed with `_` instead
+const PATH_KEYWORDS: &[&str] = &["crate", "self", "super", "Self"];
+
+/// JSON schema of a contract as written by `cosmwasm_schema::write_api!`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ContractSchema {
+    /// contract_name is the crate name of the contract, e.g. `bridge`
+    pub contract_name: String,
+    #[serde(default)]
+    pub contract_version: String,
+    #[serde(default)]
+    pub instantiate: Option<Value>,
+    #[serde(default)]
+    pub execute: Option<Value>,
+    #[serde(default)]
+    pub query: Option<Value>,
+    #[serde(default)]
+    pub migrate: Option<Value>,
+    /// responses are the response schemas of the query messages, by query name
+    #[serde(default)]
+    pub responses: Option<BTreeMap<String, Value>>,
+}
+
+impl ContractSchema {
+    /// Loads the schema from the `<contract>.json` file, or from the `schema` directory
+    /// of a contract holding either that file or the `raw/*.json` files
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ClientError> {
+        let path = path.as_ref();
+        if !path.is_dir() {
+            return serde_json::from_slice(&read(path)?).map_err(|e| {
+                ClientError::serialization(format!("parse contract schema {}", path.display()), e)
+            });
+        }
+
+        let entries = std::fs::read_dir(path)
+            .map_err(|e| ClientError::invalid(format!("schema dir {}", path.display()), e))?;
+        for entry in entries.flatten() {
+            let file = entry.path();
+            if file.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let schema: Value = serde_json::from_slice(&read(&file)?).map_err(|e| {
+                ClientError::serialization(format!("parse contract schema {}", file.display()), e)
+            })?;
+            if schema.get("idl_version").is_some() {
+                return Self::load(file);
+            }
+        }
+
+        Self::load_raw(&path.join("raw"))
+    }
+
+    /// Loads the schema from the `instantiate.json`, `execute.json`, `query.json`,
+    /// `migrate.json` and `response_to_<query>.json` files of a `schema/raw` directory
+    fn load_raw(dir: &Path) -> Result<Self, ClientError> {
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| ClientError::invalid(format!("schema dir {}", dir.display()), e))?;
+        let contract_name = dir
+            .parent()
+            .and_then(|schema_dir| schema_dir.parent())
+            .and_then(|contract_dir| contract_dir.file_name())
+            .and_then(|name| name.to_str())
+            .unwrap_or("contract")
+            .to_string();
+        let mut schema = Self {
+            contract_name,
+            ..Self::default()
+        };
+
+        for entry in entries.flatten() {
+            let file = entry.path();
+            let Some(stem) = file.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let value = || -> Result<Value, ClientError> {
+                serde_json::from_slice(&read(&file)?).map_err(|e| {
+                    ClientError::serialization(
+                        format!("parse contract schema {}", file.display()),
+                        e,
+                    )
+                })
+            };
+            match stem {
+                "instantiate" => schema.instantiate = Some(value()?),
+                "execute" => schema.execute = Some(value()?),
+                "query" => schema.query = Some(value()?),
+                "migrate" => schema.migrate = Some(value()?),
+                _ => {
+                    if let Some(query) = stem.strip_prefix("response_to_") {
+                        schema
+                            .responses
+                            .get_or_insert_with(BTreeMap::new)
+                            .insert(query.to_string(), value()?);
+                    }
+                }
+            }
+        }
+
+        Ok(schema)
+    }
+
+    /// Returns the Rust source of the message types and of a `<Contract>Client`
+    /// with one method per execute and query message
+    pub fn generate(&self) -> Result<String, ClientError> {
+        let mut generator = Generator::default();
+        let roots = [
+            ("InstantiateMsg", &self.instantiate),
+            ("ExecuteMsg", &self.execute),
+            ("QueryMsg", &self.query),
+            ("MigrateMsg", &self.migrate),
+        ];
+        for (name, root) in roots {
+            if let Some(root) = root {
+                generator.add_root(name, root)?;
+            }
+        }
+
+        let mut responses = BTreeMap::new();
+        for (query, root) in self.responses.iter().flatten() {
+            let title = root
+                .get("title")
+                .and_then(Value::as_str)
+                .unwrap_or(query.as_str());
+            let response = if is_type_definition(root) {
+                let name = to_pascal(title);
+                generator.add_root(&name, root)?;
+                name
+            } else {
+                generator.add_definitions(root)?;
+                rust_type(root)?
+            };
+            responses.insert(query.clone(), response);
+        }
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "// @generated by cosmwasm-client-rs from the {} {} schema, do not edit",
+            self.contract_name, self.contract_version
+        );
+        let _ = writeln!(out);
+        let _ = writeln!(out, "use cosmwasm_schema::cw_serde;");
+        for code in generator.types.values() {
+            let _ = writeln!(out);
+            out.push_str(code);
+        }
+        let _ = writeln!(out);
+        out.push_str(&self.client(&responses));
+
+        Ok(out)
+    }
+
+    fn client(&self, responses: &BTreeMap<String, String>) -> String {
+        let name = format!(
+            "{}Client",
+            to_pascal(self.contract_name.rsplit(':').next().unwrap_or_default())
+        );
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "/// Typed client of the `{}` contract",
+            self.contract_name
+        );
+        let _ = writeln!(out, "pub struct {}<'a> {{", name);
+        let _ = writeln!(out, "    client: &'a ::cosmwasm_client_rs::CosmWasmClient,");
+        let _ = writeln!(out, "    contract: String,");
+        let _ = writeln!(out, "}}");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "impl<'a> {}<'a> {{", name);
+        let _ = writeln!(
+            out,
+            "    pub fn new(client: &'a ::cosmwasm_client_rs::CosmWasmClient, contract: impl Into<String>) -> Self {{"
+        );
+        let _ = writeln!(out, "        Self {{ client, contract: contract.into() }}");
+        let _ = writeln!(out, "    }}");
+        let _ = writeln!(out);
+        let _ = writeln!(out, "    pub fn contract(&self) -> &str {{");
+        let _ = writeln!(out, "        &self.contract");
+        let _ = writeln!(out, "    }}");
+
+        // the variants were validated when generating the message types
+        let variants = |root: &Option<Value>| {
+            root.as_ref()
+                .and_then(variants)
+                .and_then(Result::ok)
+                .unwrap_or_default()
+        };
+        for variant in variants(&self.execute) {
+            let method = match variant.method.as_str() {
+                "new" | "contract" => format!("execute_{}", variant.method),
+                _ => variant.method.clone(),
+            };
+            let _ = writeln!(out);
+            out.push_str(&variant.method_code(
+                &method,
+                "ExecuteMsg",
+                "String",
+                "execute_contract_at",
+            ));
+        }
+        for variant in variants(&self.query) {
+            let response = responses
+                .get(&variant.key)
+                .cloned()
+                .unwrap_or_else(|| "serde_json::Value".to_string());
+            let _ = writeln!(out);
+            out.push_str(&variant.method_code(
+                &format!("query_{}", variant.method.trim_start_matches("r#")),
+                "QueryMsg",
+                &response,
+                "query_smart",
+            ));
+        }
+        let _ = writeln!(out, "}}");
+
+        out
+    }
+}
+
+/// Reads the schema at `schema_path` and writes its bindings to `out_path`, e.g. from
+/// a `build.rs` with `out_path` in `OUT_DIR` and an `include!` of the file
+pub fn write_bindings(
+    schema_path: impl AsRef<Path>,
+    out_path: impl AsRef<Path>,
+) -> Result<(), ClientError> {
+    let out_path = out_path.as_ref();
+    let code = ContractSchema::load(schema_path)?.generate()?;
+    std::fs::write(out_path, code)
+        .map_err(|e| ClientError::invalid(format!("bindings path {}", out_path.display()), e))
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, ClientError> {
+    std::fs::read(path)
+        .map_err(|e| ClientError::invalid(format!("contract schema {}", path.display()), e))
+}
+
+/// Rust code of the types, by type name so the output is sorted and each
+/// definition shared by several messages is only emitted once
+#[derive(Default)]
+struct Generator {
+    types: BTreeMap<String, String>,
+}
+
+impl Generator {
+    fn add_root(&mut self, name: &str, root: &Value) -> Result<(), ClientError> {
+        self.add_definitions(root)?;
+        self.add_type(name, root)
+    }
+
+    fn add_definitions(&mut self, root: &Value) -> Result<(), ClientError> {
+        let Some(definitions) = root.get("definitions").and_then(Value::as_object) else {
+            return Ok(());
+        };
+        for (name, schema) in definitions {
+            if !STD_TYPES.contains(&name.as_str()) {
+                self.add_type(&to_pascal(name), schema)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn add_type(&mut self, name: &str, schema: &Value) -> Result<(), ClientError> {
+        if self.types.contains_key(name) {
+            return Ok(());
+        }
+
+        let mut code = String::new();
+        doc_comment(&mut code, "", schema);
+        if let Some(variants) = variants(schema) {
+            let _ = writeln!(code, "#[cw_serde]");
+            let _ = writeln!(code, "pub enum {} {{", name);
+            for variant in variants? {
+                code.push_str(&variant.code());
+            }
+            let _ = writeln!(code, "}}");
+        } else if is_empty_object(schema) {
+            let _ = writeln!(code, "#[cw_serde]");
+            let _ = writeln!(code, "pub struct {} {{}}", name);
+        } else if schema.get("properties").is_some() {
+            let _ = writeln!(code, "#[cw_serde]");
+            let _ = writeln!(code, "pub struct {} {{", name);
+            for field in fields(schema)? {
+                code.push_str(&field.code("    ", "pub "));
+            }
+            let _ = writeln!(code, "}}");
+        } else {
+            let _ = writeln!(code, "pub type {} = {};", name, rust_type(schema)?);
+        }
+
+        self.types.insert(name.to_string(), code);
+        Ok(())
+    }
+}
+
+/// Variant of a message enum
+struct Variant {
+    /// key is the snake_case name of the variant in the JSON message
+    key: String,
+    name: String,
+    method: String,
+    description: Option<String>,
+    kind: VariantKind,
+}
+
+enum VariantKind {
+    Unit,
+    Struct(Vec<Field>),
+    Tuple(String),
+}
+
+impl Variant {
+    fn code(&self) -> String {
+        let mut code = String::new();
+        if let Some(description) = &self.description {
+            doc_lines(&mut code, "    ", description);
+        }
+        if serde_snake_case(&self.name) != self.key {
+            let _ = writeln!(code, "    #[serde(rename = \"{}\")]", self.key);
+        }
+        match &self.kind {
+            VariantKind::Unit => {
+                let _ = writeln!(code, "    {},", self.name);
+            }
+            VariantKind::Struct(fields) if fields.is_empty() => {
+                let _ = writeln!(code, "    {} {{}},", self.name);
+            }
+            VariantKind::Struct(fields) => {
+                let _ = writeln!(code, "    {} {{", self.name);
+                for field in fields {
+                    code.push_str(&field.code("        ", ""));
+                }
+                let _ = writeln!(code, "    }},");
+            }
+            VariantKind::Tuple(ty) => {
+                let _ = writeln!(code, "    {}({}),", self.name, ty);
+            }
+        }
+        code
+    }
+
+    /// Returns a client method sending the variant through `CosmWasmClient::<call>`
+    fn method_code(&self, method: &str, msg: &str, output: &str, call: &str) -> String {
+        let (params, value) = match &self.kind {
+            VariantKind::Unit => (String::new(), format!("{}::{}", msg, self.name)),
+            VariantKind::Struct(fields) if fields.is_empty() => {
+                (String::new(), format!("{}::{} {{}}", msg, self.name))
+            }
+            VariantKind::Struct(fields) => {
+                let params: String = fields
+                    .iter()
+                    .map(|field| format!(", {}: {}", field.ident, field.ty))
+                    .collect();
+                let args: Vec<_> = fields.iter().map(|field| field.ident.as_str()).collect();
+                (
+                    params,
+                    format!("{}::{} {{ {} }}", msg, self.name, args.join(", ")),
+                )
+            }
+            VariantKind::Tuple(ty) => (
+                format!(", msg: {}", ty),
+                format!("{}::{}(msg)", msg, self.name),
+            ),
+        };
+
+        let mut code = String::new();
+        if let Some(description) = &self.description {
+            doc_lines(&mut code, "    ", description);
+        }
+        let _ = writeln!(
+            code,
+            "    pub async fn {}(&self{}) -> Result<{}, ::cosmwasm_client_rs::ClientError> {{",
+            method, params, output
+        );
+        let _ = writeln!(code, "        let msg = {};", value);
+        let _ = writeln!(
+            code,
+            "        self.client.{}(&self.contract, &msg).await",
+            call
+        );
+        let _ = writeln!(code, "    }}");
+        code
+    }
+}
+
+/// Field of a struct or of a struct variant
+struct Field {
+    /// key is the name of the field in the JSON message
+    key: String,
+    ident: String,
+    ty: String,
+    description: Option<String>,
+    /// skip_none marks a field the schema doesn't require but can't be null either
+    skip_none: bool,
+}
+
+impl Field {
+    fn code(&self, indent: &str, visibility: &str) -> String {
+        let mut code = String::new();
+        if let Some(description) = &self.description {
+            doc_lines(&mut code, indent, description);
+        }
+        if self.ident.trim_start_matches("r#") != self.key {
+            let _ = writeln!(code, "{}#[serde(rename = \"{}\")]", indent, self.key);
+        }
+        if self.skip_none {
+            let _ = writeln!(
+                code,
+                "{}#[serde(default, skip_serializing_if = \"Option::is_none\")]",
+                indent
+            );
+        }
+        let _ = writeln!(code, "{}{}{}: {},", indent, visibility, self.ident, self.ty);
+        code
+    }
+}
+
+/// Returns the variants of an enum schema, `None` if the schema isn't an enum
+fn variants(schema: &Value) -> Option<Result<Vec<Variant>, ClientError>> {
+    if let Some(values) = string_enum(schema) {
+        return Some(Ok(values
+            .iter()
+            .map(|value| unit_variant(value, None))
+            .collect()));
+    }
+
+    let one_of = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))?
+        .as_array()?;
+    // `Option<T>` fields are written as `anyOf: [T, null]`
+    if one_of
+        .iter()
+        .any(|variant| variant.get("type").and_then(Value::as_str) == Some("null"))
+    {
+        return None;
+    }
+    let mut variants = vec![];
+    for variant in one_of {
+        let description = description(variant);
+        if let Some(values) = string_enum(variant) {
+            variants.extend(
+                values
+                    .iter()
+                    .map(|value| unit_variant(value, description.clone())),
+            );
+            continue;
+        }
+
+        let properties = variant.get("properties").and_then(Value::as_object);
+        let Some((key, inner)) = properties
+            .filter(|properties| properties.len() == 1)
+            .and_then(|properties| properties.iter().next())
+        else {
+            return Some(Err(ClientError::invalid(
+                "contract schema",
+                format!("unsupported enum variant {}", variant),
+            )));
+        };
+
+        let kind = if inner.get("properties").is_some() || is_empty_object(inner) {
+            match fields(inner) {
+                Ok(fields) => VariantKind::Struct(fields),
+                Err(e) => return Some(Err(e)),
+            }
+        } else {
+            match rust_type(inner) {
+                Ok(ty) => VariantKind::Tuple(ty),
+                Err(e) => return Some(Err(e)),
+            }
+        };
+        variants.push(Variant {
+            key: key.clone(),
+            name: to_pascal(key),
+            method: ident(key),
+            description: description.or_else(|| self::description(inner)),
+            kind,
+        });
+    }
+    Some(Ok(variants))
+}
+
+fn unit_variant(key: &str, description: Option<String>) -> Variant {
+    Variant {
+        key: key.to_string(),
+        name: to_pascal(key),
+        method: ident(key),
+        description,
+        kind: VariantKind::Unit,
+    }
+}
+
+fn fields(schema: &Value) -> Result<Vec<Field>, ClientError> {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|required| required.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    let mut fields = vec![];
+    for (key, property) in schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+    {
+        let mut ty = rust_type(property)?;
+        let skip_none = !required.contains(&key.as_str()) && !ty.starts_with("Option<");
+        if skip_none {
+            ty = format!("Option<{}>", ty);
+        }
+        fields.push(Field {
+            key: key.clone(),
+            ident: ident(key),
+            ty,
+            description: description(property),
+            skip_none,
+        });
+    }
+    Ok(fields)
+}
+
+/// Returns the Rust type of a field or of an inline schema
+fn rust_type(schema: &Value) -> Result<String, ClientError> {
+    if schema.as_bool() == Some(true) || schema.as_object().is_some_and(|s| s.is_empty()) {
+        return Ok("serde_json::Value".to_string());
+    }
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let name = reference.trim_start_matches("#/definitions/");
+        return Ok(if STD_TYPES.contains(&name) {
+            format!("cosmwasm_std::{}", name)
+        } else {
+            to_pascal(name)
+        });
+    }
+    if let Some([inner]) = schema
+        .get("allOf")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+    {
+        return rust_type(inner);
+    }
+    if let Some([first, second]) = schema
+        .get("anyOf")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+    {
+        for (inner, other) in [(first, second), (second, first)] {
+            if other.get("type").and_then(Value::as_str) == Some("null") {
+                return Ok(format!("Option<{}>", rust_type(inner)?));
+            }
+        }
+    }
+
+    let ty = match schema.get("type") {
+        Some(Value::String(ty)) => ty.as_str(),
+        Some(Value::Array(types)) => {
+            let types: Vec<_> = types.iter().filter_map(Value::as_str).collect();
+            if let [ty, "null"] | ["null", ty] = types.as_slice() {
+                let mut inner = schema.clone();
+                inner["type"] = Value::String(ty.to_string());
+                return Ok(format!("Option<{}>", rust_type(&inner)?));
+            }
+            return Ok("serde_json::Value".to_string());
+        }
+        _ => return Ok("serde_json::Value".to_string()),
+    };
+
+    Ok(match ty {
+        "string" => "String".to_string(),
+        "boolean" => "bool".to_string(),
+        "number" => "f64".to_string(),
+        "null" => "()".to_string(),
+        "integer" => match schema.get("format").and_then(Value::as_str) {
+            Some(
+                format @ ("uint8" | "uint16" | "uint32" | "uint64" | "uint128" | "int8" | "int16"
+                | "int32" | "int64" | "int128"),
+            ) => format.replace("uint", "u").replace("int", "i"),
+            Some("uint") => "usize".to_string(),
+            _ => "i64".to_string(),
+        },
+        "array" => match schema.get("items") {
+            Some(Value::Array(items)) => {
+                let items = items.iter().map(rust_type).collect::<Result<Vec<_>, _>>()?;
+                format!("({})", items.join(", "))
+            }
+            Some(items) => format!("Vec<{}>", rust_type(items)?),
+            None => "Vec<serde_json::Value>".to_string(),
+        },
+        "object" => match schema.get("additionalProperties") {
+            Some(values) if values.is_object() && schema.get("properties").is_none() => {
+                format!("std::collections::BTreeMap<String, {}>", rust_type(values)?)
+            }
+            _ => "serde_json::Value".to_string(),
+        },
+        _ => {
+            return Err(ClientError::invalid(
+                "contract schema",
+                format!("unsupported type {}", ty),
+            ))
+        }
+    })
+}
+
+/// Returns true if the schema is emitted as a struct or an enum of its own
+fn is_type_definition(schema: &Value) -> bool {
+    schema.get("properties").is_some() || variants(schema).is_some()
+}
+
+fn is_empty_object(schema: &Value) -> bool {
+    schema.get("type").and_then(Value::as_str) == Some("object")
+        && schema.get("properties").is_none()
+        && schema
+            .get("additionalProperties")
+            .is_none_or(|a| a == false)
+}
+
+fn string_enum(schema: &Value) -> Option<Vec<&str>> {
+    if schema.get("type").and_then(Value::as_str) != Some("string") {
+        return None;
+    }
+    let values = schema.get("enum")?.as_array()?;
+    Some(values.iter().filter_map(Value::as_str).collect())
+}
+
+fn description(schema: &Value) -> Option<String> {
+    schema
+        .get("description")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+fn doc_comment(code: &mut String, indent: &str, schema: &Value) {
+    if let Some(description) = description(schema) {
+        doc_lines(code, indent, &description);
+    }
+}
+
+fn doc_lines(code: &mut String, indent: &str, description: &str) {
+    for line in description.lines().map(str::trim_end) {
+        if line.is_empty() {
+            let _ = writeln!(code, "{}///", indent);
+        } else {
+            let _ = writeln!(code, "{}/// {}", indent, line);
+        }
+    }
+}
+
+/// Converts e.g. `peg_in` or `Array_of_Operator` to `PegIn` and `ArrayOfOperator`
+fn to_pascal(name: &str) -> String {
+    let mut pascal: String = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect();
+    if PATH_KEYWORDS.contains(&pascal.as_str()) {
+        pascal.push('_');
+    }
+    pascal
+}
+
+/// Converts a JSON key to a snake_case Rust identifier, escaping keywords
+fn ident(key: &str) -> String {
+    let mut ident = String::new();
+    let mut prev_lower = false;
+    for c in key.chars() {
+        if c.is_ascii_uppercase() && prev_lower {
+            ident.push('_');
+        }
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        ident.push(if c.is_ascii_alphanumeric() {
+            c.to_ascii_lowercase()
+        } else {
+            '_'
+        });
+    }
+    if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    if KEYWORDS.contains(&ident.as_str()) {
+        ident.insert_str(0, "r#");
+    } else if PATH_KEYWORDS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+    ident
+}
+
+/// Converts a variant name the way `#[serde(rename_all = "snake_case")]` does
+fn serde_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.char_indices() {
+        if c.is_uppercase() && i > 0 {
+            snake.push('_');
+        }
+        snake.push(c.to_ascii_lowercase());
+    }
+    snake
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        let schema: ContractSchema = serde_json::from_value(serde_json::json!({
+            "contract_name": "crates.io:bridge",
+            "contract_version": "0.1.0",
+            "idl_version": "1.0.0",
+            "execute": {
+                "title": "ExecuteMsg",
+                "oneOf": [
+                    {
+                        "description": "Pause rejects all peg ins and peg outs",
+                        "type": "string",
+                        "enum": ["pause"]
+                    },
+                    {
+                        "type": "object",
+                        "required": ["peg_out"],
+                        "properties": {
+                            "peg_out": {
+                                "type": "object",
+                                "required": ["amount", "btc_address"],
+                                "properties": {
+                                    "amount": { "$ref": "#/definitions/Uint128" },
+                                    "btc_address": { "type": "string" },
+                                    "fee_rate": {
+                                        "type": ["integer", "null"],
+                                        "format": "uint32"
+                                    }
+                                }
+                            }
+                        },
+                        "additionalProperties": false
+                    }
+                ],
+                "definitions": {
+                    "Uint128": { "type": "string" }
+                }
+            },
+            "query": {
+                "title": "QueryMsg",
+                "oneOf": [
+                    {
+                        "type": "object",
+                        "required": ["operators"],
+                        "properties": {
+                            "operators": { "type": "object", "additionalProperties": false }
+                        }
+                    },
+                    {
+                        "type": "object",
+                        "required": ["self"],
+                        "properties": {
+                            "self": { "type": "object", "additionalProperties": false }
+                        }
+                    }
+                ]
+            },
+            "responses": {
+                "operators": {
+                    "title": "Array_of_Operator",
+                    "type": "array",
+                    "items": { "$ref": "#/definitions/Operator" },
+                    "definitions": {
+                        "Operator": {
+                            "type": "object",
+                            "required": ["type", "btc_pk", "crate"],
+                            "properties": {
+                                "type": { "type": "string" },
+                                "btc_pk": { "type": "string" },
+                                "crate": { "type": "string" }
+                            }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        let code = schema.generate().unwrap();
+        assert!(code.contains("pub enum ExecuteMsg {\n"));
+        assert!(code.contains("    /// Pause rejects all peg ins and peg outs\n    Pause,\n"));
+        assert!(code.contains("        amount: cosmwasm_std::Uint128,\n"));
+        assert!(code.contains("        fee_rate: Option<u32>,\n"));
+        assert!(code.contains("    pub r#type: String,\n"));
+        assert!(code.contains("    #[serde(rename = \"crate\")]\n    pub crate_: String,\n"));
+        assert!(code.contains("    #[serde(rename = \"self\")]\n    Self_ {"));
+        assert!(code.contains("pub struct BridgeClient<'a> {"));
+        assert!(code.contains(
+            "pub async fn peg_out(&self, amount: cosmwasm_std::Uint128, btc_address: String, fee_rate: Option<u32>)"
+        ));
+        assert!(code.contains(
+            "pub async fn query_operators(&self) -> Result<Vec<Operator>, ::cosmwasm_client_rs::ClientError>"
+        ));
+        assert!(code.contains("pub async fn query_self_(&self)"));
+        // fields keep the order of the schema
+        assert!(
+            code.find("pub r#type: String").unwrap() < code.find("pub btc_pk: String").unwrap()
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use cosmwasm_schema::cw_serde;
+
+use crate::client::CosmWasmClient;
+use crate::error::ClientError;
+
+/// Default page size of the CW721 `tokens` query
+const DEFAULT_TOKENS_LIMIT: u32 = 30;
+
+/// Subset of the cw721-base execute messages
+#[cw_serde]
+pub enum Cw721ExecuteMsg {
+    /// Mint a new NFT, can only be called by the contract minter
+    Mint {
+        /// token_id is the unique ID of the NFT
+        token_id: String,
+        /// owner is the owner of the newly minted NFT
+        owner: String,
+        /// token_uri is a URI pointing to the ERC721 metadata JSON schema
+        token_uri: Option<String>,
+        /// extension is any custom extension used by the contract
+        extension: Option<serde_json::Value>,
+    },
+    /// Transfer is a base message to move a token to another account without triggering actions
+    TransferNft { recipient: String, token_id: String },
+}
+
+/// Subset of the cw721-base query messages
+#[cw_serde]
+pub enum Cw721QueryMsg {
+    /// Return the owner of the given token
+    OwnerOf {
+        token_id: String,
+        /// include_expired also returns expired approvals
+        include_expired: Option<bool>,
+    },
+    /// Returns all tokens owned by the given address
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+/// Spender allowed to transfer a token
+#[cw_serde]
+pub struct Approval {
+    pub spender: String,
+    /// expires is the cw-utils `Expiration` of the approval
+    pub expires: serde_json::Value,
+}
+
+#[cw_serde]
+pub struct OwnerOfResponse {
+    /// owner is the owner of the token
+    pub owner: String,
+    /// approvals are the spenders allowed to transfer the token
+    pub approvals: Vec<Approval>,
+}
+
+#[cw_serde]
+pub struct TokensResponse {
+    /// tokens are the token IDs in ascending order
+    pub tokens: Vec<String>,
+}
+
+#[cfg(feature = "wallet")]
+impl CosmWasmClient {
+    /// Mints a new NFT on the given CW721 contract
+    pub async fn cw721_mint(
+        &self,
+        contract: &str,
+        token_id: &str,
+        owner: &str,
+        token_uri: Option<String>,
+    ) -> Result<String, ClientError> {
+        let msg = Cw721ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri,
+            extension: None,
+        };
+
+        self.execute_contract_at(contract, &msg).await
+    }
+
+    /// Transfers an NFT owned by the wallet to the recipient
+    pub async fn cw721_transfer_nft(
+        &self,
+        contract: &str,
+        recipient: &str,
+        token_id: &str,
+    ) -> Result<String, ClientError> {
+        let msg = Cw721ExecuteMsg::TransferNft {
+            recipient: recipient.to_string(),
+            token_id: token_id.to_string(),
+        };
+
+        self.execute_contract_at(contract, &msg).await
+    }
+}
+
+impl CosmWasmClient {
+    /// Returns the owner of the given NFT
+    pub async fn cw721_owner_of(
+        &self,
+        contract: &str,
+        token_id: &str,
+    ) -> Result<OwnerOfResponse, ClientError> {
+        let msg = Cw721QueryMsg::OwnerOf {
+            token_id: token_id.to_string(),
+            include_expired: None,
+        };
+
+        self.query_smart(contract, &msg).await
+    }
+
+    /// Returns one page of the token IDs owned by the given address
+    pub async fn cw721_tokens(
+        &self,
+        contract: &str,
+        owner: &str,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<TokensResponse, ClientError> {
+        let msg = Cw721QueryMsg::Tokens {
+            owner: owner.to_string(),
+            start_after,
+            limit,
+        };
+
+        self.query_smart(contract, &msg).await
+    }
+
+    /// Returns all token IDs owned by the given address, following pagination
+    pub async fn cw721_all_tokens(
+        &self,
+        contract: &str,
+        owner: &str,
+    ) -> Result<Vec<String>, ClientError> {
+        let mut tokens = vec![];
+        let mut start_after = None;
+
+        loop {
+            let page = self
+                .cw721_tokens(contract, owner, start_after, Some(DEFAULT_TOKENS_LIMIT))
+                .await?
+                .tokens;
+            let done = page.len() < DEFAULT_TOKENS_LIMIT as usize;
+            start_after = page.last().cloned();
+            tokens.extend(page);
+
+            if done || start_after.is_none() {
+                break;
+            }
+        }
+
+        Ok(tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cw721_msg_format() {
+        let msg = Cw721ExecuteMsg::TransferNft {
+            recipient: "bbn1recipient".to_string(),
+            token_id: "1".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_string(&msg).unwrap(),
+            r#"{"transfer_nft":{"recipient":"bbn1recipient","token_id":"1"}}"#
+        );
+
+        let resp: OwnerOfResponse = serde_json::from_str(
+            r#"{"owner":"bbn1owner","approvals":[{"spender":"bbn1spender","expires":{"never":{}}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(resp.owner, "bbn1owner");
+        assert_eq!(resp.approvals[0].spender, "bbn1spender");
+    }
+}
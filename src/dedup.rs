@@ -0,0 +1,252 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::events::{BlockEvents, ContractEvent, EventOrigin, TxEvent};
+use crate::transactions::TxHash;
+
+/// Number of recent blocks whose delivered events are remembered
+pub const DEFAULT_DEDUP_WINDOW: u64 = 1000;
+
+/// Identifies a delivered contract event
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EventKey {
+    pub tx_hash: TxHash,
+    pub msg_index: u32,
+    pub action: String,
+    /// origin keeps apart the same action emitted by a message and its replies
+    #[serde(default)]
+    pub origin: EventOrigin,
+}
+
+impl EventKey {
+    /// Returns the key of the event, `None` for raw events without a `msg_index`
+    pub fn from_event(event: &TxEvent) -> Option<Self> {
+        let (msg_index, action) = match &event.event {
+            ContractEvent::PegIn(peg_in) => (peg_in.msg_index, "peg_in".to_string()),
+            ContractEvent::PegOut(peg_out) => (peg_out.msg_index, "peg_out".to_string()),
+            ContractEvent::Cw20Transfer(transfer) => (transfer.msg_index, "transfer".to_string()),
+            ContractEvent::Cw20Mint(mint) => (mint.msg_index, "mint".to_string()),
+            ContractEvent::Cw20Burn(burn) => (burn.msg_index, "burn".to_string()),
+            ContractEvent::Instantiate(instantiate) => {
+                (instantiate.msg_index, "instantiate".to_string())
+            }
+            ContractEvent::Migrate(migrate) => (migrate.msg_index, "migrate".to_string()),
+            ContractEvent::UpdateAdmin(update) => (update.msg_index, "update_admin".to_string()),
+            ContractEvent::Raw(raw) => {
+                let attr = |key: &str| {
+                    raw.attributes
+                        .iter()
+                        .find(|(k, _)| k == key)
+                        .map(|(_, v)| v.as_str())
+                };
+                let msg_index = attr("msg_index")?.parse().ok()?;
+                // keep raw events apart from the parsed event of the same action
+                let action = format!("{}/{}", raw.kind, attr("action").unwrap_or_default());
+                (msg_index, action)
+            }
+        };
+
+        Some(Self {
+            tx_hash: event.tx_hash.clone(),
+            msg_index,
+            action,
+            origin: event.origin,
+        })
+    }
+}
+
+/// Persists the keys of the events an `EventListener` delivered in recent blocks,
+/// so a restarted listener doesn't deliver them again
+#[async_trait]
+pub trait DedupStore: Send + Sync {
+    /// Returns the delivered keys by block height, empty if nothing has been saved yet
+    async fn load(&self) -> anyhow::Result<BTreeMap<u64, Vec<EventKey>>>;
+
+    /// Replaces the stored keys
+    async fn save(&self, delivered: &BTreeMap<u64, Vec<EventKey>>) -> anyhow::Result<()>;
+}
+
+/// Stores the delivered keys as JSON in a file
+#[derive(Debug, Clone)]
+pub struct FileDedupStore {
+    path: PathBuf,
+}
+
+impl FileDedupStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl DedupStore for FileDedupStore {
+    async fn load(&self) -> anyhow::Result<BTreeMap<u64, Vec<EventKey>>> {
+        let content = match tokio::fs::read(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to read dedup file {}", self.path.display()))
+            }
+        };
+
+        serde_json::from_slice(&content)
+            .with_context(|| format!("Invalid dedup file {}", self.path.display()))
+    }
+
+    async fn save(&self, delivered: &BTreeMap<u64, Vec<EventKey>>) -> anyhow::Result<()> {
+        let content = serde_json::to_vec(delivered).context("Failed to serialize dedup keys")?;
+
+        // same as the checkpoint file, never leave a truncated file behind
+        let tmp_path = self.path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, content)
+            .await
+            .with_context(|| format!("Failed to write dedup file {}", tmp_path.display()))?;
+        tokio::fs::rename(&tmp_path, &self.path)
+            .await
+            .with_context(|| format!("Failed to write dedup file {}", self.path.display()))
+    }
+}
+
+/// Events delivered in the last `window` blocks, backed by a `DedupStore`
+pub(crate) struct DeliveredEvents {
+    store: Box<dyn DedupStore>,
+    window: u64,
+    delivered: BTreeMap<u64, Vec<EventKey>>,
+    keys: HashSet<EventKey>,
+}
+
+impl DeliveredEvents {
+    pub(crate) fn new(store: Box<dyn DedupStore>, window: u64) -> Self {
+        Self {
+            store,
+            window: window.max(1),
+            delivered: BTreeMap::new(),
+            keys: HashSet::new(),
+        }
+    }
+
+    pub(crate) async fn load(&mut self) -> anyhow::Result<()> {
+        self.delivered = self.store.load().await?;
+        self.keys = self.delivered.values().flatten().cloned().collect();
+        Ok(())
+    }
+
+    /// Removes the already delivered events and returns the keys of the remaining ones
+    pub(crate) fn filter(&self, block_events: &mut BlockEvents) -> Vec<EventKey> {
+        let mut new_keys = Vec::new();
+        block_events
+            .events
+            .retain(|event| match EventKey::from_event(event) {
+                Some(key) if self.keys.contains(&key) => {
+                    tracing::info!(
+                        "Skipping already delivered {} event of tx {}",
+                        key.action,
+                        key.tx_hash
+                    );
+                    false
+                }
+                Some(key) => {
+                    new_keys.push(key);
+                    true
+                }
+                None => true,
+            });
+        new_keys
+    }
+
+    /// Records the keys delivered at `height` and forgets blocks outside the window
+    pub(crate) async fn record(&mut self, height: u64, keys: Vec<EventKey>) -> anyhow::Result<()> {
+        let pruned = self
+            .delivered
+            .split_off(&height.saturating_sub(self.window - 1));
+        let expired = std::mem::replace(&mut self.delivered, pruned);
+        if keys.is_empty() && expired.is_empty() {
+            return Ok(());
+        }
+
+        for key in expired.into_values().flatten() {
+            self.keys.remove(&key);
+        }
+        self.keys.extend(keys.iter().cloned());
+        if !keys.is_empty() {
+            self.delivered.entry(height).or_default().extend(keys);
+        }
+        self.store.save(&self.delivered).await
+    }
+
+    /// Forgets the events delivered from `height` onwards, e.g. after a reorg
+    pub(crate) async fn rollback(&mut self, height: u64) -> anyhow::Result<()> {
+        let rolled_back = self.delivered.split_off(&height);
+        if rolled_back.is_empty() {
+            return Ok(());
+        }
+
+        for key in rolled_back.into_values().flatten() {
+            self.keys.remove(&key);
+        }
+        self.store.save(&self.delivered).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::PegInEvent;
+
+    fn peg_in(tx: &str) -> TxEvent {
+        TxEvent {
+            tx_hash: TxHash::from_tx_bytes(tx.as_bytes()),
+            tx_index: 0,
+            code: 0,
+            gas_used: 0,
+            contract: String::new(),
+            msg_index: Some(0),
+            origin: Default::default(),
+            event: ContractEvent::PegIn(PegInEvent::default()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delivered_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dedup.json");
+
+        let mut delivered = DeliveredEvents::new(Box::new(FileDedupStore::new(&path)), 10);
+        delivered.load().await.unwrap();
+        let mut block_events = BlockEvents {
+            height: 1,
+            events: vec![peg_in("a"), peg_in("b")],
+            ..Default::default()
+        };
+        let keys = delivered.filter(&mut block_events);
+        assert_eq!(keys.len(), 2);
+        delivered.record(1, keys).await.unwrap();
+
+        // a restarted listener skips the events it already delivered
+        let mut delivered = DeliveredEvents::new(Box::new(FileDedupStore::new(&path)), 10);
+        delivered.load().await.unwrap();
+        let mut block_events = BlockEvents {
+            height: 1,
+            events: vec![peg_in("a"), peg_in("c")],
+            ..Default::default()
+        };
+        let keys = delivered.filter(&mut block_events);
+        assert_eq!(block_events.events.len(), 1);
+        assert_eq!(block_events.events[0].tx_hash, TxHash::from_tx_bytes(b"c"));
+        delivered.record(1, keys).await.unwrap();
+
+        // blocks outside the window are forgotten
+        delivered.record(20, vec![]).await.unwrap();
+        let mut block_events = BlockEvents {
+            height: 21,
+            events: vec![peg_in("a")],
+            ..Default::default()
+        };
+        assert_eq!(delivered.filter(&mut block_events).len(), 1);
+    }
+}
@@ -0,0 +1,178 @@
+use cosmos_sdk_proto::cosmos::base::v1beta1::DecCoin;
+use cosmos_sdk_proto::cosmos::distribution::v1beta1::{
+    query_client::QueryClient as DistributionQueryClient, QueryDelegationTotalRewardsRequest,
+    QueryValidatorCommissionRequest,
+};
+#[cfg(feature = "wallet")]
+use cosmrs::distribution::{MsgWithdrawDelegatorReward, MsgWithdrawValidatorCommission};
+#[cfg(feature = "wallet")]
+use cosmrs::tx::Msg;
+#[cfg(feature = "wallet")]
+use cosmrs::AccountId;
+#[cfg(feature = "wallet")]
+use std::str::FromStr;
+
+use crate::client::CosmWasmClient;
+use crate::error::ClientError;
+
+/// Decimal places of the `Dec` amounts returned by the distribution module
+const DEC_PRECISION: usize = 18;
+
+/// Rewards of a delegator accumulated with a validator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rewards {
+    pub validator: String,
+    /// amount is the withdrawable amount by denom, fractions of a unit are
+    /// left out as they stay with the distribution module on withdrawal
+    pub amount: Vec<(String, u128)>,
+}
+
+/// Converts `DecCoin`s to whole units by denom, dropping the coins below one unit.
+/// Over gRPC `Dec` amounts are integers scaled by 10^18, the decimal form is
+/// accepted as well.
+fn whole_coins(coins: Vec<DecCoin>) -> Result<Vec<(String, u128)>, ClientError> {
+    let mut whole = vec![];
+    for coin in coins {
+        let units = match coin.amount.split_once('.') {
+            Some((units, _)) => units,
+            None => &coin.amount[..coin.amount.len().saturating_sub(DEC_PRECISION)],
+        };
+        if units.is_empty() {
+            continue;
+        }
+        let amount = units
+            .parse::<u128>()
+            .map_err(|e| ClientError::invalid("reward amount", e))?;
+        if amount > 0 {
+            whole.push((coin.denom, amount));
+        }
+    }
+    Ok(whole)
+}
+
+impl CosmWasmClient {
+    async fn distribution_query_client(
+        &self,
+    ) -> Result<DistributionQueryClient<tonic::transport::Channel>, ClientError> {
+        DistributionQueryClient::connect(self.grpc_url.clone())
+            .await
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))
+    }
+
+    /// Returns the rewards of the delegator by validator, leaving out the
+    /// validators with nothing to withdraw
+    pub async fn query_delegation_rewards(
+        &self,
+        delegator: &str,
+    ) -> Result<Vec<Rewards>, ClientError> {
+        let resp = self
+            .distribution_query_client()
+            .await?
+            .delegation_total_rewards(QueryDelegationTotalRewardsRequest {
+                delegator_address: delegator.to_string(),
+            })
+            .await
+            .map_err(|e| ClientError::grpc("query delegation rewards", e))?
+            .into_inner();
+
+        let mut rewards = vec![];
+        for reward in resp.rewards {
+            let amount = whole_coins(reward.reward)?;
+            if !amount.is_empty() {
+                rewards.push(Rewards {
+                    validator: reward.validator_address,
+                    amount,
+                });
+            }
+        }
+        Ok(rewards)
+    }
+
+    /// Returns the withdrawable commission of the validator by denom, see
+    /// `validator_address` for the operator address of an account
+    pub async fn query_commission(
+        &self,
+        validator: &str,
+    ) -> Result<Vec<(String, u128)>, ClientError> {
+        let resp = self
+            .distribution_query_client()
+            .await?
+            .validator_commission(QueryValidatorCommissionRequest {
+                validator_address: validator.to_string(),
+            })
+            .await
+            .map_err(|e| ClientError::grpc("query validator commission", e))?
+            .into_inner();
+
+        whole_coins(resp.commission.map(|c| c.commission).unwrap_or_default())
+    }
+}
+
+#[cfg(feature = "wallet")]
+impl CosmWasmClient {
+    /// Withdraws the rewards of the wallet from every validator it delegates to, in
+    /// a single transaction. They are paid to the withdraw address of the wallet,
+    /// the wallet itself unless changed.
+    pub async fn withdraw_rewards(&self) -> Result<String, ClientError> {
+        let delegator = self.signer()?.account_id.clone();
+        let rewards = self.query_delegation_rewards(delegator.as_ref()).await?;
+        if rewards.is_empty() {
+            return Err(ClientError::not_found("delegation rewards"));
+        }
+
+        let msgs = rewards
+            .iter()
+            .map(|reward| {
+                MsgWithdrawDelegatorReward {
+                    delegator_address: delegator.clone(),
+                    validator_address: AccountId::from_str(&reward.validator)
+                        .map_err(|e| ClientError::invalid("validator address", e))?,
+                }
+                .to_any()
+                .map_err(|e| ClientError::serialization("convert message to Any", e))
+            })
+            .collect::<Result<Vec<_>, ClientError>>()?;
+        tracing::info!("Withdrawing rewards from {} validators", msgs.len());
+
+        self.build_and_broadcast_msgs(msgs).await
+    }
+
+    /// Withdraws the commission of the validator operated by the wallet
+    pub async fn withdraw_commission(&self) -> Result<String, ClientError> {
+        let validator = CosmWasmClient::validator_address(self.signer()?.account_id.as_ref())?;
+        let msg = MsgWithdrawValidatorCommission {
+            validator_address: AccountId::from_str(&validator)
+                .map_err(|e| ClientError::invalid("validator address", e))?,
+        }
+        .to_any()
+        .map_err(|e| ClientError::serialization("convert message to Any", e))?;
+        tracing::info!("Withdrawing commission of {}", validator);
+
+        self.build_and_broadcast_msgs(vec![msg]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whole_coins() {
+        let coin = |denom: &str, amount: &str| DecCoin {
+            denom: denom.to_string(),
+            amount: amount.to_string(),
+        };
+        let coins = whole_coins(vec![
+            coin("ubbn", "1500000000000000000000"),
+            coin("uatom", "999999999999999999"),
+            coin("ufoo", "42.75"),
+            coin("ubar", "0.5"),
+        ])
+        .unwrap();
+        assert_eq!(
+            coins,
+            vec![("ubbn".to_string(), 1500), ("ufoo".to_string(), 42)]
+        );
+        assert!(whole_coins(vec![coin("ubbn", "abc000000000000000000")]).is_err());
+    }
+}
@@ -0,0 +1,578 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use cosmos_sdk_proto::cosmos::base::abci::v1beta1::TxResponse;
+use serde::{Deserialize, Serialize};
+
+use crate::transactions::TxHash;
+
+/// Boxed source of an error, downcast it to inspect the underlying error
+pub type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Errors returned by the client and the event listener, grouped by failure
+/// category so callers can match on them. The underlying gRPC, RPC,
+/// serialization, wallet, store and sink errors are kept as `source()`.
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    /// The peg in transaction has already been minted by the bridge contract
+    #[error("Peg in tx {txid} has already been processed")]
+    AlreadyPegged { txid: String },
+    /// The Bitcoin address is malformed or belongs to another network
+    #[error("Invalid BTC address {address}: {reason}")]
+    InvalidBtcAddress { address: String, reason: String },
+    /// The node pruned the block or the state at that height, it's only available
+    /// from an archive node
+    #[error("Height {height} has been pruned by the node")]
+    Pruned { height: u64 },
+    /// The `ChainConfig` doesn't match the chain the node belongs to
+    #[error("Chain config {field} is {expected} but the node reports {actual}")]
+    ChainMismatch {
+        field: String,
+        expected: String,
+        actual: String,
+    },
+    /// The gRPC, RPC or WebSocket endpoint is unreachable
+    #[error("Failed to connect to {endpoint}: {source}")]
+    Connection {
+        endpoint: String,
+        /// source is a `tonic::transport::Error` or a `tendermint_rpc::Error`
+        #[source]
+        source: BoxError,
+    },
+    /// A gRPC request to the node failed
+    #[error("Failed to {action}: {status}")]
+    Grpc {
+        action: String,
+        #[source]
+        status: Box<tonic::Status>,
+    },
+    /// A Tendermint RPC request to the node failed
+    #[cfg(feature = "events")]
+    #[error("Failed to {action}: {source}")]
+    Rpc {
+        action: String,
+        #[source]
+        source: Box<tendermint_rpc::Error>,
+    },
+    /// Encoding or decoding a message, a response or a transaction failed
+    #[error("Failed to {action}: {source}")]
+    Serialization {
+        action: String,
+        /// source is e.g. a `serde_json::Error` or a `prost::DecodeError`
+        #[source]
+        source: BoxError,
+    },
+    /// The node has no data for the request, e.g. an unknown epoch
+    #[error("No {what} found")]
+    NotFound { what: String },
+    /// An argument or a configuration value is invalid
+    #[error("Invalid {what}: {reason}")]
+    Invalid { what: String, reason: String },
+    /// Loading the signing key or signing a transaction failed
+    #[error("Failed to {action}: {source}")]
+    Wallet {
+        action: String,
+        /// source is e.g. a `cosmrs::ErrorReport` or a `hex::FromHexError`
+        #[source]
+        source: BoxError,
+    },
+    /// The node rejected the transaction or its execution failed
+    #[error("{0}")]
+    TxFailed(Box<TransactionError>),
+    /// The signed transaction couldn't be broadcast, e.g. the connection dropped
+    /// mid-request. The node may still have received it: check `tx_hash` with
+    /// `get_tx` before submitting it again.
+    #[error("Failed to broadcast transaction {tx_hash}: {source}")]
+    BroadcastFailed {
+        tx_hash: TxHash,
+        #[source]
+        source: Box<ClientError>,
+    },
+    /// The operation didn't complete in time, e.g. waiting for a tx confirmation
+    #[error("{what} timed out after {timeout:?}")]
+    Timeout { what: String, timeout: Duration },
+    /// A background task of the client stopped, e.g. the transaction submitter
+    #[error("{what} stopped")]
+    Stopped { what: String },
+    /// A checkpoint, event or spill store of the listener failed
+    #[error("Failed to {action}: {}", error_chain(.source))]
+    Store {
+        action: String,
+        /// source is the `anyhow::Error` returned by the store
+        #[source]
+        source: BoxError,
+    },
+    /// The event sink or a channel of the listener is closed or failed
+    #[error("Failed to {action}: {}", error_chain(.source))]
+    Sink {
+        action: String,
+        /// source is the `anyhow::Error` returned by the sink or a channel send error
+        #[source]
+        source: BoxError,
+    },
+    /// Reading or writing a local file failed, e.g. a chain config file or an export
+    #[error("Failed to {action}: {source}")]
+    Io {
+        action: String,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// Joins the messages of the error and its sources, like `{:#}` of an `anyhow::Error`
+fn error_chain(e: &BoxError) -> String {
+    let mut chain = e.to_string();
+    let mut source = e.source();
+    while let Some(e) = source {
+        chain.push_str(": ");
+        chain.push_str(&e.to_string());
+        source = e.source();
+    }
+    chain
+}
+
+/// Reason of a failed transaction, decoded from its ABCI codespace and code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TxErrorKind {
+    /// The tx bytes couldn't be decoded
+    TxDecode,
+    /// The account sequence doesn't match the signed one
+    WrongSequence,
+    /// The signer isn't allowed to perform the action
+    Unauthorized,
+    /// The account can't pay the fee or the transferred funds
+    InsufficientFunds,
+    /// The fee is below the node's minimum gas price
+    InsufficientFee,
+    /// The gas limit is too low for the execution
+    OutOfGas,
+    /// An address in the messages is malformed
+    InvalidAddress,
+    /// A coin amount or denom in the messages is invalid
+    InvalidCoins,
+    /// The messages are malformed or unsupported
+    InvalidRequest,
+    /// The transaction was signed for another chain
+    InvalidChainId,
+    /// Identical tx bytes are already in the mempool
+    TxInMempoolCache,
+    /// The mempool doesn't accept more transactions
+    MempoolFull,
+    /// The transaction exceeds the maximum tx size
+    TxTooLarge,
+    /// The transaction wasn't included before its timeout
+    TxTimeout,
+    /// The contract doesn't exist
+    ContractNotFound,
+    /// The contract returned an error, e.g. rejected a peg in proof. The error
+    /// of the contract is returned by `ClientError::contract_error`
+    ContractFailed,
+    /// Any other codespace or code
+    Other,
+}
+
+impl TxErrorKind {
+    /// Codespace of the Cosmos SDK errors
+    pub const SDK_CODESPACE: &'static str = "sdk";
+    /// Codespace of the wasmd errors
+    pub const WASM_CODESPACE: &'static str = "wasm";
+
+    pub fn from_abci(codespace: &str, code: u32) -> Self {
+        match (codespace, code) {
+            (Self::SDK_CODESPACE, 2) => Self::TxDecode,
+            (Self::SDK_CODESPACE, 3 | 32) => Self::WrongSequence,
+            (Self::SDK_CODESPACE, 4) => Self::Unauthorized,
+            (Self::SDK_CODESPACE, 5) => Self::InsufficientFunds,
+            (Self::SDK_CODESPACE, 7) => Self::InvalidAddress,
+            (Self::SDK_CODESPACE, 10) => Self::InvalidCoins,
+            (Self::SDK_CODESPACE, 11) | (Self::WASM_CODESPACE, 6) => Self::OutOfGas,
+            (Self::SDK_CODESPACE, 13) => Self::InsufficientFee,
+            (Self::SDK_CODESPACE, 18) | (Self::WASM_CODESPACE, 10 | 14) => Self::InvalidRequest,
+            (Self::SDK_CODESPACE, 19) => Self::TxInMempoolCache,
+            (Self::SDK_CODESPACE, 20) => Self::MempoolFull,
+            (Self::SDK_CODESPACE, 21) => Self::TxTooLarge,
+            (Self::SDK_CODESPACE, 28) => Self::InvalidChainId,
+            (Self::SDK_CODESPACE, 30 | 42) => Self::TxTimeout,
+            (Self::WASM_CODESPACE, 8) => Self::ContractNotFound,
+            (Self::WASM_CODESPACE, 4 | 5 | 11) => Self::ContractFailed,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Failed transaction with what the node reported about it, so it can be
+/// investigated without querying the node again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionError {
+    /// txhash is the hash of the transaction, if known
+    pub txhash: Option<String>,
+    pub kind: TxErrorKind,
+    pub codespace: String,
+    pub code: u32,
+    pub raw_log: String,
+    /// height is the block including the transaction, 0 if CheckTx rejected it
+    pub height: i64,
+    pub gas_wanted: i64,
+    pub gas_used: i64,
+    /// events are the events emitted before the failure, e.g. the fee deduction
+    pub events: Vec<RawEvent>,
+}
+
+/// Unparsed wasm event of a watched contract
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
+pub struct RawEvent {
+    /// kind is the event type, e.g. `wasm` or `wasm-<custom>`
+    pub kind: String,
+    pub attributes: Vec<(String, String)>,
+}
+
+impl TransactionError {
+    /// Sets the hash of the transaction if the node didn't report it
+    #[cfg_attr(not(feature = "wallet"), allow(dead_code))]
+    pub(crate) fn or_tx_hash(mut self, tx_hash: &TxHash) -> Self {
+        if self.txhash.is_none() {
+            self.txhash = Some(tx_hash.to_string());
+        }
+        self
+    }
+
+    /// Returns the error message of the contract if it rejected the transaction,
+    /// e.g. `Invalid merkle proof` out of the wasmd log
+    /// `failed to execute message; message index: 0: Invalid merkle proof: execute wasm contract failed`
+    pub fn contract_error(&self) -> Option<&str> {
+        if self.kind != TxErrorKind::ContractFailed {
+            return None;
+        }
+
+        let message = match self.raw_log.find("message index: ") {
+            Some(start) => {
+                let rest = &self.raw_log[start + "message index: ".len()..];
+                rest.split_once(": ").map_or(rest, |(_, message)| message)
+            }
+            None => self.raw_log.as_str(),
+        };
+        Some(
+            [
+                ": execute wasm contract failed",
+                ": instantiate wasm contract failed",
+                ": migrate wasm contract failed",
+            ]
+            .iter()
+            .find_map(|suffix| message.strip_suffix(suffix))
+            .unwrap_or(message),
+        )
+    }
+}
+
+impl From<TxResponse> for TransactionError {
+    fn from(tx_response: TxResponse) -> Self {
+        Self {
+            txhash: (!tx_response.txhash.is_empty()).then_some(tx_response.txhash),
+            kind: TxErrorKind::from_abci(&tx_response.codespace, tx_response.code),
+            codespace: tx_response.codespace,
+            code: tx_response.code,
+            raw_log: tx_response.raw_log,
+            height: tx_response.height,
+            gas_wanted: tx_response.gas_wanted,
+            gas_used: tx_response.gas_used,
+            events: tx_response
+                .events
+                .into_iter()
+                .map(|event| RawEvent {
+                    kind: event.r#type,
+                    attributes: event
+                        .attributes
+                        .into_iter()
+                        .map(|attr| (attr.key, attr.value))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Transaction {} failed with {} code {} (gas used {} of {}): {}",
+            self.txhash.as_deref().unwrap_or("<unknown>"),
+            self.codespace,
+            self.code,
+            self.gas_used,
+            self.gas_wanted,
+            self.raw_log
+        )
+    }
+}
+
+impl ClientError {
+    /// Returns the reason of a failed transaction
+    pub fn tx_error_kind(&self) -> Option<TxErrorKind> {
+        match self {
+            Self::TxFailed(tx_error) => Some(tx_error.kind),
+            _ => None,
+        }
+    }
+
+    /// Returns the error message of the contract if it rejected the transaction,
+    /// see `TransactionError::contract_error`
+    pub fn contract_error(&self) -> Option<&str> {
+        match self {
+            Self::TxFailed(tx_error) => tx_error.contract_error(),
+            _ => None,
+        }
+    }
+
+    /// Returns the hash of the transaction that failed or may not have been
+    /// broadcast, if known
+    pub fn tx_hash(&self) -> Option<&str> {
+        match self {
+            Self::TxFailed(tx_error) => tx_error.txhash.as_deref(),
+            Self::BroadcastFailed { tx_hash, .. } => Some(tx_hash.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Returns the gRPC status of a failed gRPC request, e.g. to check its code,
+    /// including the broadcast request of `BroadcastFailed`
+    pub fn grpc_status(&self) -> Option<&tonic::Status> {
+        match self {
+            Self::Grpc { status, .. } => Some(status.as_ref()),
+            Self::BroadcastFailed { source, .. } => source.grpc_status(),
+            _ => None,
+        }
+    }
+
+    /// Returns the error of a failed Tendermint RPC request
+    #[cfg(feature = "events")]
+    pub fn rpc_error(&self) -> Option<&tendermint_rpc::Error> {
+        match self {
+            Self::Rpc { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn connection(endpoint: &str, e: impl Into<BoxError>) -> Self {
+        Self::Connection {
+            endpoint: endpoint.to_string(),
+            source: e.into(),
+        }
+    }
+
+    pub(crate) fn grpc(action: impl Into<String>, status: tonic::Status) -> Self {
+        Self::Grpc {
+            action: action.into(),
+            status: Box::new(status),
+        }
+    }
+
+    #[cfg(feature = "events")]
+    pub(crate) fn rpc(action: impl Into<String>, e: tendermint_rpc::Error) -> Self {
+        Self::Rpc {
+            action: action.into(),
+            source: Box::new(e),
+        }
+    }
+
+    /// Returns `Pruned` if the node answered a query at `height` saying it pruned the
+    /// state of that height, the error unchanged otherwise. Every query transport
+    /// classifies its errors with it, so callers only match the variant.
+    pub(crate) fn classify_pruned(self, height: Option<u64>) -> Self {
+        // Cosmos SDK: "failed to load state at height 5; version does not exist (latest
+        // height: 100)"
+        let pruned = |message: &str| {
+            message.contains("failed to load state at height")
+                || message.contains("version does not exist")
+        };
+        match (height, &self) {
+            (Some(height), Self::Grpc { status, .. }) if pruned(status.message()) => {
+                Self::Pruned { height }
+            }
+            #[cfg(feature = "events")]
+            (Some(height), Self::Rpc { source, .. }) if pruned(&source.to_string()) => {
+                Self::Pruned { height }
+            }
+            _ => self,
+        }
+    }
+
+    pub(crate) fn serialization(action: impl Into<String>, e: impl Into<BoxError>) -> Self {
+        Self::Serialization {
+            action: action.into(),
+            source: e.into(),
+        }
+    }
+
+    pub(crate) fn not_found(what: impl Into<String>) -> Self {
+        Self::NotFound { what: what.into() }
+    }
+
+    pub(crate) fn invalid(what: impl Into<String>, reason: impl Display) -> Self {
+        Self::Invalid {
+            what: what.into(),
+            reason: reason.to_string(),
+        }
+    }
+
+    #[cfg_attr(not(feature = "wallet"), allow(dead_code))]
+    pub(crate) fn broadcast_failed(tx_hash: &TxHash, e: ClientError) -> Self {
+        Self::BroadcastFailed {
+            tx_hash: tx_hash.clone(),
+            source: Box::new(e),
+        }
+    }
+
+    pub(crate) fn wallet(action: impl Into<String>, e: impl Into<BoxError>) -> Self {
+        Self::Wallet {
+            action: action.into(),
+            source: e.into(),
+        }
+    }
+
+    pub(crate) fn io(action: impl Into<String>, e: std::io::Error) -> Self {
+        Self::Io {
+            action: action.into(),
+            source: e,
+        }
+    }
+
+    /// Wraps an error of a store, displayed with its whole context chain
+    #[cfg(feature = "events")]
+    pub(crate) fn store(action: impl Into<String>, e: impl Into<BoxError>) -> Self {
+        Self::Store {
+            action: action.into(),
+            source: e.into(),
+        }
+    }
+
+    /// Wraps an error of a sink, displayed with its whole context chain
+    #[cfg(feature = "events")]
+    pub(crate) fn sink(action: impl Into<String>, e: impl Into<BoxError>) -> Self {
+        Self::Sink {
+            action: action.into(),
+            source: e.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tx_error_kind() {
+        assert_eq!(
+            TxErrorKind::from_abci("sdk", 5),
+            TxErrorKind::InsufficientFunds
+        );
+        assert_eq!(
+            TxErrorKind::from_abci("sdk", 13),
+            TxErrorKind::InsufficientFee
+        );
+        assert_eq!(
+            TxErrorKind::from_abci("wasm", 5),
+            TxErrorKind::ContractFailed
+        );
+        assert_eq!(TxErrorKind::from_abci("bank", 5), TxErrorKind::Other);
+
+        let tx_error = TransactionError::from(TxResponse {
+            codespace: "wasm".to_string(),
+            code: 5,
+            raw_log: "failed to execute message; message index: 0: Invalid merkle proof: execute wasm contract failed".to_string(),
+            gas_wanted: 200_000,
+            gas_used: 81_234,
+            events: vec![cosmos_sdk_proto::tendermint::abci::Event {
+                r#type: "tx".to_string(),
+                attributes: vec![cosmos_sdk_proto::tendermint::abci::EventAttribute {
+                    key: "fee".to_string(),
+                    value: "200ubbn".to_string(),
+                    index: true,
+                }],
+            }],
+            ..Default::default()
+        });
+        assert_eq!(tx_error.txhash, None);
+        assert_eq!(tx_error.events[0].attributes[0].1, "200ubbn");
+
+        let tx_hash = TxHash::from_tx_bytes(b"tx");
+        let error = ClientError::TxFailed(Box::new(tx_error.or_tx_hash(&tx_hash)));
+        assert_eq!(error.tx_error_kind(), Some(TxErrorKind::ContractFailed));
+        assert_eq!(error.contract_error(), Some("Invalid merkle proof"));
+        assert!(error.to_string().contains(tx_hash.as_str()));
+        assert!(error.to_string().contains("gas used 81234 of 200000"));
+        assert_eq!(error.tx_hash(), Some(tx_hash.as_str()));
+
+        let error = ClientError::broadcast_failed(
+            &tx_hash,
+            ClientError::connection("http://localhost:9090", "connection reset"),
+        );
+        assert_eq!(error.tx_hash(), Some(tx_hash.as_str()));
+        assert!(matches!(
+            &error,
+            ClientError::BroadcastFailed { source, .. } if matches!(**source, ClientError::Connection { .. })
+        ));
+
+        let error = ClientError::grpc("query tip", tonic::Status::not_found("no tip"));
+        assert_eq!(error.grpc_status().unwrap().code(), tonic::Code::NotFound);
+        assert!(matches!(
+            &error,
+            ClientError::Grpc { status, .. } if status.message() == "no tip"
+        ));
+        assert!(std::error::Error::source(&error).is_some());
+    }
+
+    #[test]
+    fn test_classify_pruned() {
+        let pruned = || {
+            ClientError::grpc(
+                "query contract state",
+                tonic::Status::invalid_argument(
+                    "failed to load state at height 5; version does not exist (latest height: 100)",
+                ),
+            )
+        };
+        assert!(matches!(
+            pruned().classify_pruned(Some(5)),
+            ClientError::Pruned { height: 5 }
+        ));
+        // the latest state can't be pruned
+        assert!(matches!(
+            pruned().classify_pruned(None),
+            ClientError::Grpc { .. }
+        ));
+        let not_found = ClientError::grpc(
+            "query contract state",
+            tonic::Status::not_found("contract: not found"),
+        );
+        assert!(matches!(
+            not_found.classify_pruned(Some(5)),
+            ClientError::Grpc { .. }
+        ));
+        assert!(matches!(
+            ClientError::not_found("version does not exist").classify_pruned(Some(5)),
+            ClientError::NotFound { .. }
+        ));
+    }
+
+    #[cfg(feature = "events")]
+    #[test]
+    fn test_store_error_source() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+        let error = ClientError::store(
+            "load checkpoint",
+            anyhow::Error::new(io_error).context("Failed to read checkpoint.json"),
+        );
+        assert_eq!(
+            error.to_string(),
+            "Failed to load checkpoint: Failed to read checkpoint.json: no such file"
+        );
+
+        let source = std::error::Error::source(&error).unwrap();
+        let io_error = source.source().unwrap();
+        assert_eq!(
+            io_error.downcast_ref::<std::io::Error>().unwrap().kind(),
+            std::io::ErrorKind::NotFound
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use async_trait::async_trait;
+
+use crate::events::BlockEvents;
+
+/// Keeps the parsed events of processed blocks, so they can be replayed without
+/// hitting the RPC node
+#[async_trait]
+pub trait EventStore: Send + Sync {
+    /// Stores the events of a block, replacing previously stored events at its height
+    async fn put(&self, block_events: &BlockEvents) -> anyhow::Result<()>;
+
+    /// Returns the stored events in `from..=to`, ordered by height
+    async fn range(&self, from: u64, to: u64) -> anyhow::Result<Vec<BlockEvents>>;
+
+    /// Removes the events from `height` onwards, e.g. after a reorg
+    async fn remove_from(&self, height: u64) -> anyhow::Result<()>;
+}
+
+/// Stores the events of every block as a JSON file named by its height
+#[derive(Debug, Clone)]
+pub struct FileEventStore {
+    dir: PathBuf,
+}
+
+impl FileEventStore {
+    pub fn new(dir: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create event store directory {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    /// Returns the stored heights matching the filter, in order
+    async fn heights(&self, filter: impl Fn(u64) -> bool) -> anyhow::Result<Vec<u64>> {
+        let mut entries = tokio::fs::read_dir(&self.dir).await.with_context(|| {
+            format!(
+                "Failed to read event store directory {}",
+                self.dir.display()
+            )
+        })?;
+
+        let mut heights = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            if let Some(height) = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok())
+                .filter(|height| filter(*height))
+            {
+                heights.push(height);
+            }
+        }
+        heights.sort_unstable();
+        Ok(heights)
+    }
+
+    fn path(&self, height: u64) -> PathBuf {
+        self.dir.join(format!("{:020}.json", height))
+    }
+}
+
+#[async_trait]
+impl EventStore for FileEventStore {
+    async fn put(&self, block_events: &BlockEvents) -> anyhow::Result<()> {
+        let content =
+            serde_json::to_vec(block_events).context("Failed to serialize block events")?;
+        let path = self.path(block_events.height);
+
+        let tmp_path = path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, content)
+            .await
+            .with_context(|| format!("Failed to write event file {}", tmp_path.display()))?;
+        tokio::fs::rename(&tmp_path, &path)
+            .await
+            .with_context(|| format!("Failed to write event file {}", path.display()))
+    }
+
+    async fn range(&self, from: u64, to: u64) -> anyhow::Result<Vec<BlockEvents>> {
+        let mut block_events = Vec::new();
+        for height in self.heights(|height| (from..=to).contains(&height)).await? {
+            let path = self.path(height);
+            let content = tokio::fs::read(&path)
+                .await
+                .with_context(|| format!("Failed to read event file {}", path.display()))?;
+            block_events.push(
+                serde_json::from_slice(&content)
+                    .with_context(|| format!("Invalid event file {}", path.display()))?,
+            );
+        }
+        Ok(block_events)
+    }
+
+    async fn remove_from(&self, height: u64) -> anyhow::Result<()> {
+        for height in self.heights(|h| h >= height).await? {
+            let path = self.path(height);
+            tokio::fs::remove_file(&path)
+                .await
+                .with_context(|| format!("Failed to remove event file {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Stores the events in a SQLite table as JSON, one row per block
+#[cfg(feature = "sqlite")]
+pub struct SqliteEventStore {
+    conn: std::sync::Mutex<rusqlite::Connection>,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteEventStore {
+    pub fn open(path: impl AsRef<std::path::Path>) -> anyhow::Result<Self> {
+        let conn = rusqlite::Connection::open(path).context("Failed to open SQLite database")?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS block_events (height INTEGER PRIMARY KEY, events TEXT NOT NULL)",
+            [],
+        )
+        .context("Failed to create block_events table")?;
+
+        Ok(Self {
+            conn: std::sync::Mutex::new(conn),
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[async_trait]
+impl EventStore for SqliteEventStore {
+    async fn put(&self, block_events: &BlockEvents) -> anyhow::Result<()> {
+        let events =
+            serde_json::to_string(block_events).context("Failed to serialize block events")?;
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        conn.execute(
+            "INSERT INTO block_events (height, events) VALUES (?1, ?2)
+             ON CONFLICT(height) DO UPDATE SET events = excluded.events",
+            rusqlite::params![block_events.height as i64, events],
+        )
+        .context("Failed to store block events")?;
+
+        Ok(())
+    }
+
+    async fn range(&self, from: u64, to: u64) -> anyhow::Result<Vec<BlockEvents>> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stmt = conn
+            .prepare(
+                "SELECT events FROM block_events WHERE height BETWEEN ?1 AND ?2 ORDER BY height",
+            )
+            .context("Failed to query block events")?;
+        let rows = stmt
+            .query_map(rusqlite::params![from as i64, to as i64], |row| {
+                row.get::<_, String>(0)
+            })
+            .context("Failed to query block events")?;
+
+        rows.map(|events| {
+            let events = events.context("Failed to read block events")?;
+            serde_json::from_str(&events).context("Invalid stored block events")
+        })
+        .collect()
+    }
+
+    async fn remove_from(&self, height: u64) -> anyhow::Result<()> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        conn.execute(
+            "DELETE FROM block_events WHERE height >= ?1",
+            [height as i64],
+        )
+        .context("Failed to remove block events")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_event_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileEventStore::new(dir.path()).unwrap();
+
+        for height in [5, 12, 7, 30] {
+            store
+                .put(&BlockEvents {
+                    height,
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+        let heights =
+            |events: Vec<BlockEvents>| events.iter().map(|e| e.height).collect::<Vec<_>>();
+        assert_eq!(heights(store.range(6, 30).await.unwrap()), vec![7, 12, 30]);
+
+        store.remove_from(12).await.unwrap();
+        assert_eq!(heights(store.range(0, 100).await.unwrap()), vec![5, 7]);
+    }
+}
@@ -1,11 +1,28 @@
-use anyhow::{anyhow, Context, Result};
-use hex;
+use crate::checkpoint::CheckpointStore;
+use crate::client::CosmWasmClient;
+use crate::dedup::{DedupStore, DeliveredEvents};
+use crate::error::ClientError;
+pub use crate::error::RawEvent;
+use crate::event_store::EventStore;
+use crate::logging;
+use crate::metrics::{ListenerMetrics, ListenerStatus};
+use crate::rpc::{RpcClient, RpcEndpoints};
+use crate::sink::EventSink;
+use crate::spill::SpillQueue;
+use crate::transactions::TxHash;
+use anyhow::{anyhow, Result};
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
 use tendermint::abci;
 use tendermint::block::Height;
-use tendermint_rpc::{Client, HttpClient};
-use tokio::sync::mpsc;
+use tendermint::Hash;
+use tendermint_rpc::event::EventData;
+use tendermint_rpc::query::{EventType, Query};
+use tendermint_rpc::{Client, HttpClient, Order, SubscriptionClient, WebSocketClient};
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
 use tokio::time::{Duration, Instant};
 use tracing;
 
@@ -21,165 +38,1935 @@ pub struct PegOutEvent {
     pub msg_index: u32,
     pub sender: String,
     pub btc_address: String,
+    /// fee_rate is the requested BTC fee rate in sat/vB
     pub fee_rate: u32,
     pub operator_btc_pk: String,
     pub amount: u128,
 }
 
-#[derive(Debug, Clone)]
+/// CW20 `transfer` or `send` of the configured token contract
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Cw20TransferEvent {
+    pub msg_index: u32,
+    pub from: String,
+    pub to: String,
+    pub amount: u128,
+}
+
+/// CW20 `mint` of the configured token contract
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Cw20MintEvent {
+    pub msg_index: u32,
+    pub to: String,
+    pub amount: u128,
+}
+
+/// CW20 `burn` of the configured token contract
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Cw20BurnEvent {
+    pub msg_index: u32,
+    pub from: String,
+    pub amount: u128,
+}
+
+/// Instantiation of a watched contract
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct InstantiateEvent {
+    pub msg_index: u32,
+    pub code_id: u64,
+}
+
+/// Migration of a watched contract to a new code
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct MigrateEvent {
+    pub msg_index: u32,
+    /// code_id is the code the contract was migrated to
+    pub code_id: u64,
+}
+
+/// Admin change of a watched contract
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct UpdateAdminEvent {
+    pub msg_index: u32,
+    /// new_admin is `None` if the admin was cleared
+    pub new_admin: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ContractEvent {
     PegIn(PegInEvent),
     PegOut(PegOutEvent),
+    Cw20Transfer(Cw20TransferEvent),
+    Cw20Mint(Cw20MintEvent),
+    Cw20Burn(Cw20BurnEvent),
+    Instantiate(InstantiateEvent),
+    Migrate(MigrateEvent),
+    UpdateAdmin(UpdateAdminEvent),
+    Raw(RawEvent),
+}
+
+impl ContractEvent {
+    /// Returns the action of the event, e.g. `peg_in` or `cw20_transfer`. Raw events
+    /// return their `action` attribute, or their type if they have none.
+    pub fn action(&self) -> &str {
+        match self {
+            Self::PegIn(_) => "peg_in",
+            Self::PegOut(_) => "peg_out",
+            Self::Cw20Transfer(_) => "cw20_transfer",
+            Self::Cw20Mint(_) => "cw20_mint",
+            Self::Cw20Burn(_) => "cw20_burn",
+            Self::Instantiate(_) => "instantiate",
+            Self::Migrate(_) => "migrate",
+            Self::UpdateAdmin(_) => "update_admin",
+            Self::Raw(event) => event
+                .attributes
+                .iter()
+                .find(|(key, _)| key == "action")
+                .map_or(event.kind.as_str(), |(_, value)| value.as_str()),
+        }
+    }
+}
+
+/// Whether the listener emits raw wasm events of the watched contracts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RawEventMode {
+    /// Only emit parsed events
+    #[default]
+    Off,
+    /// Emit raw events in addition to parsed events
+    Alongside,
+    /// Only emit raw events
+    Only,
+}
+
+/// What the listener does when the consumer doesn't keep up with the event sink
+#[derive(Default)]
+pub enum BackpressurePolicy {
+    /// Wait until the consumer takes the events, stalling block processing
+    #[default]
+    Block,
+    /// Wait like `Block`, logging a warning every given interval the consumer
+    /// doesn't take the events
+    WarnAfter(Duration),
+    /// Write the events to the queue and keep processing blocks, they are
+    /// delivered in order once the consumer catches up
+    Spill(Box<dyn SpillQueue>),
+}
+
+/// WebSocket events that trigger fetching new blocks, blocks are still fetched
+/// and parsed through `block_results` either way
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WsTrigger {
+    /// Txs of the watched contracts, blocks without contract txs are picked up
+    /// by the status poll
+    #[default]
+    ContractTxs,
+    /// Every new block, removing the status poll latency entirely. Subscribes to
+    /// `NewBlock` since tendermint-rpc can't decode `NewBlockHeader` events.
+    NewBlocks,
+}
+
+/// Which contract call of a message emitted an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum EventOrigin {
+    /// The contract executed by the message itself
+    #[default]
+    Execute,
+    /// A contract called through a submessage
+    Submessage,
+    /// A contract's `reply` entry point handling a submessage result
+    Reply,
 }
 
-#[derive(Debug)]
+/// Contract event together with the tx and contract it came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxEvent {
+    pub tx_hash: TxHash,
+    /// tx_index is the index of the tx in the block
+    pub tx_index: u32,
+    /// code is the ABCI result code of the tx, 0 on success
+    pub code: u32,
+    pub gas_used: i64,
+    /// contract is the address of the contract that emitted the event
+    pub contract: String,
+    /// msg_index is the index of the tx message the event is attributed to, also for
+    /// events of its submessages and replies. `None` if the chain doesn't index events
+    /// by message (Cosmos SDK < 0.50).
+    #[serde(default)]
+    pub msg_index: Option<u32>,
+    #[serde(default)]
+    pub origin: EventOrigin,
+    pub event: ContractEvent,
+}
+
+/// Contract event emitted outside of any tx, e.g. by a module calling the
+/// contract's sudo entry point while finalizing the block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinalizeBlockEvent {
+    /// contract is the address of the contract that emitted the event
+    pub contract: String,
+    pub event: ContractEvent,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct BlockEvents {
     pub height: u64,
+    /// block_time is the unix timestamp of the block in seconds
     pub block_time: u64,
-    pub events: Vec<(String, ContractEvent)>, // (tx_hash, event)
+    /// block_hash is the uppercase hex hash of the block
+    pub block_hash: String,
+    /// proposer is the uppercase hex address of the validator that proposed the block
+    pub proposer: String,
+    pub events: Vec<TxEvent>,
+    /// finalize_block_events are the contract events emitted outside of txs, from
+    /// FinalizeBlock or BeginBlock/EndBlock on older chains
+    pub finalize_block_events: Vec<FinalizeBlockEvent>,
 }
 
-pub struct EventListener {
-    rpc_client: HttpClient,
-    event_sender: mpsc::Sender<BlockEvents>,
-    checkpoint_sender: mpsc::Sender<u64>,
-    contract_address: String,
-    last_processed_height: u64,
-}
+impl BlockEvents {
+    /// Returns the tx events grouped by tx index and message index, in order. Events
+    /// without a message index are grouped under message 0.
+    pub fn events_by_message(&self) -> BTreeMap<(u32, u32), Vec<&TxEvent>> {
+        let mut messages: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for event in &self.events {
+            messages
+                .entry((event.tx_index, event.msg_index.unwrap_or_default()))
+                .or_default()
+                .push(event);
+        }
+        messages
+    }
+}
+
+/// Notification that previously emitted events from `from_height` onwards are no
+/// longer part of the canonical chain and will be re-emitted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reorg {
+    /// from_height is the first height that was rolled back
+    pub from_height: u64,
+}
+
+/// Block data fetched for processing
+struct FetchedBlock {
+    block_time: u64,
+    hash: Hash,
+    proposer: String,
+    /// parent_hash is the hash of the previous block as committed in the header
+    parent_hash: Option<Hash>,
+    txs: Vec<FetchedTx>,
+    /// finalize_block_events are the block level events, not part of any tx
+    finalize_block_events: Vec<abci::Event>,
+}
+
+/// Result of a tx in a fetched block
+struct FetchedTx {
+    hash: TxHash,
+    index: u32,
+    code: u32,
+    gas_used: i64,
+    events: Vec<abci::Event>,
+}
+
+impl FetchedTx {
+    fn tx_event(
+        &self,
+        contract: String,
+        message: &MessageContext,
+        origin: EventOrigin,
+        event: ContractEvent,
+    ) -> TxEvent {
+        TxEvent {
+            tx_hash: self.hash.clone(),
+            tx_index: self.index,
+            code: self.code,
+            gas_used: self.gas_used,
+            contract,
+            msg_index: message.msg_index,
+            origin,
+            event,
+        }
+    }
+}
+
+/// Tracks the message of a tx and the contract call its events come from, relying
+/// on the node returning a tx's events in execution order
+#[derive(Default)]
+struct MessageContext {
+    /// msg_index is the SDK `msg_index` attribute of the current message's events
+    msg_index: Option<u32>,
+    /// executed_contract is the contract executed by the current message
+    executed_contract: Option<String>,
+    /// in_reply is set once a contract's `reply` was called in the current message
+    in_reply: bool,
+}
+
+impl MessageContext {
+    /// Moves the context past the event and returns the origin of the event, which
+    /// is only meaningful for wasm events
+    fn observe(&mut self, event: &abci::Event) -> EventOrigin {
+        let msg_index = event
+            .attributes
+            .iter()
+            .find(|attr| attr.key_bytes() == b"msg_index")
+            .and_then(|attr| attr.value_str().ok())
+            .and_then(|msg_index| msg_index.parse().ok());
+        if msg_index.is_some() && msg_index != self.msg_index {
+            *self = Self {
+                msg_index,
+                ..Self::default()
+            };
+        }
+
+        match event.kind.as_str() {
+            // wasmd emits `execute`/`instantiate` before the events of the contract call
+            "execute" | "instantiate" if self.executed_contract.is_none() => {
+                self.executed_contract = event_contract(event);
+            }
+            "reply" => self.in_reply = true,
+            _ => {}
+        }
+
+        if self.in_reply {
+            EventOrigin::Reply
+        } else if self.executed_contract.is_some()
+            && self.executed_contract == event_contract(event)
+        {
+            EventOrigin::Execute
+        } else if self.executed_contract.is_some() {
+            EventOrigin::Submessage
+        } else {
+            EventOrigin::Execute
+        }
+    }
+}
+
+/// Polling intervals and delays of the listener, tune to the node's rate limits
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListenerConfig {
+    /// synced_status_interval is the status poll interval when at most
+    /// `near_lag_threshold` blocks behind
+    pub synced_status_interval: Duration,
+    /// lagging_status_interval is the status poll interval when at most
+    /// `far_lag_threshold` blocks behind
+    pub lagging_status_interval: Duration,
+    /// catch_up_status_interval is the status poll interval when further behind
+    pub catch_up_status_interval: Duration,
+    pub near_lag_threshold: u64,
+    pub far_lag_threshold: u64,
+    /// error_backoff is the delay before retrying after a failed request
+    pub error_backoff: BackoffPolicy,
+    /// block_delay is an optional pause after every processed block
+    pub block_delay: Duration,
+    /// failover_threshold is the number of consecutive failures after which the
+    /// listener switches to the next RPC endpoint
+    pub failover_threshold: u32,
+    /// primary_retry_interval is how long to wait before switching back to the
+    /// primary RPC endpoint after a failover
+    pub primary_retry_interval: Duration,
+}
+
+impl Default for ListenerConfig {
+    fn default() -> Self {
+        Self {
+            synced_status_interval: Duration::from_secs(5),
+            lagging_status_interval: Duration::from_secs(15),
+            catch_up_status_interval: Duration::from_secs(30),
+            near_lag_threshold: 10,
+            far_lag_threshold: 100,
+            error_backoff: BackoffPolicy::default(),
+            block_delay: Duration::ZERO,
+            failover_threshold: 3,
+            primary_retry_interval: Duration::from_secs(300),
+        }
+    }
+}
+
+impl ListenerConfig {
+    /// Returns the status poll interval for the given lag
+    pub fn status_interval(&self, blocks_behind: u64) -> Duration {
+        if blocks_behind <= self.near_lag_threshold {
+            self.synced_status_interval
+        } else if blocks_behind <= self.far_lag_threshold {
+            self.lagging_status_interval
+        } else {
+            self.catch_up_status_interval
+        }
+    }
+}
+
+/// Exponential backoff between retries, reset after the first success
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackoffPolicy {
+    /// base is the delay after the first failure
+    pub base: Duration,
+    /// multiplier is applied to the delay after every further failure
+    pub multiplier: f64,
+    /// cap is the maximum delay
+    pub cap: Duration,
+    /// jitter is the fraction (0.0 - 1.0) of the delay that is randomized, so
+    /// several listeners don't retry in lockstep
+    pub jitter: f64,
+}
+
+impl Default for BackoffPolicy {
+    fn default() -> Self {
+        Self {
+            base: Duration::from_secs(1),
+            multiplier: 2.0,
+            cap: Duration::from_secs(60),
+            jitter: 0.2,
+        }
+    }
+}
+
+impl BackoffPolicy {
+    /// Flat delay without growth or jitter, as before backoff was configurable
+    pub fn fixed(delay: Duration) -> Self {
+        Self {
+            base: delay,
+            multiplier: 1.0,
+            cap: delay,
+            jitter: 0.0,
+        }
+    }
+
+    /// Returns the delay before the retry following `failures` consecutive failures
+    pub fn delay(&self, failures: u32) -> Duration {
+        let exponent = failures.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay = (self.base.as_secs_f64() * self.multiplier.max(1.0).powi(exponent))
+            .min(self.cap.as_secs_f64());
+        let jitter = self.jitter.clamp(0.0, 1.0) * random_fraction();
+        Duration::from_secs_f64(delay * (1.0 - jitter))
+    }
+}
+
+/// Returns a pseudo-random number in [0, 1), good enough to spread out retries
+fn random_fraction() -> f64 {
+    use std::hash::{BuildHasher, Hasher};
+
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Fraction of the event channel capacity above which the listener stops fetching blocks
+pub const DEFAULT_HIGH_WATER_MARK: f64 = 0.8;
+const SATURATED_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Number of blocks between two checkpoint notifications
+pub const DEFAULT_CHECKPOINT_INTERVAL: u64 = 10;
+/// Number of blocks fetched concurrently while catching up
+pub const DEFAULT_FETCH_CONCURRENCY: usize = 8;
+/// Number of recent block hashes kept to detect reorgs
+pub const REORG_WINDOW: u64 = 100;
+/// Number of txs requested per `tx_search` page, the maximum allowed by the node
+const TX_SEARCH_PAGE_SIZE: u8 = 100;
+/// Number of heights loaded from the event store at once while replaying
+const REPLAY_CHUNK_SIZE: u64 = 1000;
+/// Maximum number of blocks processed between two status checks while catching up
+const CATCH_UP_BATCH_SIZE: u64 = 100;
+/// Buffer size of the `into_stream` channel if the sink doesn't buffer events
+const DEFAULT_STREAM_BUFFER: usize = 100;
+/// Delay before re-subscribing after the WebSocket connection dropped
+const WS_RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Requests from `ListenerHandle`s to the running listener
+#[derive(Debug, Default)]
+struct ListenerControl {
+    /// seek_height is the last processed height requested by `set_height`
+    seek_height: std::sync::Mutex<Option<u64>>,
+    /// wakes up the idle listener to apply a request
+    notify: tokio::sync::Notify,
+}
+
+/// Predicate deciding whether the listener emits a parsed event
+type EventFilter = Box<dyn Fn(&ContractEvent) -> bool + Send + Sync>;
+
+/// Cloneable handle to a running `EventListener`
+#[derive(Clone)]
+pub struct ListenerHandle {
+    metrics: Arc<ListenerMetrics>,
+    control: Arc<ListenerControl>,
+}
+
+impl ListenerHandle {
+    /// Returns the current health of the listener
+    pub fn status(&self) -> ListenerStatus {
+        self.metrics.status()
+    }
+
+    pub fn last_processed_height(&self) -> u64 {
+        self.metrics.status().last_processed_height
+    }
+
+    /// Makes the listener continue after `height`, e.g. to skip a poison block or
+    /// rewind to reprocess blocks. Applied before the next batch of blocks: the
+    /// height is checkpointed, and on rewind the deduplication and event store
+    /// entries above it are dropped so the events are delivered again.
+    pub fn set_height(&self, height: u64) {
+        *self
+            .control
+            .seek_height
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = Some(height);
+        self.control.notify.notify_one();
+    }
+
+    pub fn metrics(&self) -> Arc<ListenerMetrics> {
+        self.metrics.clone()
+    }
+}
+
+pub struct EventListener {
+    rpc: RpcEndpoints,
+    control: Arc<ListenerControl>,
+    /// archive_rpc serves the blocks pruned by the regular endpoints
+    archive_rpc: Option<HttpClient>,
+    /// skip_empty_blocks skips the `block_results` request for blocks without txs
+    skip_empty_blocks: bool,
+    sink: Box<dyn EventSink>,
+    checkpoint_sender: mpsc::Sender<u64>,
+    contract_addresses: HashSet<String>,
+    /// cw20_contract is the token whose transfers, mints and burns are parsed
+    cw20_contract: Option<String>,
+    /// actions limits the emitted events to these actions, all are emitted if `None`
+    actions: Option<HashSet<String>>,
+    /// event_filters must all accept an event for it to be emitted
+    event_filters: Vec<EventFilter>,
+    last_processed_height: u64,
+    high_water_mark: f64,
+    checkpoint_interval: u64,
+    /// checkpoint_period also triggers a checkpoint once this much time has passed
+    /// since the last one, even if all blocks in between were empty
+    checkpoint_period: Option<Duration>,
+    last_checkpoint_height: u64,
+    last_checkpoint_at: Instant,
+    ws_url: Option<String>,
+    ws_trigger: WsTrigger,
+    checkpoint_store: Option<Box<dyn CheckpointStore>>,
+    event_store: Option<Box<dyn EventStore>>,
+    reorg_sender: Option<mpsc::Sender<Reorg>>,
+    fetch_concurrency: usize,
+    config: ListenerConfig,
+    raw_event_mode: RawEventMode,
+    delivered_events: Option<DeliveredEvents>,
+    metrics: Arc<ListenerMetrics>,
+    backpressure: BackpressurePolicy,
+    /// spilled is true while the spill queue holds undelivered events
+    spilled: bool,
+    /// block_hashes are the hashes of the last `REORG_WINDOW` processed blocks
+    block_hashes: BTreeMap<u64, Hash>,
+}
+
+/// Builds an `EventListener`, validating its options. Options not covered here,
+/// e.g. stores and the backpressure policy, are set on the built listener with
+/// its `with_*` methods.
+pub struct EventListenerBuilder {
+    rpc_url: String,
+    /// shared_rpc is the client of the `CosmWasmClient` the builder was created from
+    shared_rpc: Option<RpcClient>,
+    fallback_rpc_urls: Vec<String>,
+    archive_rpc_url: Option<String>,
+    ws_url: Option<String>,
+    sink: Option<Box<dyn EventSink>>,
+    checkpoint_sender: Option<mpsc::Sender<u64>>,
+    contract_addresses: Vec<String>,
+    start_height: u64,
+    checkpoint_interval: u64,
+    checkpoint_period: Option<Duration>,
+    high_water_mark: f64,
+    fetch_concurrency: usize,
+    config: ListenerConfig,
+}
+
+impl EventListenerBuilder {
+    pub fn new(rpc_url: &str) -> Self {
+        Self {
+            rpc_url: rpc_url.to_string(),
+            shared_rpc: None,
+            fallback_rpc_urls: Vec::new(),
+            archive_rpc_url: None,
+            ws_url: None,
+            sink: None,
+            checkpoint_sender: None,
+            contract_addresses: Vec::new(),
+            start_height: 0,
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
+            checkpoint_period: None,
+            high_water_mark: DEFAULT_HIGH_WATER_MARK,
+            fetch_concurrency: DEFAULT_FETCH_CONCURRENCY,
+            config: ListenerConfig::default(),
+        }
+    }
+
+    /// Returns a builder listening to the client's contract over the client's
+    /// Tendermint RPC endpoint, which must be set with `CosmWasmClient::with_rpc`
+    pub fn from_client(client: &CosmWasmClient) -> Result<Self, ClientError> {
+        let rpc = client
+            .rpc
+            .clone()
+            .ok_or_else(|| ClientError::not_found("Tendermint RPC endpoint in the client"))?;
+        let mut builder = Self::new(&rpc.url);
+        builder.shared_rpc = Some(rpc);
+        if let Some(contract) = &client.contract {
+            builder = builder.with_contract(contract.as_ref());
+        }
+        Ok(builder)
+    }
+
+    pub fn with_fallback_rpcs<I, S>(mut self, rpc_urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.fallback_rpc_urls
+            .extend(rpc_urls.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_archive_rpc(mut self, rpc_url: &str) -> Self {
+        self.archive_rpc_url = Some(rpc_url.to_string());
+        self
+    }
+
+    pub fn with_websocket(mut self, ws_url: &str) -> Self {
+        self.ws_url = Some(ws_url.to_string());
+        self
+    }
+
+    pub fn with_event_sender(mut self, event_sender: mpsc::Sender<BlockEvents>) -> Self {
+        self.sink = Some(Box::new(event_sender));
+        self
+    }
+
+    pub fn with_sink(mut self, sink: impl EventSink + 'static) -> Self {
+        self.sink = Some(Box::new(sink));
+        self
+    }
+
+    /// Sets the channel notified of checkpoints, none are sent if it isn't set
+    pub fn with_checkpoint_sender(mut self, checkpoint_sender: mpsc::Sender<u64>) -> Self {
+        self.checkpoint_sender = Some(checkpoint_sender);
+        self
+    }
+
+    pub fn with_contract(mut self, contract_address: &str) -> Self {
+        self.contract_addresses.push(contract_address.to_string());
+        self
+    }
+
+    pub fn with_contracts<I, S>(mut self, contract_addresses: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.contract_addresses
+            .extend(contract_addresses.into_iter().map(Into::into));
+        self
+    }
+
+    /// Sets the last processed height, the listener starts at the block after it
+    pub fn with_start_height(mut self, last_processed_height: u64) -> Self {
+        self.start_height = last_processed_height;
+        self
+    }
+
+    pub fn with_checkpoint_interval(mut self, checkpoint_interval: u64) -> Self {
+        self.checkpoint_interval = checkpoint_interval;
+        self
+    }
+
+    pub fn with_checkpoint_period(mut self, checkpoint_period: Duration) -> Self {
+        self.checkpoint_period = Some(checkpoint_period);
+        self
+    }
+
+    pub fn with_high_water_mark(mut self, high_water_mark: f64) -> Self {
+        self.high_water_mark = high_water_mark;
+        self
+    }
+
+    pub fn with_fetch_concurrency(mut self, fetch_concurrency: usize) -> Self {
+        self.fetch_concurrency = fetch_concurrency;
+        self
+    }
+
+    pub fn with_config(mut self, config: ListenerConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn build(self) -> Result<EventListener, ClientError> {
+        let sink = self.sink.ok_or_else(|| {
+            ClientError::invalid("listener", "an event sender or sink is required")
+        })?;
+        if self.contract_addresses.is_empty() {
+            return Err(ClientError::invalid(
+                "listener",
+                "at least one contract address is required",
+            ));
+        }
+        if let Some(contract_address) = self
+            .contract_addresses
+            .iter()
+            .find(|contract_address| contract_address.trim().is_empty())
+        {
+            return Err(ClientError::invalid(
+                "contract address",
+                format!("{:?}", contract_address),
+            ));
+        }
+        // without a receiver the listener skips checkpoint notifications
+        let checkpoint_sender = self.checkpoint_sender.unwrap_or_else(|| mpsc::channel(1).0);
+        let rpc = match self.shared_rpc {
+            Some(shared_rpc) => RpcEndpoints::with_primary(shared_rpc),
+            None => RpcEndpoints::new(&self.rpc_url)?,
+        };
+        let mut listener = EventListener::with_sink_and_contracts(
+            rpc,
+            sink,
+            checkpoint_sender,
+            self.contract_addresses,
+            self.start_height,
+        )
+        .with_fallback_rpcs(self.fallback_rpc_urls)?
+        .with_checkpoint_interval(self.checkpoint_interval)?
+        .with_high_water_mark(self.high_water_mark)?
+        .with_fetch_concurrency(self.fetch_concurrency)?
+        .with_config(self.config)?;
+        if let Some(archive_rpc_url) = &self.archive_rpc_url {
+            listener = listener.with_archive_rpc(archive_rpc_url)?;
+        }
+        listener.checkpoint_period = self.checkpoint_period;
+        listener.ws_url = self.ws_url;
+
+        Ok(listener)
+    }
+}
+
+impl EventListener {
+    pub async fn new(
+        rpc_url: &str,
+        event_sender: mpsc::Sender<BlockEvents>,
+        checkpoint_sender: mpsc::Sender<u64>,
+        contract_address: &str,
+        last_processed_height: u64,
+    ) -> Result<Self, ClientError> {
+        Ok(Self::with_sink_and_contracts(
+            RpcEndpoints::new(rpc_url)?,
+            Box::new(event_sender),
+            checkpoint_sender,
+            vec![contract_address.to_string()],
+            last_processed_height,
+        ))
+    }
+
+    /// Creates a listener for the client's contract, sharing the client's Tendermint
+    /// RPC connection instead of opening a separate one
+    pub fn from_client(
+        client: &CosmWasmClient,
+        event_sender: mpsc::Sender<BlockEvents>,
+        checkpoint_sender: mpsc::Sender<u64>,
+        last_processed_height: u64,
+    ) -> Result<Self, ClientError> {
+        EventListenerBuilder::from_client(client)?
+            .with_event_sender(event_sender)
+            .with_checkpoint_sender(checkpoint_sender)
+            .with_start_height(last_processed_height)
+            .build()
+    }
+
+    /// Returns a builder, the preferred way to create a listener with many options
+    pub fn builder(rpc_url: &str) -> EventListenerBuilder {
+        EventListenerBuilder::new(rpc_url)
+    }
+
+    fn with_sink_and_contracts(
+        rpc: RpcEndpoints,
+        sink: Box<dyn EventSink>,
+        checkpoint_sender: mpsc::Sender<u64>,
+        contract_addresses: Vec<String>,
+        last_processed_height: u64,
+    ) -> Self {
+        Self {
+            rpc,
+            archive_rpc: None,
+            skip_empty_blocks: false,
+            sink,
+            checkpoint_sender,
+            contract_addresses: contract_addresses.into_iter().collect(),
+            cw20_contract: None,
+            actions: None,
+            event_filters: Vec::new(),
+            last_processed_height,
+            high_water_mark: DEFAULT_HIGH_WATER_MARK,
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
+            checkpoint_period: None,
+            last_checkpoint_height: last_processed_height,
+            last_checkpoint_at: Instant::now(),
+            ws_url: None,
+            ws_trigger: WsTrigger::ContractTxs,
+            checkpoint_store: None,
+            event_store: None,
+            reorg_sender: None,
+            fetch_concurrency: DEFAULT_FETCH_CONCURRENCY,
+            config: ListenerConfig::default(),
+            raw_event_mode: RawEventMode::Off,
+            delivered_events: None,
+            metrics: Arc::new(ListenerMetrics::default()),
+            control: Arc::new(ListenerControl::default()),
+            backpressure: BackpressurePolicy::Block,
+            spilled: false,
+            block_hashes: BTreeMap::new(),
+        }
+    }
+
+    /// Adds fallback Tendermint RPC endpoints, used in order when the current one
+    /// keeps failing. The primary endpoint is retried periodically.
+    pub fn with_fallback_rpcs<I, S>(mut self, rpc_urls: I) -> Result<Self, ClientError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for rpc_url in rpc_urls {
+            self.rpc.add(rpc_url.as_ref())?;
+        }
+        Ok(self)
+    }
+
+    /// Fetches the blocks pruned by the regular RPC endpoints from the given archive
+    /// node. Without it the listener stops with `ClientError::Pruned` when it
+    /// reaches a pruned block, as retrying would never succeed.
+    pub fn with_archive_rpc(mut self, rpc_url: &str) -> Result<Self, ClientError> {
+        let client = HttpClient::new(rpc_url)
+            .map_err(|e| ClientError::invalid(format!("RPC URL {}", rpc_url), e))?;
+        self.archive_rpc = Some(client);
+        Ok(self)
+    }
+
+    /// Skips the `block_results` request for blocks without txs, which are most
+    /// blocks on low-activity chains. Contract events emitted outside of txs in
+    /// such blocks, e.g. by sudo calls, are then missed.
+    pub fn with_skip_empty_blocks(mut self, skip_empty_blocks: bool) -> Self {
+        self.skip_empty_blocks = skip_empty_blocks;
+        self
+    }
+
+    /// Sets the fraction (0.0 - 1.0] of the event channel capacity above which
+    /// the listener pauses fetching new blocks until the consumer catches up
+    pub fn with_high_water_mark(mut self, high_water_mark: f64) -> Result<Self, ClientError> {
+        if !(high_water_mark > 0.0 && high_water_mark <= 1.0) {
+            return Err(ClientError::invalid(
+                "high-water mark",
+                format!("must be in (0.0, 1.0], got {}", high_water_mark),
+            ));
+        }
+        self.high_water_mark = high_water_mark;
+        Ok(self)
+    }
+
+    /// Sets the number of blocks between two checkpoint notifications, e.g. the
+    /// Babylon epoch interval to checkpoint on epoch boundaries
+    pub fn with_checkpoint_interval(
+        mut self,
+        checkpoint_interval: u64,
+    ) -> Result<Self, ClientError> {
+        if checkpoint_interval == 0 {
+            return Err(ClientError::invalid(
+                "checkpoint interval",
+                "must be positive",
+            ));
+        }
+        self.checkpoint_interval = checkpoint_interval;
+        Ok(self)
+    }
+
+    /// Also sends a checkpoint notification once `checkpoint_period` has passed since
+    /// the last one, so consumers see progress on quiet chains or contracts. Only
+    /// new heights are notified, an idle listener doesn't repeat its checkpoint.
+    pub fn with_checkpoint_period(mut self, checkpoint_period: Duration) -> Self {
+        self.checkpoint_period = Some(checkpoint_period);
+        self
+    }
+
+    /// Subscribes to the contract's txs over the Tendermint WebSocket endpoint
+    /// (e.g. `wss://rpc.example.com/websocket`) and processes new blocks as soon as
+    /// a contract tx is committed. Polling keeps running as a fallback while the
+    /// subscription is down.
+    pub fn with_websocket(mut self, ws_url: &str) -> Self {
+        self.ws_url = Some(ws_url.to_string());
+        self
+    }
+
+    /// Sets which WebSocket events wake up the listener, see `WsTrigger`.
+    /// Has no effect without `with_websocket`.
+    pub fn with_websocket_trigger(mut self, ws_trigger: WsTrigger) -> Self {
+        self.ws_trigger = ws_trigger;
+        self
+    }
+
+    /// Persists every checkpoint to the given store. On `start()` the listener resumes
+    /// from the stored height if it is above `last_processed_height`.
+    pub fn with_checkpoint_store(mut self, store: impl CheckpointStore + 'static) -> Self {
+        self.checkpoint_store = Some(Box::new(store));
+        self
+    }
+
+    /// Keeps the events of every processed block in the given store, so they can
+    /// be re-emitted with `replay`
+    pub fn with_event_store(mut self, store: impl EventStore + 'static) -> Self {
+        self.event_store = Some(Box::new(store));
+        self
+    }
+
+    /// Sends a `Reorg` notification whenever the listener rolls back processed blocks
+    pub fn with_reorg_sender(mut self, reorg_sender: mpsc::Sender<Reorg>) -> Self {
+        self.reorg_sender = Some(reorg_sender);
+        self
+    }
+
+    /// Sets the number of blocks fetched concurrently while catching up.
+    /// Blocks are still processed and delivered in order.
+    pub fn with_fetch_concurrency(mut self, fetch_concurrency: usize) -> Result<Self, ClientError> {
+        if fetch_concurrency == 0 {
+            return Err(ClientError::invalid(
+                "fetch concurrency",
+                "must be positive",
+            ));
+        }
+        self.fetch_concurrency = fetch_concurrency;
+        Ok(self)
+    }
+
+    pub fn with_config(mut self, config: ListenerConfig) -> Result<Self, ClientError> {
+        if config.near_lag_threshold > config.far_lag_threshold {
+            return Err(ClientError::invalid(
+                "lag thresholds",
+                format!(
+                    "near lag threshold {} is above far lag threshold {}",
+                    config.near_lag_threshold, config.far_lag_threshold
+                ),
+            ));
+        }
+        self.config = config;
+        Ok(self)
+    }
+
+    /// Also listens to the given contracts, e.g. the CW20 token next to the bridge
+    pub fn with_contracts<I, S>(mut self, contract_addresses: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.contract_addresses
+            .extend(contract_addresses.into_iter().map(Into::into));
+        self
+    }
+
+    /// Listens to the given CW20 token contract and parses its transfers, sends,
+    /// mints and burns into the `ContractEvent::Cw20*` variants
+    pub fn with_cw20_token(mut self, contract_address: &str) -> Self {
+        self.contract_addresses.insert(contract_address.to_string());
+        self.cw20_contract = Some(contract_address.to_string());
+        self
+    }
+
+    /// Only emits the events of the given actions, named as by `ContractEvent::action`,
+    /// e.g. `["peg_out", "cw20_transfer"]`. Other events are skipped before they are
+    /// parsed. Events unknown to the parser are matched by their `action` attribute,
+    /// or by their event type without one, e.g. `wasm-transfer`.
+    pub fn with_actions<I, S>(mut self, actions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.actions = Some(actions.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Only emits the events accepted by the predicate, e.g. peg outs above 1 BTC.
+    /// Can be called several times, an event must pass all predicates. Raw events
+    /// are passed as `ContractEvent::Raw`, so predicates can inspect any attribute.
+    pub fn with_event_filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&ContractEvent) -> bool + Send + Sync + 'static,
+    {
+        self.event_filters.push(Box::new(filter));
+        self
+    }
+
+    /// Emits all wasm events of the watched contracts as `ContractEvent::Raw`, so
+    /// actions unknown to the parser are observable
+    pub fn with_raw_events(mut self, raw_event_mode: RawEventMode) -> Self {
+        self.raw_event_mode = raw_event_mode;
+        self
+    }
+
+    /// Remembers the events delivered in the last `window` blocks in the given store
+    /// and never delivers them again, e.g. when resuming from an older checkpoint
+    /// after a crash. Raw events are only deduplicated if they carry a `msg_index`.
+    pub fn with_dedup(mut self, store: impl DedupStore + 'static, window: u64) -> Self {
+        self.delivered_events = Some(DeliveredEvents::new(Box::new(store), window));
+        self
+    }
+
+    /// Delivers the events to the given sink instead of the channel given to `new`
+    pub fn with_sink(mut self, sink: impl EventSink + 'static) -> Self {
+        self.sink = Box::new(sink);
+        self
+    }
+
+    /// Sets what happens when the event channel is full. With `Spill` the
+    /// high-water mark is ignored, as blocks keep being processed.
+    pub fn with_backpressure(mut self, backpressure: BackpressurePolicy) -> Self {
+        self.backpressure = backpressure;
+        self
+    }
+
+    /// Returns a handle to query the listener while it runs
+    pub fn handle(&self) -> ListenerHandle {
+        ListenerHandle {
+            metrics: self.metrics.clone(),
+            control: self.control.clone(),
+        }
+    }
+
+    /// Returns the listener's metrics, which keep updating once it is started
+    pub fn metrics(&self) -> Arc<ListenerMetrics> {
+        self.metrics.clone()
+    }
+
+    /// Returns true when the sink's buffer is filled above the high-water mark
+    fn consumer_saturated(&self) -> bool {
+        self.sink
+            .capacity()
+            .is_some_and(|(max_capacity, capacity)| {
+                is_saturated(max_capacity, capacity, self.high_water_mark)
+            })
+    }
+    pub async fn start(&mut self) -> Result<(), ClientError> {
+        self.run(None).await
+    }
+
+    /// Processes blocks up to and including `stop_height`, then checkpoints at
+    /// `stop_height` and returns, e.g. for migration or audit jobs
+    pub async fn start_until(&mut self, stop_height: u64) -> Result<(), ClientError> {
+        self.run(Some(stop_height)).await
+    }
+
+    async fn run(&mut self, stop_height: Option<u64>) -> Result<(), ClientError> {
+        if let Some(store) = &self.checkpoint_store {
+            let checkpoint = store
+                .load()
+                .await
+                .map_err(|e| ClientError::store("load checkpoint", e))?;
+            if let Some(height) = checkpoint {
+                if height > self.last_processed_height {
+                    tracing::info!("Resuming from stored checkpoint at height {}", height);
+                    self.last_processed_height = height;
+                    self.last_checkpoint_height = height;
+                }
+            }
+        }
+        if let Some(delivered_events) = &mut self.delivered_events {
+            delivered_events
+                .load()
+                .await
+                .map_err(|e| ClientError::store("load delivered events", e))?;
+        }
+        self.metrics
+            .set_last_processed_height(self.last_processed_height);
+        if let BackpressurePolicy::Spill(queue) = &self.backpressure {
+            self.spilled = queue
+                .peek()
+                .await
+                .map_err(|e| ClientError::store("read spill queue", e))?
+                .is_some();
+        }
+
+        let mut status_check_interval = self.config.synced_status_interval;
+        let mut next_status_check = Instant::now();
+        let mut latest_height = 0;
+        let mut saturated_since: Option<Instant> = None;
+        let mut consecutive_errors = 0;
+        // failures of the sink or a store, backed off without failing over the endpoint
+        let mut consecutive_delivery_errors = 0;
+
+        // heights of new contract txs or blocks pushed by the WebSocket subscription, if enabled
+        let (tx_height_sender, mut tx_heights) = watch::channel(0u64);
+        let tx_height_sender = Arc::new(tx_height_sender);
+        let _subscriptions: Vec<_> = match (&self.ws_url, self.ws_trigger) {
+            (Some(ws_url), WsTrigger::ContractTxs) => self
+                .contract_addresses
+                .iter()
+                .map(|contract_address| {
+                    let query = Query::from(EventType::Tx)
+                        .and_eq("wasm._contract_address", contract_address.as_str());
+                    AbortOnDrop(tokio::spawn(subscribe_heights(
+                        ws_url.clone(),
+                        query,
+                        tx_height_sender.clone(),
+                    )))
+                })
+                .collect(),
+            (Some(ws_url), WsTrigger::NewBlocks) => {
+                vec![AbortOnDrop(tokio::spawn(subscribe_heights(
+                    ws_url.clone(),
+                    Query::from(EventType::NewBlock),
+                    tx_height_sender.clone(),
+                )))]
+            }
+            (None, _) => vec![],
+        };
+
+        loop {
+            let seek_height = self
+                .control
+                .seek_height
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .take();
+            if let Some(height) = seek_height {
+                if let Err(e) = self.seek(height).await {
+                    tracing::error!("Failed to move to height {}: {:#}", height, e);
+                    self.metrics.set_last_error(&e);
+                }
+            }
+
+            if let Some(stop_height) = stop_height {
+                if self.last_processed_height >= stop_height {
+                    self.checkpoint(self.last_processed_height).await?;
+                    tracing::info!("Reached stop height {}", stop_height);
+                    return Ok(());
+                }
+            }
+
+            if let Err(e) = self.drain_spilled().await {
+                tracing::error!("Failed to deliver spilled events: {}", e);
+            }
+
+            // Don't fetch and parse new blocks while the consumer is saturated
+            if !matches!(self.backpressure, BackpressurePolicy::Spill(_))
+                && self.consumer_saturated()
+            {
+                if saturated_since.is_none() {
+                    tracing::warn!(
+                        "Event channel above high-water mark, pausing at height {}",
+                        self.last_processed_height
+                    );
+                    saturated_since = Some(Instant::now());
+                }
+                tokio::time::sleep(SATURATED_POLL_INTERVAL).await;
+                continue;
+            }
+            if let Some(since) = saturated_since.take() {
+                tracing::info!("Event consumer recovered after {:?}", since.elapsed());
+            }
+
+            self.rpc.retry_primary(self.config.primary_retry_interval);
+            self.metrics.set_rpc_endpoint(self.rpc.active_url());
+            let now = Instant::now();
+
+            // Only check status when it's time
+            if now >= next_status_check {
+                let status = match self.rpc.client().status().await {
+                    Ok(status) => status,
+                    Err(e) => {
+                        tracing::error!(
+                            "Failed to get status from {}: {}",
+                            self.rpc.active_url(),
+                            e
+                        );
+                        self.metrics.set_last_error(&e);
+                        self.rpc.record_failure(self.config.failover_threshold);
+                        self.metrics.record_rpc_error();
+                        consecutive_errors += 1;
+                        tokio::time::sleep(self.config.error_backoff.delay(consecutive_errors))
+                            .await;
+                        continue;
+                    }
+                };
+                self.rpc.record_success();
+                consecutive_errors = 0;
+                latest_height = status.sync_info.latest_block_height.value();
+                self.metrics.set_latest_height(latest_height);
+
+                // Dynamically adjust the next check interval based on the lag
+                let blocks_behind = latest_height.saturating_sub(self.last_processed_height);
+                status_check_interval = self.config.status_interval(blocks_behind);
+
+                // Use the calculated status_check_interval to set the next check time
+                next_status_check = now + status_check_interval;
+                tracing::info!(
+                    "Latest height: {}, blocks behind: {}, next check in {:?}",
+                    latest_height,
+                    blocks_behind,
+                    status_check_interval
+                );
+            }
+
+            // If there are still blocks to process
+            let target_height = stop_height.map_or(latest_height, |h| latest_height.min(h));
+            if target_height > self.last_processed_height {
+                let from = self.last_processed_height + 1;
+                let to = target_height.min(from + CATCH_UP_BATCH_SIZE - 1);
+                if let Err(e) = self.process_blocks(from, to).await {
+                    tracing::error!("Error processing blocks {}..={}: {}", from, to, e);
+                    if let ClientError::Pruned { .. } = e {
+                        self.metrics.set_last_error(&e);
+                        return Err(e);
+                    }
+                    self.record_error(&e);
+                    let attempt = match is_endpoint_error(&e) {
+                        true => {
+                            consecutive_errors += 1;
+                            consecutive_errors
+                        }
+                        false => {
+                            consecutive_delivery_errors += 1;
+                            consecutive_delivery_errors
+                        }
+                    };
+                    tokio::time::sleep(self.config.error_backoff.delay(attempt)).await;
+                    continue;
+                }
+                self.rpc.record_success();
+                consecutive_errors = 0;
+                consecutive_delivery_errors = 0;
+            } else {
+                if self.checkpoint_due(self.last_processed_height) {
+                    if let Err(e) = self.checkpoint(self.last_processed_height).await {
+                        tracing::error!("{:#}", e);
+                    }
+                }
+
+                // already sync to latest, sleep until the next status check or a new contract tx
+                tokio::select! {
+                    _ = tokio::time::sleep(status_check_interval) => {}
+                    _ = self.control.notify.notified() => {}
+                    Ok(()) = tx_heights.changed() => {
+                        let tx_height = *tx_heights.borrow_and_update();
+                        latest_height = latest_height.max(tx_height);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Records an error of `process_blocks`, counted toward the failover of the
+    /// active RPC endpoint only if the endpoint failed, not the sink or a store
+    fn record_error(&mut self, e: &ClientError) {
+        self.metrics.set_last_error(e);
+        if is_endpoint_error(e) {
+            self.rpc.record_failure(self.config.failover_threshold);
+            self.metrics.record_rpc_error();
+        }
+    }
+
+    /// Runs the listener in a background task and returns its events as a stream,
+    /// instead of the sink. The stream ends when the listener stops, and dropping
+    /// it stops the listener.
+    pub fn into_stream(mut self) -> impl futures::Stream<Item = BlockEvents> {
+        let buffer = self
+            .sink
+            .capacity()
+            .map_or(DEFAULT_STREAM_BUFFER, |(max_capacity, _)| max_capacity);
+        let (event_sender, event_receiver) = mpsc::channel(buffer);
+        self.sink = Box::new(event_sender.clone());
+
+        tokio::spawn(async move {
+            tokio::select! {
+                result = self.start() => {
+                    if let Err(e) = result {
+                        tracing::error!("Event listener stopped: {}", e);
+                    }
+                }
+                _ = event_sender.closed() => {
+                    tracing::info!("Event stream dropped, stopping listener");
+                }
+            }
+        });
+
+        tokio_stream::wrappers::ReceiverStream::new(event_receiver)
+    }
+
+    async fn get_block_events(&self, height: u64) -> Result<FetchedBlock, ClientError> {
+        self.block_fetcher().fetch(height).await
+    }
+
+    fn block_fetcher(&self) -> BlockFetcher {
+        BlockFetcher {
+            client: self.rpc.client().clone(),
+            archive_rpc: self.archive_rpc.clone(),
+            skip_empty_blocks: self.skip_empty_blocks,
+        }
+    }
+
+    /// Fetches the blocks in `from..=to` and processes them in order, stopping at the
+    /// first error or reorg. Up to `fetch_concurrency` upcoming blocks are prefetched
+    /// while the current one is processed.
+    async fn process_blocks(&mut self, from: u64, to: u64) -> Result<(), ClientError> {
+        // fetch in spawned tasks, so upcoming blocks keep being fetched while the
+        // current one is processed, pending fetches are aborted on early return
+        let fetcher = self.block_fetcher();
+        let blocks = futures::stream::iter(from..=to)
+            .map(|height| {
+                let fetcher = fetcher.clone();
+                AbortOnDrop(tokio::spawn(async move { fetcher.fetch(height).await }))
+            })
+            .buffered(self.fetch_concurrency);
+        futures::pin_mut!(blocks);
+
+        let mut height = from;
+        while let Some(block) = blocks.next().await {
+            let block = block.map_err(|e| {
+                tracing::error!("Fetch task of block {} failed: {}", height, e);
+                ClientError::Stopped {
+                    what: format!("Fetch task of block {}", height),
+                }
+            })??;
+            self.process_block(height, block).await?;
+            if self.last_processed_height != height {
+                // rolled back, the remaining blocks are stale
+                break;
+            }
+            if !self.config.block_delay.is_zero() {
+                tokio::time::sleep(self.config.block_delay).await;
+            }
+            height += 1;
+        }
+
+        Ok(())
+    }
+
+    async fn process_block(&mut self, height: u64, block: FetchedBlock) -> Result<(), ClientError> {
+        tracing::debug!("Processing block at height: {}", height);
+
+        if let (Some(parent_hash), Some(known_hash)) =
+            (block.parent_hash, self.block_hashes.get(&(height - 1)))
+        {
+            if parent_hash != *known_hash {
+                return self.rollback(height - 1).await;
+            }
+        }
+
+        let hash = block.hash;
+
+        let mut block_events = self.collect_block_events(height, block)?;
+        let mut delivered_keys = Vec::new();
+        if let (Some(delivered_events), Some(events)) = (&self.delivered_events, &mut block_events)
+        {
+            delivered_keys = delivered_events.filter(events);
+            if events.events.is_empty() && events.finalize_block_events.is_empty() {
+                block_events = None;
+            }
+        }
+
+        // If we have any events, send them
+        if let Some(block_events) = block_events {
+            if let Some(event_store) = &self.event_store {
+                event_store
+                    .put(&block_events)
+                    .await
+                    .map_err(|e| ClientError::store("store events", e))?;
+            }
+            self.metrics.record_events(&block_events);
+            self.deliver(block_events).await?;
+        }
+        if let Some(delivered_events) = &mut self.delivered_events {
+            delivered_events
+                .record(height, delivered_keys)
+                .await
+                .map_err(|e| ClientError::store("record delivered events", e))?;
+        }
+
+        self.block_hashes.insert(height, hash);
+        self.block_hashes = self
+            .block_hashes
+            .split_off(&height.saturating_sub(REORG_WINDOW - 1));
+        self.last_processed_height = height;
+        self.metrics.record_block(height);
+
+        // event listener checkpoint
+        if self.checkpoint_due(height) {
+            if let Err(e) = self.checkpoint(height).await {
+                tracing::error!("{:#}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Moves the listener to `height` as requested by `ListenerHandle::set_height`
+    async fn seek(&mut self, height: u64) -> Result<(), ClientError> {
+        tracing::warn!(
+            "Moving from height {} to {} as requested",
+            self.last_processed_height,
+            height
+        );
+        if height < self.last_processed_height {
+            self.remove_from(height + 1).await?;
+        }
+
+        // the parent of the next block is unknown, don't mistake it for a reorg
+        self.block_hashes.clear();
+        self.last_processed_height = height;
+        self.metrics.set_last_processed_height(height);
+        self.checkpoint(height).await
+    }
+
+    /// Returns true if `height` is on a checkpoint interval boundary, or the
+    /// checkpoint period passed since the last checkpoint at a lower height
+    fn checkpoint_due(&self, height: u64) -> bool {
+        height.is_multiple_of(self.checkpoint_interval)
+            || (height > self.last_checkpoint_height
+                && self
+                    .checkpoint_period
+                    .is_some_and(|period| self.last_checkpoint_at.elapsed() >= period))
+    }
+
+    /// Stores the checkpoint and notifies the checkpoint channel, unless its
+    /// receiver was dropped
+    async fn checkpoint(&mut self, height: u64) -> Result<(), ClientError> {
+        if let Some(store) = &self.checkpoint_store {
+            store.save(height).await.map_err(|e| {
+                ClientError::store(format!("store checkpoint for height {}", height), e)
+            })?;
+        }
+        self.last_checkpoint_height = height;
+        self.last_checkpoint_at = Instant::now();
+
+        if self.checkpoint_sender.is_closed() {
+            return Ok(());
+        }
+        if let Err(e) = self.checkpoint_sender.send(height).await {
+            tracing::error!("Failed to send checkpoint for height {}: {}", height, e);
+        } else {
+            tracing::debug!("Checkpoint saved at height: {}", height);
+        }
+
+        Ok(())
+    }
+
+    /// Sends the events to the consumer according to the backpressure policy
+    async fn deliver(&mut self, block_events: BlockEvents) -> Result<(), ClientError> {
+        match &self.backpressure {
+            BackpressurePolicy::Block => self.send(block_events).await,
+            BackpressurePolicy::WarnAfter(interval) => {
+                let height = block_events.height;
+                let waiting_since = Instant::now();
+                let send = self.send(block_events);
+                tokio::pin!(send);
+                loop {
+                    match tokio::time::timeout(*interval, &mut send).await {
+                        Ok(result) => return result,
+                        Err(_) => tracing::warn!(
+                            "Event consumer hasn't taken block {} for {:?}",
+                            height,
+                            waiting_since.elapsed()
+                        ),
+                    }
+                }
+            }
+            BackpressurePolicy::Spill(queue) => {
+                // spilled events go first, keep queueing until they are delivered
+                let block_events = if self.spilled {
+                    block_events
+                } else {
+                    match self.try_send(block_events).await? {
+                        None => return Ok(()),
+                        Some(block_events) => block_events,
+                    }
+                };
+
+                tracing::debug!("Spilling events of block {}", block_events.height);
+                queue
+                    .push(&block_events)
+                    .await
+                    .map_err(|e| ClientError::store("spill events", e))?;
+                self.spilled = true;
+                Ok(())
+            }
+        }
+    }
+
+    async fn send(&self, block_events: BlockEvents) -> Result<(), ClientError> {
+        self.sink
+            .send(block_events)
+            .await
+            .map_err(|e| ClientError::sink("send block events", e))
+    }
+
+    async fn try_send(
+        &self,
+        block_events: BlockEvents,
+    ) -> Result<Option<BlockEvents>, ClientError> {
+        self.sink
+            .try_send(block_events)
+            .await
+            .map_err(|e| ClientError::sink("send block events", e))
+    }
+
+    /// Moves spilled events to the sink while it has capacity
+    async fn drain_spilled(&mut self) -> Result<(), ClientError> {
+        let BackpressurePolicy::Spill(queue) = &self.backpressure else {
+            return Ok(());
+        };
+
+        while self.spilled {
+            let spilled = queue
+                .peek()
+                .await
+                .map_err(|e| ClientError::store("read spill queue", e))?;
+            match spilled {
+                Some(block_events) => {
+                    if self.try_send(block_events).await?.is_some() {
+                        break;
+                    }
+                    queue
+                        .pop()
+                        .await
+                        .map_err(|e| ClientError::store("pop spill queue", e))?;
+                }
+                None => {
+                    tracing::info!("Delivered all spilled events");
+                    self.spilled = false;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parses the contract events of a fetched block, `None` if there are none
+    fn collect_block_events(
+        &self,
+        height: u64,
+        block: FetchedBlock,
+    ) -> Result<Option<BlockEvents>, ClientError> {
+        let mut contract_events = Vec::new();
+
+        // Collect all contract events from this block
+        for tx in &block.txs {
+            let mut message = MessageContext::default();
+            for event in &tx.events {
+                let origin = message.observe(event);
+                for (contract, contract_event) in self.contract_events(event)? {
+                    contract_events.push(tx.tx_event(contract, &message, origin, contract_event));
+                }
+            }
+        }
+
+        let mut finalize_block_events = Vec::new();
+        for event in &block.finalize_block_events {
+            for (contract, event) in self.contract_events(event)? {
+                finalize_block_events.push(FinalizeBlockEvent { contract, event });
+            }
+        }
 
-impl EventListener {
-    pub async fn new(
-        rpc_url: &str,
-        event_sender: mpsc::Sender<BlockEvents>,
-        checkpoint_sender: mpsc::Sender<u64>,
-        contract_address: &str,
-        last_processed_height: u64,
-    ) -> anyhow::Result<Self> {
-        let rpc_client = HttpClient::new(rpc_url).context("Failed to create HTTP client")?;
+        if contract_events.is_empty() && finalize_block_events.is_empty() {
+            return Ok(None);
+        }
 
-        Ok(Self {
-            rpc_client,
-            event_sender,
-            checkpoint_sender,
-            contract_address: contract_address.to_string(),
-            last_processed_height,
-        })
+        tracing::debug!(
+            "Found {} contract events at height {}: {}",
+            contract_events.len() + finalize_block_events.len(),
+            height,
+            logging::text(&format!(
+                "{:?} {:?}",
+                contract_events, finalize_block_events
+            ))
+        );
+        Ok(Some(BlockEvents {
+            height,
+            block_time: block.block_time,
+            block_hash: block.hash.to_string(),
+            proposer: block.proposer,
+            events: contract_events,
+            finalize_block_events,
+        }))
     }
-    pub async fn start(&mut self) -> anyhow::Result<()> {
-        let mut status_check_interval = Duration::from_secs(5);
-        let mut next_status_check = Instant::now();
-        let mut latest_height = 0;
 
-        loop {
-            let now = Instant::now();
+    /// Returns the parsed and raw events of a watched contract, according to the raw
+    /// event mode, together with the contract that emitted them
+    fn contract_events(
+        &self,
+        event: &abci::Event,
+    ) -> Result<Vec<(String, ContractEvent)>, ClientError> {
+        let mut contract_events = Vec::new();
+        if !self.action_wanted(event) {
+            return Ok(contract_events);
+        }
+        if self.raw_event_mode != RawEventMode::Only {
+            let invalid_event = |e: anyhow::Error| {
+                ClientError::serialization(
+                    format!("parse {} event", event.kind),
+                    format!("{:#}", e),
+                )
+            };
+            if let Some(contract_event) =
+                self.parse_lifecycle_event(event).map_err(invalid_event)?
+            {
+                let contract = event_contract(event).unwrap_or_default();
+                contract_events.push((contract, contract_event));
+            }
+            if let Some(contract_event) = self.parse_contract_event(event).map_err(invalid_event)? {
+                let contract = event_contract(event).unwrap_or_default();
+                contract_events.push((contract, contract_event));
+            }
+        }
+        if self.raw_event_mode != RawEventMode::Off {
+            if let Some(contract) = self.watched_wasm_event(event) {
+                contract_events.push((contract, ContractEvent::Raw(raw_event(event))));
+            }
+        }
 
-            // Only check status when it's time
-            if now >= next_status_check {
-                let status = self.rpc_client.status().await?;
-                latest_height = status.sync_info.latest_block_height.value();
+        contract_events.retain(|(_, contract_event)| {
+            self.event_filters
+                .iter()
+                .all(|filter| filter(contract_event))
+        });
 
-                // Dynamically adjust the next check interval based on the lag
-                let blocks_behind = latest_height.saturating_sub(self.last_processed_height);
-                status_check_interval = match blocks_behind {
-                    0..=10 => Duration::from_secs(5), // close to sync, 5 seconds query once
-                    11..=100 => Duration::from_secs(15), // lag more, 15 seconds query once
-                    _ => Duration::from_secs(30),     // lag more, 30 seconds query once
-                };
+        Ok(contract_events)
+    }
 
-                // Use the calculated status_check_interval to set the next check time
-                next_status_check = now + status_check_interval;
-                tracing::info!(
-                    "Latest height: {}, blocks behind: {}, next check in {:?}",
-                    latest_height,
-                    blocks_behind,
-                    status_check_interval
-                );
-            }
+    /// Scans the blocks in `from..=to` and returns their contract events in order.
+    /// Unlike `start()` this terminates, and neither sends events nor checkpoints,
+    /// e.g. to rebuild an index without running a live listener.
+    pub async fn backfill(&self, from: u64, to: u64) -> Result<Vec<BlockEvents>, ClientError> {
+        let mut block_events = Vec::new();
+        if from > to {
+            return Ok(block_events);
+        }
 
-            // If there are still blocks to process
-            if latest_height > self.last_processed_height {
-                let next_height = self.last_processed_height + 1;
-                if let Err(e) = self.process_block(next_height).await {
-                    tracing::error!("Error processing block {}: {}", next_height, e);
-                    tokio::time::sleep(Duration::from_secs(1)).await;
-                    continue;
-                }
-                self.last_processed_height = next_height;
-            } else {
-                // already sync to latest, sleep a short time
-                tokio::time::sleep(status_check_interval).await;
+        let blocks = futures::stream::iter(from..=to)
+            .map(|height| async move { (height, self.get_block_events(height).await) })
+            .buffered(self.fetch_concurrency);
+        futures::pin_mut!(blocks);
+
+        while let Some((height, block)) = blocks.next().await {
+            if let Some(events) = self.collect_block_events(height, block?)? {
+                block_events.push(events);
             }
         }
+        tracing::info!(
+            "Backfilled {} blocks with events in {}..={}",
+            block_events.len(),
+            from,
+            to
+        );
+
+        Ok(block_events)
     }
-    async fn get_block_events(
-        &self,
-        height: u64,
-    ) -> anyhow::Result<(u64, Vec<(String, Vec<abci::Event>)>)> {
-        let height = Height::try_from(height).context("Failed to convert height")?;
 
-        // get block and block results
-        let block = self.rpc_client.block(height).await?;
-        let block_results = self.rpc_client.block_results(height).await?;
+    /// Like `backfill`, but finds the contract txs with paginated `tx_search` queries
+    /// instead of scanning every block, which is far cheaper for sparse contracts.
+    /// Requires the node to index txs.
+    pub async fn backfill_tx_search(
+        &self,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<BlockEvents>, ClientError> {
+        let mut block_events = Vec::new();
+        if from > to {
+            return Ok(block_events);
+        }
 
-        let mut tx_events = Vec::new();
+        // txs by height and index, a tx touching several contracts is found more than once
+        let mut txs: BTreeMap<u64, BTreeMap<u32, FetchedTx>> = BTreeMap::new();
+        for contract_address in &self.contract_addresses {
+            let query = Query::from(EventType::Tx)
+                .and_eq("wasm._contract_address", contract_address.as_str())
+                .and_gte("tx.height", from)
+                .and_lte("tx.height", to);
 
-        if let Some(tx_results) = block_results.txs_results {
-            let txs = block.block.data;
+            let mut page = 1;
+            let mut found = 0;
+            loop {
+                let response = self
+                    .rpc
+                    .client()
+                    .tx_search(
+                        query.clone(),
+                        false,
+                        page,
+                        TX_SEARCH_PAGE_SIZE,
+                        Order::Ascending,
+                    )
+                    .await
+                    .map_err(|e| {
+                        ClientError::rpc(format!("search txs of {}", contract_address), e)
+                    })?;
 
-            if txs.len() == tx_results.len() {
-                for (i, tx) in txs.iter().enumerate() {
-                    if let Some(result) = tx_results.get(i) {
-                        let tx_hash = calculate_tx_hash(tx);
-                        tx_events.push((tx_hash, result.events.clone()));
-                    }
+                let page_len = response.txs.len();
+                found += page_len;
+                for tx in response.txs {
+                    txs.entry(tx.height.value())
+                        .or_default()
+                        .entry(tx.index)
+                        .or_insert_with(|| FetchedTx {
+                            hash: TxHash::from_tx_bytes(&tx.tx),
+                            index: tx.index,
+                            code: tx.tx_result.code.value(),
+                            gas_used: tx.tx_result.gas_used,
+                            events: tx.tx_result.events,
+                        });
+                }
+                if page_len == 0 || found >= response.total_count as usize {
+                    break;
                 }
+                page += 1;
+            }
+        }
+
+        let blocks = futures::stream::iter(txs)
+            .map(|(height, txs)| async move {
+                let block = self
+                    .rpc
+                    .client()
+                    .block(tm_height(height)?)
+                    .await
+                    .map_err(|e| ClientError::rpc(format!("fetch block {}", height), e))?;
+                Ok::<_, ClientError>((height, fetched_block(block, txs.into_values().collect())))
+            })
+            .buffered(self.fetch_concurrency);
+        futures::pin_mut!(blocks);
+
+        while let Some(block) = blocks.next().await {
+            let (height, block) = block?;
+            if let Some(events) = self.collect_block_events(height, block)? {
+                block_events.push(events);
             }
         }
+        tracing::info!(
+            "Backfilled {} blocks with events in {}..={} using tx_search",
+            block_events.len(),
+            from,
+            to
+        );
 
-        // Return block time along with events
-        let block_time = block.block.header.time.unix_timestamp() as u64;
-        Ok((block_time, tx_events))
+        Ok(block_events)
     }
 
-    async fn process_block(&self, height: u64) -> anyhow::Result<()> {
-        tracing::debug!("Processing block at height: {}", height);
-        let (block_time, tx_events) = self.get_block_events(height).await?;
-        let mut contract_events = Vec::new();
+    /// Re-emits the stored events in `from..=to` from the event store, without
+    /// hitting the RPC node. Returns the number of blocks sent.
+    pub async fn replay(
+        &self,
+        from: u64,
+        to: u64,
+        sink: &impl EventSink,
+    ) -> Result<usize, ClientError> {
+        let event_store = self
+            .event_store
+            .as_ref()
+            .ok_or_else(|| ClientError::not_found("event store to replay from"))?;
 
-        // Collect all contract events from this block
-        for (tx_hash, events) in tx_events {
-            for event in events {
-                if let Some(contract_event) = self.parse_contract_event(&event)? {
-                    contract_events.push((tx_hash.clone(), contract_event));
-                }
+        let mut replayed = 0;
+        let mut chunk_start = from;
+        while chunk_start <= to {
+            let chunk_end = to.min(chunk_start.saturating_add(REPLAY_CHUNK_SIZE - 1));
+            let stored = event_store
+                .range(chunk_start, chunk_end)
+                .await
+                .map_err(|e| ClientError::store("read stored events", e))?;
+            for block_events in stored {
+                sink.send(block_events)
+                    .await
+                    .map_err(|e| ClientError::sink("send replayed events", e))?;
+                replayed += 1;
+            }
+            if chunk_end == u64::MAX {
+                break;
             }
+            chunk_start = chunk_end + 1;
         }
+        tracing::info!(
+            "Replayed {} blocks with events in {}..={}",
+            replayed,
+            from,
+            to
+        );
 
-        // If we have any events, send them
-        if !contract_events.is_empty() {
-            let block_events = BlockEvents {
-                height,
-                block_time,
-                events: contract_events,
-            };
-            self.event_sender
-                .send(block_events)
+        Ok(replayed)
+    }
+
+    /// Rolls back to the highest known block still on the canonical chain, starting
+    /// from `height`, and notifies consumers that the blocks above it are invalid
+    async fn rollback(&mut self, height: u64) -> Result<(), ClientError> {
+        let mut fork_point = self
+            .block_hashes
+            .keys()
+            .next()
+            .map_or(0, |h| h.saturating_sub(1));
+
+        for (&known_height, known_hash) in self.block_hashes.range(..=height).rev() {
+            let block = self
+                .rpc
+                .client()
+                .block(tm_height(known_height)?)
                 .await
-                .map_err(|e| anyhow!("Failed to send block events: {}", e))?;
+                .map_err(|e| ClientError::rpc(format!("fetch block {}", known_height), e))?;
+            if block.block_id.hash == *known_hash {
+                fork_point = known_height;
+                break;
+            }
         }
 
-        // event listener checkpoint
-        if height % 10 == 0 {
-            if let Err(e) = self.checkpoint_sender.send(height).await {
-                tracing::error!("Failed to send checkpoint for height {}: {}", height, e);
-            } else {
-                tracing::debug!("Checkpoint saved at height: {}", height);
-            }
+        let reorg = Reorg {
+            from_height: fork_point + 1,
+        };
+        tracing::warn!(
+            "Reorg detected at height {}, rolling back to height {}",
+            height + 1,
+            fork_point
+        );
+
+        self.block_hashes.split_off(&reorg.from_height);
+        self.last_processed_height = fork_point;
+        self.metrics.set_last_processed_height(fork_point);
+        if let Some(store) = &self.checkpoint_store {
+            store.save(fork_point).await.map_err(|e| {
+                ClientError::store(format!("store checkpoint for height {}", fork_point), e)
+            })?;
         }
+        self.remove_from(reorg.from_height).await?;
+        if let Some(reorg_sender) = &self.reorg_sender {
+            reorg_sender
+                .send(reorg)
+                .await
+                .map_err(|e| ClientError::sink("send reorg notification", e))?;
+        }
+
+        Ok(())
+    }
 
+    /// Forgets the delivered, stored and spilled events from `height` on
+    async fn remove_from(&mut self, height: u64) -> Result<(), ClientError> {
+        if let Some(delivered_events) = &mut self.delivered_events {
+            delivered_events
+                .rollback(height)
+                .await
+                .map_err(|e| ClientError::store("roll back delivered events", e))?;
+        }
+        if let Some(event_store) = &self.event_store {
+            event_store
+                .remove_from(height)
+                .await
+                .map_err(|e| ClientError::store("remove stored events", e))?;
+        }
+        if let BackpressurePolicy::Spill(queue) = &self.backpressure {
+            queue
+                .remove_from(height)
+                .await
+                .map_err(|e| ClientError::store("remove spilled events", e))?;
+            self.spilled = queue
+                .peek()
+                .await
+                .map_err(|e| ClientError::store("read spill queue", e))?
+                .is_some();
+        }
         Ok(())
     }
 
+    /// Returns true if the event's action passes the configured action filter
+    fn action_wanted(&self, event: &abci::Event) -> bool {
+        self.actions
+            .as_ref()
+            .is_none_or(|actions| actions.contains(&self.event_action(event)))
+    }
+
+    /// Returns the action of the event as named by `ContractEvent::action`, e.g.
+    /// `cw20_transfer` for a `transfer` of the CW20 token. Events unknown to the
+    /// parser keep their `action` attribute, or their event type without one.
+    fn event_action(&self, event: &abci::Event) -> String {
+        let action = event
+            .attributes
+            .iter()
+            .find(|attr| attr.key_bytes() == b"action")
+            .and_then(|attr| attr.value_str().ok());
+        let Some(action) = action else {
+            return match event.kind.as_str() {
+                "update_contract_admin" => "update_admin",
+                kind => kind,
+            }
+            .to_string();
+        };
+
+        let cw20 = event.kind == "wasm"
+            && self.cw20_contract.is_some()
+            && event_contract(event) == self.cw20_contract;
+        match action {
+            "transfer" | "send" if cw20 => "cw20_transfer",
+            "mint" if cw20 => "cw20_mint",
+            "burn" if cw20 => "cw20_burn",
+            action => action,
+        }
+        .to_string()
+    }
+
+    /// Returns the emitting contract if the event is a wasm event of a watched contract
+    fn watched_wasm_event(&self, event: &abci::Event) -> Option<String> {
+        if event.kind != "wasm" && !event.kind.starts_with("wasm-") {
+            return None;
+        }
+        event_contract(event).filter(|contract| self.contract_addresses.contains(contract))
+    }
+
+    /// Parses the `instantiate`, `migrate` and `update_contract_admin` events wasmd
+    /// emits for the watched contracts
+    fn parse_lifecycle_event(&self, event: &abci::Event) -> Result<Option<ContractEvent>> {
+        if !matches!(
+            event.kind.as_str(),
+            "instantiate" | "migrate" | "update_contract_admin"
+        ) || !event_contract(event)
+            .is_some_and(|contract| self.contract_addresses.contains(&contract))
+        {
+            return Ok(None);
+        }
+
+        let attr = |key: &str| {
+            event
+                .attributes
+                .iter()
+                .find(|attr| attr.key_bytes() == key.as_bytes())
+                .and_then(|attr| attr.value_str().ok())
+        };
+        // chains before Cosmos SDK 0.50 don't index events by message
+        let msg_index = attr("msg_index")
+            .map(|msg_index| msg_index.parse::<u32>())
+            .transpose()
+            .map_err(|e| anyhow!("Failed to parse msg_index: {}", e))?
+            .unwrap_or_default();
+        let code_id = || {
+            attr("code_id")
+                .ok_or_else(|| anyhow!("Missing code_id"))?
+                .parse::<u64>()
+                .map_err(|e| anyhow!("Failed to parse code_id: {}", e))
+        };
+
+        let event = match event.kind.as_str() {
+            "instantiate" => ContractEvent::Instantiate(InstantiateEvent {
+                msg_index,
+                code_id: code_id()?,
+            }),
+            "migrate" => ContractEvent::Migrate(MigrateEvent {
+                msg_index,
+                code_id: code_id()?,
+            }),
+            _ => ContractEvent::UpdateAdmin(UpdateAdminEvent {
+                msg_index,
+                new_admin: attr("new_admin_address")
+                    .filter(|admin| !admin.is_empty())
+                    .map(str::to_string),
+            }),
+        };
+        Ok(Some(event))
+    }
+
     /// Parse blockchain events into ContractEvent
     fn parse_contract_event(&self, event: &abci::Event) -> Result<Option<ContractEvent>> {
         if event.kind != "wasm" {
@@ -198,10 +1985,19 @@ impl EventListener {
             })
             .collect();
 
-        // Skip if not our contract or not a relevant action
-        if attrs.get("_contract_address") != Some(&self.contract_address)
-            || (attrs.get("action") != Some(&"peg_out".to_string())
-                && attrs.get("action") != Some(&"peg_in".to_string()))
+        let Some(contract) = attrs
+            .get("_contract_address")
+            .filter(|contract| self.contract_addresses.contains(*contract))
+        else {
+            return Ok(None);
+        };
+        if self.cw20_contract.as_ref() == Some(contract) {
+            return parse_cw20_event(&attrs);
+        }
+
+        // Skip if not a relevant action
+        if attrs.get("action") != Some(&"peg_out".to_string())
+            && attrs.get("action") != Some(&"peg_in".to_string())
         {
             return Ok(None);
         }
@@ -263,11 +2059,718 @@ impl EventListener {
     }
 }
 
-// Calculate transaction hash
-fn calculate_tx_hash(tx: &[u8]) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(tx);
-    let hash = hasher.finalize();
-    let tx_hash = hex::encode(hash);
-    tx_hash
+/// Parses the attributes of a CW20 `wasm` event, as emitted by cw20-base
+fn parse_cw20_event(
+    attrs: &std::collections::HashMap<&str, String>,
+) -> Result<Option<ContractEvent>> {
+    let action = attrs.get("action").map(String::as_str);
+    if !matches!(action, Some("transfer" | "send" | "mint" | "burn")) {
+        return Ok(None);
+    }
+
+    let attr = |key: &str| {
+        attrs
+            .get(key)
+            .cloned()
+            .ok_or_else(|| anyhow!("Missing {} in CW20 event", key))
+    };
+    let amount = attr("amount")?
+        .parse::<u128>()
+        .map_err(|e| anyhow!("Failed to parse amount: {}", e))?;
+    let msg_index = attr("msg_index")?
+        .parse::<u32>()
+        .map_err(|e| anyhow!("Failed to parse msg_index: {}", e))?;
+
+    let event = match action {
+        Some("mint") => ContractEvent::Cw20Mint(Cw20MintEvent {
+            msg_index,
+            to: attr("to")?,
+            amount,
+        }),
+        Some("burn") => ContractEvent::Cw20Burn(Cw20BurnEvent {
+            msg_index,
+            from: attr("from")?,
+            amount,
+        }),
+        _ => ContractEvent::Cw20Transfer(Cw20TransferEvent {
+            msg_index,
+            from: attr("from")?,
+            to: attr("to")?,
+            amount,
+        }),
+    };
+    Ok(Some(event))
+}
+
+/// Fetches blocks with their results, cloned into the fetch tasks
+#[derive(Clone)]
+struct BlockFetcher {
+    client: HttpClient,
+    /// archive_rpc serves the blocks pruned by the client's node
+    archive_rpc: Option<HttpClient>,
+    /// skip_empty_blocks skips the `block_results` request for blocks without txs
+    skip_empty_blocks: bool,
+}
+
+impl BlockFetcher {
+    /// Fetches a block, falling back to the archive node if the client's node pruned it
+    async fn fetch(&self, height: u64) -> Result<FetchedBlock, ClientError> {
+        let fetch_error = |e| ClientError::rpc(format!("fetch block {}", height), e);
+        let tm_height = tm_height(height)?;
+        match self.fetch_from(&self.client, tm_height).await {
+            Err(e) if is_pruned_error(&e.to_string()) => match &self.archive_rpc {
+                Some(archive_rpc) => {
+                    tracing::debug!(
+                        "Block {} is pruned, fetching it from the archive node",
+                        height
+                    );
+                    self.fetch_from(archive_rpc, tm_height)
+                        .await
+                        .map_err(fetch_error)
+                }
+                None => Err(ClientError::Pruned { height }),
+            },
+            result => result.map_err(fetch_error),
+        }
+    }
+
+    async fn fetch_from(
+        &self,
+        client: &HttpClient,
+        height: Height,
+    ) -> Result<FetchedBlock, tendermint_rpc::Error> {
+        let (block, block_results) = if self.skip_empty_blocks {
+            let block = client.block(height).await?;
+            if block.block.data.is_empty() {
+                return Ok(fetched_block(block, Vec::new()));
+            }
+            (block, client.block_results(height).await?)
+        } else {
+            futures::try_join!(client.block(height), client.block_results(height))?
+        };
+
+        let mut fetched_txs = Vec::new();
+
+        if let Some(tx_results) = block_results.txs_results {
+            let txs = &block.block.data;
+
+            if txs.len() == tx_results.len() {
+                for (i, (tx, result)) in txs.iter().zip(tx_results).enumerate() {
+                    fetched_txs.push(FetchedTx {
+                        hash: TxHash::from_tx_bytes(tx),
+                        index: i as u32,
+                        code: result.code.value(),
+                        gas_used: result.gas_used,
+                        events: result.events,
+                    });
+                }
+            }
+        }
+
+        let mut fetched_block = fetched_block(block, fetched_txs);
+        fetched_block.finalize_block_events = block_results
+            .begin_block_events
+            .unwrap_or_default()
+            .into_iter()
+            .chain(block_results.finalize_block_events)
+            .chain(block_results.end_block_events.unwrap_or_default())
+            .collect();
+
+        Ok(fetched_block)
+    }
+}
+
+/// Returns true if the error comes from the RPC endpoint, as opposed to the sink
+/// or a store of the listener
+fn is_endpoint_error(e: &ClientError) -> bool {
+    matches!(e, ClientError::Rpc { .. } | ClientError::Connection { .. })
+}
+
+/// Returns true if the node's error says the requested height was pruned
+fn is_pruned_error(message: &str) -> bool {
+    // CometBFT: "height 5 is not available, lowest height is 100" for blocks
+    // and "could not find results for height #5" for block results
+    message.contains("is not available, lowest height is")
+        || message.contains("could not find results for height")
+}
+
+fn fetched_block(
+    block: tendermint_rpc::endpoint::block::Response,
+    txs: Vec<FetchedTx>,
+) -> FetchedBlock {
+    FetchedBlock {
+        block_time: block.block.header.time.unix_timestamp() as u64,
+        hash: block.block_id.hash,
+        proposer: block.block.header.proposer_address.to_string(),
+        parent_hash: block.block.header.last_block_id.map(|id| id.hash),
+        txs,
+        finalize_block_events: Vec::new(),
+    }
+}
+
+/// Returns the address of the contract that emitted the wasm event
+fn event_contract(event: &abci::Event) -> Option<String> {
+    event
+        .attributes
+        .iter()
+        .find(|attr| attr.key_bytes() == b"_contract_address")
+        .and_then(|attr| attr.value_str().ok())
+        .map(str::to_string)
+}
+
+fn raw_event(event: &abci::Event) -> RawEvent {
+    RawEvent {
+        kind: event.kind.clone(),
+        attributes: event
+            .attributes
+            .iter()
+            .filter_map(|attr| {
+                attr.key_str()
+                    .ok()
+                    .zip(attr.value_str().ok())
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+            })
+            .collect(),
+    }
+}
+
+/// Aborts the task when dropped, so it doesn't outlive `start()`
+struct AbortOnDrop<T = ()>(JoinHandle<T>);
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+impl<T> std::future::Future for AbortOnDrop<T> {
+    type Output = std::result::Result<T, tokio::task::JoinError>;
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        std::pin::Pin::new(&mut self.0).poll(cx)
+    }
+}
+
+/// Keeps a WebSocket subscription alive, reconnecting on failure
+async fn subscribe_heights(ws_url: String, query: Query, heights: Arc<watch::Sender<u64>>) {
+    loop {
+        if let Err(e) = forward_heights(&ws_url, &query, &heights).await {
+            tracing::warn!(
+                "WebSocket subscription failed, falling back to polling: {:?}",
+                e
+            );
+        }
+        tokio::time::sleep(WS_RECONNECT_DELAY).await;
+    }
+}
+
+/// Forwards the height of every tx or block received on the subscription
+async fn forward_heights(
+    ws_url: &str,
+    query: &Query,
+    heights: &watch::Sender<u64>,
+) -> Result<(), ClientError> {
+    let (client, driver) = WebSocketClient::new(ws_url)
+        .await
+        .map_err(|e| ClientError::connection(ws_url, e))?;
+    let _driver = AbortOnDrop(tokio::spawn(async move {
+        if let Err(e) = driver.run().await {
+            tracing::warn!("WebSocket driver stopped: {}", e);
+        }
+    }));
+
+    let mut subscription = client
+        .subscribe(query.clone())
+        .await
+        .map_err(|e| ClientError::rpc(format!("subscribe to {}", query), e))?;
+    tracing::info!("Subscribed to {}", query);
+
+    while let Some(event) = subscription.next().await {
+        let event = event.map_err(|e| ClientError::rpc("receive WebSocket event", e))?;
+        match event.data {
+            EventData::Tx { tx_result } => {
+                tracing::debug!("Contract tx committed at height {}", tx_result.height);
+                heights.send_replace(tx_result.height as u64);
+            }
+            EventData::NewBlock {
+                block: Some(block), ..
+            }
+            | EventData::LegacyNewBlock {
+                block: Some(block), ..
+            } => {
+                let height = block.header.height.value();
+                tracing::debug!(
+                    "New block at height {} with {} txs",
+                    height,
+                    block.data.len()
+                );
+                heights.send_replace(height);
+            }
+            _ => {}
+        }
+    }
+
+    Err(ClientError::Stopped {
+        what: "WebSocket subscription".to_string(),
+    })
+}
+
+fn tm_height(height: u64) -> Result<Height, ClientError> {
+    Height::try_from(height).map_err(|e| ClientError::invalid("height", e))
+}
+
+fn is_saturated(max_capacity: usize, capacity: usize, high_water_mark: f64) -> bool {
+    let queued = max_capacity.saturating_sub(capacity);
+    queued as f64 >= max_capacity as f64 * high_water_mark
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spill::FileSpillQueue;
+
+    const CONTRACT: &str = "bbn17p9rzwnnfxcjp32un9ug7yhhzgtkhvl9jfksztgw5uh69wac2pgs6spw0g";
+
+    async fn test_listener() -> EventListener {
+        let (event_sender, _) = mpsc::channel(1);
+        let (checkpoint_sender, _) = mpsc::channel(1);
+        EventListener::new(
+            "http://localhost:26657",
+            event_sender,
+            checkpoint_sender,
+            CONTRACT,
+            0,
+        )
+        .await
+        .unwrap()
+    }
+
+    fn wasm_event(attrs: &[(&str, &str)]) -> abci::Event {
+        abci::Event::new(
+            "wasm",
+            attrs
+                .iter()
+                .map(|(k, v)| abci::EventAttribute::from((*k, *v, true))),
+        )
+    }
+
+    fn test_block(events: Vec<abci::Event>) -> FetchedBlock {
+        FetchedBlock {
+            block_time: 0,
+            hash: Hash::None,
+            proposer: String::new(),
+            parent_hash: None,
+            txs: vec![FetchedTx {
+                hash: TxHash::from_tx_bytes(b"tx"),
+                index: 0,
+                code: 0,
+                gas_used: 0,
+                events,
+            }],
+            finalize_block_events: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_collect_block_events() {
+        let peg_in = wasm_event(&[
+            ("_contract_address", CONTRACT),
+            ("action", "peg_in"),
+            ("receiver", "bbn1receiver"),
+            ("amount", "1000"),
+            ("msg_index", "0"),
+        ]);
+        let unknown = wasm_event(&[("_contract_address", CONTRACT), ("action", "pause")]);
+        let other = wasm_event(&[("_contract_address", "bbn1other"), ("action", "peg_in")]);
+        let events = vec![peg_in, unknown, other];
+
+        let listener = test_listener().await;
+        let block_events = listener
+            .collect_block_events(1, test_block(events.clone()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(block_events.events.len(), 1);
+        assert_eq!(block_events.events[0].contract, CONTRACT);
+        assert!(matches!(
+            &block_events.events[0].event,
+            ContractEvent::PegIn(PegInEvent { amount: 1000, .. })
+        ));
+
+        // events emitted outside of txs, e.g. by a sudo call
+        let mut block = test_block(vec![]);
+        block.finalize_block_events = vec![events[0].clone()];
+        let block_events = listener.collect_block_events(1, block).unwrap().unwrap();
+        assert!(block_events.events.is_empty());
+        assert_eq!(block_events.finalize_block_events.len(), 1);
+
+        let listener = test_listener().await.with_event_filter(
+            |event| matches!(event, ContractEvent::PegIn(peg_in) if peg_in.amount > 1000),
+        );
+        let block_events = listener
+            .collect_block_events(1, test_block(events.clone()))
+            .unwrap();
+        assert!(block_events.is_none());
+
+        let listener = test_listener().await.with_actions(["peg_out"]);
+        let block_events = listener
+            .collect_block_events(1, test_block(events.clone()))
+            .unwrap();
+        assert!(block_events.is_none());
+
+        let listener = test_listener().await.with_raw_events(RawEventMode::Only);
+        let block_events = listener
+            .collect_block_events(1, test_block(events))
+            .unwrap()
+            .unwrap();
+        assert_eq!(block_events.events.len(), 2);
+        assert!(matches!(
+            &block_events.events[1].event,
+            ContractEvent::Raw(RawEvent { kind, .. }) if kind == "wasm"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cw20_events() {
+        const TOKEN: &str = "bbn1token";
+        let transfer = wasm_event(&[
+            ("_contract_address", TOKEN),
+            ("action", "transfer"),
+            ("from", "bbn1from"),
+            ("to", "bbn1to"),
+            ("amount", "42"),
+            ("msg_index", "0"),
+        ]);
+        let burn = wasm_event(&[
+            ("_contract_address", TOKEN),
+            ("action", "burn"),
+            ("from", "bbn1from"),
+            ("amount", "7"),
+            ("msg_index", "1"),
+        ]);
+
+        let listener = test_listener().await;
+        let block_events = listener
+            .collect_block_events(1, test_block(vec![transfer.clone(), burn.clone()]))
+            .unwrap();
+        assert!(block_events.is_none());
+
+        let listener = test_listener().await.with_cw20_token(TOKEN);
+        let block_events = listener
+            .collect_block_events(1, test_block(vec![transfer.clone(), burn.clone()]))
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            &block_events.events[0].event,
+            ContractEvent::Cw20Transfer(Cw20TransferEvent { amount: 42, to, .. }) if to == "bbn1to"
+        ));
+        assert!(matches!(
+            &block_events.events[1].event,
+            ContractEvent::Cw20Burn(Cw20BurnEvent {
+                amount: 7,
+                msg_index: 1,
+                ..
+            })
+        ));
+
+        // actions are named as by `ContractEvent::action`
+        let listener = test_listener()
+            .await
+            .with_cw20_token(TOKEN)
+            .with_actions(["cw20_burn"]);
+        let block_events = listener
+            .collect_block_events(1, test_block(vec![transfer, burn]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(block_events.events.len(), 1);
+        assert_eq!(block_events.events[0].event.action(), "cw20_burn");
+    }
+
+    #[tokio::test]
+    async fn test_builder() {
+        let (event_sender, _) = mpsc::channel(1);
+        let builder = || {
+            EventListener::builder("http://localhost:26657")
+                .with_event_sender(event_sender.clone())
+                .with_start_height(100)
+        };
+
+        let error = builder().build().err().unwrap();
+        assert!(error.to_string().contains("contract address"));
+        let error = builder()
+            .with_contract(CONTRACT)
+            .with_high_water_mark(1.5)
+            .build()
+            .err()
+            .unwrap();
+        assert!(matches!(error, ClientError::Invalid { what, .. } if what == "high-water mark"));
+        // the listener setters validate like the builder
+        let error = test_listener()
+            .await
+            .with_high_water_mark(1.5)
+            .err()
+            .unwrap();
+        assert!(matches!(error, ClientError::Invalid { what, .. } if what == "high-water mark"));
+        assert!(test_listener().await.with_checkpoint_interval(0).is_err());
+
+        let listener = builder()
+            .with_contracts([CONTRACT, "bbn1token"])
+            .with_checkpoint_interval(5)
+            .build()
+            .unwrap();
+        assert_eq!(listener.contract_addresses.len(), 2);
+        assert_eq!(listener.last_processed_height, 100);
+        assert!(listener.checkpoint_due(105));
+        let client = CosmWasmClient::new(
+            "http://localhost:9090",
+            "5d386fbdbf11f1141010f81a46b40f94887367562bd33b452bbaa6ce1cd1381e",
+            CONTRACT,
+            crate::chain::ChainConfig::new(
+                "bbn".to_string(),
+                "bbn-test-5".to_string(),
+                "ubbn".to_string(),
+                2_000_000,
+                "0.001ubbn".parse().unwrap(),
+            ),
+        )
+        .unwrap();
+        assert!(EventListenerBuilder::from_client(&client).is_err());
+
+        let client = client.with_rpc("http://localhost:26657").unwrap();
+        let listener = EventListenerBuilder::from_client(&client)
+            .unwrap()
+            .with_event_sender(event_sender.clone())
+            .build()
+            .unwrap();
+        assert!(listener.contract_addresses.contains(CONTRACT));
+        assert_eq!(listener.rpc.active_url(), "http://localhost:26657");
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_due() {
+        let listener = test_listener().await;
+        assert!(listener.checkpoint_due(10));
+        assert!(!listener.checkpoint_due(11));
+
+        let mut listener = listener.with_checkpoint_period(Duration::ZERO);
+        assert!(listener.checkpoint_due(11));
+        listener.checkpoint(11).await.unwrap();
+        assert!(!listener.checkpoint_due(11));
+        assert!(listener.checkpoint_due(12));
+    }
+
+    #[tokio::test]
+    async fn test_event_origin() {
+        let event = |kind: &str, contract: &str, msg_index: &str| {
+            abci::Event::new(
+                kind,
+                [
+                    abci::EventAttribute::from(("_contract_address", contract, true)),
+                    abci::EventAttribute::from(("action", "peg_in", true)),
+                    abci::EventAttribute::from(("receiver", "bbn1receiver", true)),
+                    abci::EventAttribute::from(("amount", "1", true)),
+                    abci::EventAttribute::from(("msg_index", msg_index, true)),
+                ],
+            )
+        };
+        let events = vec![
+            event("execute", CONTRACT, "0"),
+            event("wasm", CONTRACT, "0"),
+            event("execute", "bbn1router", "0"),
+            event("wasm", "bbn1router", "0"),
+            event("reply", CONTRACT, "0"),
+            event("wasm", CONTRACT, "0"),
+            event("execute", "bbn1router", "1"),
+            event("wasm", "bbn1router", "1"),
+            event("execute", CONTRACT, "1"),
+            event("wasm", CONTRACT, "1"),
+        ];
+
+        let listener = test_listener().await.with_contracts(["bbn1router"]);
+        let block_events = listener
+            .collect_block_events(1, test_block(events))
+            .unwrap()
+            .unwrap();
+        let origins: Vec<_> = block_events
+            .events
+            .iter()
+            .map(|event| (event.msg_index, event.origin))
+            .collect();
+        assert_eq!(
+            origins,
+            vec![
+                (Some(0), EventOrigin::Execute),
+                (Some(0), EventOrigin::Submessage),
+                (Some(0), EventOrigin::Reply),
+                (Some(1), EventOrigin::Execute),
+                (Some(1), EventOrigin::Submessage),
+            ]
+        );
+        let messages = block_events.events_by_message();
+        assert_eq!(messages[&(0, 0)].len(), 3);
+        assert_eq!(messages[&(0, 1)].len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_events() {
+        let event = |kind: &str, attrs: &[(&str, &str)]| {
+            abci::Event::new(
+                kind,
+                attrs
+                    .iter()
+                    .map(|(k, v)| abci::EventAttribute::from((*k, *v, true))),
+            )
+        };
+        let events = vec![
+            event(
+                "migrate",
+                &[("_contract_address", CONTRACT), ("code_id", "42")],
+            ),
+            event(
+                "update_contract_admin",
+                &[("_contract_address", CONTRACT), ("new_admin_address", "")],
+            ),
+            event(
+                "migrate",
+                &[("_contract_address", "bbn1other"), ("code_id", "1")],
+            ),
+        ];
+
+        let listener = test_listener().await;
+        let block_events = listener
+            .collect_block_events(1, test_block(events))
+            .unwrap()
+            .unwrap();
+        assert_eq!(block_events.events.len(), 2);
+        assert!(matches!(
+            &block_events.events[0].event,
+            ContractEvent::Migrate(MigrateEvent { code_id: 42, .. })
+        ));
+        assert!(matches!(
+            &block_events.events[1].event,
+            ContractEvent::UpdateAdmin(UpdateAdminEvent {
+                new_admin: None,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_set_height() {
+        let mut listener = test_listener().await;
+        let handle = listener.handle();
+        handle.set_height(500);
+
+        let seek_height = listener.control.seek_height.lock().unwrap().take();
+        listener.seek(seek_height.unwrap()).await.unwrap();
+        assert_eq!(listener.last_processed_height, 500);
+        assert_eq!(handle.last_processed_height(), 500);
+    }
+
+    #[tokio::test]
+    async fn test_remove_spilled_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = FileSpillQueue::new(dir.path()).unwrap();
+        let mut listener = test_listener()
+            .await
+            .with_backpressure(BackpressurePolicy::Spill(Box::new(queue.clone())));
+        for height in [9, 10, 11] {
+            queue
+                .push(&BlockEvents {
+                    height,
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+        listener.spilled = true;
+
+        listener.remove_from(10).await.unwrap();
+        assert_eq!(queue.peek().await.unwrap().unwrap().height, 9);
+        assert!(listener.spilled);
+
+        listener.remove_from(9).await.unwrap();
+        assert!(queue.peek().await.unwrap().is_none());
+        assert!(!listener.spilled);
+    }
+
+    #[tokio::test]
+    async fn test_sink_error_keeps_endpoint() {
+        // the receiver of the test listener is dropped, delivering fails
+        let mut listener = test_listener()
+            .await
+            .with_fallback_rpcs(["http://localhost:26658"])
+            .unwrap();
+        let peg_in = wasm_event(&[
+            ("_contract_address", CONTRACT),
+            ("action", "peg_in"),
+            ("receiver", "bbn1receiver"),
+            ("amount", "1000"),
+            ("msg_index", "0"),
+        ]);
+        let error = listener
+            .process_block(1, test_block(vec![peg_in]))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ClientError::Sink { .. }));
+        for _ in 0..listener.config.failover_threshold {
+            listener.record_error(&error);
+        }
+        assert_eq!(listener.rpc.active_url(), "http://localhost:26657");
+
+        let error = ClientError::connection("http://localhost:26657", "connection refused");
+        for _ in 0..listener.config.failover_threshold {
+            listener.record_error(&error);
+        }
+        assert_eq!(listener.rpc.active_url(), "http://localhost:26658");
+    }
+
+    #[test]
+    fn test_is_pruned_error() {
+        assert!(is_pruned_error(
+            "Internal error: height 5 is not available, lowest height is 100 (code: -32603)"
+        ));
+        assert!(is_pruned_error("could not find results for height #5"));
+        assert!(!is_pruned_error(
+            "height 500 must be less than or equal to the current blockchain height 400"
+        ));
+    }
+
+    #[test]
+    fn test_backoff_policy() {
+        let policy = BackoffPolicy {
+            jitter: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(policy.delay(1), Duration::from_secs(1));
+        assert_eq!(policy.delay(3), Duration::from_secs(4));
+        assert_eq!(policy.delay(100), Duration::from_secs(60));
+
+        let policy = BackoffPolicy::default();
+        let delay = policy.delay(3);
+        assert!(delay > Duration::from_millis(3200) && delay <= Duration::from_secs(4));
+
+        let policy = BackoffPolicy::fixed(Duration::from_secs(1));
+        assert_eq!(policy.delay(10), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_status_interval() {
+        let config = ListenerConfig::default();
+        assert_eq!(config.status_interval(0), Duration::from_secs(5));
+        assert_eq!(config.status_interval(10), Duration::from_secs(5));
+        assert_eq!(config.status_interval(11), Duration::from_secs(15));
+        assert_eq!(config.status_interval(101), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_is_saturated() {
+        assert!(!is_saturated(100, 100, DEFAULT_HIGH_WATER_MARK));
+        assert!(!is_saturated(100, 21, DEFAULT_HIGH_WATER_MARK));
+        assert!(is_saturated(100, 20, DEFAULT_HIGH_WATER_MARK));
+        assert!(is_saturated(100, 0, DEFAULT_HIGH_WATER_MARK));
+        assert!(is_saturated(1, 0, 1.0));
+    }
 }
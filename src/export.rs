@@ -0,0 +1,340 @@
+use std::io::Write;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ClientError;
+use crate::events::{BlockEvents, ContractEvent, EventListener};
+use crate::sink::EventSink;
+use crate::transactions::TxHash;
+
+/// Blocks backfilled at once by `EventListener::export`
+const EXPORT_CHUNK_BLOCKS: u64 = 1000;
+
+const CSV_HEADER: &str = "height,block_time,timestamp,tx_hash,msg_index,kind,contract,address,amount,btc_address,fee_rate,operator_btc_pk";
+
+/// Output format of an `EventExporter`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One JSON object per line
+    JsonLines,
+    /// Comma separated values with a header row
+    Csv,
+}
+
+impl FromStr for ExportFormat {
+    type Err = ClientError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "jsonl" | "json" | "ndjson" => Ok(Self::JsonLines),
+            "csv" => Ok(Self::Csv),
+            _ => Err(ClientError::invalid(
+                "export format",
+                format!("{}, expected jsonl or csv", s),
+            )),
+        }
+    }
+}
+
+/// Kind of an `ExportRecord`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportKind {
+    PegIn,
+    PegOut,
+}
+
+impl ExportKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PegIn => "peg_in",
+            Self::PegOut => "peg_out",
+        }
+    }
+}
+
+/// Peg in or peg out as written by an `EventExporter`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportRecord {
+    pub height: u64,
+    /// block_time is the unix timestamp of the block in seconds
+    pub block_time: u64,
+    /// timestamp is the block time in RFC 3339
+    pub timestamp: String,
+    pub tx_hash: TxHash,
+    pub msg_index: u32,
+    pub kind: ExportKind,
+    pub contract: String,
+    /// address is the receiver of a peg in or the sender of a peg out
+    pub address: String,
+    pub amount: u128,
+    pub btc_address: Option<String>,
+    pub fee_rate: Option<u32>,
+    pub operator_btc_pk: Option<String>,
+}
+
+impl ExportRecord {
+    /// Returns the peg ins and peg outs of the successful txs of the block, in order
+    pub fn from_block(block_events: &BlockEvents) -> Vec<Self> {
+        let timestamp = tendermint::Time::from_unix_timestamp(block_events.block_time as i64, 0)
+            .map(|time| time.to_rfc3339())
+            .unwrap_or_default();
+
+        block_events
+            .events
+            .iter()
+            .filter(|tx_event| tx_event.code == 0)
+            .filter_map(|tx_event| {
+                let record = |kind, msg_index, address: &str, amount| Self {
+                    height: block_events.height,
+                    block_time: block_events.block_time,
+                    timestamp: timestamp.clone(),
+                    tx_hash: tx_event.tx_hash.clone(),
+                    msg_index,
+                    kind,
+                    contract: tx_event.contract.clone(),
+                    address: address.to_string(),
+                    amount,
+                    btc_address: None,
+                    fee_rate: None,
+                    operator_btc_pk: None,
+                };
+                match &tx_event.event {
+                    ContractEvent::PegIn(event) => Some(record(
+                        ExportKind::PegIn,
+                        event.msg_index,
+                        &event.receiver,
+                        event.amount,
+                    )),
+                    ContractEvent::PegOut(event) => Some(Self {
+                        btc_address: Some(event.btc_address.clone()),
+                        fee_rate: Some(event.fee_rate),
+                        operator_btc_pk: Some(event.operator_btc_pk.clone()),
+                        ..record(
+                            ExportKind::PegOut,
+                            event.msg_index,
+                            &event.sender,
+                            event.amount,
+                        )
+                    }),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    fn csv_row(&self) -> String {
+        let fields = [
+            self.height.to_string(),
+            self.block_time.to_string(),
+            self.timestamp.clone(),
+            self.tx_hash.to_string(),
+            self.msg_index.to_string(),
+            self.kind.as_str().to_string(),
+            self.contract.clone(),
+            self.address.clone(),
+            self.amount.to_string(),
+            self.btc_address.clone().unwrap_or_default(),
+            self.fee_rate
+                .map(|rate| rate.to_string())
+                .unwrap_or_default(),
+            self.operator_btc_pk.clone().unwrap_or_default(),
+        ];
+        fields
+            .iter()
+            .map(|field| csv_field(field))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// Quotes the field if it contains a separator, a quote or a line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Writes the peg ins and peg outs of blocks to JSON Lines or CSV, e.g. for
+/// accounting outside Rust. Fed by `EventListener::export` for a height range
+/// or used as the listener's sink to export live.
+pub struct EventExporter<W> {
+    format: ExportFormat,
+    /// writer is the output and whether the CSV header was written to it
+    writer: Mutex<(W, bool)>,
+}
+
+impl<W: Write + Send> EventExporter<W> {
+    pub fn new(writer: W, format: ExportFormat) -> Self {
+        Self {
+            format,
+            writer: Mutex::new((writer, false)),
+        }
+    }
+
+    /// Writes the records of the block and returns how many were written
+    pub fn write_block(&self, block_events: &BlockEvents) -> Result<usize, ClientError> {
+        let records = ExportRecord::from_block(block_events);
+        let mut guard = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        let (writer, header_written) = &mut *guard;
+
+        if self.format == ExportFormat::Csv && !*header_written {
+            writeln!(writer, "{}", CSV_HEADER)
+                .map_err(|e| ClientError::io("write CSV header", e))?;
+            *header_written = true;
+        }
+        for record in &records {
+            match self.format {
+                ExportFormat::JsonLines => {
+                    serde_json::to_writer(&mut *writer, record)
+                        .map_err(|e| ClientError::serialization("serialize export record", e))?;
+                    writeln!(writer)
+                }
+                ExportFormat::Csv => writeln!(writer, "{}", record.csv_row()),
+            }
+            .map_err(|e| ClientError::io("write export record", e))?;
+        }
+
+        Ok(records.len())
+    }
+
+    pub fn flush(&self) -> Result<(), ClientError> {
+        let mut guard = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        guard
+            .0
+            .flush()
+            .map_err(|e| ClientError::io("flush export", e))
+    }
+
+    /// Returns the writer, e.g. the buffer written to
+    pub fn into_inner(self) -> W {
+        self.writer
+            .into_inner()
+            .unwrap_or_else(|e| e.into_inner())
+            .0
+    }
+}
+
+#[async_trait]
+impl<W: Write + Send> EventSink for EventExporter<W> {
+    async fn send(&self, block_events: BlockEvents) -> anyhow::Result<()> {
+        self.write_block(&block_events)?;
+        Ok(self.flush()?)
+    }
+}
+
+impl EventListener {
+    /// Scans the blocks in `from..=to` and writes their peg ins and peg outs to the
+    /// exporter, returning the number of records written. Like `backfill` this
+    /// neither sends events nor checkpoints.
+    pub async fn export<W: Write + Send>(
+        &self,
+        from: u64,
+        to: u64,
+        exporter: &EventExporter<W>,
+    ) -> Result<u64, ClientError> {
+        let mut written = 0;
+        let mut chunk_start = from;
+        while chunk_start <= to {
+            let chunk_end = to.min(chunk_start.saturating_add(EXPORT_CHUNK_BLOCKS - 1));
+            for block_events in self.backfill(chunk_start, chunk_end).await? {
+                written += exporter.write_block(&block_events)? as u64;
+            }
+            exporter.flush()?;
+            tracing::info!("Exported {} records up to height {}", written, chunk_end);
+
+            if chunk_end == u64::MAX {
+                break;
+            }
+            chunk_start = chunk_end + 1;
+        }
+
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{PegInEvent, PegOutEvent, TxEvent};
+
+    #[test]
+    fn test_export_formats() {
+        let tx_event = |code, event| TxEvent {
+            tx_hash: TxHash::from_tx_bytes(b"tx"),
+            tx_index: 0,
+            code,
+            gas_used: 0,
+            contract: "bbn1contract".to_string(),
+            msg_index: Some(0),
+            origin: Default::default(),
+            event,
+        };
+        let block = BlockEvents {
+            height: 10,
+            block_time: 1_700_000_000,
+            events: vec![
+                tx_event(
+                    0,
+                    ContractEvent::PegIn(PegInEvent {
+                        msg_index: 0,
+                        receiver: "bbn1receiver".to_string(),
+                        amount: 5000,
+                    }),
+                ),
+                tx_event(
+                    0,
+                    ContractEvent::PegOut(PegOutEvent {
+                        msg_index: 1,
+                        sender: "bbn1sender".to_string(),
+                        btc_address: "bc1qaddress".to_string(),
+                        fee_rate: 10,
+                        operator_btc_pk: "02ab".to_string(),
+                        amount: 3000,
+                    }),
+                ),
+                // failed txs are skipped
+                tx_event(5, ContractEvent::PegIn(PegInEvent::default())),
+            ],
+            ..Default::default()
+        };
+
+        let exporter = EventExporter::new(Vec::new(), ExportFormat::Csv);
+        assert_eq!(exporter.write_block(&block).unwrap(), 2);
+        exporter.write_block(&block).unwrap();
+        let csv = String::from_utf8(exporter.into_inner()).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], CSV_HEADER);
+        assert!(lines[1].starts_with("10,1700000000,2023-11-14T22:13:20Z,"));
+        assert!(lines[2].ends_with(",peg_out,bbn1contract,bbn1sender,3000,bc1qaddress,10,02ab"));
+
+        let exporter = EventExporter::new(Vec::new(), ExportFormat::JsonLines);
+        exporter.write_block(&block).unwrap();
+        let records: Vec<ExportRecord> = String::from_utf8(exporter.into_inner())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records, ExportRecord::from_block(&block));
+        assert_eq!(records[0].kind, ExportKind::PegIn);
+        assert_eq!(records[0].btc_address, None);
+
+        // a full writer fails with the I/O error
+        let mut full = [0u8; 0];
+        let exporter = EventExporter::new(&mut full[..], ExportFormat::Csv);
+        assert!(matches!(
+            exporter.write_block(&block),
+            Err(ClientError::Io { .. })
+        ));
+
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+        assert_eq!("CSV".parse::<ExportFormat>().unwrap(), ExportFormat::Csv);
+        assert!("xml".parse::<ExportFormat>().is_err());
+    }
+}
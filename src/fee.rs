@@ -0,0 +1,613 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use cosmos_sdk_proto::cosmos::bank::v1beta1::{
+    query_client::QueryClient as BankQueryClient, QueryAllBalancesRequest,
+};
+#[cfg(feature = "wallet")]
+use cosmos_sdk_proto::cosmos::base::abci::v1beta1::TxResponse;
+use cosmos_sdk_proto::cosmos::base::node::v1beta1::{
+    service_client::ServiceClient as NodeServiceClient, ConfigRequest,
+};
+#[cfg(feature = "wallet")]
+use cosmos_sdk_proto::cosmos::tx::v1beta1::{service_client::ServiceClient, SimulateRequest};
+#[cfg(feature = "wallet")]
+use cosmrs::tx::Fee;
+#[cfg(feature = "wallet")]
+use cosmrs::{Any, Coin, Denom};
+use cosmwasm_std::{Decimal, Uint128};
+use serde::{Deserialize, Serialize};
+
+use crate::chain::ChainConfig;
+use crate::client::CosmWasmClient;
+use crate::error::ClientError;
+#[cfg(feature = "wallet")]
+use crate::error::TxErrorKind;
+#[cfg(feature = "wallet")]
+use crate::transactions::{ExecuteMsg, SignedTx};
+
+/// Default multiplier applied to the simulated gas usage
+pub const DEFAULT_GAS_ADJUSTMENT: f64 = 1.3;
+
+/// Resubmission of transactions running out of gas, see
+/// `CosmWasmClient::submit_with_gas_retry`
+#[derive(Debug, Clone, PartialEq)]
+pub struct GasRetryConfig {
+    /// max_attempts is the number of submissions, including the first one
+    pub max_attempts: u32,
+    /// adjustment_step is added to the gas adjustment on every resubmission
+    pub adjustment_step: f64,
+    /// confirmation_timeout is how long each submission is awaited
+    pub confirmation_timeout: Duration,
+    pub poll_interval: Duration,
+}
+
+impl Default for GasRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            adjustment_step: 0.5,
+            confirmation_timeout: Duration::from_secs(60),
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Replacement of transactions stuck in the mempool, see
+/// `CosmWasmClient::submit_with_fee_bump`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeBumpConfig {
+    /// stuck_timeout is how long a transaction may stay pending before it's replaced
+    pub stuck_timeout: Duration,
+    /// fee_multiplier is applied to the fee of the pending transaction on every replacement
+    pub fee_multiplier: f64,
+    /// max_bumps is the number of replacements before giving up
+    pub max_bumps: u32,
+    pub poll_interval: Duration,
+}
+
+impl Default for FeeBumpConfig {
+    fn default() -> Self {
+        Self {
+            stuck_timeout: Duration::from_secs(60),
+            fee_multiplier: 1.5,
+            max_bumps: 3,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Priority hint of the transactions of a client. The Cosmos SDK mempool orders
+/// transactions by gas price, so higher priorities pay a multiple of the gas price
+/// to be included ahead of routine traffic, e.g. for urgent peg outs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxPriority {
+    /// Pays the gas price
+    #[default]
+    Normal,
+    /// Pays 1.5 times the gas price
+    High,
+    /// Pays 3 times the gas price
+    Urgent,
+}
+
+impl TxPriority {
+    /// Returns the multiplier applied to the gas price
+    pub fn gas_price_multiplier(self) -> Decimal {
+        match self {
+            Self::Normal => Decimal::one(),
+            Self::High => Decimal::percent(150),
+            Self::Urgent => Decimal::percent(300),
+        }
+    }
+
+    /// Returns the gas price paid with this priority
+    pub fn gas_price(self, price: GasPrice) -> GasPrice {
+        GasPrice {
+            amount: price.amount * self.gas_price_multiplier(),
+            denom: price.denom,
+        }
+    }
+}
+
+/// Gas price as a decimal amount of `denom` per unit of gas, e.g. `0.002ubbn`.
+/// Serialized in that string form.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct GasPrice {
+    pub amount: Decimal,
+    pub denom: String,
+}
+
+impl GasPrice {
+    /// Returns the fee for the given amount of gas, rounded up
+    pub fn fee_for_gas(&self, gas: u64) -> u128 {
+        Uint128::from(gas).mul_ceil(self.amount).u128()
+    }
+}
+
+impl std::fmt::Display for GasPrice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.amount, self.denom)
+    }
+}
+
+impl TryFrom<String> for GasPrice {
+    type Error = ClientError;
+
+    fn try_from(s: String) -> Result<Self, ClientError> {
+        s.parse()
+    }
+}
+
+impl From<GasPrice> for String {
+    fn from(gas_price: GasPrice) -> Self {
+        gas_price.to_string()
+    }
+}
+
+impl FromStr for GasPrice {
+    type Err = ClientError;
+
+    fn from_str(s: &str) -> Result<Self, ClientError> {
+        let s = s.trim();
+        let split = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .ok_or_else(|| ClientError::invalid(format!("gas price {}", s), "missing denom"))?;
+        let (amount, denom) = s.split_at(split);
+
+        Ok(Self {
+            amount: Decimal::from_str(amount)
+                .map_err(|e| ClientError::invalid(format!("gas price amount {}", amount), e))?,
+            denom: denom.to_string(),
+        })
+    }
+}
+
+/// Parses a comma separated list of gas prices as reported by the node, e.g. `0.002ubbn,0.1uatom`
+pub fn parse_gas_prices(s: &str) -> Result<Vec<GasPrice>, ClientError> {
+    s.split(',')
+        .filter(|price| !price.trim().is_empty())
+        .map(GasPrice::from_str)
+        .collect()
+}
+
+impl CosmWasmClient {
+    /// Returns the minimum gas prices configured on the node
+    pub async fn query_min_gas_prices(&self) -> Result<Vec<GasPrice>, ClientError> {
+        let mut client = NodeServiceClient::connect(self.grpc_url.clone())
+            .await
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))?;
+
+        let resp = client
+            .config(ConfigRequest {})
+            .await
+            .map_err(|e| ClientError::grpc("query node config", e))?;
+
+        parse_gas_prices(&resp.into_inner().minimum_gas_price)
+    }
+
+    /// Queries the node minimum gas price for the fee denom and uses it for all following
+    /// transactions. A zero or missing price keeps the configured gas price.
+    pub async fn refresh_gas_price(&self) -> Result<Option<GasPrice>, ClientError> {
+        let configured = self.configured_gas_price();
+        let gas_price = self
+            .query_min_gas_prices()
+            .await?
+            .into_iter()
+            .find(|price| price.denom == configured.denom && !price.amount.is_zero());
+
+        match &gas_price {
+            Some(price) => tracing::debug!("Using gas price {}{}", price.amount, price.denom),
+            None => tracing::debug!(
+                "No minimum gas price for {}, using configured gas price {}",
+                configured.denom,
+                configured
+            ),
+        }
+        *self.gas_price.write().unwrap_or_else(|e| e.into_inner()) = gas_price.clone();
+
+        Ok(gas_price)
+    }
+
+    /// Returns the gas price discovered by the last `refresh_gas_price`
+    pub fn gas_price(&self) -> Option<GasPrice> {
+        self.gas_price
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Returns the configured gas price of the fee denom, the one picked by
+    /// `select_fee_denom` if any, otherwise `ChainConfig.gas_price`
+    pub fn configured_gas_price(&self) -> GasPrice {
+        self.fee_gas_price
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+            .unwrap_or_else(|| self.config.gas_price.clone())
+    }
+
+    /// Picks the first of `ChainConfig.gas_prices` whose denom the wallet holds and pays
+    /// the fees of all following transactions in it
+    pub async fn select_fee_denom(&self) -> Result<GasPrice, ClientError> {
+        let mut client = BankQueryClient::connect(self.grpc_url.clone())
+            .await
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))?;
+
+        let address = self.signer()?.account_id.to_string();
+        let balances = client
+            .all_balances(QueryAllBalancesRequest {
+                address: address.clone(),
+                pagination: None,
+                resolve_denom: false,
+            })
+            .await
+            .map_err(|e| ClientError::grpc("query wallet balances", e))?
+            .into_inner()
+            .balances;
+
+        let held: Vec<&str> = balances
+            .iter()
+            .filter(|coin| !coin.amount.is_empty() && coin.amount != "0")
+            .map(|coin| coin.denom.as_str())
+            .collect();
+        let gas_price = first_held_gas_price(&self.config, &held).ok_or_else(|| {
+            ClientError::not_found(format!(
+                "balance of the fee denoms {} in wallet {}",
+                self.config
+                    .gas_prices()
+                    .map(|price| price.denom.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                address
+            ))
+        })?;
+
+        tracing::debug!("Paying fees in {}", gas_price.denom);
+        *self
+            .fee_gas_price
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = Some(gas_price.clone());
+        let mut discovered = self.gas_price.write().unwrap_or_else(|e| e.into_inner());
+        if discovered
+            .as_ref()
+            .is_some_and(|price| price.denom != gas_price.denom)
+        {
+            *discovered = None;
+        }
+
+        Ok(gas_price)
+    }
+
+    /// Refreshes the gas price in the background every `interval`
+    pub fn spawn_gas_price_refresh(&self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let client = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = client.refresh_gas_price().await {
+                    tracing::warn!("Failed to refresh gas price: {:?}", e);
+                }
+            }
+        })
+    }
+
+    /// Returns the fee for the given gas limit as `gas × price`, priced with the
+    /// discovered gas price of the fee denom if any, otherwise with the configured one,
+    /// and raised by the `TxPriority` of the client
+    #[cfg(feature = "wallet")]
+    pub(crate) fn fee_for_gas(&self, gas_limit: u64) -> Result<Fee, ClientError> {
+        let configured = self.configured_gas_price();
+        let price = self.priority.gas_price(
+            self.gas_price()
+                .filter(|price| price.denom == configured.denom)
+                .unwrap_or(configured),
+        );
+
+        let fee = Coin {
+            amount: price.fee_for_gas(gas_limit),
+            denom: Denom::from_str(&price.denom).map_err(|e| ClientError::invalid("denom", e))?,
+        };
+        Ok(Fee::from_amount_and_gas(fee, gas_limit))
+    }
+
+    pub fn with_gas_adjustment(mut self, gas_adjustment: f64) -> Self {
+        self.gas_adjustment = gas_adjustment;
+        self
+    }
+
+    /// Sets the priority of the transactions, e.g. on a clone of the client used
+    /// for urgent peg outs only
+    pub fn with_priority(mut self, priority: TxPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+#[cfg(feature = "wallet")]
+impl CosmWasmClient {
+    /// Simulates a transaction with the given messages and returns the gas used
+    pub async fn simulate(&self, msgs: Vec<Any>) -> Result<u64, ClientError> {
+        // the simulated tx doesn't take a sequence from the account cache
+        let (account_number, sequence) = self.next_sequence(false).await?;
+        let fee = self.fee_for_gas(self.config.gas_limit)?;
+        let tx_bytes = self
+            .build_tx_with_sequence(msgs, fee, account_number, sequence)?
+            .to_bytes()
+            .map_err(|e| ClientError::serialization("serialize transaction", e))?;
+
+        let mut client = ServiceClient::connect(self.grpc_url.clone())
+            .await
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))?;
+
+        #[allow(deprecated)]
+        let resp = client
+            .simulate(SimulateRequest { tx: None, tx_bytes })
+            .await
+            .map_err(|e| ClientError::grpc("simulate transaction", e))?;
+
+        resp.into_inner()
+            .gas_info
+            .map(|info| info.gas_used)
+            .ok_or_else(|| ClientError::not_found("gas info in the simulation"))
+    }
+
+    /// Estimates the fee of a transaction with the given messages without broadcasting it.
+    /// The simulated gas is multiplied by the gas adjustment and priced with the
+    /// discovered or configured gas price.
+    pub async fn estimate_fee(&self, msgs: Vec<Any>) -> Result<Fee, ClientError> {
+        let gas_used = self.simulate(msgs).await?;
+        let gas_limit = adjusted_gas(gas_used, self.gas_adjustment);
+        tracing::debug!("Simulated gas used {}, gas limit {}", gas_used, gas_limit);
+
+        self.fee_for_gas(gas_limit)
+    }
+
+    /// Submits a transaction with the configured gas limit and waits for it. If it
+    /// runs out of gas, it's simulated again and re-signed with the gas adjustment
+    /// raised by `adjustment_step` per attempt, since e.g. peg in proofs vary in size.
+    pub async fn submit_with_gas_retry(
+        &self,
+        msgs: Vec<Any>,
+        config: &GasRetryConfig,
+    ) -> Result<TxResponse, ClientError> {
+        let mut signed_tx = self.sign_msgs(msgs.clone()).await?;
+        let mut attempt = 1;
+        loop {
+            let error = match self
+                .broadcast_and_wait(
+                    &signed_tx,
+                    config.confirmation_timeout,
+                    config.poll_interval,
+                )
+                .await
+            {
+                Ok(tx_response) => return Ok(tx_response),
+                Err(e) => e,
+            };
+            let ClientError::TxFailed(tx_error) = &error else {
+                return Err(error);
+            };
+            if tx_error.kind != TxErrorKind::OutOfGas || attempt >= config.max_attempts {
+                return Err(error);
+            }
+
+            // the failed execution used at least the gas it was given
+            let gas_used = self.simulate(msgs.clone()).await?;
+            let gas_adjustment = self.gas_adjustment + config.adjustment_step * attempt as f64;
+            let gas_limit = adjusted_gas(
+                gas_used.max(tx_error.gas_used.max(0) as u64),
+                gas_adjustment,
+            );
+            tracing::warn!(
+                "Transaction {} ran out of gas with {} gas, resubmitting with {}",
+                signed_tx.tx_hash,
+                tx_error.gas_wanted,
+                gas_limit
+            );
+
+            let tx_bytes = self
+                .build_tx_with_fee(msgs.clone(), self.fee_for_gas(gas_limit)?)
+                .await?
+                .to_bytes()
+                .map_err(|e| ClientError::serialization("serialize transaction", e))?;
+            signed_tx = SignedTx::from_bytes(tx_bytes);
+            attempt += 1;
+        }
+    }
+
+    /// Submits a transaction and waits for it. If it's still pending after
+    /// `stuck_timeout`, the same messages are re-signed with the same sequence and a
+    /// fee raised by `fee_multiplier`, so the mempool replaces the low fee transaction.
+    /// Whichever of the transactions is included first is returned.
+    ///
+    /// Replacement needs a mempool ordering transactions by fee, e.g. the priority
+    /// mempool of the Cosmos SDK. Rejected replacements are logged and the pending
+    /// transactions are awaited further.
+    pub async fn submit_with_fee_bump(
+        &self,
+        msgs: Vec<Any>,
+        config: &FeeBumpConfig,
+    ) -> Result<TxResponse, ClientError> {
+        let mut fee = self.fee_for_gas(self.config.gas_limit)?;
+        let (account_number, sequence) = self.next_sequence(true).await?;
+        let mut pending: Vec<SignedTx> = vec![];
+
+        for bump in 0..=config.max_bumps {
+            if bump > 0 {
+                fee = bumped_fee(&fee, config.fee_multiplier);
+                tracing::warn!(
+                    "Transaction with sequence {} pending for {:?}, replacing it with fee {}",
+                    sequence,
+                    config.stuck_timeout,
+                    fee.amount
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(",")
+                );
+            }
+            let tx_bytes = self
+                .build_tx_with_sequence(msgs.clone(), fee.clone(), account_number, sequence)
+                .and_then(|tx_raw| {
+                    tx_raw
+                        .to_bytes()
+                        .map_err(|e| ClientError::serialization("serialize transaction", e))
+                })
+                .inspect_err(|_| {
+                    // the reserved sequence is only taken once a tx is pending
+                    if pending.is_empty() {
+                        self.invalidate_account_cache();
+                    }
+                })?;
+            let signed_tx = SignedTx::from_bytes(tx_bytes);
+
+            match self.broadcast_signed_tx(&signed_tx).await {
+                Ok(_) => pending.push(signed_tx),
+                Err(ClientError::BroadcastFailed { tx_hash, source }) => {
+                    tracing::warn!(
+                        "Broadcast of transaction {} failed, checking whether it landed: {}",
+                        tx_hash,
+                        source
+                    );
+                    pending.push(signed_tx);
+                }
+                // the sequence is taken by a pending tx which wasn't replaced
+                Err(e) if !pending.is_empty() => {
+                    tracing::warn!("Replacement of transaction rejected: {}", e);
+                }
+                Err(e) => return Err(e),
+            }
+
+            let hashes = pending
+                .iter()
+                .map(|signed_tx| signed_tx.tx_hash.as_str())
+                .collect::<Vec<_>>();
+            if let Some(tx_response) = self
+                .wait_for_any_tx(&hashes, config.stuck_timeout, config.poll_interval)
+                .await?
+            {
+                return Ok(tx_response);
+            }
+        }
+
+        Err(ClientError::Timeout {
+            what: format!(
+                "Confirmation of transaction with sequence {} after {} fee bumps",
+                sequence, config.max_bumps
+            ),
+            timeout: config.stuck_timeout * (config.max_bumps + 1),
+        })
+    }
+
+    /// Estimates the fee of a peg out without broadcasting it
+    pub async fn estimate_peg_out_fee(
+        &self,
+        btc_address: &str,
+        fee_rate: u32,
+        amount: u128,
+        operator_btc_pk: &str,
+    ) -> Result<Fee, ClientError> {
+        let contract = self
+            .contract
+            .clone()
+            .ok_or_else(|| ClientError::not_found("contract address"))?;
+
+        let msg = self.execute_msg(
+            contract,
+            &ExecuteMsg::PegOut {
+                btc_address: btc_address.to_string(),
+                fee_rate,
+                amount: Uint128::from(amount),
+                operator_btc_pk: operator_btc_pk.to_string(),
+            },
+        )?;
+
+        self.estimate_fee(vec![msg]).await
+    }
+}
+
+/// Returns the first accepted gas price whose denom is in `held`
+fn first_held_gas_price(config: &ChainConfig, held: &[&str]) -> Option<GasPrice> {
+    config
+        .gas_prices()
+        .find(|price| held.contains(&price.denom.as_str()))
+        .cloned()
+}
+
+/// Returns the gas limit for the simulated gas usage, rounded up
+#[cfg(feature = "wallet")]
+fn adjusted_gas(gas_used: u64, gas_adjustment: f64) -> u64 {
+    (gas_used as f64 * gas_adjustment).ceil() as u64
+}
+
+/// Returns the fee with every amount multiplied and rounded up, raised by at least 1
+#[cfg(feature = "wallet")]
+fn bumped_fee(fee: &Fee, multiplier: f64) -> Fee {
+    let mut fee = fee.clone();
+    for coin in &mut fee.amount {
+        coin.amount = ((coin.amount as f64 * multiplier).ceil() as u128).max(coin.amount + 1);
+    }
+    fee
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gas_prices() {
+        let prices = parse_gas_prices("0.002ubbn,0.1uatom").unwrap();
+        assert_eq!(prices.len(), 2);
+        assert_eq!(prices[0].denom, "ubbn");
+        assert_eq!(prices[0].amount, Decimal::from_str("0.002").unwrap());
+
+        assert!(parse_gas_prices("").unwrap().is_empty());
+        assert!(parse_gas_prices("0.002").is_err());
+
+        // rounded up
+        assert_eq!(prices[0].fee_for_gas(1_000_001), 2001);
+
+        let price: GasPrice = serde_json::from_str("\"0.0025ubbn\"").unwrap();
+        assert_eq!(price.fee_for_gas(200_000), 500);
+        assert_eq!(serde_json::to_string(&price).unwrap(), "\"0.0025ubbn\"");
+
+        assert_eq!(
+            TxPriority::default()
+                .gas_price(price.clone())
+                .fee_for_gas(200_000),
+            500
+        );
+        let urgent: TxPriority = serde_json::from_str("\"urgent\"").unwrap();
+        assert_eq!(urgent.gas_price(price).to_string(), "0.0075ubbn");
+    }
+
+    #[test]
+    fn test_first_held_gas_price() {
+        let mut config = ChainConfig::new(
+            "bbn".to_string(),
+            "bbn-test-5".to_string(),
+            "ubbn".to_string(),
+            1_000_000,
+            "0.002ubbn".parse().unwrap(),
+        );
+        config.fallback_gas_prices = parse_gas_prices("0.1uatom,0.01uusdc").unwrap();
+
+        let price = first_held_gas_price(&config, &["uusdc", "uatom"]).unwrap();
+        assert_eq!(price.denom, "uatom");
+        let price = first_held_gas_price(&config, &["uusdc", "ubbn"]).unwrap();
+        assert_eq!(price.denom, "ubbn");
+        assert!(first_held_gas_price(&config, &["uosmo"]).is_none());
+    }
+
+    #[cfg(feature = "wallet")]
+    #[test]
+    fn test_adjusted_gas() {
+        assert_eq!(adjusted_gas(100_000, DEFAULT_GAS_ADJUSTMENT), 130_000);
+        assert_eq!(adjusted_gas(3, 1.5), 5);
+        assert_eq!(adjusted_gas(0, DEFAULT_GAS_ADJUSTMENT), 0);
+    }
+}
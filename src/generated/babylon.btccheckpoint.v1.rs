@@ -0,0 +1,482 @@
+// This file is @generated by prost-build.
+/// Params defines the parameters for the module.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Params {
+    /// btc_confirmation_depth is the confirmation depth in BTC.
+    /// A block is considered irreversible only when it is at least k-deep in BTC
+    /// (k in research paper)
+    #[prost(uint32, tag = "1")]
+    pub btc_confirmation_depth: u32,
+    /// checkpoint_finalization_timeout is the maximum number of BTC blocks that
+    /// will be watched for checkpoint submissions (w in research paper)
+    #[prost(uint32, tag = "2")]
+    pub checkpoint_finalization_timeout: u32,
+    /// 4byte tag in hex format, required to be present in the OP_RETURN transaction
+    /// related to babylon
+    #[prost(string, tag = "3")]
+    pub checkpoint_tag: ::prost::alloc::string::String,
+}
+/// QueryParamsRequest is request type for the Query/Params RPC method.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct QueryParamsRequest {}
+/// QueryParamsResponse is response type for the Query/Params RPC method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryParamsResponse {
+    /// params holds all the parameters of this module.
+    #[prost(message, optional, tag = "1")]
+    pub params: ::core::option::Option<Params>,
+}
+/// QueryBtcCheckpointInfoRequest defines the query to get the best checkpoint
+/// for a given epoch
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct QueryBtcCheckpointInfoRequest {
+    /// Number of epoch for which the earliest checkpointing btc height is
+    /// requested
+    #[prost(uint64, tag = "1")]
+    pub epoch_num: u64,
+}
+/// QueryBtcCheckpointInfoResponse is response type for the
+/// Query/BtcCheckpointInfo RPC method
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryBtcCheckpointInfoResponse {
+    #[prost(message, optional, tag = "1")]
+    pub info: ::core::option::Option<BtcCheckpointInfoResponse>,
+}
+/// BTCCheckpointInfoResponse contains all data about best checkpoint for given
+/// epoch
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BtcCheckpointInfoResponse {
+    /// EpochNumber of this checkpoint.
+    #[prost(uint64, tag = "1")]
+    pub epoch_number: u64,
+    /// btc height of the best submission of the epoch
+    #[prost(uint32, tag = "2")]
+    pub best_submission_btc_block_height: u32,
+    /// hash of the btc block which determines checkpoint btc block height i.e.
+    /// youngest block of best submission Hexadecimal
+    #[prost(string, tag = "3")]
+    pub best_submission_btc_block_hash: ::prost::alloc::string::String,
+    /// the BTC checkpoint transactions of the best submission
+    #[prost(message, repeated, tag = "4")]
+    pub best_submission_transactions: ::prost::alloc::vec::Vec<TransactionInfoResponse>,
+    /// list of vigilantes' addresses of the best submission
+    #[prost(message, repeated, tag = "5")]
+    pub best_submission_vigilante_address_list: ::prost::alloc::vec::Vec<
+        CheckpointAddressesResponse,
+    >,
+}
+/// TransactionInfoResponse is the info of a tx on Bitcoin,
+/// including
+/// - the position of the tx on BTC blockchain
+/// - the full tx content
+/// - the Merkle proof that this tx is on the above position
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TransactionInfoResponse {
+    /// Index Bitcoin Transaction index in block.
+    #[prost(uint32, tag = "1")]
+    pub index: u32,
+    /// Hash BTC Header hash as hex.
+    #[prost(string, tag = "2")]
+    pub hash: ::prost::alloc::string::String,
+    /// transaction is the full transaction data as str hex.
+    #[prost(string, tag = "3")]
+    pub transaction: ::prost::alloc::string::String,
+    /// proof is the Merkle proof that this tx is included in the position in `key`
+    #[prost(string, tag = "4")]
+    pub proof: ::prost::alloc::string::String,
+}
+/// CheckpointAddressesResponse contains the addresses of the submitter and reporter of a
+/// given checkpoint
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CheckpointAddressesResponse {
+    /// submitter is the address of the checkpoint submitter to BTC, extracted from
+    /// the checkpoint itself.
+    #[prost(string, tag = "1")]
+    pub submitter: ::prost::alloc::string::String,
+    /// reporter is the address of the reporter who reported the submissions,
+    /// calculated from submission message MsgInsertBTCSpvProof itself
+    #[prost(string, tag = "2")]
+    pub reporter: ::prost::alloc::string::String,
+}
+/// Generated client implementations.
+pub mod query_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    /// Query defines the gRPC querier service.
+    #[derive(Debug, Clone)]
+    pub struct QueryClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl QueryClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> QueryClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> QueryClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            QueryClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// Parameters queries the parameters of the module.
+        pub async fn params(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryParamsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryParamsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/babylon.btccheckpoint.v1.Query/Params",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("babylon.btccheckpoint.v1.Query", "Params"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// BtcCheckpointInfo returns checkpoint info for a given epoch
+        pub async fn btc_checkpoint_info(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryBtcCheckpointInfoRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryBtcCheckpointInfoResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/babylon.btccheckpoint.v1.Query/BtcCheckpointInfo",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "babylon.btccheckpoint.v1.Query",
+                        "BtcCheckpointInfo",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod query_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with QueryServer.
+    #[async_trait]
+    pub trait Query: std::marker::Send + std::marker::Sync + 'static {
+        /// Parameters queries the parameters of the module.
+        async fn params(
+            &self,
+            request: tonic::Request<super::QueryParamsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryParamsResponse>,
+            tonic::Status,
+        >;
+        /// BtcCheckpointInfo returns checkpoint info for a given epoch
+        async fn btc_checkpoint_info(
+            &self,
+            request: tonic::Request<super::QueryBtcCheckpointInfoRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryBtcCheckpointInfoResponse>,
+            tonic::Status,
+        >;
+    }
+    /// Query defines the gRPC querier service.
+    #[derive(Debug)]
+    pub struct QueryServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> QueryServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for QueryServer<T>
+    where
+        T: Query,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/babylon.btccheckpoint.v1.Query/Params" => {
+                    #[allow(non_camel_case_types)]
+                    struct ParamsSvc<T: Query>(pub Arc<T>);
+                    impl<T: Query> tonic::server::UnaryService<super::QueryParamsRequest>
+                    for ParamsSvc<T> {
+                        type Response = super::QueryParamsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::QueryParamsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Query>::params(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ParamsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/babylon.btccheckpoint.v1.Query/BtcCheckpointInfo" => {
+                    #[allow(non_camel_case_types)]
+                    struct BtcCheckpointInfoSvc<T: Query>(pub Arc<T>);
+                    impl<
+                        T: Query,
+                    > tonic::server::UnaryService<super::QueryBtcCheckpointInfoRequest>
+                    for BtcCheckpointInfoSvc<T> {
+                        type Response = super::QueryBtcCheckpointInfoResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::QueryBtcCheckpointInfoRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Query>::btc_checkpoint_info(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = BtcCheckpointInfoSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        let mut response = http::Response::new(empty_body());
+                        let headers = response.headers_mut();
+                        headers
+                            .insert(
+                                tonic::Status::GRPC_STATUS,
+                                (tonic::Code::Unimplemented as i32).into(),
+                            );
+                        headers
+                            .insert(
+                                http::header::CONTENT_TYPE,
+                                tonic::metadata::GRPC_CONTENT_TYPE,
+                            );
+                        Ok(response)
+                    })
+                }
+            }
+        }
+    }
+    impl<T> Clone for QueryServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    /// Generated gRPC service name
+    pub const SERVICE_NAME: &str = "babylon.btccheckpoint.v1.Query";
+    impl<T> tonic::server::NamedService for QueryServer<T> {
+        const NAME: &'static str = SERVICE_NAME;
+    }
+}
@@ -0,0 +1,878 @@
+// This file is @generated by prost-build.
+/// QueryFinalityProvidersRequest is the request type for the
+/// Query/FinalityProviders RPC method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryFinalityProvidersRequest {
+    /// pagination defines an optional pagination for the request.
+    #[prost(message, optional, tag = "1")]
+    pub pagination: ::core::option::Option<
+        super::super::super::cosmos::base::query::v1beta1::PageRequest,
+    >,
+}
+/// QueryFinalityProvidersResponse is the response type for the
+/// Query/FinalityProviders RPC method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryFinalityProvidersResponse {
+    /// finality_providers contains all the finality providers
+    #[prost(message, repeated, tag = "1")]
+    pub finality_providers: ::prost::alloc::vec::Vec<FinalityProviderResponse>,
+    /// pagination defines the pagination in the response.
+    #[prost(message, optional, tag = "2")]
+    pub pagination: ::core::option::Option<
+        super::super::super::cosmos::base::query::v1beta1::PageResponse,
+    >,
+}
+/// QueryFinalityProviderRequest requests information about a finality provider
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryFinalityProviderRequest {
+    /// fp_btc_pk_hex is the hex str of Bitcoin secp256k1 PK of the finality provider
+    #[prost(string, tag = "1")]
+    pub fp_btc_pk_hex: ::prost::alloc::string::String,
+}
+/// QueryFinalityProviderResponse contains information about a finality provider
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryFinalityProviderResponse {
+    /// finality_provider contains the FinalityProvider
+    #[prost(message, optional, tag = "1")]
+    pub finality_provider: ::core::option::Option<FinalityProviderResponse>,
+}
+/// QueryBTCDelegationsRequest is the request type for the
+/// Query/BTCDelegations RPC method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryBtcDelegationsRequest {
+    /// status is the queried status for BTC delegations
+    #[prost(enumeration = "BtcDelegationStatus", tag = "1")]
+    pub status: i32,
+    /// pagination defines an optional pagination for the request.
+    #[prost(message, optional, tag = "2")]
+    pub pagination: ::core::option::Option<
+        super::super::super::cosmos::base::query::v1beta1::PageRequest,
+    >,
+}
+/// QueryBTCDelegationsResponse is the response type for the
+/// Query/BTCDelegations RPC method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryBtcDelegationsResponse {
+    /// btc_delegations contains all the queried BTC delegations under the given
+    /// status
+    #[prost(message, repeated, tag = "1")]
+    pub btc_delegations: ::prost::alloc::vec::Vec<BtcDelegationResponse>,
+    /// pagination defines the pagination in the response.
+    #[prost(message, optional, tag = "2")]
+    pub pagination: ::core::option::Option<
+        super::super::super::cosmos::base::query::v1beta1::PageResponse,
+    >,
+}
+/// QueryFinalityProviderDelegationsRequest is the request type for the
+/// Query/FinalityProviderDelegations RPC method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryFinalityProviderDelegationsRequest {
+    /// fp_btc_pk_hex is the hex str of Bitcoin secp256k1 PK of the finality
+    /// provider that this BTC delegation delegates to the PK follows encoding in
+    /// BIP-340 spec
+    #[prost(string, tag = "1")]
+    pub fp_btc_pk_hex: ::prost::alloc::string::String,
+    /// pagination defines an optional pagination for the request.
+    #[prost(message, optional, tag = "2")]
+    pub pagination: ::core::option::Option<
+        super::super::super::cosmos::base::query::v1beta1::PageRequest,
+    >,
+}
+/// QueryFinalityProviderDelegationsResponse is the response type for the
+/// Query/FinalityProviderDelegations RPC method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryFinalityProviderDelegationsResponse {
+    /// btc_delegator_delegations contains all the queried BTC delegations.
+    #[prost(message, repeated, tag = "1")]
+    pub btc_delegator_delegations: ::prost::alloc::vec::Vec<
+        BtcDelegatorDelegationsResponse,
+    >,
+    /// pagination defines the pagination in the response.
+    #[prost(message, optional, tag = "2")]
+    pub pagination: ::core::option::Option<
+        super::super::super::cosmos::base::query::v1beta1::PageResponse,
+    >,
+}
+/// QueryBTCDelegationRequest is the request type to retrieve a BTC delegation by
+/// staking tx hash
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryBtcDelegationRequest {
+    /// Hash of staking transaction in btc format
+    #[prost(string, tag = "1")]
+    pub staking_tx_hash_hex: ::prost::alloc::string::String,
+}
+/// QueryBTCDelegationResponse is response type matching
+/// QueryBTCDelegationRequest and containing BTC delegation information
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryBtcDelegationResponse {
+    /// BTCDelegation represents the client needed information of an BTCDelegation.
+    #[prost(message, optional, tag = "1")]
+    pub btc_delegation: ::core::option::Option<BtcDelegationResponse>,
+}
+/// BTCDelegationResponse is the client needed information from a BTCDelegation.
+/// Fields not used by this client are omitted.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BtcDelegationResponse {
+    /// staker_addr is the address to receive rewards from BTC delegation.
+    #[prost(string, tag = "1")]
+    pub staker_addr: ::prost::alloc::string::String,
+    /// btc_pk is the Bitcoin secp256k1 PK of this BTC delegation
+    /// the PK follows encoding in BIP-340 spec
+    #[prost(bytes = "vec", tag = "2")]
+    pub btc_pk: ::prost::alloc::vec::Vec<u8>,
+    /// fp_btc_pk_list is the list of BIP-340 PKs of the finality providers that
+    /// this BTC delegation delegates to
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub fp_btc_pk_list: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    /// staking_time is the number of blocks for which the delegation is locked on
+    /// BTC chain
+    #[prost(uint32, tag = "4")]
+    pub staking_time: u32,
+    /// start_height is the start BTC height of the BTC delegation
+    /// it is the start BTC height of the timelock
+    #[prost(uint32, tag = "5")]
+    pub start_height: u32,
+    /// end_height is the end height of the BTC delegation
+    /// it is the end BTC height of the timelock - w
+    #[prost(uint32, tag = "6")]
+    pub end_height: u32,
+    /// total_sat is the total amount of BTC stakes in this delegation
+    /// quantified in satoshi
+    #[prost(uint64, tag = "7")]
+    pub total_sat: u64,
+    /// staking_tx_hex is the hex string of staking tx
+    #[prost(string, tag = "8")]
+    pub staking_tx_hex: ::prost::alloc::string::String,
+    /// staking_output_idx is the index of the staking output in the staking tx
+    #[prost(uint32, tag = "12")]
+    pub staking_output_idx: u32,
+    /// whether this delegation is active
+    #[prost(bool, tag = "13")]
+    pub active: bool,
+    /// descriptive status of current delegation.
+    #[prost(string, tag = "14")]
+    pub status_desc: ::prost::alloc::string::String,
+}
+/// BTCDelegatorDelegationsResponse is a collection of BTC delegations responses
+/// from the same delegator.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BtcDelegatorDelegationsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub dels: ::prost::alloc::vec::Vec<BtcDelegationResponse>,
+}
+/// FinalityProviderResponse defines a finality provider with voting power
+/// information. Fields not used by this client are omitted.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FinalityProviderResponse {
+    /// commission defines the commission rate of the finality provider.
+    #[prost(string, tag = "2")]
+    pub commission: ::prost::alloc::string::String,
+    /// addr is the address to receive commission from delegations.
+    #[prost(string, tag = "3")]
+    pub addr: ::prost::alloc::string::String,
+    /// btc_pk is the Bitcoin secp256k1 PK of this finality provider
+    /// the PK follows encoding in BIP-340 spec
+    #[prost(bytes = "vec", tag = "4")]
+    pub btc_pk: ::prost::alloc::vec::Vec<u8>,
+    /// slashed_babylon_height indicates the Babylon height when
+    /// the finality provider is slashed.
+    /// if it's 0 then the finality provider is not slashed
+    #[prost(uint64, tag = "6")]
+    pub slashed_babylon_height: u64,
+    /// slashed_btc_height indicates the BTC height when
+    /// the finality provider is slashed.
+    /// if it's 0 then the finality provider is not slashed
+    #[prost(uint32, tag = "7")]
+    pub slashed_btc_height: u32,
+    /// height is the queried Babylon height
+    #[prost(uint64, tag = "8")]
+    pub height: u64,
+    /// jailed defines whether the finality provider is jailed
+    #[prost(bool, tag = "9")]
+    pub jailed: bool,
+    /// highest_voted_height is the highest height for which the
+    /// finality provider has voted
+    #[prost(uint32, tag = "10")]
+    pub highest_voted_height: u32,
+}
+/// BTCDelegationStatus is the status of a delegation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum BtcDelegationStatus {
+    /// PENDING defines a delegation that is waiting for covenant signatures.
+    Pending = 0,
+    /// VERIFIED defines a delegation that has covenant signatures but is not yet
+    /// included in the BTC chain.
+    Verified = 1,
+    /// ACTIVE defines a delegation that has voting power
+    Active = 2,
+    /// UNBONDED defines a delegation no longer has voting power
+    /// by receiving unbonding tx with signatures from staker and covenant
+    /// committee
+    Unbonded = 3,
+    /// EXPIRED defines a delegation no longer has voting power
+    /// for reaching the end of staking transaction timelock
+    Expired = 4,
+    /// ANY is any of the above status
+    Any = 5,
+}
+impl BtcDelegationStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Pending => "PENDING",
+            Self::Verified => "VERIFIED",
+            Self::Active => "ACTIVE",
+            Self::Unbonded => "UNBONDED",
+            Self::Expired => "EXPIRED",
+            Self::Any => "ANY",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "PENDING" => Some(Self::Pending),
+            "VERIFIED" => Some(Self::Verified),
+            "ACTIVE" => Some(Self::Active),
+            "UNBONDED" => Some(Self::Unbonded),
+            "EXPIRED" => Some(Self::Expired),
+            "ANY" => Some(Self::Any),
+            _ => None,
+        }
+    }
+}
+/// Generated client implementations.
+pub mod query_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    /// Query defines the gRPC querier service.
+    #[derive(Debug, Clone)]
+    pub struct QueryClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl QueryClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> QueryClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> QueryClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            QueryClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// FinalityProviders queries all finality providers
+        pub async fn finality_providers(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryFinalityProvidersRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryFinalityProvidersResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/babylon.btcstaking.v1.Query/FinalityProviders",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("babylon.btcstaking.v1.Query", "FinalityProviders"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// FinalityProvider info about one finality provider
+        pub async fn finality_provider(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryFinalityProviderRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryFinalityProviderResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/babylon.btcstaking.v1.Query/FinalityProvider",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("babylon.btcstaking.v1.Query", "FinalityProvider"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// BTCDelegations queries all BTC delegations under a given status
+        pub async fn btc_delegations(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryBtcDelegationsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryBtcDelegationsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/babylon.btcstaking.v1.Query/BTCDelegations",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("babylon.btcstaking.v1.Query", "BTCDelegations"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// FinalityProviderDelegations queries all BTC delegations of the given
+        /// finality provider
+        pub async fn finality_provider_delegations(
+            &mut self,
+            request: impl tonic::IntoRequest<
+                super::QueryFinalityProviderDelegationsRequest,
+            >,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryFinalityProviderDelegationsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/babylon.btcstaking.v1.Query/FinalityProviderDelegations",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "babylon.btcstaking.v1.Query",
+                        "FinalityProviderDelegations",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// BTCDelegation retrieves delegation by corresponding staking tx hash
+        pub async fn btc_delegation(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryBtcDelegationRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryBtcDelegationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/babylon.btcstaking.v1.Query/BTCDelegation",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("babylon.btcstaking.v1.Query", "BTCDelegation"));
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod query_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with QueryServer.
+    #[async_trait]
+    pub trait Query: std::marker::Send + std::marker::Sync + 'static {
+        /// FinalityProviders queries all finality providers
+        async fn finality_providers(
+            &self,
+            request: tonic::Request<super::QueryFinalityProvidersRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryFinalityProvidersResponse>,
+            tonic::Status,
+        >;
+        /// FinalityProvider info about one finality provider
+        async fn finality_provider(
+            &self,
+            request: tonic::Request<super::QueryFinalityProviderRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryFinalityProviderResponse>,
+            tonic::Status,
+        >;
+        /// BTCDelegations queries all BTC delegations under a given status
+        async fn btc_delegations(
+            &self,
+            request: tonic::Request<super::QueryBtcDelegationsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryBtcDelegationsResponse>,
+            tonic::Status,
+        >;
+        /// FinalityProviderDelegations queries all BTC delegations of the given
+        /// finality provider
+        async fn finality_provider_delegations(
+            &self,
+            request: tonic::Request<super::QueryFinalityProviderDelegationsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryFinalityProviderDelegationsResponse>,
+            tonic::Status,
+        >;
+        /// BTCDelegation retrieves delegation by corresponding staking tx hash
+        async fn btc_delegation(
+            &self,
+            request: tonic::Request<super::QueryBtcDelegationRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryBtcDelegationResponse>,
+            tonic::Status,
+        >;
+    }
+    /// Query defines the gRPC querier service.
+    #[derive(Debug)]
+    pub struct QueryServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> QueryServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for QueryServer<T>
+    where
+        T: Query,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/babylon.btcstaking.v1.Query/FinalityProviders" => {
+                    #[allow(non_camel_case_types)]
+                    struct FinalityProvidersSvc<T: Query>(pub Arc<T>);
+                    impl<
+                        T: Query,
+                    > tonic::server::UnaryService<super::QueryFinalityProvidersRequest>
+                    for FinalityProvidersSvc<T> {
+                        type Response = super::QueryFinalityProvidersResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::QueryFinalityProvidersRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Query>::finality_providers(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = FinalityProvidersSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/babylon.btcstaking.v1.Query/FinalityProvider" => {
+                    #[allow(non_camel_case_types)]
+                    struct FinalityProviderSvc<T: Query>(pub Arc<T>);
+                    impl<
+                        T: Query,
+                    > tonic::server::UnaryService<super::QueryFinalityProviderRequest>
+                    for FinalityProviderSvc<T> {
+                        type Response = super::QueryFinalityProviderResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::QueryFinalityProviderRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Query>::finality_provider(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = FinalityProviderSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/babylon.btcstaking.v1.Query/BTCDelegations" => {
+                    #[allow(non_camel_case_types)]
+                    struct BTCDelegationsSvc<T: Query>(pub Arc<T>);
+                    impl<
+                        T: Query,
+                    > tonic::server::UnaryService<super::QueryBtcDelegationsRequest>
+                    for BTCDelegationsSvc<T> {
+                        type Response = super::QueryBtcDelegationsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::QueryBtcDelegationsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Query>::btc_delegations(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = BTCDelegationsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/babylon.btcstaking.v1.Query/FinalityProviderDelegations" => {
+                    #[allow(non_camel_case_types)]
+                    struct FinalityProviderDelegationsSvc<T: Query>(pub Arc<T>);
+                    impl<
+                        T: Query,
+                    > tonic::server::UnaryService<
+                        super::QueryFinalityProviderDelegationsRequest,
+                    > for FinalityProviderDelegationsSvc<T> {
+                        type Response = super::QueryFinalityProviderDelegationsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::QueryFinalityProviderDelegationsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Query>::finality_provider_delegations(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = FinalityProviderDelegationsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/babylon.btcstaking.v1.Query/BTCDelegation" => {
+                    #[allow(non_camel_case_types)]
+                    struct BTCDelegationSvc<T: Query>(pub Arc<T>);
+                    impl<
+                        T: Query,
+                    > tonic::server::UnaryService<super::QueryBtcDelegationRequest>
+                    for BTCDelegationSvc<T> {
+                        type Response = super::QueryBtcDelegationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::QueryBtcDelegationRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Query>::btc_delegation(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = BTCDelegationSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        let mut response = http::Response::new(empty_body());
+                        let headers = response.headers_mut();
+                        headers
+                            .insert(
+                                tonic::Status::GRPC_STATUS,
+                                (tonic::Code::Unimplemented as i32).into(),
+                            );
+                        headers
+                            .insert(
+                                http::header::CONTENT_TYPE,
+                                tonic::metadata::GRPC_CONTENT_TYPE,
+                            );
+                        Ok(response)
+                    })
+                }
+            }
+        }
+    }
+    impl<T> Clone for QueryServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    /// Generated gRPC service name
+    pub const SERVICE_NAME: &str = "babylon.btcstaking.v1.Query";
+    impl<T> tonic::server::NamedService for QueryServer<T> {
+        const NAME: &'static str = SERVICE_NAME;
+    }
+}
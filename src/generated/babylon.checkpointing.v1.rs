@@ -0,0 +1,649 @@
+// This file is @generated by prost-build.
+/// RawCheckpoint wraps the BLS multi sig with metadata
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RawCheckpoint {
+    /// epoch_num defines the epoch number the raw checkpoint is for
+    #[prost(uint64, tag = "1")]
+    pub epoch_num: u64,
+    /// block_hash defines the 'BlockID.Hash', which is the hash of
+    /// the block that individual BLS sigs are signed on
+    #[prost(bytes = "vec", tag = "2")]
+    pub block_hash: ::prost::alloc::vec::Vec<u8>,
+    /// bitmap defines the bitmap that indicates the signers of the BLS multi sig
+    #[prost(bytes = "vec", tag = "3")]
+    pub bitmap: ::prost::alloc::vec::Vec<u8>,
+    /// bls_multi_sig defines the multi sig that is aggregated from individual BLS
+    /// sigs
+    #[prost(bytes = "vec", tag = "4")]
+    pub bls_multi_sig: ::prost::alloc::vec::Vec<u8>,
+}
+/// CheckpointStateUpdate defines a state transition on the checkpoint.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct CheckpointStateUpdate {
+    /// state defines the event of a state transition towards this state
+    #[prost(enumeration = "CheckpointStatus", tag = "1")]
+    pub state: i32,
+    /// block_height is the height of the Babylon block that triggers the state
+    /// update
+    #[prost(uint64, tag = "2")]
+    pub block_height: u64,
+    /// block_time is the timestamp in the Babylon block that triggers the state
+    /// update
+    #[prost(message, optional, tag = "3")]
+    pub block_time: ::core::option::Option<::prost_types::Timestamp>,
+}
+/// CheckpointStatus is the status of a checkpoint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum CheckpointStatus {
+    /// ACCUMULATING defines a checkpoint that is awaiting for BLS signatures.
+    CkptStatusAccumulating = 0,
+    /// SEALED defines a checkpoint that has accumulated sufficient BLS signatures.
+    CkptStatusSealed = 1,
+    /// SUBMITTED defines a checkpoint that is included on BTC.
+    CkptStatusSubmitted = 2,
+    /// CONFIRMED defines a checkpoint that is k-deep on BTC.
+    CkptStatusConfirmed = 3,
+    /// FINALIZED defines a checkpoint that is w-deep on BTC.
+    CkptStatusFinalized = 4,
+}
+impl CheckpointStatus {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::CkptStatusAccumulating => "CKPT_STATUS_ACCUMULATING",
+            Self::CkptStatusSealed => "CKPT_STATUS_SEALED",
+            Self::CkptStatusSubmitted => "CKPT_STATUS_SUBMITTED",
+            Self::CkptStatusConfirmed => "CKPT_STATUS_CONFIRMED",
+            Self::CkptStatusFinalized => "CKPT_STATUS_FINALIZED",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "CKPT_STATUS_ACCUMULATING" => Some(Self::CkptStatusAccumulating),
+            "CKPT_STATUS_SEALED" => Some(Self::CkptStatusSealed),
+            "CKPT_STATUS_SUBMITTED" => Some(Self::CkptStatusSubmitted),
+            "CKPT_STATUS_CONFIRMED" => Some(Self::CkptStatusConfirmed),
+            "CKPT_STATUS_FINALIZED" => Some(Self::CkptStatusFinalized),
+            _ => None,
+        }
+    }
+}
+/// QueryRawCheckpointRequest defines the query to get a raw checkpoint at a
+/// given epoch number
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct QueryRawCheckpointRequest {
+    /// epoch_num defines the epoch for the queried checkpoint
+    #[prost(uint64, tag = "1")]
+    pub epoch_num: u64,
+}
+/// QueryRawCheckpointResponse is the response type for the Query/RawCheckpoint
+/// RPC method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryRawCheckpointResponse {
+    #[prost(message, optional, tag = "1")]
+    pub raw_checkpoint: ::core::option::Option<RawCheckpointWithMetaResponse>,
+}
+/// QueryEpochStatusRequest is the request type for the Query/EpochStatus
+/// RPC method.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct QueryEpochStatusRequest {
+    #[prost(uint64, tag = "1")]
+    pub epoch_num: u64,
+}
+/// QueryEpochStatusResponse is the response type for the Query/EpochStatus
+/// RPC method.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct QueryEpochStatusResponse {
+    #[prost(enumeration = "CheckpointStatus", tag = "1")]
+    pub status: i32,
+}
+/// QueryLastCheckpointWithStatusRequest is the request type for the
+/// Query/LastCheckpointWithStatus RPC method.
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct QueryLastCheckpointWithStatusRequest {
+    #[prost(enumeration = "CheckpointStatus", tag = "1")]
+    pub status: i32,
+}
+/// QueryLastCheckpointWithStatusResponse is the response type for the
+/// Query/LastCheckpointWithStatus RPC method.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryLastCheckpointWithStatusResponse {
+    #[prost(message, optional, tag = "1")]
+    pub raw_checkpoint: ::core::option::Option<RawCheckpointResponse>,
+}
+/// RawCheckpointResponse wraps the BLS multi sig with metadata
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RawCheckpointResponse {
+    /// epoch_num defines the epoch number the raw checkpoint is for
+    #[prost(uint64, tag = "1")]
+    pub epoch_num: u64,
+    /// block_hash_hex defines the 'BlockID.Hash', which is the hash of
+    /// the block that individual BLS sigs are signed on as hex string
+    #[prost(string, tag = "2")]
+    pub block_hash_hex: ::prost::alloc::string::String,
+    /// bitmap defines the bitmap that indicates the signers of the BLS multi sig
+    #[prost(bytes = "vec", tag = "3")]
+    pub bitmap: ::prost::alloc::vec::Vec<u8>,
+    /// bls_multi_sig defines the multi sig that is aggregated from individual BLS
+    /// sigs
+    #[prost(bytes = "vec", tag = "4")]
+    pub bls_multi_sig: ::prost::alloc::vec::Vec<u8>,
+}
+/// CheckpointStateUpdateResponse defines a state transition on the checkpoint.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CheckpointStateUpdateResponse {
+    /// state defines the event of a state transition towards this state
+    #[prost(enumeration = "CheckpointStatus", tag = "1")]
+    pub state: i32,
+    /// status_desc represents the description of status enum.
+    #[prost(string, tag = "2")]
+    pub status_desc: ::prost::alloc::string::String,
+    /// block_height is the height of the Babylon block that triggers the state
+    /// update
+    #[prost(uint64, tag = "3")]
+    pub block_height: u64,
+    /// block_time is the timestamp in the Babylon block that triggers the state
+    /// update
+    #[prost(message, optional, tag = "4")]
+    pub block_time: ::core::option::Option<::prost_types::Timestamp>,
+}
+/// RawCheckpointWithMetaResponse wraps the raw checkpoint with metadata.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RawCheckpointWithMetaResponse {
+    #[prost(message, optional, tag = "1")]
+    pub ckpt: ::core::option::Option<RawCheckpointResponse>,
+    /// status defines the status of the checkpoint
+    #[prost(enumeration = "CheckpointStatus", tag = "2")]
+    pub status: i32,
+    /// status_desc represents the description of status enum.
+    #[prost(string, tag = "3")]
+    pub status_desc: ::prost::alloc::string::String,
+    /// bls_aggr_pk defines the aggregated BLS public key
+    #[prost(bytes = "vec", tag = "4")]
+    pub bls_aggr_pk: ::prost::alloc::vec::Vec<u8>,
+    /// power_sum defines the accumulated voting power for the checkpoint
+    #[prost(uint64, tag = "5")]
+    pub power_sum: u64,
+    /// lifecycle defines the lifecycle of this checkpoint, i.e., each state
+    /// transition and the time (in both timestamp and block height) of this
+    /// transition.
+    #[prost(message, repeated, tag = "6")]
+    pub lifecycle: ::prost::alloc::vec::Vec<CheckpointStateUpdateResponse>,
+}
+/// Generated client implementations.
+pub mod query_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    /// Query defines the gRPC querier service.
+    #[derive(Debug, Clone)]
+    pub struct QueryClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl QueryClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> QueryClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> QueryClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            QueryClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// RawCheckpoint queries a checkpoints at a given epoch number.
+        pub async fn raw_checkpoint(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryRawCheckpointRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryRawCheckpointResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/babylon.checkpointing.v1.Query/RawCheckpoint",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("babylon.checkpointing.v1.Query", "RawCheckpoint"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// EpochStatus queries the status of the checkpoint at a given epoch
+        pub async fn epoch_status(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryEpochStatusRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryEpochStatusResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/babylon.checkpointing.v1.Query/EpochStatus",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new("babylon.checkpointing.v1.Query", "EpochStatus"),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// LastCheckpointWithStatus queries the last checkpoint with a given status or
+        /// a more matured status
+        pub async fn last_checkpoint_with_status(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryLastCheckpointWithStatusRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryLastCheckpointWithStatusResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/babylon.checkpointing.v1.Query/LastCheckpointWithStatus",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "babylon.checkpointing.v1.Query",
+                        "LastCheckpointWithStatus",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod query_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with QueryServer.
+    #[async_trait]
+    pub trait Query: std::marker::Send + std::marker::Sync + 'static {
+        /// RawCheckpoint queries a checkpoints at a given epoch number.
+        async fn raw_checkpoint(
+            &self,
+            request: tonic::Request<super::QueryRawCheckpointRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryRawCheckpointResponse>,
+            tonic::Status,
+        >;
+        /// EpochStatus queries the status of the checkpoint at a given epoch
+        async fn epoch_status(
+            &self,
+            request: tonic::Request<super::QueryEpochStatusRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryEpochStatusResponse>,
+            tonic::Status,
+        >;
+        /// LastCheckpointWithStatus queries the last checkpoint with a given status or
+        /// a more matured status
+        async fn last_checkpoint_with_status(
+            &self,
+            request: tonic::Request<super::QueryLastCheckpointWithStatusRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryLastCheckpointWithStatusResponse>,
+            tonic::Status,
+        >;
+    }
+    /// Query defines the gRPC querier service.
+    #[derive(Debug)]
+    pub struct QueryServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> QueryServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for QueryServer<T>
+    where
+        T: Query,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/babylon.checkpointing.v1.Query/RawCheckpoint" => {
+                    #[allow(non_camel_case_types)]
+                    struct RawCheckpointSvc<T: Query>(pub Arc<T>);
+                    impl<
+                        T: Query,
+                    > tonic::server::UnaryService<super::QueryRawCheckpointRequest>
+                    for RawCheckpointSvc<T> {
+                        type Response = super::QueryRawCheckpointResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::QueryRawCheckpointRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Query>::raw_checkpoint(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RawCheckpointSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/babylon.checkpointing.v1.Query/EpochStatus" => {
+                    #[allow(non_camel_case_types)]
+                    struct EpochStatusSvc<T: Query>(pub Arc<T>);
+                    impl<
+                        T: Query,
+                    > tonic::server::UnaryService<super::QueryEpochStatusRequest>
+                    for EpochStatusSvc<T> {
+                        type Response = super::QueryEpochStatusResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::QueryEpochStatusRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Query>::epoch_status(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = EpochStatusSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/babylon.checkpointing.v1.Query/LastCheckpointWithStatus" => {
+                    #[allow(non_camel_case_types)]
+                    struct LastCheckpointWithStatusSvc<T: Query>(pub Arc<T>);
+                    impl<
+                        T: Query,
+                    > tonic::server::UnaryService<
+                        super::QueryLastCheckpointWithStatusRequest,
+                    > for LastCheckpointWithStatusSvc<T> {
+                        type Response = super::QueryLastCheckpointWithStatusResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::QueryLastCheckpointWithStatusRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Query>::last_checkpoint_with_status(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = LastCheckpointWithStatusSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        let mut response = http::Response::new(empty_body());
+                        let headers = response.headers_mut();
+                        headers
+                            .insert(
+                                tonic::Status::GRPC_STATUS,
+                                (tonic::Code::Unimplemented as i32).into(),
+                            );
+                        headers
+                            .insert(
+                                http::header::CONTENT_TYPE,
+                                tonic::metadata::GRPC_CONTENT_TYPE,
+                            );
+                        Ok(response)
+                    })
+                }
+            }
+        }
+    }
+    impl<T> Clone for QueryServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    /// Generated gRPC service name
+    pub const SERVICE_NAME: &str = "babylon.checkpointing.v1.Query";
+    impl<T> tonic::server::NamedService for QueryServer<T> {
+        const NAME: &'static str = SERVICE_NAME;
+    }
+}
@@ -0,0 +1,23 @@
+// This file is @generated by prost-build.
+/// Coin defines a token with a denomination and an amount.
+///
+/// NOTE: The amount field is an Int which implements the custom method
+/// signatures required by gogoproto.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Coin {
+    #[prost(string, tag = "1")]
+    pub denom: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub amount: ::prost::alloc::string::String,
+}
+/// DecCoin defines a token with a denomination and a decimal amount.
+///
+/// NOTE: The amount field is an Dec which implements the custom method
+/// signatures required by gogoproto.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DecCoin {
+    #[prost(string, tag = "1")]
+    pub denom: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub amount: ::prost::alloc::string::String,
+}
@@ -5,6 +5,9 @@ pub mod cosmos {
                 include!("cosmos.base.query.v1beta1.rs");
             }
         }
+        pub mod v1beta1 {
+            include!("cosmos.base.v1beta1.rs");
+        }
     }
 }
 pub mod babylon {
@@ -13,6 +16,31 @@ pub mod babylon {
             include!("babylon.btclightclient.v1.rs");
         }
     }
+    pub mod checkpointing {
+        pub mod v1 {
+            include!("babylon.checkpointing.v1.rs");
+        }
+    }
+    pub mod btccheckpoint {
+        pub mod v1 {
+            include!("babylon.btccheckpoint.v1.rs");
+        }
+    }
+    pub mod btcstaking {
+        pub mod v1 {
+            include!("babylon.btcstaking.v1.rs");
+        }
+    }
+    pub mod epoching {
+        pub mod v1 {
+            include!("babylon.epoching.v1.rs");
+        }
+    }
+    pub mod incentive {
+        pub mod v1 {
+            include!("babylon.incentive.v1.rs");
+        }
+    }
 }
 pub mod cosmos_proto {
     include!("cosmos_proto.rs");
@@ -21,4 +49,4 @@ pub mod google {
     pub mod api {
         include!("google.api.rs");
     }
-}
\ No newline at end of file
+}
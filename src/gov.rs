@@ -0,0 +1,402 @@
+use cosmos_sdk_proto::cosmos::base::query::v1beta1::PageRequest;
+#[cfg(feature = "wallet")]
+use cosmos_sdk_proto::cosmos::base::v1beta1::Coin;
+use cosmos_sdk_proto::cosmos::gov::v1::{
+    query_client::QueryClient as GovQueryClient, Proposal, QueryProposalRequest,
+    QueryProposalsRequest, QueryTallyResultRequest, TallyResult,
+};
+#[cfg(feature = "wallet")]
+use cosmos_sdk_proto::cosmos::gov::v1::{MsgDeposit, MsgSubmitProposal, MsgVote};
+pub use cosmos_sdk_proto::cosmos::gov::v1::{ProposalStatus, VoteOption};
+#[cfg(feature = "wallet")]
+use cosmos_sdk_proto::cosmwasm::wasm::v1::{MsgMigrateContract, MsgStoreCode};
+use cosmrs::AccountId;
+#[cfg(feature = "wallet")]
+use cosmrs::Any;
+#[cfg(feature = "wallet")]
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::client::CosmWasmClient;
+use crate::error::ClientError;
+#[cfg(feature = "wallet")]
+use crate::transactions::contract_msg_bytes;
+
+/// Proposals queried per page by `query_proposals`
+const PROPOSALS_PAGE_LIMIT: u64 = 100;
+
+/// Returns the address of a module account, e.g. `gov`, the authority of the
+/// messages executed by governance
+pub fn module_address(module: &str, prefix: &str) -> Result<AccountId, ClientError> {
+    let hash = Sha256::digest(module.as_bytes());
+    AccountId::new(prefix, &hash[..20]).map_err(|e| ClientError::invalid("module address", e))
+}
+
+/// Title, summary and deposit of a proposal to submit
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProposalRequest {
+    pub title: String,
+    pub summary: String,
+    /// metadata is e.g. a link to the full proposal text
+    pub metadata: String,
+    /// initial_deposit is paid in `ChainConfig.denom`
+    pub initial_deposit: u128,
+    pub expedited: bool,
+}
+
+impl ProposalRequest {
+    pub fn new(title: &str, summary: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            summary: summary.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_metadata(mut self, metadata: &str) -> Self {
+        self.metadata = metadata.to_string();
+        self
+    }
+
+    pub fn with_initial_deposit(mut self, initial_deposit: u128) -> Self {
+        self.initial_deposit = initial_deposit;
+        self
+    }
+
+    pub fn with_expedited(mut self, expedited: bool) -> Self {
+        self.expedited = expedited;
+        self
+    }
+}
+
+/// Vote counts of a proposal, in staking units
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub yes: u128,
+    pub no: u128,
+    pub abstain: u128,
+    pub no_with_veto: u128,
+}
+
+impl TryFrom<TallyResult> for Tally {
+    type Error = ClientError;
+
+    fn try_from(tally: TallyResult) -> Result<Self, ClientError> {
+        let count = |count: &str| {
+            count
+                .parse::<u128>()
+                .map_err(|e| ClientError::invalid("tally count", e))
+        };
+        Ok(Self {
+            yes: count(&tally.yes_count)?,
+            no: count(&tally.no_count)?,
+            abstain: count(&tally.abstain_count)?,
+            no_with_veto: count(&tally.no_with_veto_count)?,
+        })
+    }
+}
+
+/// Governance proposal as returned by the gov module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProposalInfo {
+    pub id: u64,
+    pub title: String,
+    pub summary: String,
+    pub metadata: String,
+    pub status: ProposalStatus,
+    pub proposer: String,
+    /// message_type_urls are the type URLs of the messages executed if it passes
+    pub message_type_urls: Vec<String>,
+    /// total_deposit is the deposit by denom
+    pub total_deposit: Vec<(String, u128)>,
+    /// voting_end_time is the unix timestamp in seconds voting ends at, `None`
+    /// during the deposit period
+    pub voting_end_time: Option<i64>,
+    /// final_tally is only set once voting has ended, see `query_tally`
+    pub final_tally: Option<Tally>,
+}
+
+impl TryFrom<Proposal> for ProposalInfo {
+    type Error = ClientError;
+
+    fn try_from(proposal: Proposal) -> Result<Self, ClientError> {
+        let total_deposit = proposal
+            .total_deposit
+            .into_iter()
+            .map(|coin| {
+                let amount = coin
+                    .amount
+                    .parse::<u128>()
+                    .map_err(|e| ClientError::invalid("deposit amount", e))?;
+                Ok((coin.denom, amount))
+            })
+            .collect::<Result<_, ClientError>>()?;
+
+        Ok(Self {
+            id: proposal.id,
+            status: ProposalStatus::try_from(proposal.status).unwrap_or_default(),
+            title: proposal.title,
+            summary: proposal.summary,
+            metadata: proposal.metadata,
+            proposer: proposal.proposer,
+            message_type_urls: proposal
+                .messages
+                .into_iter()
+                .map(|msg| msg.type_url)
+                .collect(),
+            total_deposit,
+            voting_end_time: proposal.voting_end_time.map(|time| time.seconds),
+            final_tally: proposal
+                .final_tally_result
+                .map(Tally::try_from)
+                .transpose()?,
+        })
+    }
+}
+
+impl CosmWasmClient {
+    async fn gov_query_client(
+        &self,
+    ) -> Result<GovQueryClient<tonic::transport::Channel>, ClientError> {
+        GovQueryClient::connect(self.grpc_url.clone())
+            .await
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))
+    }
+
+    /// Returns the address of the gov module, the authority of governance messages
+    pub fn gov_authority(&self) -> Result<AccountId, ClientError> {
+        module_address("gov", &self.config.account_prefix)
+    }
+
+    pub async fn query_proposal(&self, proposal_id: u64) -> Result<ProposalInfo, ClientError> {
+        self.gov_query_client()
+            .await?
+            .proposal(QueryProposalRequest { proposal_id })
+            .await
+            .map_err(|e| ClientError::grpc(format!("query proposal {}", proposal_id), e))?
+            .into_inner()
+            .proposal
+            .ok_or_else(|| ClientError::not_found(format!("proposal {}", proposal_id)))?
+            .try_into()
+    }
+
+    /// Returns the proposals with the given status, all if `None`, in ascending id order
+    pub async fn query_proposals(
+        &self,
+        status: Option<ProposalStatus>,
+    ) -> Result<Vec<ProposalInfo>, ClientError> {
+        let mut client = self.gov_query_client().await?;
+
+        let mut proposals = vec![];
+        let mut key = vec![];
+        loop {
+            let resp = client
+                .proposals(QueryProposalsRequest {
+                    proposal_status: status.unwrap_or_default() as i32,
+                    pagination: Some(PageRequest {
+                        key,
+                        limit: PROPOSALS_PAGE_LIMIT,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+                .await
+                .map_err(|e| ClientError::grpc("query proposals", e))?
+                .into_inner();
+            for proposal in resp.proposals {
+                proposals.push(proposal.try_into()?);
+            }
+
+            key = resp.pagination.map(|p| p.next_key).unwrap_or_default();
+            if key.is_empty() {
+                break;
+            }
+        }
+
+        Ok(proposals)
+    }
+
+    /// Returns the current tally of a proposal in its voting period
+    pub async fn query_tally(&self, proposal_id: u64) -> Result<Tally, ClientError> {
+        self.gov_query_client()
+            .await?
+            .tally_result(QueryTallyResultRequest { proposal_id })
+            .await
+            .map_err(|e| ClientError::grpc(format!("query tally of proposal {}", proposal_id), e))?
+            .into_inner()
+            .tally
+            .ok_or_else(|| ClientError::not_found(format!("tally of proposal {}", proposal_id)))?
+            .try_into()
+    }
+
+    /// Returns the id of the proposal submitted by the given tx
+    pub async fn query_submitted_proposal_id(&self, tx_hash: &str) -> Result<u64, ClientError> {
+        let tx_response = self
+            .get_tx(tx_hash)
+            .await?
+            .tx_response
+            .ok_or_else(|| ClientError::not_found(format!("tx {}", tx_hash)))?;
+
+        tx_response
+            .events
+            .iter()
+            .filter(|event| event.r#type == "submit_proposal")
+            .flat_map(|event| &event.attributes)
+            .find(|attr| attr.key == "proposal_id")
+            .ok_or_else(|| ClientError::not_found(format!("proposal id in tx {}", tx_hash)))?
+            .value
+            .parse()
+            .map_err(|e| ClientError::invalid("proposal id", e))
+    }
+}
+
+#[cfg(feature = "wallet")]
+fn to_any<M: cosmos_sdk_proto::traits::Name>(msg: &M) -> Result<Any, ClientError> {
+    Any::from_msg(msg).map_err(|e| ClientError::serialization("convert message to Any", e))
+}
+
+#[cfg(feature = "wallet")]
+impl CosmWasmClient {
+    /// Submits a proposal executing the given messages if it passes. Their
+    /// signer must be `gov_authority()`. Returns the tx hash, see
+    /// `query_submitted_proposal_id` for the proposal id.
+    pub async fn submit_proposal(
+        &self,
+        proposal: &ProposalRequest,
+        messages: Vec<Any>,
+    ) -> Result<String, ClientError> {
+        let msg = MsgSubmitProposal {
+            messages,
+            initial_deposit: self.coins(proposal.initial_deposit),
+            proposer: self.signer()?.account_id.to_string(),
+            metadata: proposal.metadata.clone(),
+            title: proposal.title.clone(),
+            summary: proposal.summary.clone(),
+            expedited: proposal.expedited,
+        };
+        tracing::info!("Submitting proposal: {}", proposal.title);
+
+        self.build_and_broadcast_msgs(vec![to_any(&msg)?]).await
+    }
+
+    /// Submits a proposal storing the wasm code, e.g. a new bridge contract
+    /// version on a chain with permissioned uploads
+    pub async fn propose_store_code(
+        &self,
+        proposal: &ProposalRequest,
+        wasm_byte_code: Vec<u8>,
+    ) -> Result<String, ClientError> {
+        let msg = MsgStoreCode {
+            sender: self.gov_authority()?.to_string(),
+            wasm_byte_code,
+            instantiate_permission: None,
+        };
+
+        self.submit_proposal(proposal, vec![to_any(&msg)?]).await
+    }
+
+    /// Submits a proposal migrating the contract to the code, for contracts
+    /// whose admin is the gov module
+    pub async fn propose_migrate_contract<T: Serialize>(
+        &self,
+        proposal: &ProposalRequest,
+        contract: &str,
+        code_id: u64,
+        migrate_msg: &T,
+    ) -> Result<String, ClientError> {
+        let msg = MsgMigrateContract {
+            sender: self.gov_authority()?.to_string(),
+            contract: contract.to_string(),
+            code_id,
+            msg: contract_msg_bytes(migrate_msg)?,
+        };
+
+        self.submit_proposal(proposal, vec![to_any(&msg)?]).await
+    }
+
+    /// Adds a deposit in `ChainConfig.denom` to a proposal in its deposit period
+    pub async fn deposit(&self, proposal_id: u64, amount: u128) -> Result<String, ClientError> {
+        let msg = MsgDeposit {
+            proposal_id,
+            depositor: self.signer()?.account_id.to_string(),
+            amount: self.coins(amount),
+        };
+
+        self.build_and_broadcast_msgs(vec![to_any(&msg)?]).await
+    }
+
+    pub async fn vote(&self, proposal_id: u64, option: VoteOption) -> Result<String, ClientError> {
+        let msg = MsgVote {
+            proposal_id,
+            voter: self.signer()?.account_id.to_string(),
+            option: option as i32,
+            metadata: String::new(),
+        };
+        tracing::info!(
+            "Voting {} on proposal {}",
+            option.as_str_name(),
+            proposal_id
+        );
+
+        self.build_and_broadcast_msgs(vec![to_any(&msg)?]).await
+    }
+
+    fn coins(&self, amount: u128) -> Vec<Coin> {
+        if amount == 0 {
+            return vec![];
+        }
+        vec![Coin {
+            denom: self.config.denom.clone(),
+            amount: amount.to_string(),
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmos_sdk_proto::cosmos::base::v1beta1::Coin;
+
+    #[test]
+    fn test_proposal_info_conversion() {
+        // the gov module account of every Cosmos SDK chain
+        assert_eq!(
+            module_address("gov", "cosmos").unwrap().to_string(),
+            "cosmos10d07y265gmmuvt4z0w9aw880jnsr700j6zn9kn"
+        );
+
+        let proposal = Proposal {
+            id: 7,
+            status: ProposalStatus::VotingPeriod as i32,
+            title: "Upgrade bridge".to_string(),
+            messages: vec![cosmrs::Any {
+                type_url: "/cosmwasm.wasm.v1.MsgMigrateContract".to_string(),
+                value: vec![],
+            }],
+            total_deposit: vec![Coin {
+                denom: "ubbn".to_string(),
+                amount: "1000".to_string(),
+            }],
+            final_tally_result: Some(TallyResult {
+                yes_count: "10".to_string(),
+                abstain_count: "0".to_string(),
+                no_count: "2".to_string(),
+                no_with_veto_count: "1".to_string(),
+            }),
+            ..Default::default()
+        };
+        let info = ProposalInfo::try_from(proposal.clone()).unwrap();
+        assert_eq!(info.status, ProposalStatus::VotingPeriod);
+        assert_eq!(info.total_deposit, vec![("ubbn".to_string(), 1000)]);
+        assert_eq!(
+            info.message_type_urls,
+            vec!["/cosmwasm.wasm.v1.MsgMigrateContract"]
+        );
+        assert_eq!(info.final_tally.unwrap().no_with_veto, 1);
+        assert_eq!(info.voting_end_time, None);
+
+        let mut invalid = proposal;
+        invalid.total_deposit[0].amount = "x".to_string();
+        assert!(ProposalInfo::try_from(invalid).is_err());
+    }
+}
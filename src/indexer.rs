@@ -0,0 +1,389 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+use crate::checkpoint::CheckpointStore;
+use crate::error::ClientError;
+use crate::events::{BlockEvents, ContractEvent};
+use crate::sink::EventSink;
+use crate::transactions::TxHash;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS indexed_events (
+    height INTEGER NOT NULL,
+    event_index INTEGER NOT NULL,
+    block_time INTEGER NOT NULL,
+    tx_hash TEXT,
+    tx_index INTEGER,
+    code INTEGER NOT NULL,
+    msg_index INTEGER,
+    contract TEXT NOT NULL,
+    action TEXT NOT NULL,
+    from_address TEXT,
+    to_address TEXT,
+    event TEXT NOT NULL,
+    PRIMARY KEY (height, event_index)
+);
+CREATE INDEX IF NOT EXISTS indexed_events_action ON indexed_events (action, height);
+CREATE INDEX IF NOT EXISTS indexed_events_from ON indexed_events (from_address, height);
+CREATE INDEX IF NOT EXISTS indexed_events_to ON indexed_events (to_address, height);
+CREATE INDEX IF NOT EXISTS indexed_events_contract ON indexed_events (contract, height);
+CREATE TABLE IF NOT EXISTS indexer_checkpoint (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    height INTEGER NOT NULL
+);
+";
+
+const SELECT_EVENTS: &str = "SELECT height, block_time, tx_hash, tx_index, code, msg_index, contract, event FROM indexed_events";
+
+/// Contract event as stored by a `SqliteIndexer`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedEvent {
+    pub height: u64,
+    /// block_time is the unix timestamp of the block in seconds
+    pub block_time: u64,
+    /// tx_hash is `None` for events emitted outside of txs, e.g. in FinalizeBlock
+    pub tx_hash: Option<TxHash>,
+    pub tx_index: Option<u32>,
+    /// code is the ABCI result code of the tx, 0 on success
+    pub code: u32,
+    pub msg_index: Option<u32>,
+    pub contract: String,
+    pub event: ContractEvent,
+}
+
+/// Returns the addresses an event moves funds or rights from and to, used to
+/// look up the events of an address
+fn event_addresses(event: &ContractEvent) -> (Option<&str>, Option<&str>) {
+    match event {
+        ContractEvent::PegIn(event) => (None, Some(&event.receiver)),
+        ContractEvent::PegOut(event) => (Some(&event.sender), None),
+        ContractEvent::Cw20Transfer(event) => (Some(&event.from), Some(&event.to)),
+        ContractEvent::Cw20Mint(event) => (None, Some(&event.to)),
+        ContractEvent::Cw20Burn(event) => (Some(&event.from), None),
+        ContractEvent::UpdateAdmin(event) => (None, event.new_admin.as_deref()),
+        ContractEvent::Instantiate(_) | ContractEvent::Migrate(_) | ContractEvent::Raw(_) => {
+            (None, None)
+        }
+    }
+}
+
+/// Persists the events and the checkpoint of every processed block to SQLite
+/// and answers queries on them, for durable history without a separate
+/// indexing stack. Pass clones of it to the listener as both its sink and its
+/// checkpoint store.
+///
+/// The events of a block and its checkpoint are written in one transaction,
+/// redelivered blocks replace their events. After a reorg, call `remove_from`
+/// with the fork height.
+#[derive(Clone)]
+pub struct SqliteIndexer {
+    conn: Arc<Mutex<rusqlite::Connection>>,
+}
+
+impl SqliteIndexer {
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, ClientError> {
+        let conn = rusqlite::Connection::open(path)
+            .map_err(|e| ClientError::store("open SQLite database", e))?;
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| ClientError::store("create indexer tables", e))?;
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Stores the events of the block and advances the checkpoint to its height
+    pub async fn index_block(&self, block_events: &BlockEvents) -> Result<(), ClientError> {
+        let mut conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let tx = conn
+            .transaction()
+            .map_err(|e| ClientError::store("start indexer transaction", e))?;
+        let height = block_events.height as i64;
+        tx.execute("DELETE FROM indexed_events WHERE height = ?1", [height])
+            .map_err(|e| ClientError::store("replace indexed events", e))?;
+
+        {
+            let mut insert = tx
+                .prepare(
+                    "INSERT INTO indexed_events (height, event_index, block_time, tx_hash, tx_index,
+                     code, msg_index, contract, action, from_address, to_address, event)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                )
+                .map_err(|e| ClientError::store("prepare indexed event insert", e))?;
+            let tx_events = block_events
+                .events
+                .iter()
+                .map(|tx_event| (Some(tx_event), tx_event.contract.as_str(), &tx_event.event));
+            let finalize_block_events = block_events
+                .finalize_block_events
+                .iter()
+                .map(|event| (None, event.contract.as_str(), &event.event));
+            for (event_index, (tx_event, contract, event)) in
+                tx_events.chain(finalize_block_events).enumerate()
+            {
+                let (from_address, to_address) = event_addresses(event);
+                let event_json = serde_json::to_string(event)
+                    .map_err(|e| ClientError::serialization("serialize indexed event", e))?;
+                insert
+                    .execute(rusqlite::params![
+                        height,
+                        event_index as i64,
+                        block_events.block_time as i64,
+                        tx_event.map(|tx_event| tx_event.tx_hash.as_str()),
+                        tx_event.map(|tx_event| tx_event.tx_index),
+                        tx_event.map_or(0, |tx_event| tx_event.code),
+                        tx_event.and_then(|tx_event| tx_event.msg_index),
+                        contract,
+                        event.action(),
+                        from_address,
+                        to_address,
+                        event_json,
+                    ])
+                    .map_err(|e| ClientError::store("insert indexed event", e))?;
+            }
+        }
+
+        save_checkpoint(&tx, block_events.height)?;
+        tx.commit()
+            .map_err(|e| ClientError::store("commit indexed events", e))
+    }
+
+    /// Removes the events from `height` onwards and moves the checkpoint before
+    /// it, e.g. after a reorg
+    pub async fn remove_from(&self, height: u64) -> Result<(), ClientError> {
+        let mut conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let tx = conn
+            .transaction()
+            .map_err(|e| ClientError::store("start indexer transaction", e))?;
+        tx.execute(
+            "DELETE FROM indexed_events WHERE height >= ?1",
+            [height as i64],
+        )
+        .map_err(|e| ClientError::store("remove indexed events", e))?;
+        tx.execute(
+            "UPDATE indexer_checkpoint SET height = ?1 WHERE height >= ?2",
+            rusqlite::params![height.saturating_sub(1) as i64, height as i64],
+        )
+        .map_err(|e| ClientError::store("roll back indexer checkpoint", e))?;
+        tx.commit()
+            .map_err(|e| ClientError::store("commit indexer rollback", e))
+    }
+
+    /// Returns the height the indexer has processed up to, `None` if empty
+    pub async fn last_height(&self) -> Result<Option<u64>, ClientError> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let height: Option<i64> = conn
+            .query_row(
+                "SELECT height FROM indexer_checkpoint WHERE id = 0",
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| ClientError::store("load indexer checkpoint", e))?;
+
+        Ok(height.map(|h| h as u64))
+    }
+
+    /// Returns the events an address sent, received or emitted as a contract, in order
+    pub async fn events_by_address(&self, address: &str) -> Result<Vec<IndexedEvent>, ClientError> {
+        self.query(
+            "WHERE from_address = ?1 OR to_address = ?1 OR contract = ?1",
+            rusqlite::params![address],
+        )
+    }
+
+    /// Returns the events in `from..=to`, in order
+    pub async fn events_in_range(
+        &self,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<IndexedEvent>, ClientError> {
+        self.query(
+            "WHERE height BETWEEN ?1 AND ?2",
+            rusqlite::params![from as i64, to as i64],
+        )
+    }
+
+    /// Returns the events with the given action, e.g. `peg_out`, in order. See
+    /// `ContractEvent::action`.
+    pub async fn events_by_action(&self, action: &str) -> Result<Vec<IndexedEvent>, ClientError> {
+        self.query("WHERE action = ?1", rusqlite::params![action])
+    }
+
+    fn query(
+        &self,
+        filter: &str,
+        params: impl rusqlite::Params,
+    ) -> Result<Vec<IndexedEvent>, ClientError> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stmt = conn
+            .prepare(&format!(
+                "{} {} ORDER BY height, event_index",
+                SELECT_EVENTS, filter
+            ))
+            .map_err(|e| ClientError::store("query indexed events", e))?;
+        let rows = stmt
+            .query_map(params, |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<u32>>(3)?,
+                    row.get::<_, u32>(4)?,
+                    row.get::<_, Option<u32>>(5)?,
+                    row.get::<_, String>(6)?,
+                    row.get::<_, String>(7)?,
+                ))
+            })
+            .map_err(|e| ClientError::store("query indexed events", e))?;
+
+        rows.map(|row| {
+            let (height, block_time, tx_hash, tx_index, code, msg_index, contract, event) =
+                row.map_err(|e| ClientError::store("read indexed event", e))?;
+            Ok(IndexedEvent {
+                height: height as u64,
+                block_time: block_time as u64,
+                tx_hash: tx_hash.map(|tx_hash| tx_hash.parse()).transpose()?,
+                tx_index,
+                code,
+                msg_index,
+                contract,
+                event: serde_json::from_str(&event)
+                    .map_err(|e| ClientError::serialization("deserialize indexed event", e))?,
+            })
+        })
+        .collect()
+    }
+}
+
+fn save_checkpoint(conn: &rusqlite::Connection, height: u64) -> Result<(), ClientError> {
+    conn.execute(
+        "INSERT INTO indexer_checkpoint (id, height) VALUES (0, ?1)
+         ON CONFLICT(id) DO UPDATE SET height = excluded.height",
+        [height as i64],
+    )
+    .map_err(|e| ClientError::store("save indexer checkpoint", e))?;
+
+    Ok(())
+}
+
+#[async_trait]
+impl EventSink for SqliteIndexer {
+    async fn send(&self, block_events: BlockEvents) -> anyhow::Result<()> {
+        Ok(self.index_block(&block_events).await?)
+    }
+}
+
+#[async_trait]
+impl CheckpointStore for SqliteIndexer {
+    async fn load(&self) -> anyhow::Result<Option<u64>> {
+        Ok(self.last_height().await?)
+    }
+
+    async fn save(&self, height: u64) -> anyhow::Result<()> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(save_checkpoint(&conn, height)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{Cw20TransferEvent, FinalizeBlockEvent, PegInEvent, PegOutEvent, TxEvent};
+
+    fn block(height: u64, events: Vec<ContractEvent>) -> BlockEvents {
+        BlockEvents {
+            height,
+            block_time: height * 6,
+            events: events
+                .into_iter()
+                .enumerate()
+                .map(|(i, event)| TxEvent {
+                    tx_hash: TxHash::from_tx_bytes(&[height as u8, i as u8]),
+                    tx_index: i as u32,
+                    code: 0,
+                    gas_used: 0,
+                    contract: "bbn1bridge".to_string(),
+                    msg_index: Some(0),
+                    origin: Default::default(),
+                    event,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_indexer() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            SqliteIndexer::open(dir.path()),
+            Err(ClientError::Store { .. })
+        ));
+        let indexer = SqliteIndexer::open(dir.path().join("events.db")).unwrap();
+        assert_eq!(indexer.load().await.unwrap(), None);
+
+        let peg_in = |receiver: &str| {
+            ContractEvent::PegIn(PegInEvent {
+                receiver: receiver.to_string(),
+                amount: 100,
+                ..Default::default()
+            })
+        };
+        let peg_out = ContractEvent::PegOut(PegOutEvent {
+            sender: "bbn1alice".to_string(),
+            amount: 50,
+            ..Default::default()
+        });
+        let transfer = ContractEvent::Cw20Transfer(Cw20TransferEvent {
+            from: "bbn1bob".to_string(),
+            to: "bbn1alice".to_string(),
+            amount: 10,
+            ..Default::default()
+        });
+        indexer
+            .send(block(10, vec![peg_in("bbn1alice"), peg_in("bbn1bob")]))
+            .await
+            .unwrap();
+        let mut block_20 = block(20, vec![peg_out.clone(), transfer]);
+        block_20.finalize_block_events.push(FinalizeBlockEvent {
+            contract: "bbn1bridge".to_string(),
+            event: peg_in("bbn1carol"),
+        });
+        indexer.send(block_20).await.unwrap();
+        // redelivered blocks replace their events
+        indexer
+            .send(block(30, vec![peg_in("bbn1dave")]))
+            .await
+            .unwrap();
+        indexer.send(block(30, vec![peg_out])).await.unwrap();
+        assert_eq!(indexer.load().await.unwrap(), Some(30));
+
+        let alice = indexer.events_by_address("bbn1alice").await.unwrap();
+        assert_eq!(
+            alice.iter().map(|e| e.height).collect::<Vec<_>>(),
+            vec![10, 20, 20, 30]
+        );
+        assert_eq!(alice[0].block_time, 60);
+        assert_eq!(
+            indexer.events_by_address("bbn1bridge").await.unwrap().len(),
+            6
+        );
+
+        let peg_ins = indexer.events_by_action("peg_in").await.unwrap();
+        assert_eq!(peg_ins.len(), 3);
+        assert_eq!(peg_ins[2].tx_hash, None);
+        assert!(matches!(
+            &peg_ins[2].event,
+            ContractEvent::PegIn(PegInEvent { receiver, .. }) if receiver == "bbn1carol"
+        ));
+
+        assert_eq!(indexer.events_in_range(11, 30).await.unwrap().len(), 4);
+        indexer.remove_from(20).await.unwrap();
+        assert_eq!(indexer.events_in_range(0, 100).await.unwrap().len(), 2);
+        assert_eq!(indexer.last_height().await.unwrap(), Some(19));
+    }
+}
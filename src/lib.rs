@@ -1,8 +1,67 @@
+#[cfg(feature = "babylon")]
+pub mod babylon;
+#[cfg(feature = "btc")]
+pub mod btc;
+pub mod btc_address;
 pub mod chain;
+pub mod checkpoint;
 pub mod client;
+pub mod codegen;
+pub mod cw721;
+#[cfg(feature = "events")]
+pub mod dedup;
+pub mod distribution;
+pub mod error;
+#[cfg(feature = "events")]
+pub mod event_store;
+#[cfg(feature = "events")]
 pub mod events;
+#[cfg(feature = "events")]
+pub mod export;
+pub mod fee;
+#[cfg(feature = "babylon")]
+#[allow(dead_code, clippy::all)]
 pub(crate) mod generated;
+pub mod gov;
+#[cfg(all(feature = "events", feature = "sqlite"))]
+pub mod indexer;
+pub mod logging;
+#[cfg(feature = "events")]
+pub mod metrics;
+#[cfg(any(feature = "mock", all(test, feature = "babylon", feature = "wallet")))]
+pub mod mock;
+#[cfg(feature = "multitest")]
+pub mod multitest;
+#[cfg(feature = "events")]
+pub mod operator;
+#[cfg(feature = "events")]
+pub mod peg_out_store;
+pub mod query;
+#[cfg(feature = "relayer")]
+pub mod relayer;
+#[cfg(feature = "rest")]
+pub(crate) mod rest;
+#[cfg(feature = "events")]
+pub(crate) mod rpc;
+#[cfg(feature = "events")]
+pub mod sink;
+pub mod slashing;
+#[cfg(feature = "events")]
+pub mod spill;
+pub mod staking;
+#[cfg(feature = "wallet")]
+pub mod submitter;
 pub mod transactions;
+pub mod version;
 pub mod wallet;
-pub use client::CosmWasmClient;
-pub use events::EventListener;
+#[cfg(feature = "wallet")]
+pub mod wallet_pool;
+pub mod watcher;
+#[cfg(feature = "webhook")]
+pub mod webhook;
+pub use client::{CosmWasmApi, CosmWasmClient};
+pub use error::{BoxError, ClientError, TransactionError, TxErrorKind};
+#[cfg(feature = "events")]
+pub use events::{EventListener, EventListenerBuilder};
+#[cfg(feature = "events")]
+pub use rpc::{MempoolStatus, UnconfirmedTxs};
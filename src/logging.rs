@@ -0,0 +1,175 @@
+use regex::Regex;
+use sha2::{Digest, Sha256};
+use std::sync::{OnceLock, RwLock};
+
+/// Matches bech32 account and contract addresses, e.g. `bbn1...`
+pub const ADDRESS_PATTERN: &str = r"\b[a-z]{2,12}1[02-9ac-hj-np-z]{38,58}\b";
+/// Matches hex strings of 64 characters or more, e.g. keys and raw txs
+pub const HEX_PATTERN: &str = r"\b(?:0x)?[0-9a-fA-F]{64,}\b";
+
+const REDACTED: &str = "<redacted>";
+
+/// How payloads are rendered in logs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PayloadMode {
+    /// Log the payload after redaction and truncation
+    #[default]
+    Full,
+    /// Log only the sha256 hash of the payload
+    Hash,
+    /// Log only the payload size
+    Omit,
+}
+
+/// Policy applied to every payload (tx bytes, contract messages, proofs,
+/// parsed events) before it is written to the logs
+#[derive(Debug, Clone)]
+pub struct LogPolicy {
+    /// max_payload_bytes is the maximum number of bytes of a payload written to the logs
+    pub max_payload_bytes: usize,
+    /// redact_patterns are replaced with `<redacted>` before truncation
+    pub redact_patterns: Vec<Regex>,
+    /// mode selects how payloads are rendered
+    pub mode: PayloadMode,
+}
+
+impl Default for LogPolicy {
+    fn default() -> Self {
+        Self {
+            max_payload_bytes: 256,
+            redact_patterns: vec![],
+            mode: PayloadMode::Full,
+        }
+    }
+}
+
+impl LogPolicy {
+    /// Redacts addresses and long hex strings and logs at most 128 bytes
+    pub fn strict() -> Self {
+        Self {
+            max_payload_bytes: 128,
+            redact_patterns: vec![
+                Regex::new(ADDRESS_PATTERN).expect("valid address pattern"),
+                Regex::new(HEX_PATTERN).expect("valid hex pattern"),
+            ],
+            mode: PayloadMode::Full,
+        }
+    }
+
+    pub fn with_max_payload_bytes(mut self, max_payload_bytes: usize) -> Self {
+        self.max_payload_bytes = max_payload_bytes;
+        self
+    }
+
+    pub fn with_redaction(mut self, pattern: &str) -> anyhow::Result<Self> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| anyhow::anyhow!("Invalid redaction pattern {}: {}", pattern, e))?;
+        self.redact_patterns.push(regex);
+        Ok(self)
+    }
+
+    pub fn with_mode(mut self, mode: PayloadMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Renders a text payload according to the policy
+    pub fn render(&self, payload: &str) -> String {
+        match self.mode {
+            PayloadMode::Omit => format!("<{} bytes>", payload.len()),
+            PayloadMode::Hash => format!("sha256:{}", hex::encode(Sha256::digest(payload))),
+            PayloadMode::Full => {
+                let mut rendered = payload.to_string();
+                for pattern in &self.redact_patterns {
+                    rendered = pattern.replace_all(&rendered, REDACTED).into_owned();
+                }
+                truncate(rendered, self.max_payload_bytes)
+            }
+        }
+    }
+}
+
+fn truncate(mut payload: String, max_bytes: usize) -> String {
+    if payload.len() <= max_bytes {
+        return payload;
+    }
+
+    let mut end = max_bytes;
+    while !payload.is_char_boundary(end) {
+        end -= 1;
+    }
+    let omitted = payload.len() - end;
+    payload.truncate(end);
+    payload.push_str(&format!("...(+{} bytes)", omitted));
+    payload
+}
+
+fn global_policy() -> &'static RwLock<LogPolicy> {
+    static POLICY: OnceLock<RwLock<LogPolicy>> = OnceLock::new();
+    POLICY.get_or_init(|| RwLock::new(LogPolicy::default()))
+}
+
+/// Sets the policy used by the client, transactions and events modules
+pub fn set_log_policy(policy: LogPolicy) {
+    *global_policy().write().unwrap_or_else(|e| e.into_inner()) = policy;
+}
+
+/// Returns the policy currently in use
+pub fn log_policy() -> LogPolicy {
+    global_policy()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Renders a text payload (e.g. a JSON contract message) for logging
+pub fn text(payload: &str) -> String {
+    global_policy()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .render(payload)
+}
+
+/// Renders a binary payload (e.g. signed tx bytes) as hex for logging
+pub fn bytes(payload: &[u8]) -> String {
+    text(&hex::encode(payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_policy() {
+        let policy = LogPolicy::strict().with_max_payload_bytes(1024);
+        let msg = r#"{"peg_in":{"receiver_address":"bbn1ad2u30qd2vx6es4pmn28y23qtz6hea7708574y","pegin_tx":"5d386fbdbf11f1141010f81a46b40f94887367562bd33b452bbaa6ce1cd1381e"}}"#;
+        assert_eq!(
+            policy.render(msg),
+            r#"{"peg_in":{"receiver_address":"<redacted>","pegin_tx":"<redacted>"}}"#
+        );
+
+        let truncated = LogPolicy::default()
+            .with_max_payload_bytes(4)
+            .render("abcdefgh");
+        assert_eq!(truncated, "abcd...(+4 bytes)");
+
+        // never split a multi-byte character
+        let truncated = LogPolicy::default().with_max_payload_bytes(2).render("aé");
+        assert_eq!(truncated, "a...(+2 bytes)");
+
+        let hashed = LogPolicy::default()
+            .with_mode(PayloadMode::Hash)
+            .render("abc");
+        assert_eq!(
+            hashed,
+            "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        let omitted = LogPolicy::default()
+            .with_mode(PayloadMode::Omit)
+            .render("abc");
+        assert_eq!(omitted, "<3 bytes>");
+
+        assert!(LogPolicy::default().with_redaction("(").is_err());
+    }
+}
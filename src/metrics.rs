@@ -0,0 +1,269 @@
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::events::{BlockEvents, ContractEvent};
+
+/// Counters updated by a running `EventListener`, shared through an `Arc`
+#[derive(Debug)]
+pub struct ListenerMetrics {
+    started_at: Instant,
+    latest_height: AtomicU64,
+    last_processed_height: AtomicU64,
+    blocks_processed: AtomicU64,
+    peg_in_events: AtomicU64,
+    peg_out_events: AtomicU64,
+    cw20_events: AtomicU64,
+    lifecycle_events: AtomicU64,
+    raw_events: AtomicU64,
+    rpc_errors: AtomicU64,
+    /// last_error is the last error the listener recovered from
+    last_error: Mutex<Option<String>>,
+    /// rpc_endpoint is the Tendermint RPC endpoint currently in use
+    rpc_endpoint: Mutex<String>,
+}
+
+/// Health of a running listener, e.g. for a service health endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListenerStatus {
+    pub last_processed_height: u64,
+    /// head_height is the chain head at the last status check
+    pub head_height: u64,
+    /// lag is the number of blocks the listener is behind the head
+    pub lag: u64,
+    pub last_error: Option<String>,
+    pub rpc_endpoint: String,
+    pub uptime: Duration,
+}
+
+/// Point in time copy of the listener metrics
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsSnapshot {
+    /// latest_height is the chain head at the last status check
+    pub latest_height: u64,
+    pub last_processed_height: u64,
+    pub blocks_behind: u64,
+    pub blocks_processed: u64,
+    /// blocks_per_second is the average throughput since the listener was created
+    pub blocks_per_second: f64,
+    pub peg_in_events: u64,
+    pub peg_out_events: u64,
+    /// cw20_events counts the transfers, mints and burns of the CW20 token
+    pub cw20_events: u64,
+    /// lifecycle_events counts the instantiations, migrations and admin changes
+    pub lifecycle_events: u64,
+    pub raw_events: u64,
+    pub rpc_errors: u64,
+}
+
+impl Default for ListenerMetrics {
+    fn default() -> Self {
+        Self {
+            started_at: Instant::now(),
+            latest_height: AtomicU64::new(0),
+            last_processed_height: AtomicU64::new(0),
+            blocks_processed: AtomicU64::new(0),
+            peg_in_events: AtomicU64::new(0),
+            peg_out_events: AtomicU64::new(0),
+            cw20_events: AtomicU64::new(0),
+            lifecycle_events: AtomicU64::new(0),
+            raw_events: AtomicU64::new(0),
+            rpc_errors: AtomicU64::new(0),
+            last_error: Mutex::new(None),
+            rpc_endpoint: Mutex::new(String::new()),
+        }
+    }
+}
+
+impl ListenerMetrics {
+    pub(crate) fn set_latest_height(&self, height: u64) {
+        self.latest_height.store(height, Ordering::Relaxed);
+    }
+
+    pub(crate) fn set_last_processed_height(&self, height: u64) {
+        self.last_processed_height.store(height, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_block(&self, height: u64) {
+        self.blocks_processed.fetch_add(1, Ordering::Relaxed);
+        self.set_last_processed_height(height);
+    }
+
+    pub(crate) fn record_events(&self, block_events: &BlockEvents) {
+        let events = block_events.events.iter().map(|event| &event.event).chain(
+            block_events
+                .finalize_block_events
+                .iter()
+                .map(|event| &event.event),
+        );
+        for event in events {
+            let counter = match event {
+                ContractEvent::PegIn(_) => &self.peg_in_events,
+                ContractEvent::PegOut(_) => &self.peg_out_events,
+                ContractEvent::Cw20Transfer(_)
+                | ContractEvent::Cw20Mint(_)
+                | ContractEvent::Cw20Burn(_) => &self.cw20_events,
+                ContractEvent::Instantiate(_)
+                | ContractEvent::Migrate(_)
+                | ContractEvent::UpdateAdmin(_) => &self.lifecycle_events,
+                ContractEvent::Raw(_) => &self.raw_events,
+            };
+            counter.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn record_rpc_error(&self) {
+        self.rpc_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn set_last_error(&self, error: &dyn std::fmt::Display) {
+        *self.last_error.lock().unwrap_or_else(|e| e.into_inner()) = Some(format!("{:#}", error));
+    }
+
+    pub(crate) fn set_rpc_endpoint(&self, rpc_endpoint: &str) {
+        let mut current = self.rpc_endpoint.lock().unwrap_or_else(|e| e.into_inner());
+        if *current != rpc_endpoint {
+            *current = rpc_endpoint.to_string();
+        }
+    }
+
+    pub fn status(&self) -> ListenerStatus {
+        let head_height = self.latest_height.load(Ordering::Relaxed);
+        let last_processed_height = self.last_processed_height.load(Ordering::Relaxed);
+
+        ListenerStatus {
+            last_processed_height,
+            head_height,
+            lag: head_height.saturating_sub(last_processed_height),
+            last_error: self
+                .last_error
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone(),
+            rpc_endpoint: self
+                .rpc_endpoint
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone(),
+            uptime: self.started_at.elapsed(),
+        }
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let latest_height = self.latest_height.load(Ordering::Relaxed);
+        let last_processed_height = self.last_processed_height.load(Ordering::Relaxed);
+        let blocks_processed = self.blocks_processed.load(Ordering::Relaxed);
+        let elapsed = self.started_at.elapsed().as_secs_f64();
+
+        MetricsSnapshot {
+            latest_height,
+            last_processed_height,
+            blocks_behind: latest_height.saturating_sub(last_processed_height),
+            blocks_processed,
+            blocks_per_second: if elapsed > 0.0 {
+                blocks_processed as f64 / elapsed
+            } else {
+                0.0
+            },
+            peg_in_events: self.peg_in_events.load(Ordering::Relaxed),
+            peg_out_events: self.peg_out_events.load(Ordering::Relaxed),
+            cw20_events: self.cw20_events.load(Ordering::Relaxed),
+            lifecycle_events: self.lifecycle_events.load(Ordering::Relaxed),
+            raw_events: self.raw_events.load(Ordering::Relaxed),
+            rpc_errors: self.rpc_errors.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl MetricsSnapshot {
+    /// Renders the metrics in the Prometheus text exposition format
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, value: String| {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} {}", name, kind);
+            let _ = writeln!(out, "{} {}", name, value);
+        };
+
+        metric(
+            "event_listener_latest_height",
+            "gauge",
+            "Chain head at the last status check",
+            self.latest_height.to_string(),
+        );
+        metric(
+            "event_listener_last_processed_height",
+            "gauge",
+            "Last processed block height",
+            self.last_processed_height.to_string(),
+        );
+        metric(
+            "event_listener_blocks_behind",
+            "gauge",
+            "Number of blocks the listener is behind the chain head",
+            self.blocks_behind.to_string(),
+        );
+        metric(
+            "event_listener_blocks_processed_total",
+            "counter",
+            "Number of processed blocks",
+            self.blocks_processed.to_string(),
+        );
+        metric(
+            "event_listener_rpc_errors_total",
+            "counter",
+            "Number of failed RPC requests",
+            self.rpc_errors.to_string(),
+        );
+
+        let _ = writeln!(
+            out,
+            "# HELP event_listener_events_total Number of emitted events by type"
+        );
+        let _ = writeln!(out, "# TYPE event_listener_events_total counter");
+        for (kind, count) in [
+            ("peg_in", self.peg_in_events),
+            ("peg_out", self.peg_out_events),
+            ("cw20", self.cw20_events),
+            ("lifecycle", self.lifecycle_events),
+            ("raw", self.raw_events),
+        ] {
+            let _ = writeln!(
+                out,
+                "event_listener_events_total{{type=\"{}\"}} {}",
+                kind, count
+            );
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics_snapshot() {
+        let metrics = ListenerMetrics::default();
+        metrics.set_latest_height(110);
+        metrics.record_block(99);
+        metrics.record_block(100);
+        metrics.record_rpc_error();
+        metrics.set_last_error(&anyhow::anyhow!("node unreachable"));
+
+        let status = metrics.status();
+        assert_eq!(status.lag, 10);
+        assert_eq!(status.last_error.as_deref(), Some("node unreachable"));
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.blocks_behind, 10);
+        assert_eq!(snapshot.blocks_processed, 2);
+        assert_eq!(snapshot.rpc_errors, 1);
+
+        let text = snapshot.to_prometheus();
+        assert!(text.contains("event_listener_blocks_behind 10\n"));
+        assert!(text.contains("event_listener_events_total{type=\"peg_in\"} 0\n"));
+    }
+}
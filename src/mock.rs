@@ -0,0 +1,1293 @@
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use cosmos_sdk_proto::cosmos::auth::v1beta1::{
+    BaseAccount, QueryAccountRequest, QueryAccountResponse,
+};
+use cosmos_sdk_proto::cosmos::base::abci::v1beta1::{GasInfo, TxResponse};
+use cosmos_sdk_proto::cosmos::tx::v1beta1::{AuthInfo, TxRaw};
+use cosmos_sdk_proto::cosmos::tx::v1beta1::{
+    BroadcastTxRequest, BroadcastTxResponse, GetTxRequest, GetTxResponse, SimulateRequest,
+    SimulateResponse,
+};
+use cosmos_sdk_proto::cosmwasm::wasm::v1::{
+    CodeInfoResponse, QueryCodeRequest, QueryCodeResponse, QueryRawContractStateRequest,
+    QueryRawContractStateResponse, QuerySmartContractStateRequest, QuerySmartContractStateResponse,
+};
+use cosmos_sdk_proto::traits::Message;
+use cosmos_sdk_proto::Any;
+use cosmrs::crypto::PublicKey;
+use cosmrs::AccountId;
+use sha2::{Digest, Sha256};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tonic::codec::ProstCodec;
+use tonic::codegen::{http, Body, BoxFuture, Context, Poll, Service, StdError};
+use tonic::server::NamedService;
+use tonic::transport::server::TcpIncoming;
+use tonic::Status;
+
+use crate::client::{BtcHeaderInfo, CosmWasmApi};
+use crate::error::{ClientError, TxErrorKind};
+use crate::generated::babylon::btclightclient::v1::{
+    BtcHeaderInfoResponse, QueryBaseHeaderRequest, QueryBaseHeaderResponse,
+    QueryContainsBytesRequest, QueryContainsBytesResponse, QueryHeaderDepthRequest,
+    QueryHeaderDepthResponse, QueryMainChainRequest, QueryMainChainResponse, QueryTipRequest,
+    QueryTipResponse,
+};
+use crate::generated::cosmos::base::query::v1beta1::PageResponse;
+use crate::transactions::TxHash;
+
+/// gRPC services served by the mock, the index is the `MockService` parameter
+const SERVICES: [&str; 4] = [
+    "cosmos.auth.v1beta1.Query",
+    "cosmos.tx.v1beta1.Service",
+    "cosmwasm.wasm.v1.Query",
+    "babylon.btclightclient.v1.Query",
+];
+/// Default gas used by simulated and included transactions
+const DEFAULT_GAS_USED: u64 = 100_000;
+/// Default page size of the main chain query
+const DEFAULT_PAGE_LIMIT: u64 = 100;
+/// ABCI code returned for undecodable tx bytes (sdk ErrTxDecode)
+const CODE_TX_DECODE: u32 = 2;
+/// ABCI code returned when the signer has no account (sdk ErrUnknownAddress)
+const CODE_UNKNOWN_ADDRESS: u32 = 9;
+/// ABCI code returned when the tx is already in the mempool cache (sdk ErrTxInMempoolCache)
+const CODE_TX_IN_MEMPOOL_CACHE: u32 = 19;
+/// ABCI code returned when the tx sequence doesn't match the account (sdk ErrWrongSequence)
+const CODE_WRONG_SEQUENCE: u32 = 32;
+
+/// Outcome of a broadcast, queued with `MockGrpcServer::push_broadcast`.
+/// Broadcasts without a queued outcome are committed.
+#[derive(Debug, Clone)]
+pub enum MockBroadcast {
+    /// Accepts the tx and includes it in the next block
+    Commit,
+    /// Accepts the tx into the mempool but never includes it, so waiting for its
+    /// confirmation times out
+    Pending,
+    /// Rejects the tx in CheckTx, the sequence isn't consumed
+    Reject {
+        codespace: String,
+        code: u32,
+        raw_log: String,
+    },
+    /// Includes the tx in the next block with a failed result, e.g. out of gas
+    Fail {
+        codespace: String,
+        code: u32,
+        raw_log: String,
+    },
+    /// Fails the gRPC request itself, e.g. with `Status::unavailable`
+    Status(Status),
+}
+
+impl MockBroadcast {
+    /// Rejects the tx in CheckTx with the given sdk error
+    pub fn reject(kind: TxErrorKind, raw_log: &str) -> Self {
+        let (codespace, code) = sdk_code(kind);
+        Self::Reject {
+            codespace,
+            code,
+            raw_log: raw_log.to_string(),
+        }
+    }
+
+    /// Includes the tx in the next block, failed with the given sdk error
+    pub fn fail(kind: TxErrorKind, raw_log: &str) -> Self {
+        let (codespace, code) = sdk_code(kind);
+        Self::Fail {
+            codespace,
+            code,
+            raw_log: raw_log.to_string(),
+        }
+    }
+}
+
+/// Returns the codespace and code of a `TxErrorKind`, the inverse of `TxErrorKind::from_abci`
+fn sdk_code(kind: TxErrorKind) -> (String, u32) {
+    let (codespace, code) = match kind {
+        TxErrorKind::TxDecode => (TxErrorKind::SDK_CODESPACE, 2),
+        TxErrorKind::WrongSequence => (TxErrorKind::SDK_CODESPACE, 32),
+        TxErrorKind::Unauthorized => (TxErrorKind::SDK_CODESPACE, 4),
+        TxErrorKind::InsufficientFunds => (TxErrorKind::SDK_CODESPACE, 5),
+        TxErrorKind::InsufficientFee => (TxErrorKind::SDK_CODESPACE, 13),
+        TxErrorKind::OutOfGas => (TxErrorKind::SDK_CODESPACE, 11),
+        TxErrorKind::InvalidAddress => (TxErrorKind::SDK_CODESPACE, 7),
+        TxErrorKind::InvalidCoins => (TxErrorKind::SDK_CODESPACE, 10),
+        TxErrorKind::InvalidRequest => (TxErrorKind::SDK_CODESPACE, 18),
+        TxErrorKind::InvalidChainId => (TxErrorKind::SDK_CODESPACE, 28),
+        TxErrorKind::TxInMempoolCache => (TxErrorKind::SDK_CODESPACE, 19),
+        TxErrorKind::MempoolFull => (TxErrorKind::SDK_CODESPACE, 20),
+        TxErrorKind::TxTooLarge => (TxErrorKind::SDK_CODESPACE, 21),
+        TxErrorKind::TxTimeout => (TxErrorKind::SDK_CODESPACE, 30),
+        TxErrorKind::ContractNotFound => (TxErrorKind::WASM_CODESPACE, 8),
+        TxErrorKind::ContractFailed => (TxErrorKind::WASM_CODESPACE, 5),
+        TxErrorKind::Other => (TxErrorKind::SDK_CODESPACE, 1),
+    };
+    (codespace.to_string(), code)
+}
+
+/// State of the mocked chain, shared between the server and its handle
+#[derive(Default)]
+struct MockState {
+    accounts: HashMap<String, BaseAccount>,
+    broadcasts: VecDeque<MockBroadcast>,
+    /// txs are the included transactions by hash
+    txs: HashMap<String, TxResponse>,
+    /// mempool holds the signer and sequence of the accepted transactions that are
+    /// never included, by hash
+    mempool: HashMap<String, (String, u64)>,
+    height: i64,
+    gas_used: u64,
+    /// smart_responses are the responses by contract and JSON query
+    smart_responses: Vec<(String, serde_json::Value, Vec<u8>)>,
+    raw_state: HashMap<(String, Vec<u8>), Vec<u8>>,
+    /// codes are the stored wasm bytecodes by code id
+    codes: HashMap<u64, Vec<u8>>,
+    /// btc_headers is the BTC light client main chain, from the base header to the tip
+    btc_headers: Vec<BtcHeaderInfo>,
+    delay: Duration,
+    calls: Vec<String>,
+}
+
+/// In-process gRPC server mocking the auth, tx, wasm and btclightclient services of a
+/// node with programmable responses, for deterministic tests of broadcast failures,
+/// sequence mismatches and confirmation timeouts.
+///
+/// The server runs until the handle is dropped. Point a `CosmWasmClient` at `url()`.
+pub struct MockGrpcServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    task: JoinHandle<()>,
+}
+
+impl MockGrpcServer {
+    /// Starts the server on a free local port
+    pub async fn start() -> Result<Self, ClientError> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| ClientError::connection("127.0.0.1:0", e))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| ClientError::connection("127.0.0.1:0", e))?;
+        let incoming = TcpIncoming::from_listener(listener, true, None)
+            .map_err(|e| ClientError::connection(&addr.to_string(), e))?;
+
+        let state = Arc::new(Mutex::new(MockState {
+            height: 1,
+            gas_used: DEFAULT_GAS_USED,
+            ..MockState::default()
+        }));
+        let server = tonic::transport::Server::builder()
+            .add_service(MockService::<0>(state.clone()))
+            .add_service(MockService::<1>(state.clone()))
+            .add_service(MockService::<2>(state.clone()))
+            .add_service(MockService::<3>(state.clone()));
+        let task = tokio::spawn(async move {
+            if let Err(e) = server.serve_with_incoming(incoming).await {
+                tracing::error!("Mock gRPC server stopped: {}", e);
+            }
+        });
+
+        Ok(Self { addr, state, task })
+    }
+
+    /// Returns the URL to pass as the gRPC URL of a `CosmWasmClient`
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Creates or replaces the account, only known accounts can broadcast
+    pub fn set_account(&self, address: &str, account_number: u64, sequence: u64) {
+        self.state().accounts.insert(
+            address.to_string(),
+            BaseAccount {
+                address: address.to_string(),
+                pub_key: None,
+                account_number,
+                sequence,
+            },
+        );
+    }
+
+    /// Returns the current sequence of the account
+    pub fn sequence(&self, address: &str) -> Option<u64> {
+        self.state()
+            .accounts
+            .get(address)
+            .map(|account| account.sequence)
+    }
+
+    /// Queues the outcome of the next broadcast
+    pub fn push_broadcast(&self, outcome: MockBroadcast) {
+        self.state().broadcasts.push_back(outcome);
+    }
+
+    /// Sets the gas used by simulated and included transactions
+    pub fn set_gas_used(&self, gas_used: u64) {
+        self.state().gas_used = gas_used;
+    }
+
+    /// Sets the response of the contract to the given smart query
+    pub fn set_smart_response(
+        &self,
+        contract: &str,
+        query: serde_json::Value,
+        response: serde_json::Value,
+    ) {
+        let mut state = self.state();
+        state
+            .smart_responses
+            .retain(|(c, q, _)| c != contract || *q != query);
+        state.smart_responses.push((
+            contract.to_string(),
+            query,
+            response.to_string().into_bytes(),
+        ));
+    }
+
+    /// Sets the value stored under `key` in the contract storage
+    pub fn set_raw_state(&self, contract: &str, key: &[u8], value: &[u8]) {
+        self.state()
+            .raw_state
+            .insert((contract.to_string(), key.to_vec()), value.to_vec());
+    }
+
+    /// Stores the wasm bytecode under `code_id`
+    pub fn set_code(&self, code_id: u64, bytecode: &[u8]) {
+        self.state().codes.insert(code_id, bytecode.to_vec());
+    }
+
+    /// Sets the BTC light client main chain, ordered from the base header to the tip
+    pub fn set_btc_headers(&self, headers: Vec<BtcHeaderInfo>) {
+        self.state().btc_headers = headers;
+    }
+
+    /// Delays every response, e.g. to exercise client timeouts
+    pub fn set_delay(&self, delay: Duration) {
+        self.state().delay = delay;
+    }
+
+    /// Returns the called gRPC methods in order, e.g. `cosmos.tx.v1beta1.Service/BroadcastTx`
+    pub fn calls(&self) -> Vec<String> {
+        self.state().calls.clone()
+    }
+}
+
+impl Drop for MockGrpcServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+// the handlers have the signatures of the tonic generated services
+#[allow(clippy::result_large_err)]
+impl MockState {
+    fn account(&mut self, request: QueryAccountRequest) -> Result<QueryAccountResponse, Status> {
+        let account = self
+            .accounts
+            .get(&request.address)
+            .ok_or_else(|| Status::not_found(format!("account {} not found", request.address)))?;
+        let account = Any::from_msg(account).map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(QueryAccountResponse {
+            account: Some(account),
+        })
+    }
+
+    fn simulate(&mut self, _request: SimulateRequest) -> Result<SimulateResponse, Status> {
+        Ok(SimulateResponse {
+            gas_info: Some(GasInfo {
+                gas_wanted: 0,
+                gas_used: self.gas_used,
+            }),
+            result: None,
+        })
+    }
+
+    fn broadcast_tx(&mut self, request: BroadcastTxRequest) -> Result<BroadcastTxResponse, Status> {
+        let tx_hash = TxHash::from_tx_bytes(&request.tx_bytes).to_string();
+        let outcome = self.broadcasts.pop_front().unwrap_or(MockBroadcast::Commit);
+        let check_tx = |codespace: &str, code: u32, raw_log: String| BroadcastTxResponse {
+            tx_response: Some(TxResponse {
+                txhash: tx_hash.clone(),
+                codespace: codespace.to_string(),
+                code,
+                raw_log,
+                ..Default::default()
+            }),
+        };
+
+        match &outcome {
+            MockBroadcast::Status(status) => return Err(status.clone()),
+            MockBroadcast::Reject {
+                codespace,
+                code,
+                raw_log,
+            } => return Ok(check_tx(codespace, *code, raw_log.clone())),
+            _ => {}
+        }
+        if self.mempool.contains_key(&tx_hash) || self.txs.contains_key(&tx_hash) {
+            return Ok(check_tx(
+                TxErrorKind::SDK_CODESPACE,
+                CODE_TX_IN_MEMPOOL_CACHE,
+                "tx already exists in cache".to_string(),
+            ));
+        }
+
+        let auth_info = match TxRaw::decode(request.tx_bytes.as_slice())
+            .and_then(|tx_raw| AuthInfo::decode(tx_raw.auth_info_bytes.as_slice()))
+        {
+            Ok(auth_info) => auth_info,
+            Err(e) => {
+                return Ok(check_tx(
+                    TxErrorKind::SDK_CODESPACE,
+                    CODE_TX_DECODE,
+                    format!("tx parse error: {}", e),
+                ))
+            }
+        };
+        let Some(signer) = auth_info.signer_infos.first() else {
+            return Ok(check_tx(
+                TxErrorKind::SDK_CODESPACE,
+                CODE_TX_DECODE,
+                "no signer".to_string(),
+            ));
+        };
+        let Some(account) = self.signer_account(signer.public_key.as_ref()) else {
+            return Ok(check_tx(
+                TxErrorKind::SDK_CODESPACE,
+                CODE_UNKNOWN_ADDRESS,
+                "signer account does not exist: unknown address".to_string(),
+            ));
+        };
+        let address = account.address.clone();
+        // a pending tx with the same sequence is replaced, as by a priority mempool
+        let replaced = self
+            .mempool
+            .iter()
+            .find(|(_, pending)| **pending == (address.clone(), signer.sequence))
+            .map(|(hash, _)| hash.clone());
+        let account = self.accounts.get_mut(&address).expect("signer account");
+        if let Some(replaced) = replaced {
+            self.mempool.remove(&replaced);
+            account.sequence -= 1;
+        }
+        if signer.sequence != account.sequence {
+            return Ok(check_tx(
+                TxErrorKind::SDK_CODESPACE,
+                CODE_WRONG_SEQUENCE,
+                format!(
+                    "account sequence mismatch, expected {}, got {}: incorrect account sequence",
+                    account.sequence, signer.sequence
+                ),
+            ));
+        }
+        account.sequence += 1;
+
+        let (codespace, code, raw_log) = match outcome {
+            MockBroadcast::Pending => {
+                self.mempool
+                    .insert(tx_hash.clone(), (address, signer.sequence));
+                return Ok(check_tx("", 0, String::new()));
+            }
+            MockBroadcast::Fail {
+                codespace,
+                code,
+                raw_log,
+            } => (codespace, code, raw_log),
+            _ => (String::new(), 0, String::new()),
+        };
+        self.height += 1;
+        self.txs.insert(
+            tx_hash.clone(),
+            TxResponse {
+                height: self.height,
+                txhash: tx_hash.clone(),
+                codespace,
+                code,
+                raw_log,
+                gas_wanted: auth_info.fee.map_or(0, |fee| fee.gas_limit) as i64,
+                gas_used: self.gas_used as i64,
+                ..Default::default()
+            },
+        );
+
+        Ok(check_tx("", 0, String::new()))
+    }
+
+    /// Returns the account of the tx signer, matched by the address of its public key
+    fn signer_account(&mut self, public_key: Option<&Any>) -> Option<&mut BaseAccount> {
+        let public_key = PublicKey::try_from(public_key?).ok()?;
+        self.accounts.values_mut().find(|account| {
+            AccountId::from_str(&account.address)
+                .and_then(|address| public_key.account_id(address.prefix()))
+                .is_ok_and(|signer| signer.to_string() == account.address)
+        })
+    }
+
+    fn get_tx(&mut self, request: GetTxRequest) -> Result<GetTxResponse, Status> {
+        let tx_response = self
+            .txs
+            .get(&request.hash.to_ascii_uppercase())
+            .cloned()
+            .ok_or_else(|| Status::not_found(format!("tx not found: {}", request.hash)))?;
+
+        Ok(GetTxResponse {
+            tx: None,
+            tx_response: Some(tx_response),
+        })
+    }
+
+    fn smart_contract_state(
+        &mut self,
+        request: QuerySmartContractStateRequest,
+    ) -> Result<QuerySmartContractStateResponse, Status> {
+        let query: serde_json::Value = serde_json::from_slice(&request.query_data)
+            .map_err(|e| Status::invalid_argument(format!("invalid query: {}", e)))?;
+        let (_, _, data) = self
+            .smart_responses
+            .iter()
+            .find(|(contract, q, _)| *contract == request.address && *q == query)
+            .ok_or_else(|| {
+                Status::not_found(format!("no response to {} on {}", query, request.address))
+            })?;
+
+        Ok(QuerySmartContractStateResponse { data: data.clone() })
+    }
+
+    fn raw_contract_state(
+        &mut self,
+        request: QueryRawContractStateRequest,
+    ) -> Result<QueryRawContractStateResponse, Status> {
+        let data = self
+            .raw_state
+            .get(&(request.address, request.query_data))
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(QueryRawContractStateResponse { data })
+    }
+
+    fn code(&mut self, request: QueryCodeRequest) -> Result<QueryCodeResponse, Status> {
+        let data = self
+            .codes
+            .get(&request.code_id)
+            .cloned()
+            .ok_or_else(|| Status::not_found(format!("code {} not found", request.code_id)))?;
+
+        Ok(QueryCodeResponse {
+            code_info: Some(CodeInfoResponse {
+                code_id: request.code_id,
+                creator: String::new(),
+                data_hash: Sha256::digest(&data).to_vec(),
+                instantiate_permission: None,
+            }),
+            data,
+        })
+    }
+
+    fn tip(&mut self, _request: QueryTipRequest) -> Result<QueryTipResponse, Status> {
+        let header = self
+            .btc_headers
+            .last()
+            .ok_or_else(|| Status::not_found("no BTC headers"))?;
+
+        Ok(QueryTipResponse {
+            header: Some(header_response(header)),
+        })
+    }
+
+    fn base_header(
+        &mut self,
+        _request: QueryBaseHeaderRequest,
+    ) -> Result<QueryBaseHeaderResponse, Status> {
+        let header = self
+            .btc_headers
+            .first()
+            .ok_or_else(|| Status::not_found("no BTC headers"))?;
+
+        Ok(QueryBaseHeaderResponse {
+            header: Some(header_response(header)),
+        })
+    }
+
+    fn contains_bytes(
+        &mut self,
+        request: QueryContainsBytesRequest,
+    ) -> Result<QueryContainsBytesResponse, Status> {
+        // the hash bytes are in the internal byte order, the reverse of the hex hash
+        let mut hash = request.hash;
+        hash.reverse();
+        let hash = hex::encode(hash);
+
+        Ok(QueryContainsBytesResponse {
+            contains: self.btc_headers.iter().any(|header| header.hash == hash),
+        })
+    }
+
+    fn header_depth(
+        &mut self,
+        request: QueryHeaderDepthRequest,
+    ) -> Result<QueryHeaderDepthResponse, Status> {
+        let tip = self
+            .btc_headers
+            .last()
+            .ok_or_else(|| Status::not_found("no BTC headers"))?;
+        let header = self
+            .btc_headers
+            .iter()
+            .find(|header| header.hash == request.hash)
+            .ok_or_else(|| Status::not_found(format!("header {} not found", request.hash)))?;
+
+        Ok(QueryHeaderDepthResponse {
+            depth: tip.height - header.height,
+        })
+    }
+
+    /// Pages the main chain from the tip downwards, or from the base header upwards
+    /// if `reverse` is set. The page key is the index of the next header.
+    fn main_chain(
+        &mut self,
+        request: QueryMainChainRequest,
+    ) -> Result<QueryMainChainResponse, Status> {
+        let pagination = request.pagination.unwrap_or_default();
+        let limit = match pagination.limit {
+            0 => DEFAULT_PAGE_LIMIT,
+            limit => limit,
+        } as usize;
+        let start = match pagination.key.as_slice() {
+            [] => 0,
+            key => u64::from_be_bytes(
+                key.try_into()
+                    .map_err(|_| Status::invalid_argument("invalid page key"))?,
+            ) as usize,
+        };
+
+        let headers: Vec<_> = if pagination.reverse {
+            self.btc_headers.iter().collect()
+        } else {
+            self.btc_headers.iter().rev().collect()
+        };
+        let end = (start + limit).min(headers.len());
+        let next_key = if end < headers.len() {
+            (end as u64).to_be_bytes().to_vec()
+        } else {
+            vec![]
+        };
+
+        Ok(QueryMainChainResponse {
+            headers: headers[start.min(end)..end]
+                .iter()
+                .map(|header| header_response(header))
+                .collect(),
+            pagination: Some(PageResponse {
+                next_key,
+                total: headers.len() as u64,
+            }),
+        })
+    }
+}
+
+fn header_response(header: &BtcHeaderInfo) -> BtcHeaderInfoResponse {
+    BtcHeaderInfoResponse {
+        header_hex: header.header_hex.clone(),
+        hash_hex: header.hash.clone(),
+        height: header.height,
+        work: header.work.to_string(),
+    }
+}
+
+/// One of the mocked gRPC services, all sharing the same state
+struct MockService<const SERVICE: usize>(Arc<Mutex<MockState>>);
+
+impl<const SERVICE: usize> Clone for MockService<SERVICE> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<const SERVICE: usize> NamedService for MockService<SERVICE> {
+    const NAME: &'static str = SERVICES[SERVICE];
+}
+
+impl<const SERVICE: usize, B> Service<http::Request<B>> for MockService<SERVICE>
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<tonic::body::BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let state = self.0.clone();
+        let path = request.uri().path().trim_start_matches('/').to_string();
+        let delay = {
+            let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+            state.calls.push(path.clone());
+            state.delay
+        };
+
+        match path.as_str() {
+            "cosmos.auth.v1beta1.Query/Account" => unary(state, delay, request, MockState::account),
+            "cosmos.tx.v1beta1.Service/Simulate" => {
+                unary(state, delay, request, MockState::simulate)
+            }
+            "cosmos.tx.v1beta1.Service/BroadcastTx" => {
+                unary(state, delay, request, MockState::broadcast_tx)
+            }
+            "cosmos.tx.v1beta1.Service/GetTx" => unary(state, delay, request, MockState::get_tx),
+            "cosmwasm.wasm.v1.Query/SmartContractState" => {
+                unary(state, delay, request, MockState::smart_contract_state)
+            }
+            "cosmwasm.wasm.v1.Query/RawContractState" => {
+                unary(state, delay, request, MockState::raw_contract_state)
+            }
+            "cosmwasm.wasm.v1.Query/Code" => unary(state, delay, request, MockState::code),
+            "babylon.btclightclient.v1.Query/Tip" => unary(state, delay, request, MockState::tip),
+            "babylon.btclightclient.v1.Query/BaseHeader" => {
+                unary(state, delay, request, MockState::base_header)
+            }
+            "babylon.btclightclient.v1.Query/ContainsBytes" => {
+                unary(state, delay, request, MockState::contains_bytes)
+            }
+            "babylon.btclightclient.v1.Query/HeaderDepth" => {
+                unary(state, delay, request, MockState::header_depth)
+            }
+            "babylon.btclightclient.v1.Query/MainChain" => {
+                unary(state, delay, request, MockState::main_chain)
+            }
+            _ => Box::pin(async move {
+                Ok(Status::unimplemented(format!("{} is not mocked", path)).into_http())
+            }),
+        }
+    }
+}
+
+/// Decodes the request, calls the handler on the state and encodes its response
+fn unary<B, Req, Resp>(
+    state: Arc<Mutex<MockState>>,
+    delay: Duration,
+    request: http::Request<B>,
+    handler: fn(&mut MockState, Req) -> Result<Resp, Status>,
+) -> BoxFuture<http::Response<tonic::body::BoxBody>, Infallible>
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+    Req: prost::Message + Default + Send + 'static,
+    Resp: prost::Message + Send + 'static,
+{
+    Box::pin(async move {
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+        let mut grpc = tonic::server::Grpc::new(ProstCodec::<Resp, Req>::default());
+        Ok(grpc.unary(Handler { state, handler }, request).await)
+    })
+}
+
+/// Unary service calling a `MockState` handler
+struct Handler<Req, Resp> {
+    state: Arc<Mutex<MockState>>,
+    handler: fn(&mut MockState, Req) -> Result<Resp, Status>,
+}
+
+impl<Req, Resp> Service<tonic::Request<Req>> for Handler<Req, Resp> {
+    type Response = tonic::Response<Resp>;
+    type Error = Status;
+    type Future = std::future::Ready<Result<Self::Response, Status>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Status>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: tonic::Request<Req>) -> Self::Future {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        std::future::ready(
+            (self.handler)(&mut state, request.into_inner()).map(tonic::Response::new),
+        )
+    }
+}
+
+/// Call recorded by a `MockCosmWasmClient`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockCall {
+    Execute {
+        contract: String,
+        msg: serde_json::Value,
+    },
+    Query {
+        contract: String,
+        msg: serde_json::Value,
+    },
+    QueryRaw {
+        contract: String,
+        key: Vec<u8>,
+    },
+    GetTx {
+        hash: String,
+    },
+    QueryTip,
+    QueryHeaderDepth {
+        block_hash: String,
+    },
+}
+
+#[derive(Default)]
+struct MockClientState {
+    execute_results: VecDeque<Result<String, ClientError>>,
+    /// query_responses are the responses by contract and JSON query
+    query_responses: Vec<(String, serde_json::Value, serde_json::Value)>,
+    raw_state: HashMap<(String, Vec<u8>), Vec<u8>>,
+    /// txs are the included transactions by hash, including the executed ones
+    txs: HashMap<String, TxResponse>,
+    height: i64,
+    /// btc_headers is the BTC light client main chain, from the base header to the tip
+    btc_headers: Vec<BtcHeaderInfo>,
+    calls: Vec<MockCall>,
+}
+
+/// `CosmWasmApi` with canned responses recording its calls, for testing code that
+/// uses the client without a node. Clones share the responses and the calls, so a
+/// test can keep one to inspect what the code under test did with another.
+#[derive(Clone)]
+pub struct MockCosmWasmClient {
+    sender: String,
+    state: Arc<Mutex<MockClientState>>,
+}
+
+impl MockCosmWasmClient {
+    pub fn new(sender: &str) -> Self {
+        Self {
+            sender: sender.to_string(),
+            state: Arc::new(Mutex::new(MockClientState {
+                height: 1,
+                ..MockClientState::default()
+            })),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockClientState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Queues the result of the next execute. Executes without a queued result
+    /// succeed and are included in a block right away.
+    pub fn push_execute_result(&self, result: Result<String, ClientError>) {
+        self.state().execute_results.push_back(result);
+    }
+
+    /// Sets the response of the contract to the given smart query
+    pub fn set_query_response(
+        &self,
+        contract: &str,
+        query: serde_json::Value,
+        response: serde_json::Value,
+    ) {
+        let mut state = self.state();
+        state
+            .query_responses
+            .retain(|(c, q, _)| c != contract || *q != query);
+        state
+            .query_responses
+            .push((contract.to_string(), query, response));
+    }
+
+    /// Sets the value stored under `key` in the contract storage
+    pub fn set_raw_state(&self, contract: &str, key: &[u8], value: &[u8]) {
+        self.state()
+            .raw_state
+            .insert((contract.to_string(), key.to_vec()), value.to_vec());
+    }
+
+    /// Sets the response of `get_tx` for the hash of the tx response
+    pub fn set_tx(&self, tx_response: TxResponse) {
+        self.state()
+            .txs
+            .insert(tx_response.txhash.to_ascii_uppercase(), tx_response);
+    }
+
+    /// Sets the BTC light client main chain, ordered from the base header to the tip
+    pub fn set_btc_headers(&self, headers: Vec<BtcHeaderInfo>) {
+        self.state().btc_headers = headers;
+    }
+
+    /// Returns the calls in order
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.state().calls.clone()
+    }
+
+    /// Returns the messages executed on the contract in order
+    pub fn executed(&self, contract: &str) -> Vec<serde_json::Value> {
+        self.state()
+            .calls
+            .iter()
+            .filter_map(|call| match call {
+                MockCall::Execute { contract: c, msg } if c == contract => Some(msg.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl CosmWasmApi for MockCosmWasmClient {
+    fn sender(&self) -> String {
+        self.sender.clone()
+    }
+
+    async fn execute_json(
+        &self,
+        contract: &str,
+        msg: serde_json::Value,
+    ) -> Result<String, ClientError> {
+        let mut state = self.state();
+        let tx_hash =
+            TxHash::from_tx_bytes(format!("{}/{}/{}", state.calls.len(), contract, msg).as_bytes());
+        state.calls.push(MockCall::Execute {
+            contract: contract.to_string(),
+            msg,
+        });
+
+        let tx_hash = state
+            .execute_results
+            .pop_front()
+            .unwrap_or_else(|| Ok(tx_hash.to_string()))?;
+        state.height += 1;
+        let tx_response = TxResponse {
+            height: state.height,
+            txhash: tx_hash.clone(),
+            ..Default::default()
+        };
+        state.txs.insert(tx_hash.to_ascii_uppercase(), tx_response);
+
+        Ok(tx_hash)
+    }
+
+    async fn query_json(
+        &self,
+        contract: &str,
+        msg: serde_json::Value,
+    ) -> Result<serde_json::Value, ClientError> {
+        let mut state = self.state();
+        let response = state
+            .query_responses
+            .iter()
+            .find(|(c, q, _)| c == contract && *q == msg)
+            .map(|(_, _, response)| response.clone())
+            .ok_or_else(|| {
+                ClientError::grpc(
+                    "query contract state",
+                    Status::not_found(format!("no response to {} on {}", msg, contract)),
+                )
+            });
+        state.calls.push(MockCall::Query {
+            contract: contract.to_string(),
+            msg,
+        });
+
+        response
+    }
+
+    async fn query_raw(&self, contract: &str, key: &[u8]) -> Result<Option<Vec<u8>>, ClientError> {
+        let mut state = self.state();
+        state.calls.push(MockCall::QueryRaw {
+            contract: contract.to_string(),
+            key: key.to_vec(),
+        });
+
+        Ok(state
+            .raw_state
+            .get(&(contract.to_string(), key.to_vec()))
+            .cloned())
+    }
+
+    async fn get_tx(&self, hash: &str) -> Result<GetTxResponse, ClientError> {
+        let mut state = self.state();
+        state.calls.push(MockCall::GetTx {
+            hash: hash.to_string(),
+        });
+
+        // like a node, unknown txs fail the request instead of returning no response
+        let tx_response = state
+            .txs
+            .get(&hash.to_ascii_uppercase())
+            .cloned()
+            .ok_or_else(|| {
+                ClientError::grpc(
+                    "get transaction",
+                    Status::not_found(format!("tx not found: {}", hash)),
+                )
+            })?;
+
+        Ok(GetTxResponse {
+            tx: None,
+            tx_response: Some(tx_response),
+        })
+    }
+
+    async fn query_tip(&self) -> Result<BtcHeaderInfo, ClientError> {
+        let mut state = self.state();
+        state.calls.push(MockCall::QueryTip);
+
+        state
+            .btc_headers
+            .last()
+            .cloned()
+            .ok_or_else(|| ClientError::not_found("tip header"))
+    }
+
+    async fn query_header_depth(&self, block_hash: &str) -> Result<u32, ClientError> {
+        let mut state = self.state();
+        state.calls.push(MockCall::QueryHeaderDepth {
+            block_hash: block_hash.to_string(),
+        });
+
+        let tip = state.btc_headers.last();
+        let header = state
+            .btc_headers
+            .iter()
+            .find(|header| header.hash == block_hash);
+        match (tip, header) {
+            (Some(tip), Some(header)) => Ok(tip.height - header.height),
+            _ => Err(ClientError::grpc(
+                "query header depth",
+                Status::not_found(format!("header {} not found", block_hash)),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::client::tests::{readonly_client, test_client, TEST_CONTRACT};
+    use crate::client::CosmWasmClient;
+    use crate::fee::{FeeBumpConfig, GasRetryConfig};
+    use crate::submitter::{TxSubmitter, TxSubmitterConfig};
+    use crate::transactions::PegInRequest;
+    use crate::version::{BridgeSchema, HandshakeMode};
+
+    /// Starts a mock server with a funded account for the signer of the returned client
+    async fn start_mock_server() -> (MockGrpcServer, CosmWasmClient) {
+        let server = MockGrpcServer::start().await.unwrap();
+        let client = test_client(&server.url());
+        server.set_account(&client.sender(), 7, 3);
+        (server, client)
+    }
+
+    #[tokio::test]
+    async fn test_mock_grpc_server() {
+        let (server, client) = start_mock_server().await;
+        let address = client.sender();
+
+        let (submitter, _) = TxSubmitter::spawn(
+            client.clone(),
+            TxSubmitterConfig {
+                confirmation_timeout: Duration::from_millis(200),
+                poll_interval: Duration::from_millis(20),
+                ..Default::default()
+            },
+        );
+        let result = submitter.submit(vec![]).await.unwrap();
+        assert_eq!(result.code, 0);
+        assert_eq!(server.sequence(&address), Some(4));
+
+        // another signer used the account, the submitter resyncs and retries once
+        server.set_account(&address, 7, 6);
+        submitter.submit(vec![]).await.unwrap();
+        assert_eq!(server.sequence(&address), Some(7));
+
+        // code 32 of another codespace isn't a wrong sequence, nothing is resynced
+        server.push_broadcast(MockBroadcast::Reject {
+            codespace: "btclightclient".to_string(),
+            code: CODE_WRONG_SEQUENCE,
+            raw_log: "header not found".to_string(),
+        });
+        let account_queries = || {
+            server
+                .calls()
+                .iter()
+                .filter(|call| *call == "cosmos.auth.v1beta1.Query/Account")
+                .count()
+        };
+        let before = account_queries();
+        let error = submitter.submit(vec![]).await.unwrap_err();
+        assert_eq!(error.tx_error_kind(), Some(TxErrorKind::Other));
+        assert_eq!(account_queries(), before);
+
+        server.push_broadcast(MockBroadcast::reject(
+            TxErrorKind::InsufficientFee,
+            "insufficient fees",
+        ));
+        let error = submitter.submit(vec![]).await.unwrap_err();
+        assert_eq!(error.tx_error_kind(), Some(TxErrorKind::InsufficientFee));
+
+        server.push_broadcast(MockBroadcast::Pending);
+        let error = submitter.submit(vec![]).await.unwrap_err();
+        assert!(matches!(error, ClientError::Timeout { .. }));
+
+        server.push_broadcast(MockBroadcast::Status(Status::unavailable("node down")));
+        let error = client.rebroadcast(b"tx").await.unwrap_err();
+        assert_eq!(
+            error.grpc_status().map(Status::code),
+            Some(tonic::Code::Unavailable)
+        );
+        assert_eq!(error.tx_hash(), Some(TxHash::from_tx_bytes(b"tx").as_str()));
+        assert!(server
+            .calls()
+            .contains(&"cosmos.auth.v1beta1.Query/Account".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_gas_retry() {
+        let (server, client) = start_mock_server().await;
+
+        // out of gas in DeliverTx, resubmitted with the simulated gas and a bumped adjustment
+        server.set_gas_used(1_500_000);
+        server.push_broadcast(MockBroadcast::fail(TxErrorKind::OutOfGas, "out of gas"));
+        let gas_retry = GasRetryConfig {
+            confirmation_timeout: Duration::from_millis(200),
+            poll_interval: Duration::from_millis(20),
+            ..Default::default()
+        };
+        let tx_response = client
+            .submit_with_gas_retry(vec![], &gas_retry)
+            .await
+            .unwrap();
+        assert_eq!(tx_response.code, 0);
+        assert_eq!(tx_response.gas_wanted, 2_700_000);
+
+        server.push_broadcast(MockBroadcast::fail(TxErrorKind::OutOfGas, "out of gas"));
+        let error = client
+            .submit_with_gas_retry(
+                vec![],
+                &GasRetryConfig {
+                    max_attempts: 1,
+                    ..gas_retry
+                },
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(error.tx_error_kind(), Some(TxErrorKind::OutOfGas));
+    }
+
+    #[tokio::test]
+    async fn test_fee_bump() {
+        let (server, client) = start_mock_server().await;
+        let address = client.sender();
+
+        // stuck in the mempool, replaced with the same sequence and a higher fee
+        let sequence = server.sequence(&address).unwrap();
+        let broadcasts = || {
+            server
+                .calls()
+                .iter()
+                .filter(|call| *call == "cosmos.tx.v1beta1.Service/BroadcastTx")
+                .count()
+        };
+        server.push_broadcast(MockBroadcast::Pending);
+        let fee_bump = FeeBumpConfig {
+            stuck_timeout: Duration::from_millis(100),
+            poll_interval: Duration::from_millis(20),
+            ..Default::default()
+        };
+        let tx_response = client
+            .submit_with_fee_bump(vec![], &fee_bump)
+            .await
+            .unwrap();
+        assert_eq!(tx_response.code, 0);
+        assert_eq!(broadcasts(), 2);
+        assert_eq!(server.sequence(&address), Some(sequence + 1));
+    }
+
+    #[tokio::test]
+    async fn test_query_smart_many() {
+        let (server, client) = start_mock_server().await;
+        assert!(client.clone().with_query_concurrency(0).is_err());
+        let client = client.with_query_concurrency(2).unwrap();
+
+        // batched smart queries keep the order of the queries
+        let contract = TEST_CONTRACT;
+        for id in 0..5 {
+            server.set_smart_response(
+                contract,
+                json!({ "balance": { "id": id } }),
+                json!({ "balance": id * 10 }),
+            );
+        }
+        let queries = (0..6)
+            .map(|id| (contract, json!({ "balance": { "id": id } })))
+            .collect();
+        let results: Vec<Result<serde_json::Value, ClientError>> =
+            client.query_smart_many(queries).await;
+        for (id, result) in results[..5].iter().enumerate() {
+            assert_eq!(result.as_ref().unwrap()["balance"], id * 10);
+        }
+        assert_eq!(
+            results[5]
+                .as_ref()
+                .unwrap_err()
+                .grpc_status()
+                .map(Status::code),
+            Some(tonic::Code::NotFound)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_code_checksum() {
+        let (server, client) = start_mock_server().await;
+
+        // instantiating checks the checksum of the stored bytecode first
+        server.set_code(1, b"wasm");
+        let checksum = hex::encode(Sha256::digest(b"wasm"));
+        client.verify_code_checksum(1, &checksum).await.unwrap();
+        let error = client
+            .initiate_verified_contract(
+                1,
+                &hex::encode(Sha256::digest(b"other")),
+                &json!({}),
+                "bridge",
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ClientError::Invalid { .. }));
+        assert!(client.verify_code_checksum(2, &checksum).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_foreign_mempool_cache_code() {
+        let (server, client) = start_mock_server().await;
+
+        // code 19 of another codespace is a rejection, not a tx already in the mempool
+        server.push_broadcast(MockBroadcast::Reject {
+            codespace: "btclightclient".to_string(),
+            code: CODE_TX_IN_MEMPOOL_CACHE,
+            raw_log: "header already exists".to_string(),
+        });
+        let signed_tx = client.sign_msgs(vec![]).await.unwrap();
+        let error = client.broadcast_signed_tx(&signed_tx).await.unwrap_err();
+        assert_eq!(error.tx_error_kind(), Some(TxErrorKind::Other));
+
+        // the rejected tx didn't take the cached sequence
+        let signed_tx = client.sign_msgs(vec![]).await.unwrap();
+        client.broadcast_signed_tx(&signed_tx).await.unwrap();
+        assert_eq!(server.sequence(&client.sender()), Some(4));
+    }
+
+    #[tokio::test]
+    async fn test_peg_in_batch_gas() {
+        let (server, client) = start_mock_server().await;
+
+        // the batch needs more gas than the configured limit
+        server.set_gas_used(500_000);
+        let request = PegInRequest {
+            sender_btc_pk: "02bb".to_string(),
+            recipient: "bbn1receiver".to_string(),
+            amount: 1000,
+            block_hash: "00ff".to_string(),
+            pegin_tx: "0100".to_string(),
+            pegin_tx_idx: 0,
+            pegin_tx_merkle_proof: vec![],
+        };
+        let batch = client
+            .peg_in_batch(vec![request.clone(), request])
+            .await
+            .unwrap();
+        let tx_response = client
+            .wait_for_tx(
+                &batch.tx_hash,
+                Duration::from_millis(200),
+                Duration::from_millis(20),
+            )
+            .await
+            .unwrap();
+        assert_eq!(tx_response.gas_wanted, 650_000);
+    }
+
+    #[tokio::test]
+    async fn test_handshake() {
+        let server = MockGrpcServer::start().await.unwrap();
+        let client = readonly_client(&server.url());
+
+        // no cw2 entry
+        let error = client.handshake(HandshakeMode::Enforce).await.unwrap_err();
+        assert!(matches!(error, ClientError::NotFound { .. }));
+        assert_eq!(client.handshake(HandshakeMode::Warn).await.unwrap(), None);
+
+        server.set_raw_state(
+            TEST_CONTRACT,
+            b"contract_info",
+            br#"{"contract":"crates.io:bridge","version":"latest"}"#,
+        );
+        assert!(client.handshake(HandshakeMode::Enforce).await.is_err());
+        assert_eq!(client.handshake(HandshakeMode::Warn).await.unwrap(), None);
+        assert_eq!(client.bridge_schema(), BridgeSchema::V1);
+
+        server.set_raw_state(
+            TEST_CONTRACT,
+            b"contract_info",
+            br#"{"contract":"crates.io:bridge","version":"0.1.2"}"#,
+        );
+        let version = client.handshake(HandshakeMode::Enforce).await.unwrap();
+        assert_eq!(version.unwrap().version, "0.1.2");
+    }
+
+    #[tokio::test]
+    async fn test_mock_cosmwasm_client() {
+        let contract = "bbn1qwlgtx52gsdu7dtp0cekka5zehdl0uj3fhp9acg325fvgs8jdzksawp5cw";
+        let mock = MockCosmWasmClient::new("bbn1sender");
+        mock.set_query_response(
+            contract,
+            json!({ "params": {} }),
+            json!({ "denom": "bbtc" }),
+        );
+        let client: &dyn CosmWasmApi = &mock;
+
+        let params = client
+            .query_json(contract, json!({ "params": {} }))
+            .await
+            .unwrap();
+        assert_eq!(params["denom"], "bbtc");
+        assert!(client
+            .query_json(contract, json!({ "operators": {} }))
+            .await
+            .is_err());
+
+        let tx_hash = client
+            .execute_json(contract, json!({ "pause": {} }))
+            .await
+            .unwrap();
+        let tx = client.get_tx(&tx_hash).await.unwrap();
+        assert_eq!(tx.tx_response.unwrap().code, 0);
+
+        mock.push_execute_result(Err(ClientError::not_found("account data")));
+        assert!(client
+            .execute_json(contract, json!({ "unpause": {} }))
+            .await
+            .is_err());
+        assert_eq!(
+            mock.executed(contract),
+            [json!({ "pause": {} }), json!({ "unpause": {} })]
+        );
+        assert_eq!(mock.calls().len(), 5);
+    }
+}
@@ -0,0 +1,423 @@
+use std::collections::HashMap;
+use std::sync::mpsc;
+
+use async_trait::async_trait;
+use cosmos_sdk_proto::cosmos::base::abci::v1beta1::TxResponse;
+use cosmos_sdk_proto::cosmos::tx::v1beta1::GetTxResponse;
+use cosmos_sdk_proto::tendermint::abci::{Event, EventAttribute};
+use cosmwasm_std_v3::{Addr, Api, Empty};
+use cw_multi_test::{
+    next_block, App, AppBuilder, AppResponse, BankKeeper, Contract, Executor, MockApiBech32,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::sync::oneshot;
+use tonic::Status;
+use tracing::debug;
+
+use crate::client::{BtcHeaderInfo, CosmWasmApi};
+use crate::error::{ClientError, TransactionError, TxErrorKind};
+use crate::transactions::TxHash;
+
+/// ABCI code of a contract rejecting the execution (wasmd ErrExecuteFailed)
+const CODE_EXECUTE_FAILED: u32 = 5;
+/// Label of the sender account when none is given
+const DEFAULT_SENDER: &str = "sender";
+
+type MultiTestApp = App<BankKeeper, MockApiBech32>;
+type Job = Box<dyn FnOnce(&mut Backend) + Send>;
+
+/// Contract code loaded into the app, e.g.
+/// `|| Box::new(ContractWrapper::new(execute, instantiate, query))` over the
+/// entry points of the bridge contract
+pub type ContractCode = Box<dyn Contract<Empty>>;
+
+struct Backend {
+    app: MultiTestApp,
+    /// txs are the executed transactions by hash
+    txs: HashMap<String, TxResponse>,
+}
+
+impl Backend {
+    fn execute(&mut self, sender: Addr, contract: &str, msg: serde_json::Value) -> TxResponse {
+        let block = self.app.block_info();
+        let tx_hash =
+            TxHash::from_tx_bytes(format!("{}/{}/{}", self.txs.len(), contract, msg).as_bytes());
+        let result = self
+            .app
+            .api()
+            .addr_validate(contract)
+            .and_then(|contract| self.app.execute_contract(sender, contract, &msg, &[]));
+        // every transaction is included in its own block, like a node without
+        // other traffic
+        self.app.update_block(next_block);
+
+        let mut tx_response = TxResponse {
+            height: block.height as i64,
+            txhash: tx_hash.to_string(),
+            timestamp: block.time.to_string(),
+            ..Default::default()
+        };
+        match result {
+            Ok(response) => tx_response.events = abci_events(response),
+            Err(e) => {
+                tx_response.codespace = TxErrorKind::WASM_CODESPACE.to_string();
+                tx_response.code = CODE_EXECUTE_FAILED;
+                tx_response.raw_log = format!(
+                    "failed to execute message; message index: 0: {}: execute wasm contract failed",
+                    e
+                );
+            }
+        }
+        self.txs
+            .insert(tx_response.txhash.clone(), tx_response.clone());
+
+        tx_response
+    }
+}
+
+fn abci_events(response: AppResponse) -> Vec<Event> {
+    response
+        .events
+        .into_iter()
+        .map(|event| Event {
+            r#type: event.ty,
+            attributes: event
+                .attributes
+                .into_iter()
+                .map(|attr| EventAttribute {
+                    key: attr.key,
+                    value: attr.value,
+                    index: true,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// `CosmWasmApi` backed by a local cw-multi-test app instead of a node, to test
+/// the logic of relayers and operators against the real contract code without
+/// any chain. The contracts must be built against cosmwasm-std 3.
+///
+/// cw-multi-test contracts aren't `Send`, so the app lives on its own thread and
+/// the client only sends it jobs. Clones share the app, which stops once the
+/// last clone is dropped.
+#[derive(Clone)]
+pub struct MultiTestClient {
+    /// sender is the address executing the contracts
+    sender: Addr,
+    /// prefix is the bech32 prefix of the app addresses
+    prefix: &'static str,
+    jobs: mpsc::Sender<Job>,
+}
+
+impl MultiTestClient {
+    /// Starts an app whose addresses use the bech32 `prefix`, e.g. `bbn`
+    pub fn new(prefix: &'static str) -> Self {
+        let (jobs, receiver) = mpsc::channel::<Job>();
+        std::thread::spawn(move || {
+            let mut backend = Backend {
+                app: AppBuilder::default()
+                    .with_api(MockApiBech32::new(prefix))
+                    .build(|_, _, _| {}),
+                txs: HashMap::new(),
+            };
+            while let Ok(job) = receiver.recv() {
+                job(&mut backend);
+            }
+            debug!("Multi-test app stopped");
+        });
+
+        Self {
+            sender: MockApiBech32::new(prefix).addr_make(DEFAULT_SENDER),
+            prefix,
+            jobs,
+        }
+    }
+
+    /// Sets the account executing the contracts, derived from `name` like
+    /// `addr_make` does
+    pub fn with_sender(mut self, name: &str) -> Self {
+        self.sender = self.addr_make(name);
+        self
+    }
+
+    /// Returns the address of the account derived from `name`, e.g. to make
+    /// operators or peg out recipients
+    pub fn addr_make(&self, name: &str) -> Addr {
+        MockApiBech32::new(self.prefix).addr_make(name)
+    }
+
+    async fn run<R, F>(&self, job: F) -> Result<R, ClientError>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut Backend) -> R + Send + 'static,
+    {
+        let stopped = || ClientError::Stopped {
+            what: "Multi-test app".to_string(),
+        };
+        let (result_tx, result_rx) = oneshot::channel();
+        self.jobs
+            .send(Box::new(move |backend| {
+                let _ = result_tx.send(job(backend));
+            }))
+            .map_err(|_| stopped())?;
+
+        // a panicking contract takes the app down with it
+        result_rx.await.map_err(|_| stopped())
+    }
+
+    /// Stores the contract code and returns its code id
+    pub async fn store_code<F>(&self, code: F) -> Result<u64, ClientError>
+    where
+        F: FnOnce() -> ContractCode + Send + 'static,
+    {
+        self.run(move |backend| backend.app.store_code(code()))
+            .await
+    }
+
+    /// Instantiates the code with the sender as admin and returns the contract address
+    pub async fn instantiate<T: Serialize>(
+        &self,
+        code_id: u64,
+        msg: &T,
+        label: &str,
+    ) -> Result<String, ClientError> {
+        let msg = serde_json::to_value(msg)
+            .map_err(|e| ClientError::serialization("serialize instantiate message", e))?;
+        let sender = self.sender.clone();
+        let label = label.to_string();
+
+        self.run(move |backend| {
+            backend.app.instantiate_contract(
+                code_id,
+                sender.clone(),
+                &msg,
+                &[],
+                label,
+                Some(sender.to_string()),
+            )
+        })
+        .await?
+        .map(Addr::into_string)
+        .map_err(|e| ClientError::invalid(format!("instantiate code {}", code_id), e))
+    }
+
+    /// Executes the contract and returns the hash of the transaction. A contract
+    /// error fails the transaction like on a node, with a `ContractFailed` kind.
+    pub async fn execute_contract<T: Serialize>(
+        &self,
+        contract: &str,
+        msg: &T,
+    ) -> Result<String, ClientError> {
+        let msg = serde_json::to_value(msg)
+            .map_err(|e| ClientError::serialization("serialize execute message", e))?;
+        let sender = self.sender.clone();
+        let contract = contract.to_string();
+
+        let tx_response = self
+            .run(move |backend| backend.execute(sender, &contract, msg))
+            .await?;
+        if tx_response.code != 0 {
+            return Err(ClientError::TxFailed(Box::new(TransactionError::from(
+                tx_response,
+            ))));
+        }
+
+        Ok(tx_response.txhash)
+    }
+
+    /// Runs a smart query against the contract and deserializes the JSON response
+    pub async fn query_smart<M, R>(&self, contract: &str, msg: &M) -> Result<R, ClientError>
+    where
+        M: Serialize,
+        R: DeserializeOwned,
+    {
+        let msg = serde_json::to_value(msg)
+            .map_err(|e| ClientError::serialization("serialize query message", e))?;
+        let contract = contract.to_string();
+
+        let response = self
+            .run(move |backend| {
+                backend
+                    .app
+                    .wrap()
+                    .query_wasm_smart::<serde_json::Value>(contract, &msg)
+            })
+            .await?
+            .map_err(|e| {
+                ClientError::grpc(
+                    "query contract state",
+                    Status::unknown(format!("{}: query wasm contract failed", e)),
+                )
+            })?;
+
+        serde_json::from_value(response)
+            .map_err(|e| ClientError::serialization("deserialize query response", e))
+    }
+
+    /// Moves the app to the next block, 5 seconds later
+    pub async fn next_block(&self) -> Result<(), ClientError> {
+        self.run(|backend| backend.app.update_block(next_block))
+            .await
+    }
+}
+
+#[async_trait]
+impl CosmWasmApi for MultiTestClient {
+    fn sender(&self) -> String {
+        self.sender.to_string()
+    }
+
+    async fn execute_json(
+        &self,
+        contract: &str,
+        msg: serde_json::Value,
+    ) -> Result<String, ClientError> {
+        self.execute_contract(contract, &msg).await
+    }
+
+    async fn query_json(
+        &self,
+        contract: &str,
+        msg: serde_json::Value,
+    ) -> Result<serde_json::Value, ClientError> {
+        self.query_smart(contract, &msg).await
+    }
+
+    async fn query_raw(&self, contract: &str, key: &[u8]) -> Result<Option<Vec<u8>>, ClientError> {
+        let contract = contract.to_string();
+        let key = key.to_vec();
+
+        self.run(move |backend| backend.app.wrap().query_wasm_raw(contract, key))
+            .await?
+            .map_err(|e| {
+                ClientError::grpc("query raw contract state", Status::unknown(e.to_string()))
+            })
+    }
+
+    async fn get_tx(&self, hash: &str) -> Result<GetTxResponse, ClientError> {
+        let hash = hash.to_ascii_uppercase();
+        let tx_response = self
+            .run(move |backend| backend.txs.get(&hash).cloned())
+            .await?;
+
+        match tx_response {
+            Some(tx_response) => Ok(GetTxResponse {
+                tx: None,
+                tx_response: Some(tx_response),
+            }),
+            None => Err(ClientError::grpc(
+                "get transaction",
+                Status::not_found("tx not found"),
+            )),
+        }
+    }
+
+    async fn query_tip(&self) -> Result<BtcHeaderInfo, ClientError> {
+        Err(ClientError::grpc(
+            "query tip",
+            Status::unimplemented("no BTC light client in the multi-test app"),
+        ))
+    }
+
+    async fn query_header_depth(&self, _block_hash: &str) -> Result<u32, ClientError> {
+        Err(ClientError::grpc(
+            "query header depth",
+            Status::unimplemented("no BTC light client in the multi-test app"),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std_v3::{
+        to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+    };
+    use cw_multi_test::ContractWrapper;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::*;
+
+    const COUNT_KEY: &[u8] = b"count";
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum CounterMsg {
+        Increment { by: u64 },
+        Count {},
+    }
+
+    fn count(deps: Deps) -> StdResult<u64> {
+        Ok(deps
+            .storage
+            .get(COUNT_KEY)
+            .map(|count| u64::from_be_bytes(count.try_into().unwrap()))
+            .unwrap_or_default())
+    }
+
+    fn counter() -> ContractCode {
+        let instantiate = |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+            Ok(Response::new())
+        };
+        let execute =
+            |deps: DepsMut, _: Env, _: MessageInfo, msg: CounterMsg| -> StdResult<Response> {
+                let CounterMsg::Increment { by } = msg else {
+                    return Err(StdError::msg("unsupported message"));
+                };
+                if by == 0 {
+                    return Err(StdError::msg("Nothing to increment"));
+                }
+                let count = count(deps.as_ref())? + by;
+                deps.storage.set(COUNT_KEY, &count.to_be_bytes());
+                Ok(Response::new().add_attribute("count", count.to_string()))
+            };
+        let query = |deps: Deps, _: Env, _: CounterMsg| -> StdResult<Binary> {
+            to_json_binary(&count(deps)?)
+        };
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+
+    #[tokio::test]
+    async fn test_multi_test_client() {
+        let client = MultiTestClient::new("bbn").with_sender("operator");
+        let code_id = client.store_code(counter).await.unwrap();
+        let contract = client
+            .instantiate(code_id, &json!({}), "counter")
+            .await
+            .unwrap();
+        let api: &dyn CosmWasmApi = &client;
+
+        let tx_hash = api
+            .execute_json(&contract, json!({ "increment": { "by": 2 } }))
+            .await
+            .unwrap();
+        let tx_response = api.get_tx(&tx_hash).await.unwrap().tx_response.unwrap();
+        assert!(tx_response
+            .events
+            .iter()
+            .any(|event| event.r#type == "wasm"
+                && event.attributes.iter().any(|attr| attr.value == "2")));
+        assert_eq!(
+            api.query_json(&contract, json!({ "count": {} }))
+                .await
+                .unwrap(),
+            json!(2)
+        );
+        assert_eq!(
+            api.query_raw(&contract, COUNT_KEY).await.unwrap(),
+            Some(2u64.to_be_bytes().to_vec())
+        );
+
+        let err = api
+            .execute_json(&contract, json!({ "increment": { "by": 0 } }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.tx_error_kind(), Some(TxErrorKind::ContractFailed));
+        assert!(err
+            .contract_error()
+            .unwrap()
+            .contains("Nothing to increment"));
+    }
+}
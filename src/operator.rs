@@ -0,0 +1,401 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::btc_address::{validate_btc_address, BtcNetwork};
+use crate::events::{BlockEvents, ContractEvent, PegOutEvent};
+use crate::peg_out_store::PegOutKey;
+use crate::sink::EventSink;
+
+/// Peg out an operator should pay on Bitcoin
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayoutInstruction {
+    /// key identifies the peg out, e.g. to update it in a `PegOutTracker`
+    pub key: PegOutKey,
+    /// height is the block height of the peg out tx
+    pub height: u64,
+    pub operator_btc_pk: String,
+    pub btc_address: String,
+    /// amount is the amount to pay in satoshis, one per burnt $bBTC unit
+    pub amount: u128,
+    /// fee_rate is the BTC fee rate requested by the sender in sat/vB
+    pub fee_rate: u32,
+}
+
+/// Reason a peg out is not paid
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PayoutRejection {
+    #[error("Amount {amount} is below the minimum of {min}")]
+    BelowMinimum { amount: u128, min: u128 },
+    #[error("Amount {amount} is above the maximum of {max}")]
+    AboveMaximum { amount: u128, max: u128 },
+    #[error("Invalid BTC address: {reason}")]
+    InvalidAddress { reason: String },
+    #[error("Fee rate {fee_rate} sat/vB is above the maximum of {max}")]
+    FeeRateTooHigh { fee_rate: u32, max: u32 },
+    #[error("Operator {operator_btc_pk} would pay {total} within its window, above its limit of {limit}")]
+    OperatorLimitExceeded {
+        operator_btc_pk: String,
+        total: u128,
+        limit: u128,
+    },
+}
+
+/// Amount an operator may pay out within a sliding window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperatorLimit {
+    /// max_total is the maximum sum of the payouts within `window`
+    pub max_total: u128,
+    /// window is measured in block time
+    pub window: Duration,
+}
+
+/// Checks applied to every peg out before it is paid
+#[derive(Debug, Clone, Default)]
+pub struct OperatorPolicy {
+    /// operator_btc_pks are the operators whose peg outs are handled, all if empty
+    pub operator_btc_pks: Vec<String>,
+    /// network restricts destination addresses to the given network, any network if unset
+    pub network: Option<BtcNetwork>,
+    pub min_amount: u128,
+    /// max_amount is the maximum amount of a single peg out, unlimited if unset
+    pub max_amount: Option<u128>,
+    /// max_fee_rate is the maximum fee rate in sat/vB paid for senders, unlimited if unset
+    pub max_fee_rate: Option<u32>,
+    /// limits are the payout limits by operator BTC PK, operators without one are unlimited
+    pub limits: HashMap<String, OperatorLimit>,
+}
+
+impl OperatorPolicy {
+    /// Returns true if the peg out is assigned to one of the handled operators
+    pub fn handles(&self, event: &PegOutEvent) -> bool {
+        self.operator_btc_pks.is_empty() || self.operator_btc_pks.contains(&event.operator_btc_pk)
+    }
+
+    /// Checks the amount, destination address and fee rate of the peg out, the
+    /// operator limits are enforced by `PegOutWorkflow`
+    pub fn validate(&self, event: &PegOutEvent) -> Result<(), PayoutRejection> {
+        if event.amount < self.min_amount {
+            return Err(PayoutRejection::BelowMinimum {
+                amount: event.amount,
+                min: self.min_amount,
+            });
+        }
+        if let Some(max) = self.max_amount.filter(|max| event.amount > *max) {
+            return Err(PayoutRejection::AboveMaximum {
+                amount: event.amount,
+                max,
+            });
+        }
+        validate_btc_address(&event.btc_address, self.network).map_err(|e| {
+            PayoutRejection::InvalidAddress {
+                reason: e.to_string(),
+            }
+        })?;
+        if let Some(max) = self.max_fee_rate.filter(|max| event.fee_rate > *max) {
+            return Err(PayoutRejection::FeeRateTooHigh {
+                fee_rate: event.fee_rate,
+                max,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Receives the outcome of the peg outs evaluated by a `PegOutWorkflow`
+#[async_trait]
+pub trait PayoutHandler: Send + Sync {
+    /// Pays the peg out, e.g. by building and signing the BTC transaction
+    async fn pay(&self, instruction: PayoutInstruction) -> anyhow::Result<()>;
+
+    /// Handles a peg out the policy doesn't allow paying, by default only logging it
+    async fn reject(
+        &self,
+        instruction: PayoutInstruction,
+        reason: PayoutRejection,
+    ) -> anyhow::Result<()> {
+        tracing::warn!(
+            "Rejected peg out {}/{}: {}",
+            instruction.key.tx_hash,
+            instruction.key.msg_index,
+            reason
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PayoutHandler for mpsc::Sender<PayoutInstruction> {
+    async fn pay(&self, instruction: PayoutInstruction) -> anyhow::Result<()> {
+        self.send(instruction)
+            .await
+            .map_err(|e| anyhow!("Failed to send payout instruction: {}", e))
+    }
+}
+
+/// Operator side of peg outs: turns the peg out events of the listener into
+/// payout instructions for the handled operators, rejecting those the policy
+/// doesn't allow. Used as the listener's sink.
+///
+/// Operator limits only count the payouts of this workflow since it started, a
+/// restarted operator should replay the blocks of the last window.
+pub struct PegOutWorkflow {
+    policy: OperatorPolicy,
+    handler: Box<dyn PayoutHandler>,
+    /// payouts are the recent payouts by operator
+    payouts: Mutex<HashMap<String, VecDeque<Payout>>>,
+}
+
+/// Payout counted towards an operator limit
+struct Payout {
+    block_time: u64,
+    key: PegOutKey,
+    amount: u128,
+}
+
+impl PegOutWorkflow {
+    pub fn new(policy: OperatorPolicy, handler: impl PayoutHandler + 'static) -> Self {
+        Self {
+            policy,
+            handler: Box::new(handler),
+            payouts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Checks the peg out against the policy and the operator limit at the given
+    /// block time. It only counts towards the limit once `record_payout` is called,
+    /// a peg out already counted is allowed again so a replayed block isn't counted twice.
+    pub fn evaluate(
+        &self,
+        key: &PegOutKey,
+        event: &PegOutEvent,
+        block_time: u64,
+    ) -> Result<(), PayoutRejection> {
+        self.policy.validate(event)?;
+
+        let Some(limit) = self.policy.limits.get(&event.operator_btc_pk) else {
+            return Ok(());
+        };
+        let mut payouts = self.payouts.lock().unwrap_or_else(|e| e.into_inner());
+        let recent = payouts.entry(event.operator_btc_pk.clone()).or_default();
+        let window_start = block_time.saturating_sub(limit.window.as_secs());
+        while recent
+            .front()
+            .is_some_and(|payout| payout.block_time <= window_start)
+        {
+            recent.pop_front();
+        }
+        if recent.iter().any(|payout| payout.key == *key) {
+            return Ok(());
+        }
+
+        let total = recent.iter().map(|payout| payout.amount).sum::<u128>() + event.amount;
+        if total > limit.max_total {
+            return Err(PayoutRejection::OperatorLimitExceeded {
+                operator_btc_pk: event.operator_btc_pk.clone(),
+                total,
+                limit: limit.max_total,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Counts a paid peg out towards the limit of its operator, once per key
+    pub fn record_payout(&self, key: &PegOutKey, event: &PegOutEvent, block_time: u64) {
+        if !self.policy.limits.contains_key(&event.operator_btc_pk) {
+            return;
+        }
+        let mut payouts = self.payouts.lock().unwrap_or_else(|e| e.into_inner());
+        let recent = payouts.entry(event.operator_btc_pk.clone()).or_default();
+        if !recent.iter().any(|payout| payout.key == *key) {
+            recent.push_back(Payout {
+                block_time,
+                key: key.clone(),
+                amount: event.amount,
+            });
+        }
+    }
+
+    /// Evaluates the peg outs of the successful txs of the block for the handled
+    /// operators and hands them to the handler
+    pub async fn process_block(&self, block_events: &BlockEvents) -> anyhow::Result<()> {
+        for tx_event in &block_events.events {
+            let ContractEvent::PegOut(event) = &tx_event.event else {
+                continue;
+            };
+            if tx_event.code != 0 || !self.policy.handles(event) {
+                continue;
+            }
+
+            let instruction = PayoutInstruction {
+                key: PegOutKey {
+                    tx_hash: tx_event.tx_hash.clone(),
+                    msg_index: event.msg_index,
+                },
+                height: block_events.height,
+                operator_btc_pk: event.operator_btc_pk.clone(),
+                btc_address: event.btc_address.clone(),
+                amount: event.amount,
+                fee_rate: event.fee_rate,
+            };
+            let key = instruction.key.clone();
+            match self.evaluate(&key, event, block_events.block_time) {
+                Ok(()) => {
+                    self.handler.pay(instruction).await?;
+                    self.record_payout(&key, event, block_events.block_time);
+                }
+                Err(reason) => self.handler.reject(instruction, reason).await?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventSink for PegOutWorkflow {
+    async fn send(&self, block_events: BlockEvents) -> anyhow::Result<()> {
+        self.process_block(&block_events).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::TxEvent;
+    use crate::transactions::TxHash;
+
+    #[test]
+    fn test_evaluate_peg_out() {
+        let operator = "02".to_string() + &"ab".repeat(32);
+        let policy = OperatorPolicy {
+            network: Some(BtcNetwork::Mainnet),
+            min_amount: 1000,
+            max_fee_rate: Some(50),
+            limits: HashMap::from([(
+                operator.clone(),
+                OperatorLimit {
+                    max_total: 10_000,
+                    window: Duration::from_secs(3600),
+                },
+            )]),
+            ..Default::default()
+        };
+        let (sender, _receiver) = mpsc::channel(1);
+        let workflow = PegOutWorkflow::new(policy, sender);
+        let peg_out = |amount, btc_address: &str, fee_rate| PegOutEvent {
+            btc_address: btc_address.to_string(),
+            fee_rate,
+            operator_btc_pk: operator.clone(),
+            amount,
+            ..Default::default()
+        };
+        let address = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+        let key = |msg_index| PegOutKey {
+            tx_hash: TxHash::from_tx_bytes(b"peg out"),
+            msg_index,
+        };
+
+        assert!(matches!(
+            workflow.evaluate(&key(0), &peg_out(500, address, 10), 0),
+            Err(PayoutRejection::BelowMinimum { .. })
+        ));
+        assert!(matches!(
+            workflow.evaluate(
+                &key(0),
+                &peg_out(5000, "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx", 10),
+                0
+            ),
+            Err(PayoutRejection::InvalidAddress { .. })
+        ));
+        assert!(matches!(
+            workflow.evaluate(&key(0), &peg_out(5000, address, 100), 0),
+            Err(PayoutRejection::FeeRateTooHigh { .. })
+        ));
+
+        assert!(workflow
+            .evaluate(&key(0), &peg_out(6000, address, 10), 100)
+            .is_ok());
+        // only paid peg outs count towards the limit
+        assert!(workflow
+            .evaluate(&key(1), &peg_out(6000, address, 10), 200)
+            .is_ok());
+        workflow.record_payout(&key(0), &peg_out(6000, address, 10), 100);
+        assert!(matches!(
+            workflow.evaluate(&key(1), &peg_out(6000, address, 10), 200),
+            Err(PayoutRejection::OperatorLimitExceeded { total: 12_000, .. })
+        ));
+        // a replayed peg out is counted once
+        assert!(workflow
+            .evaluate(&key(0), &peg_out(6000, address, 10), 200)
+            .is_ok());
+        workflow.record_payout(&key(0), &peg_out(6000, address, 10), 100);
+        assert!(workflow
+            .evaluate(&key(1), &peg_out(4000, address, 10), 200)
+            .is_ok());
+        // the first payout left the window
+        assert!(workflow
+            .evaluate(&key(1), &peg_out(6000, address, 10), 3700)
+            .is_ok());
+    }
+
+    struct FailingHandler;
+
+    #[async_trait]
+    impl PayoutHandler for FailingHandler {
+        async fn pay(&self, _instruction: PayoutInstruction) -> anyhow::Result<()> {
+            Err(anyhow!("BTC node unavailable"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_payout_not_counted() {
+        let operator = "02".to_string() + &"ab".repeat(32);
+        let policy = OperatorPolicy {
+            limits: HashMap::from([(
+                operator.clone(),
+                OperatorLimit {
+                    max_total: 10_000,
+                    window: Duration::from_secs(3600),
+                },
+            )]),
+            ..Default::default()
+        };
+        let workflow = PegOutWorkflow::new(policy, FailingHandler);
+        let event = PegOutEvent {
+            btc_address: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".to_string(),
+            operator_btc_pk: operator,
+            amount: 6000,
+            ..Default::default()
+        };
+        let block_events = BlockEvents {
+            height: 1,
+            block_time: 100,
+            events: vec![TxEvent {
+                tx_hash: TxHash::from_tx_bytes(b"peg out"),
+                tx_index: 0,
+                code: 0,
+                gas_used: 0,
+                contract: String::new(),
+                msg_index: Some(0),
+                origin: Default::default(),
+                event: ContractEvent::PegOut(event.clone()),
+            }],
+            ..Default::default()
+        };
+
+        assert!(workflow.process_block(&block_events).await.is_err());
+        let key = PegOutKey {
+            tx_hash: TxHash::from_tx_bytes(b"peg out"),
+            msg_index: 1,
+        };
+        assert!(workflow.evaluate(&key, &event, 100).is_ok());
+    }
+}
@@ -0,0 +1,439 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ClientError;
+use crate::events::{BlockEvents, ContractEvent, PegOutEvent};
+use crate::sink::EventSink;
+use crate::transactions::TxHash;
+
+/// Progress of a peg out through the operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PegOutState {
+    /// The peg out event has been observed on chain
+    Observed,
+    /// The BTC transaction paying the peg out has been built and signed
+    TxBuilt,
+    /// The BTC transaction has been broadcast
+    Broadcast,
+    /// The BTC transaction has been confirmed
+    Confirmed,
+}
+
+impl PegOutState {
+    /// States of the peg outs the operator still has to work on
+    pub const OUTSTANDING: [PegOutState; 3] = [Self::Observed, Self::TxBuilt, Self::Broadcast];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Observed => "observed",
+            Self::TxBuilt => "tx_built",
+            Self::Broadcast => "broadcast",
+            Self::Confirmed => "confirmed",
+        }
+    }
+
+    /// Returns true if a peg out may move from this state to `next`. Built
+    /// transactions can be rebuilt, e.g. to bump their fee, and repeated
+    /// transitions are allowed so operators can retry after a crash.
+    pub fn can_move_to(self, next: PegOutState) -> bool {
+        matches!(
+            (self, next),
+            (
+                Self::Observed | Self::TxBuilt | Self::Broadcast,
+                Self::TxBuilt
+            ) | (Self::TxBuilt | Self::Broadcast, Self::Broadcast)
+                | (Self::Broadcast | Self::Confirmed, Self::Confirmed)
+        )
+    }
+}
+
+/// Identifies a peg out by the tx and message that requested it
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PegOutKey {
+    pub tx_hash: TxHash,
+    pub msg_index: u32,
+}
+
+/// Peg out recorded by a `PegOutStore`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedPegOut {
+    pub key: PegOutKey,
+    /// height is the block height of the peg out tx
+    pub height: u64,
+    pub event: PegOutEvent,
+    pub state: PegOutState,
+    /// btc_txid is the BTC transaction paying the peg out, once built
+    pub btc_txid: Option<String>,
+    /// btc_tx is the signed BTC transaction in hex format, once built
+    pub btc_tx: Option<String>,
+}
+
+/// Persists the observed peg outs and their state, so operator software has a
+/// durable work queue that survives restarts
+#[async_trait]
+pub trait PegOutStore: Send + Sync {
+    async fn get(&self, key: &PegOutKey) -> anyhow::Result<Option<TrackedPegOut>>;
+
+    /// Stores the peg out, replacing the stored one with the same key
+    async fn put(&self, peg_out: &TrackedPegOut) -> anyhow::Result<()>;
+
+    /// Returns the peg outs in any of the given states, ordered by height
+    async fn list(&self, states: &[PegOutState]) -> anyhow::Result<Vec<TrackedPegOut>>;
+}
+
+/// Stores all peg outs as JSON in a file
+pub struct FilePegOutStore {
+    path: PathBuf,
+    /// serializes the read-modify-write cycles of `put`
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl FilePegOutStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    async fn load(&self) -> anyhow::Result<Vec<TrackedPegOut>> {
+        let content = match tokio::fs::read(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("Failed to read peg out file {}", self.path.display())
+                })
+            }
+        };
+
+        serde_json::from_slice(&content)
+            .with_context(|| format!("Invalid peg out file {}", self.path.display()))
+    }
+}
+
+#[async_trait]
+impl PegOutStore for FilePegOutStore {
+    async fn get(&self, key: &PegOutKey) -> anyhow::Result<Option<TrackedPegOut>> {
+        Ok(self
+            .load()
+            .await?
+            .into_iter()
+            .find(|peg_out| peg_out.key == *key))
+    }
+
+    async fn put(&self, peg_out: &TrackedPegOut) -> anyhow::Result<()> {
+        let _guard = self.lock.lock().await;
+        let mut peg_outs = self.load().await?;
+        match peg_outs.iter_mut().find(|p| p.key == peg_out.key) {
+            Some(stored) => *stored = peg_out.clone(),
+            None => peg_outs.push(peg_out.clone()),
+        }
+
+        let content = serde_json::to_vec(&peg_outs).context("Failed to serialize peg outs")?;
+        // write to a temporary file first so a crash never loses the queue
+        let tmp_path = self.path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, content)
+            .await
+            .with_context(|| format!("Failed to write peg out file {}", tmp_path.display()))?;
+        tokio::fs::rename(&tmp_path, &self.path)
+            .await
+            .with_context(|| format!("Failed to write peg out file {}", self.path.display()))
+    }
+
+    async fn list(&self, states: &[PegOutState]) -> anyhow::Result<Vec<TrackedPegOut>> {
+        let mut peg_outs: Vec<_> = self
+            .load()
+            .await?
+            .into_iter()
+            .filter(|peg_out| states.contains(&peg_out.state))
+            .collect();
+        peg_outs.sort_by_key(|peg_out| peg_out.height);
+        Ok(peg_outs)
+    }
+}
+
+/// Stores the peg outs in a SQLite table, with the state in its own column
+#[cfg(feature = "sqlite")]
+pub struct SqlitePegOutStore {
+    conn: std::sync::Mutex<rusqlite::Connection>,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqlitePegOutStore {
+    pub fn open(path: impl AsRef<std::path::Path>) -> anyhow::Result<Self> {
+        let conn = rusqlite::Connection::open(path).context("Failed to open SQLite database")?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS peg_outs (
+                tx_hash TEXT NOT NULL,
+                msg_index INTEGER NOT NULL,
+                height INTEGER NOT NULL,
+                state TEXT NOT NULL,
+                peg_out TEXT NOT NULL,
+                PRIMARY KEY (tx_hash, msg_index)
+            )",
+            [],
+        )
+        .context("Failed to create peg_outs table")?;
+
+        Ok(Self {
+            conn: std::sync::Mutex::new(conn),
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[async_trait]
+impl PegOutStore for SqlitePegOutStore {
+    async fn get(&self, key: &PegOutKey) -> anyhow::Result<Option<TrackedPegOut>> {
+        use rusqlite::OptionalExtension;
+
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let peg_out: Option<String> = conn
+            .query_row(
+                "SELECT peg_out FROM peg_outs WHERE tx_hash = ?1 AND msg_index = ?2",
+                rusqlite::params![key.tx_hash.as_str(), key.msg_index],
+                |row| row.get(0),
+            )
+            .optional()
+            .context("Failed to load peg out")?;
+
+        peg_out
+            .map(|peg_out| serde_json::from_str(&peg_out).context("Invalid stored peg out"))
+            .transpose()
+    }
+
+    async fn put(&self, peg_out: &TrackedPegOut) -> anyhow::Result<()> {
+        let json = serde_json::to_string(peg_out).context("Failed to serialize peg out")?;
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        conn.execute(
+            "INSERT INTO peg_outs (tx_hash, msg_index, height, state, peg_out) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(tx_hash, msg_index) DO UPDATE SET state = excluded.state, peg_out = excluded.peg_out",
+            rusqlite::params![
+                peg_out.key.tx_hash.as_str(),
+                peg_out.key.msg_index,
+                peg_out.height as i64,
+                peg_out.state.as_str(),
+                json
+            ],
+        )
+        .context("Failed to store peg out")?;
+
+        Ok(())
+    }
+
+    async fn list(&self, states: &[PegOutState]) -> anyhow::Result<Vec<TrackedPegOut>> {
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let placeholders = vec!["?"; states.len()].join(", ");
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT peg_out FROM peg_outs WHERE state IN ({}) ORDER BY height, rowid",
+                placeholders
+            ))
+            .context("Failed to query peg outs")?;
+        let rows = stmt
+            .query_map(
+                rusqlite::params_from_iter(states.iter().map(|state| state.as_str())),
+                |row| row.get::<_, String>(0),
+            )
+            .context("Failed to query peg outs")?;
+
+        rows.map(|peg_out| {
+            let peg_out = peg_out.context("Failed to read peg out")?;
+            serde_json::from_str(&peg_out).context("Invalid stored peg out")
+        })
+        .collect()
+    }
+}
+
+/// Records the peg outs of an `EventListener` in a `PegOutStore` and moves them
+/// through their states. Used as the listener's sink, a peg out is stored before
+/// the listener checkpoints past its block, so none is lost on restart. Clones
+/// share the store.
+#[derive(Clone)]
+pub struct PegOutTracker {
+    store: Arc<dyn PegOutStore>,
+}
+
+impl PegOutTracker {
+    pub fn new(store: impl PegOutStore + 'static) -> Self {
+        Self {
+            store: Arc::new(store),
+        }
+    }
+
+    /// Records the peg outs of the successful txs of the block, keeping the state
+    /// of those already recorded. Returns the number of new peg outs.
+    pub async fn record_block(&self, block_events: &BlockEvents) -> Result<usize, ClientError> {
+        let mut recorded = 0;
+
+        for tx_event in &block_events.events {
+            let ContractEvent::PegOut(event) = &tx_event.event else {
+                continue;
+            };
+            if tx_event.code != 0 {
+                continue;
+            }
+            let key = PegOutKey {
+                tx_hash: tx_event.tx_hash.clone(),
+                msg_index: event.msg_index,
+            };
+            if self.get(&key).await?.is_some() {
+                continue;
+            }
+
+            self.put(&TrackedPegOut {
+                key,
+                height: block_events.height,
+                event: event.clone(),
+                state: PegOutState::Observed,
+                btc_txid: None,
+                btc_tx: None,
+            })
+            .await?;
+            recorded += 1;
+        }
+
+        Ok(recorded)
+    }
+
+    pub async fn get(&self, key: &PegOutKey) -> Result<Option<TrackedPegOut>, ClientError> {
+        self.store
+            .get(key)
+            .await
+            .map_err(|e| ClientError::store("load peg out", e))
+    }
+
+    async fn put(&self, peg_out: &TrackedPegOut) -> Result<(), ClientError> {
+        self.store
+            .put(peg_out)
+            .await
+            .map_err(|e| ClientError::store("store peg out", e))
+    }
+
+    /// Returns the peg outs that aren't confirmed yet, ordered by height
+    pub async fn outstanding(&self) -> Result<Vec<TrackedPegOut>, ClientError> {
+        self.list(&PegOutState::OUTSTANDING).await
+    }
+
+    /// Returns the peg outs in any of the given states, ordered by height
+    pub async fn list(&self, states: &[PegOutState]) -> Result<Vec<TrackedPegOut>, ClientError> {
+        self.store
+            .list(states)
+            .await
+            .map_err(|e| ClientError::store("list peg outs", e))
+    }
+
+    /// Records the signed BTC transaction paying the peg out
+    pub async fn mark_tx_built(
+        &self,
+        key: &PegOutKey,
+        btc_txid: &str,
+        btc_tx: &str,
+    ) -> Result<TrackedPegOut, ClientError> {
+        self.transition(key, PegOutState::TxBuilt, |peg_out| {
+            peg_out.btc_txid = Some(btc_txid.to_string());
+            peg_out.btc_tx = Some(btc_tx.to_string());
+        })
+        .await
+    }
+
+    pub async fn mark_broadcast(&self, key: &PegOutKey) -> Result<TrackedPegOut, ClientError> {
+        self.transition(key, PegOutState::Broadcast, |_| {}).await
+    }
+
+    pub async fn mark_confirmed(&self, key: &PegOutKey) -> Result<TrackedPegOut, ClientError> {
+        self.transition(key, PegOutState::Confirmed, |_| {}).await
+    }
+
+    async fn transition(
+        &self,
+        key: &PegOutKey,
+        next: PegOutState,
+        update: impl FnOnce(&mut TrackedPegOut),
+    ) -> Result<TrackedPegOut, ClientError> {
+        let mut peg_out = self.get(key).await?.ok_or_else(|| {
+            ClientError::not_found(format!("peg out {}/{}", key.tx_hash, key.msg_index))
+        })?;
+        if !peg_out.state.can_move_to(next) {
+            return Err(ClientError::invalid(
+                format!("peg out {}/{}", key.tx_hash, key.msg_index),
+                format!(
+                    "cannot move from {} to {}",
+                    peg_out.state.as_str(),
+                    next.as_str()
+                ),
+            ));
+        }
+
+        peg_out.state = next;
+        update(&mut peg_out);
+        self.put(&peg_out).await?;
+        Ok(peg_out)
+    }
+}
+
+#[async_trait]
+impl EventSink for PegOutTracker {
+    async fn send(&self, block_events: BlockEvents) -> anyhow::Result<()> {
+        let recorded = self.record_block(&block_events).await?;
+        if recorded > 0 {
+            tracing::info!(
+                "Recorded {} peg outs at height {}",
+                recorded,
+                block_events.height
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::TxEvent;
+
+    #[tokio::test]
+    async fn test_peg_out_tracker() {
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = PegOutTracker::new(FilePegOutStore::new(dir.path().join("peg_outs.json")));
+
+        let tx_event = |tx_hash: &str, code: u32| TxEvent {
+            tx_hash: tx_hash.parse().unwrap(),
+            tx_index: 0,
+            code,
+            gas_used: 0,
+            contract: "bbn1contract".to_string(),
+            msg_index: Some(0),
+            origin: Default::default(),
+            event: ContractEvent::PegOut(PegOutEvent {
+                amount: 1000,
+                ..Default::default()
+            }),
+        };
+        let block_events = BlockEvents {
+            height: 7,
+            events: vec![tx_event(&"AB".repeat(32), 0), tx_event(&"CD".repeat(32), 5)],
+            ..Default::default()
+        };
+        assert_eq!(tracker.record_block(&block_events).await.unwrap(), 1);
+        assert_eq!(tracker.record_block(&block_events).await.unwrap(), 0);
+
+        let key = tracker.outstanding().await.unwrap()[0].key.clone();
+        assert!(tracker.mark_broadcast(&key).await.is_err());
+        tracker.mark_tx_built(&key, "txid", "0200").await.unwrap();
+        tracker.mark_broadcast(&key).await.unwrap();
+        let peg_out = tracker.mark_confirmed(&key).await.unwrap();
+        assert_eq!(peg_out.btc_txid.as_deref(), Some("txid"));
+        assert!(tracker.outstanding().await.unwrap().is_empty());
+        assert_eq!(
+            tracker.list(&[PegOutState::Confirmed]).await.unwrap().len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,218 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+use serde::de::DeserializeOwned;
+
+use crate::client::CosmWasmClient;
+use crate::error::ClientError;
+use crate::transactions::Operator;
+
+/// Page size used when fetching all pages of a paginated bridge query
+const DEFAULT_PAGE_LIMIT: u32 = 30;
+
+#[cw_serde]
+pub enum QueryMsg {
+    /// Params returns the bridge parameters
+    Params {},
+    /// Operators returns the list of operators
+    Operators {},
+    /// PendingPegOuts returns the peg out requests not yet processed by an operator
+    PendingPegOuts {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// ProcessedPegIns returns the txids of the peg in transactions already minted
+    ProcessedPegIns {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// IsPegInProcessed returns whether the given peg in txid has already been minted
+    IsPegInProcessed { txid: String },
+}
+
+#[cw_serde]
+pub struct ParamsResponse {
+    /// cw20_address is the address of the wrapped BTC CW20 token contract
+    pub cw20_address: Option<Addr>,
+    /// denom is the denomination of the bridged asset
+    pub denom: String,
+    /// btc_confirmation_depth is the number of blocks to confirm on BTC
+    pub btc_confirmation_depth: u32,
+}
+
+#[cw_serde]
+pub struct OperatorsResponse {
+    pub operators: Vec<Operator>,
+}
+
+#[cw_serde]
+pub struct PendingPegOut {
+    /// id is the sequence number of the peg out request
+    pub id: u64,
+    /// sender is the Cosmos address that burned the $bBTC
+    pub sender: Addr,
+    /// btc_address is the Bitcoin address receiving the $BTC
+    pub btc_address: String,
+    /// fee_rate is the fee rate of the peg out transaction in sat/vB
+    pub fee_rate: u32,
+    /// amount is the amount of $bBTC burned
+    pub amount: Uint128,
+    /// operator_btc_pk is the Bitcoin public key of the operator processing the request
+    pub operator_btc_pk: String,
+}
+
+#[cw_serde]
+pub struct PendingPegOutsResponse {
+    pub peg_outs: Vec<PendingPegOut>,
+}
+
+#[cw_serde]
+pub struct ProcessedPegInsResponse {
+    /// txids are the BTC txids of the processed peg in transactions
+    pub txids: Vec<String>,
+}
+
+#[cw_serde]
+pub struct IsPegInProcessedResponse {
+    pub processed: bool,
+}
+
+impl CosmWasmClient {
+    async fn query_bridge<R: DeserializeOwned>(&self, msg: &QueryMsg) -> Result<R, ClientError> {
+        self.query_bridge_at(msg, None).await
+    }
+
+    /// Runs the bridge query at `height` if set, the latest height otherwise
+    async fn query_bridge_at<R: DeserializeOwned>(
+        &self,
+        msg: &QueryMsg,
+        height: Option<u64>,
+    ) -> Result<R, ClientError> {
+        let contract = self
+            .contract
+            .as_ref()
+            .ok_or_else(|| ClientError::not_found("contract address"))?;
+
+        match height {
+            Some(height) => {
+                self.query_smart_at_height(contract.as_ref(), msg, height)
+                    .await
+            }
+            None => self.query_smart(contract.as_ref(), msg).await,
+        }
+    }
+
+    /// Returns the bridge parameters
+    pub async fn query_params(&self) -> Result<ParamsResponse, ClientError> {
+        self.query_bridge(&QueryMsg::Params {}).await
+    }
+
+    /// Returns the list of bridge operators
+    pub async fn query_operators(&self) -> Result<Vec<Operator>, ClientError> {
+        let resp: OperatorsResponse = self.query_bridge(&QueryMsg::Operators {}).await?;
+        Ok(resp.operators)
+    }
+
+    /// Returns one page of the pending peg out requests
+    pub async fn query_pending_peg_outs(
+        &self,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<PendingPegOut>, ClientError> {
+        let resp: PendingPegOutsResponse = self
+            .query_bridge(&QueryMsg::PendingPegOuts { start_after, limit })
+            .await?;
+        Ok(resp.peg_outs)
+    }
+
+    /// Returns all pending peg out requests, following pagination
+    pub async fn query_all_pending_peg_outs(&self) -> Result<Vec<PendingPegOut>, ClientError> {
+        self.all_pending_peg_outs(None).await
+    }
+
+    /// Returns all pending peg out requests at the given block height, e.g. to
+    /// reconstruct the pending set when a dispute arose. Every page is read at the
+    /// same height, from the archive node set by `with_archive_grpc` if pruned.
+    pub async fn query_all_pending_peg_outs_at_height(
+        &self,
+        height: u64,
+    ) -> Result<Vec<PendingPegOut>, ClientError> {
+        self.all_pending_peg_outs(Some(height)).await
+    }
+
+    async fn all_pending_peg_outs(
+        &self,
+        height: Option<u64>,
+    ) -> Result<Vec<PendingPegOut>, ClientError> {
+        let mut peg_outs = vec![];
+        let mut start_after = None;
+
+        loop {
+            let msg = QueryMsg::PendingPegOuts {
+                start_after,
+                limit: Some(DEFAULT_PAGE_LIMIT),
+            };
+            let page = self
+                .query_bridge_at::<PendingPegOutsResponse>(&msg, height)
+                .await?
+                .peg_outs;
+            let done = page.len() < DEFAULT_PAGE_LIMIT as usize;
+            start_after = page.last().map(|p| p.id);
+            peg_outs.extend(page);
+
+            if done || start_after.is_none() {
+                break;
+            }
+        }
+
+        Ok(peg_outs)
+    }
+
+    /// Returns one page of the txids of processed peg in transactions
+    pub async fn query_processed_peg_ins(
+        &self,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<Vec<String>, ClientError> {
+        let resp: ProcessedPegInsResponse = self
+            .query_bridge(&QueryMsg::ProcessedPegIns { start_after, limit })
+            .await?;
+        Ok(resp.txids)
+    }
+
+    /// Returns true if the peg in transaction with the given BTC txid has already been minted
+    pub async fn is_peg_in_processed(&self, txid: &str) -> Result<bool, ClientError> {
+        let resp: IsPegInProcessedResponse = self
+            .query_bridge(&QueryMsg::IsPegInProcessed {
+                txid: txid.to_string(),
+            })
+            .await?;
+        Ok(resp.processed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_msg_format() {
+        let msg = QueryMsg::PendingPegOuts {
+            start_after: Some(3),
+            limit: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&msg).unwrap(),
+            r#"{"pending_peg_outs":{"start_after":3,"limit":null}}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&QueryMsg::Params {}).unwrap(),
+            r#"{"params":{}}"#
+        );
+
+        let resp: PendingPegOutsResponse = serde_json::from_str(
+            r#"{"peg_outs":[{"id":1,"sender":"bbn1sender","btc_address":"bc1qaddr","fee_rate":5,"amount":"1000","operator_btc_pk":"02ab"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(resp.peg_outs[0].amount, Uint128::new(1000));
+    }
+}
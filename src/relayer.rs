@@ -0,0 +1,739 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bitcoin::blockdata::script::Instruction;
+use bitcoin::consensus::encode::deserialize;
+use bitcoin::{Address, Block, ScriptBuf, Transaction};
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use tokio::time::sleep;
+
+use crate::btc::build_peg_in_proof;
+use crate::btc_address::BtcNetwork;
+use crate::checkpoint::CheckpointStore;
+use crate::client::{CosmWasmApi, CosmWasmClient};
+use crate::error::{ClientError, TxErrorKind};
+use crate::events::BackoffPolicy;
+use crate::fee::GasRetryConfig;
+use crate::logging;
+use crate::transactions::PegInRequest;
+use crate::wallet_pool::WalletPool;
+
+/// Default number of BTC confirmations before a deposit is relayed
+pub const DEFAULT_BTC_CONFIRMATION_DEPTH: u32 = 6;
+/// Default maximum number of peg ins submitted in one transaction
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 20;
+/// Interval between `get_tx` polls while waiting for a peg in transaction
+const TX_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Length of a compressed public key in a P2WPKH witness
+const COMPRESSED_PK_LEN: usize = 33;
+
+/// Bitcoin node or indexer the relayer reads blocks from
+#[async_trait]
+pub trait BtcSource: Send + Sync {
+    /// Returns the height of the best block
+    async fn tip_height(&self) -> Result<u32, ClientError>;
+
+    /// Returns the hash of the main chain block at the given height
+    async fn block_hash(&self, height: u32) -> Result<String, ClientError>;
+
+    /// Returns the consensus encoded block
+    async fn raw_block(&self, block_hash: &str) -> Result<Vec<u8>, ClientError>;
+}
+
+/// Bitcoin Core JSON-RPC endpoint
+pub struct BitcoindRpc {
+    url: String,
+    auth: Option<(String, String)>,
+    http: reqwest::Client,
+}
+
+impl BitcoindRpc {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            auth: None,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// Sets the `rpcuser` and `rpcpassword` of the node
+    pub fn with_auth(mut self, user: &str, password: &str) -> Self {
+        self.auth = Some((user.to_string(), password.to_string()));
+        self
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<T, ClientError> {
+        let mut request = self.http.post(&self.url).json(&json!({
+            "jsonrpc": "1.0",
+            "id": "relayer",
+            "method": method,
+            "params": params,
+        }));
+        if let Some((user, password)) = &self.auth {
+            request = request.basic_auth(user, Some(password));
+        }
+
+        // bitcoind answers failed calls with an error status and a JSON-RPC error body
+        let response: serde_json::Value = request
+            .send()
+            .await
+            .map_err(|e| ClientError::connection(&self.url, e))?
+            .json()
+            .await
+            .map_err(|e| ClientError::serialization(format!("decode {} response", method), e))?;
+        if !response["error"].is_null() {
+            return Err(ClientError::invalid(
+                format!("bitcoind {}", method),
+                &response["error"],
+            ));
+        }
+
+        serde_json::from_value(response["result"].clone())
+            .map_err(|e| ClientError::serialization(format!("decode {} result", method), e))
+    }
+}
+
+#[async_trait]
+impl BtcSource for BitcoindRpc {
+    async fn tip_height(&self) -> Result<u32, ClientError> {
+        self.call("getblockcount", json!([])).await
+    }
+
+    async fn block_hash(&self, height: u32) -> Result<String, ClientError> {
+        self.call("getblockhash", json!([height])).await
+    }
+
+    async fn raw_block(&self, block_hash: &str) -> Result<Vec<u8>, ClientError> {
+        let block_hex: String = self.call("getblock", json!([block_hash, 0])).await?;
+        hex::decode(block_hex).map_err(|e| ClientError::serialization("decode block hex", e))
+    }
+}
+
+/// Esplora HTTP API, e.g. `https://blockstream.info/api`
+pub struct Esplora {
+    url: String,
+    http: reqwest::Client,
+}
+
+impl Esplora {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    async fn get(&self, path: &str) -> Result<reqwest::Response, ClientError> {
+        let url = format!("{}{}", self.url, path);
+        self.http
+            .get(&url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| ClientError::connection(&url, e))
+    }
+
+    async fn get_text(&self, path: &str) -> Result<String, ClientError> {
+        self.get(path)
+            .await?
+            .text()
+            .await
+            .map_err(|e| ClientError::connection(&self.url, e))
+    }
+}
+
+#[async_trait]
+impl BtcSource for Esplora {
+    async fn tip_height(&self) -> Result<u32, ClientError> {
+        let height = self.get_text("/blocks/tip/height").await?;
+        height
+            .trim()
+            .parse()
+            .map_err(|e| ClientError::serialization("parse tip height", e))
+    }
+
+    async fn block_hash(&self, height: u32) -> Result<String, ClientError> {
+        Ok(self
+            .get_text(&format!("/block-height/{}", height))
+            .await?
+            .trim()
+            .to_string())
+    }
+
+    async fn raw_block(&self, block_hash: &str) -> Result<Vec<u8>, ClientError> {
+        let block = self
+            .get(&format!("/block/{}/raw", block_hash))
+            .await?
+            .bytes()
+            .await
+            .map_err(|e| ClientError::connection(&self.url, e))?;
+        Ok(block.to_vec())
+    }
+}
+
+/// Payment to a watched deposit address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deposit {
+    pub txid: String,
+    /// address is the first watched address the transaction pays
+    pub address: String,
+    /// amount is the sum of the outputs paying to any watched address, in satoshis
+    pub amount: u64,
+    /// sender_btc_pk is the compressed public key of the first input, if it spends
+    /// a P2WPKH output
+    pub sender_btc_pk: Option<String>,
+    /// recipient is the Cosmos address carried by the OP_RETURN output, if any
+    pub recipient: Option<String>,
+}
+
+/// Returns the deposits of the block to the given addresses, at most one per
+/// transaction as the contract pegs in a transaction once. A transaction paying
+/// several watched addresses is one deposit of the sum of their outputs.
+pub fn find_deposits(block: &Block, addresses: &[(String, ScriptBuf)]) -> Vec<Deposit> {
+    let mut deposits = vec![];
+
+    for tx in &block.txdata {
+        let mut paid = addresses.iter().filter_map(|(address, script)| {
+            let amount: u64 = tx
+                .output
+                .iter()
+                .filter(|output| output.script_pubkey == *script)
+                .map(|output| output.value.to_sat())
+                .sum();
+            (amount > 0).then_some((address, amount))
+        });
+        let Some((address, amount)) = paid.next() else {
+            continue;
+        };
+
+        deposits.push(Deposit {
+            txid: tx.compute_txid().to_string(),
+            address: address.clone(),
+            amount: amount + paid.map(|(_, amount)| amount).sum::<u64>(),
+            sender_btc_pk: sender_btc_pk(tx),
+            recipient: op_return_recipient(tx),
+        });
+    }
+
+    deposits
+}
+
+/// Returns true if the contract rejected the peg ins, e.g. an already pegged or
+/// invalid deposit
+fn is_rejected(e: &ClientError) -> bool {
+    e.tx_error_kind() == Some(TxErrorKind::ContractFailed)
+}
+
+fn sender_btc_pk(tx: &Transaction) -> Option<String> {
+    let witness = &tx.input.first()?.witness;
+    if witness.len() != 2 {
+        return None;
+    }
+    witness
+        .last()
+        .filter(|pk| pk.len() == COMPRESSED_PK_LEN)
+        .map(hex::encode)
+}
+
+fn op_return_recipient(tx: &Transaction) -> Option<String> {
+    tx.output
+        .iter()
+        .filter(|output| output.script_pubkey.is_op_return())
+        .find_map(|output| match output.script_pubkey.instructions().nth(1)? {
+            Ok(Instruction::PushBytes(data)) => String::from_utf8(data.as_bytes().to_vec()).ok(),
+            _ => None,
+        })
+}
+
+/// Polling intervals and limits of the relayer
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelayerConfig {
+    /// btc_confirmation_depth is the number of confirmations a deposit needs, both
+    /// on the BTC node and on the Babylon BTC light client, before it is relayed
+    pub btc_confirmation_depth: u32,
+    /// poll_interval is the interval between BTC tip polls
+    pub poll_interval: Duration,
+    /// error_backoff is the delay before retrying after a failed round
+    pub error_backoff: BackoffPolicy,
+    /// max_batch_size is the maximum number of peg ins submitted in one transaction
+    pub max_batch_size: usize,
+    /// confirmation_timeout is how long to wait for a peg in transaction to be included
+    pub confirmation_timeout: Duration,
+    /// out_of_gas_attempts is the number of submissions of a batch running out of
+    /// gas, each with a higher gas limit, see `CosmWasmClient::submit_with_gas_retry`
+    pub out_of_gas_attempts: u32,
+}
+
+impl Default for RelayerConfig {
+    fn default() -> Self {
+        Self {
+            btc_confirmation_depth: DEFAULT_BTC_CONFIRMATION_DEPTH,
+            poll_interval: Duration::from_secs(60),
+            error_backoff: BackoffPolicy::default(),
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            confirmation_timeout: Duration::from_secs(60),
+            out_of_gas_attempts: 3,
+        }
+    }
+}
+
+/// Peg in relayer: watches the deposit addresses on Bitcoin and submits a
+/// `peg_in` with the SPV proof of every confirmed deposit. Deposits must carry
+/// the recipient address in an OP_RETURN output and spend a P2WPKH output first,
+/// whose key becomes the sender BTC PK.
+pub struct Relayer {
+    client: CosmWasmClient,
+    source: Box<dyn BtcSource>,
+    network: BtcNetwork,
+    /// deposit_addresses are the watched addresses and their output scripts
+    deposit_addresses: Vec<(String, ScriptBuf)>,
+    config: RelayerConfig,
+    /// last_scanned_height is the last BTC height whose deposits have been relayed,
+    /// `None` to start at the confirmed tip
+    last_scanned_height: Option<u32>,
+    checkpoint_store: Option<Box<dyn CheckpointStore>>,
+    /// wallet_pool signs the peg in transactions instead of the client wallet
+    wallet_pool: Option<WalletPool>,
+}
+
+impl Relayer {
+    pub fn new(
+        client: CosmWasmClient,
+        source: impl BtcSource + 'static,
+        network: BtcNetwork,
+    ) -> Self {
+        Self {
+            client,
+            source: Box::new(source),
+            network,
+            deposit_addresses: vec![],
+            config: RelayerConfig::default(),
+            last_scanned_height: None,
+            checkpoint_store: None,
+            wallet_pool: None,
+        }
+    }
+
+    /// Sets the watched deposit addresses, which must belong to the relayer network
+    pub fn with_deposit_addresses<I, S>(mut self, addresses: I) -> Result<Self, ClientError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.deposit_addresses = addresses
+            .into_iter()
+            .map(|address| {
+                let address = address.as_ref();
+                let script = Address::from_str(address)
+                    .and_then(|a| a.require_network(self.network.into()))
+                    .map_err(|e| ClientError::InvalidBtcAddress {
+                        address: address.to_string(),
+                        reason: e.to_string(),
+                    })?
+                    .script_pubkey();
+                Ok((address.to_string(), script))
+            })
+            .collect::<Result<_, ClientError>>()?;
+        Ok(self)
+    }
+
+    /// Sets the last BTC height already relayed, scanning resumes at the next one
+    pub fn with_start_height(mut self, last_scanned_height: u32) -> Self {
+        self.last_scanned_height = Some(last_scanned_height);
+        self
+    }
+
+    /// Persists the last relayed BTC height, which takes precedence over the start
+    /// height once saved
+    pub fn with_checkpoint_store(mut self, store: impl CheckpointStore + 'static) -> Self {
+        self.checkpoint_store = Some(Box::new(store));
+        self
+    }
+
+    /// Signs the peg in transactions with the wallets of the pool in turn, keeping
+    /// a batch in flight per wallet instead of one at a time
+    pub fn with_wallet_pool(mut self, wallet_pool: WalletPool) -> Self {
+        self.wallet_pool = Some(wallet_pool);
+        self
+    }
+
+    pub fn with_config(mut self, config: RelayerConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Returns the last BTC height whose deposits have been relayed
+    pub fn last_scanned_height(&self) -> Option<u32> {
+        self.last_scanned_height
+    }
+
+    /// Relays deposits until an unrecoverable error, retrying failed rounds
+    pub async fn start(&mut self) -> Result<(), ClientError> {
+        if self.deposit_addresses.is_empty() {
+            return Err(ClientError::invalid(
+                "relayer",
+                "no deposit addresses to watch",
+            ));
+        }
+        if let Some(store) = &self.checkpoint_store {
+            let checkpoint = store
+                .load()
+                .await
+                .map_err(|e| ClientError::store("load checkpoint", e))?;
+            if let Some(height) = checkpoint {
+                tracing::info!("Resuming from stored checkpoint at BTC height {}", height);
+                self.last_scanned_height = Some(height as u32);
+            }
+        }
+
+        let mut consecutive_errors = 0;
+        loop {
+            let delay = match self.relay_once().await {
+                Ok(_) => {
+                    consecutive_errors = 0;
+                    self.config.poll_interval
+                }
+                Err(e) => {
+                    consecutive_errors += 1;
+                    tracing::warn!("Relaying peg ins failed: {}", e);
+                    self.config.error_backoff.delay(consecutive_errors)
+                }
+            };
+            sleep(delay).await;
+        }
+    }
+
+    /// Relays the deposits of all BTC blocks confirmed since the last scanned
+    /// height and returns the number of submitted peg ins
+    pub async fn relay_once(&mut self) -> Result<usize, ClientError> {
+        let depth = self.config.btc_confirmation_depth.max(1);
+        let confirmed_height = (self.source.tip_height().await? + 1).saturating_sub(depth);
+        let mut last_scanned_height = match self.last_scanned_height {
+            Some(height) => height,
+            None => {
+                tracing::info!(
+                    "No start height, relaying deposits after BTC height {}",
+                    confirmed_height
+                );
+                self.last_scanned_height = Some(confirmed_height);
+                return Ok(0);
+            }
+        };
+
+        let mut relayed = 0;
+        while last_scanned_height < confirmed_height {
+            let height = last_scanned_height + 1;
+            let block_hash = self.source.block_hash(height).await?;
+            let raw_block = self.source.raw_block(&block_hash).await?;
+            let requests = self.peg_in_requests(&raw_block)?;
+
+            if !requests.is_empty() {
+                // the contract checks the depth on the Babylon BTC light client,
+                // which may lag behind the BTC node
+                match self.client.query_header_depth(&block_hash).await {
+                    Ok(header_depth) if header_depth + 1 >= depth => {}
+                    result => {
+                        tracing::debug!(
+                            "BTC block {} not confirmed on the light client yet: {:?}",
+                            block_hash,
+                            result
+                        );
+                        break;
+                    }
+                }
+                relayed += self.submit(requests).await?;
+            }
+
+            last_scanned_height = height;
+            self.last_scanned_height = Some(height);
+            if let Some(store) = &self.checkpoint_store {
+                store
+                    .save(height as u64)
+                    .await
+                    .map_err(|e| ClientError::store("save checkpoint", e))?;
+            }
+        }
+
+        Ok(relayed)
+    }
+
+    /// Builds the peg in requests of the valid deposits in the block
+    fn peg_in_requests(
+        &self,
+        raw_block: &[u8],
+    ) -> Result<Vec<(String, PegInRequest)>, ClientError> {
+        let block: Block = deserialize(raw_block)
+            .map_err(|e| ClientError::serialization("deserialize BTC block", e))?;
+        let prefix = self.client.config.account_prefix.as_str();
+
+        let mut requests = vec![];
+        for deposit in find_deposits(&block, &self.deposit_addresses) {
+            let recipient = deposit.recipient.as_deref().filter(|recipient| {
+                CosmWasmClient::validate_bech32_address(recipient, Some(prefix)).is_ok()
+            });
+            let (Some(sender_btc_pk), Some(recipient)) = (&deposit.sender_btc_pk, recipient) else {
+                tracing::warn!(
+                    "Skipping deposit {} to {} without sender key or valid recipient",
+                    deposit.txid,
+                    logging::text(&deposit.address)
+                );
+                continue;
+            };
+
+            let request = build_peg_in_proof(raw_block, &deposit.txid)?.into_request(
+                sender_btc_pk,
+                recipient,
+                deposit.amount as u128,
+            );
+            requests.push((deposit.txid, request));
+        }
+
+        Ok(requests)
+    }
+
+    /// Submits the peg ins not processed yet, e.g. by a previous run that stopped
+    /// before saving its checkpoint, and waits for their inclusion
+    async fn submit(&self, requests: Vec<(String, PegInRequest)>) -> Result<usize, ClientError> {
+        let mut pending = vec![];
+        for (txid, request) in requests {
+            if self.client.is_peg_in_processed(&txid).await? {
+                tracing::info!("Peg in {} already processed", txid);
+                continue;
+            }
+            pending.push(request);
+        }
+
+        // every batch runs to completion before an error is returned, so no
+        // broadcast tx is left unawaited
+        let in_flight = self.wallet_pool.as_ref().map_or(1, WalletPool::len);
+        let results: Vec<Result<usize, ClientError>> =
+            futures::stream::iter(pending.chunks(self.config.max_batch_size.max(1)))
+                .map(|batch| self.relay_batch(batch))
+                .buffer_unordered(in_flight)
+                .collect()
+                .await;
+        let mut relayed = 0;
+        let mut first_error = None;
+        for result in results {
+            match (result, &first_error) {
+                (Ok(count), _) => relayed += count,
+                (Err(e), None) => first_error = Some(e),
+                (Err(e), Some(_)) => tracing::error!("Failed to relay a peg in batch: {}", e),
+            }
+        }
+
+        first_error.map_or(Ok(relayed), Err)
+    }
+
+    /// Submits a batch of peg ins and returns the number relayed. A batch rejected by
+    /// the contract is submitted again one peg in at a time, and the peg ins the
+    /// contract rejects on their own are skipped, as retrying would never succeed.
+    async fn relay_batch(&self, batch: &[PegInRequest]) -> Result<usize, ClientError> {
+        match self.submit_batch(batch).await {
+            Ok(()) => return Ok(batch.len()),
+            Err(e) if !is_rejected(&e) => return Err(e),
+            Err(e) if batch.len() > 1 => tracing::warn!(
+                "Contract rejected a batch of {} peg ins, submitting them one by one: {}",
+                batch.len(),
+                e
+            ),
+            Err(e) => {
+                tracing::error!("Contract rejected a peg in, skipping it: {}", e);
+                return Ok(0);
+            }
+        }
+
+        let mut relayed = 0;
+        for request in batch {
+            match self.submit_batch(std::slice::from_ref(request)).await {
+                Ok(()) => relayed += 1,
+                Err(e) if is_rejected(&e) => {
+                    tracing::error!("Contract rejected a peg in, skipping it: {}", e)
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(relayed)
+    }
+
+    /// Submits a batch of peg ins with the next wallet and waits for its inclusion
+    async fn submit_batch(&self, batch: &[PegInRequest]) -> Result<(), ClientError> {
+        let client = self
+            .wallet_pool
+            .as_ref()
+            .map_or(&self.client, WalletPool::next);
+        let msgs = client.peg_in_batch_msgs(batch).await?;
+        // a failed broadcast is awaited rather than submitting the peg ins twice, if
+        // it never lands the block is retried once the wait times out
+        let tx_response = client
+            .submit_with_gas_retry(
+                msgs,
+                &GasRetryConfig {
+                    max_attempts: self.config.out_of_gas_attempts,
+                    confirmation_timeout: self.config.confirmation_timeout,
+                    poll_interval: TX_POLL_INTERVAL,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        tracing::info!(
+            "Relayed {} peg ins in tx {} signed by {}",
+            batch.len(),
+            tx_response.txhash,
+            client.sender()
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::test_client;
+    use crate::mock::{MockBroadcast, MockGrpcServer};
+    use bitcoin::absolute::LockTime;
+    use bitcoin::blockdata::constants::genesis_block;
+    use bitcoin::script::PushBytesBuf;
+    use bitcoin::transaction::Version;
+    use bitcoin::{Amount, Network, OutPoint, Sequence, TxIn, TxOut, Witness};
+
+    #[test]
+    fn test_find_deposits() {
+        let address = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080";
+        let script = Address::from_str(address)
+            .unwrap()
+            .require_network(Network::Regtest)
+            .unwrap()
+            .script_pubkey();
+        let sender_pk = [2u8; COMPRESSED_PK_LEN];
+        let recipient = "bbn1ad2u30qd2vx6es4pmn28y23qtz6hea7708574y";
+
+        let deposit_tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::from_slice(&[vec![0u8; 71], sender_pk.to_vec()]),
+            }],
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(50_000),
+                    script_pubkey: script.clone(),
+                },
+                TxOut {
+                    value: Amount::ZERO,
+                    script_pubkey: ScriptBuf::new_op_return(
+                        PushBytesBuf::try_from(recipient.as_bytes().to_vec()).unwrap(),
+                    ),
+                },
+            ],
+        };
+        let mut block = genesis_block(Network::Regtest);
+        block.txdata.push(deposit_tx.clone());
+
+        let deposits = find_deposits(&block, &[(address.to_string(), script)]);
+        assert_eq!(
+            deposits,
+            [Deposit {
+                txid: deposit_tx.compute_txid().to_string(),
+                address: address.to_string(),
+                amount: 50_000,
+                sender_btc_pk: Some(hex::encode(sender_pk)),
+                recipient: Some(recipient.to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_find_deposits_to_two_addresses() {
+        let addresses: Vec<_> = [
+            "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080",
+            "bcrt1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3qzf4jry",
+        ]
+        .into_iter()
+        .map(|address| {
+            let script = Address::from_str(address)
+                .unwrap()
+                .require_network(Network::Regtest)
+                .unwrap()
+                .script_pubkey();
+            (address.to_string(), script)
+        })
+        .collect();
+        let deposit_tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![],
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(30_000),
+                    script_pubkey: addresses[1].1.clone(),
+                },
+                TxOut {
+                    value: Amount::from_sat(20_000),
+                    script_pubkey: addresses[0].1.clone(),
+                },
+            ],
+        };
+        let mut block = genesis_block(Network::Regtest);
+        block.txdata.push(deposit_tx.clone());
+
+        // the contract pegs in a transaction once, the outputs make one deposit
+        let deposits = find_deposits(&block, &addresses);
+        assert_eq!(deposits.len(), 1);
+        assert_eq!(deposits[0].txid, deposit_tx.compute_txid().to_string());
+        assert_eq!(deposits[0].address, addresses[0].0);
+        assert_eq!(deposits[0].amount, 50_000);
+    }
+
+    #[tokio::test]
+    async fn test_skip_rejected_peg_ins() {
+        let server = MockGrpcServer::start().await.unwrap();
+        let client = test_client(&server.url());
+        server.set_account(&client.sender(), 7, 3);
+        let relayer = Relayer::new(
+            client,
+            Esplora::new("http://127.0.0.1:1"),
+            BtcNetwork::Regtest,
+        );
+        let request = |pegin_tx: &str| PegInRequest {
+            sender_btc_pk: "02bb".to_string(),
+            recipient: "bbn1receiver".to_string(),
+            amount: 1000,
+            block_hash: "00ff".to_string(),
+            pegin_tx: pegin_tx.to_string(),
+            pegin_tx_idx: 0,
+            pegin_tx_merkle_proof: vec![],
+        };
+
+        // the batch and then its first peg in are rejected, the second one is relayed
+        for _ in 0..2 {
+            server.push_broadcast(MockBroadcast::reject(
+                TxErrorKind::ContractFailed,
+                "failed to execute message; message index: 0: already pegged: execute wasm contract failed",
+            ));
+        }
+        let relayed = relayer
+            .relay_batch(&[request("0100"), request("0200")])
+            .await
+            .unwrap();
+        assert_eq!(relayed, 1);
+
+        // other failures are retried with the block
+        server.push_broadcast(MockBroadcast::reject(
+            TxErrorKind::InsufficientFee,
+            "insufficient fees",
+        ));
+        assert!(relayer.relay_batch(&[request("0300")]).await.is_err());
+    }
+}
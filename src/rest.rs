@@ -0,0 +1,395 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use base64::Engine;
+use cosmos_sdk_proto::cosmos::auth::v1beta1::BaseAccount;
+use cosmos_sdk_proto::cosmos::base::abci::v1beta1::TxResponse;
+use cosmos_sdk_proto::cosmos::tx::v1beta1::BroadcastTxResponse;
+use cosmos_sdk_proto::tendermint::abci::{Event, EventAttribute};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use serde_json::json;
+
+use crate::client::BLOCK_HEIGHT_HEADER;
+use crate::error::ClientError;
+
+/// Cosmos REST (LCD) endpoint, e.g. `http://localhost:1317`, used instead of gRPC
+/// for account lookups, broadcasts and smart queries, see `CosmWasmClient::with_rest`
+#[derive(Clone)]
+pub(crate) struct RestClient {
+    url: String,
+    http: reqwest::Client,
+}
+
+/// Error body of the gRPC gateway, carrying the gRPC status code
+#[derive(Deserialize)]
+struct GatewayError {
+    code: i32,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct AccountResponse {
+    account: RestAccount,
+}
+
+#[derive(Deserialize)]
+struct RestAccount {
+    #[serde(rename = "@type")]
+    type_url: String,
+    #[serde(default)]
+    address: String,
+    #[serde(default, deserialize_with = "from_str")]
+    account_number: u64,
+    #[serde(default, deserialize_with = "from_str")]
+    sequence: u64,
+}
+
+#[derive(Deserialize)]
+struct RestBroadcastResponse {
+    tx_response: Option<RestTxResponse>,
+}
+
+/// `TxResponse` in proto3 JSON, where 64 bit integers are strings
+#[derive(Deserialize)]
+struct RestTxResponse {
+    #[serde(deserialize_with = "from_str")]
+    height: i64,
+    txhash: String,
+    #[serde(default)]
+    codespace: String,
+    #[serde(default)]
+    code: u32,
+    #[serde(default)]
+    data: String,
+    #[serde(default)]
+    raw_log: String,
+    #[serde(deserialize_with = "from_str")]
+    gas_wanted: i64,
+    #[serde(deserialize_with = "from_str")]
+    gas_used: i64,
+    #[serde(default)]
+    events: Vec<RestEvent>,
+}
+
+#[derive(Deserialize)]
+struct RestEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    attributes: Vec<RestEventAttribute>,
+}
+
+#[derive(Deserialize)]
+struct RestEventAttribute {
+    key: String,
+    #[serde(default)]
+    value: String,
+    #[serde(default)]
+    index: bool,
+}
+
+#[derive(Deserialize)]
+struct SmartQueryResponse {
+    data: serde_json::Value,
+}
+
+fn from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(serde::de::Error::custom)
+}
+
+impl From<RestTxResponse> for TxResponse {
+    fn from(tx_response: RestTxResponse) -> Self {
+        Self {
+            height: tx_response.height,
+            txhash: tx_response.txhash,
+            codespace: tx_response.codespace,
+            code: tx_response.code,
+            data: tx_response.data,
+            raw_log: tx_response.raw_log,
+            gas_wanted: tx_response.gas_wanted,
+            gas_used: tx_response.gas_used,
+            events: tx_response
+                .events
+                .into_iter()
+                .map(|event| Event {
+                    r#type: event.kind,
+                    attributes: event
+                        .attributes
+                        .into_iter()
+                        .map(|attr| EventAttribute {
+                            key: attr.key,
+                            value: attr.value,
+                            index: attr.index,
+                        })
+                        .collect(),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl RestClient {
+    pub(crate) fn new(url: &str) -> Result<Self, ClientError> {
+        reqwest::Url::parse(url).map_err(|e| ClientError::invalid("REST URL", e))?;
+
+        Ok(Self {
+            url: url.trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+        })
+    }
+
+    /// Sends the request and decodes the JSON response, turning gateway errors into
+    /// the gRPC status the node reported
+    async fn send<T: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+        action: &str,
+    ) -> Result<T, ClientError> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ClientError::connection(&self.url, e))?;
+        let status = response.status();
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| ClientError::connection(&self.url, e))?;
+
+        if !status.is_success() {
+            let status = match serde_json::from_slice::<GatewayError>(&body) {
+                Ok(error) => tonic::Status::new(error.code.into(), error.message),
+                Err(_) => tonic::Status::unknown(format!(
+                    "{}: {}",
+                    status,
+                    String::from_utf8_lossy(&body)
+                )),
+            };
+            return Err(ClientError::grpc(action, status));
+        }
+
+        serde_json::from_slice(&body)
+            .map_err(|e| ClientError::serialization(format!("decode {} response", action), e))
+    }
+
+    pub(crate) async fn account(&self, address: &str) -> Result<BaseAccount, ClientError> {
+        let resp: AccountResponse = self
+            .send(
+                self.http.get(format!(
+                    "{}/cosmos/auth/v1beta1/accounts/{}",
+                    self.url, address
+                )),
+                "query account information",
+            )
+            .await?;
+
+        let account = resp.account;
+        if account.type_url != "/cosmos.auth.v1beta1.BaseAccount" {
+            return Err(ClientError::serialization(
+                "convert account info to BaseAccount",
+                format!("unsupported account type {}", account.type_url),
+            ));
+        }
+        Ok(BaseAccount {
+            address: account.address,
+            pub_key: None,
+            account_number: account.account_number,
+            sequence: account.sequence,
+        })
+    }
+
+    pub(crate) async fn broadcast_tx(
+        &self,
+        tx_bytes: &[u8],
+    ) -> Result<BroadcastTxResponse, ClientError> {
+        let resp: RestBroadcastResponse = self
+            .send(
+                self.http
+                    .post(format!("{}/cosmos/tx/v1beta1/txs", self.url))
+                    .json(&json!({
+                        "tx_bytes": base64::engine::general_purpose::STANDARD.encode(tx_bytes),
+                        "mode": "BROADCAST_MODE_SYNC",
+                    })),
+                "broadcast transaction",
+            )
+            .await?;
+
+        Ok(BroadcastTxResponse {
+            tx_response: resp.tx_response.map(TxResponse::from),
+        })
+    }
+
+    /// Runs a smart query, at `height` if set, and returns the JSON response of the contract
+    pub(crate) async fn smart_query(
+        &self,
+        contract: &str,
+        query_data: &[u8],
+        height: Option<u64>,
+    ) -> Result<Vec<u8>, ClientError> {
+        let mut request = self.http.get(format!(
+            "{}/cosmwasm/wasm/v1/contract/{}/smart/{}",
+            self.url,
+            contract,
+            base64::engine::general_purpose::URL_SAFE.encode(query_data)
+        ));
+        if let Some(height) = height {
+            request = request.header(BLOCK_HEIGHT_HEADER, height);
+        }
+        let resp: SmartQueryResponse = self
+            .send(request, "query contract state")
+            .await
+            .map_err(|e| e.classify_pruned(height))?;
+
+        serde_json::to_vec(&resp.data)
+            .map_err(|e| ClientError::serialization("serialize query response", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Answers the requests with the given statuses and JSON bodies in turn,
+    /// returning the request lines
+    async fn serve(
+        responses: Vec<(u16, serde_json::Value)>,
+    ) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let mut requests = vec![];
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = vec![];
+                let mut buf = [0; 4096];
+                loop {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|line| {
+                                line.to_lowercase()
+                                    .strip_prefix("content-length: ")
+                                    .map(str::to_string)
+                            })
+                            .map_or(0, |length| length.parse().unwrap());
+                        if body.len() >= length {
+                            break;
+                        }
+                    }
+                }
+
+                let body = body.to_string();
+                stream
+                    .write_all(
+                        format!(
+                            "HTTP/1.1 {} Status\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                            status,
+                            body.len(),
+                            body
+                        )
+                        .as_bytes(),
+                    )
+                    .await
+                    .unwrap();
+                let request = String::from_utf8(request).unwrap();
+                requests.push(request.lines().next().unwrap().to_string());
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn test_rest_client() {
+        let address = "bbn1hsk6jryyqjfhp5dhc55tc9jtckygx0epqw6fyc";
+        let (url, server) = serve(vec![
+            (
+                200,
+                json!({ "account": {
+                    "@type": "/cosmos.auth.v1beta1.BaseAccount",
+                    "address": address,
+                    "pub_key": null,
+                    "account_number": "12",
+                    "sequence": "7",
+                }}),
+            ),
+            (
+                200,
+                json!({ "tx_response": {
+                    "height": "0",
+                    "txhash": "ABCD",
+                    "codespace": "sdk",
+                    "code": 5,
+                    "data": "",
+                    "raw_log": "insufficient funds",
+                    "gas_wanted": "200000",
+                    "gas_used": "0",
+                    "events": [],
+                }}),
+            ),
+            (200, json!({ "data": { "count": 3 } })),
+            (
+                500,
+                json!({ "code": 2, "message": "query wasm contract failed", "details": [] }),
+            ),
+        ])
+        .await;
+        let client = RestClient::new(&format!("{}/", url)).unwrap();
+
+        let account = client.account(address).await.unwrap();
+        assert_eq!(account.account_number, 12);
+        assert_eq!(account.sequence, 7);
+
+        let tx_response = client
+            .broadcast_tx(b"tx")
+            .await
+            .unwrap()
+            .tx_response
+            .unwrap();
+        assert_eq!(tx_response.code, 5);
+        assert_eq!(tx_response.gas_wanted, 200_000);
+
+        let data = client
+            .smart_query("bbn1contract", b"{}", None)
+            .await
+            .unwrap();
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&data).unwrap(),
+            json!({ "count": 3 })
+        );
+
+        let error = client
+            .smart_query("bbn1contract", b"{}", Some(10))
+            .await
+            .unwrap_err();
+        assert_eq!(error.grpc_status().unwrap().code(), tonic::Code::Unknown);
+        assert_eq!(
+            error.grpc_status().unwrap().message(),
+            "query wasm contract failed"
+        );
+
+        let requests = server.await.unwrap();
+        assert_eq!(
+            requests[0],
+            format!("GET /cosmos/auth/v1beta1/accounts/{} HTTP/1.1", address)
+        );
+        assert_eq!(requests[1], "POST /cosmos/tx/v1beta1/txs HTTP/1.1");
+        assert_eq!(
+            requests[2],
+            "GET /cosmwasm/wasm/v1/contract/bbn1contract/smart/e30= HTTP/1.1"
+        );
+    }
+}
@@ -0,0 +1,576 @@
+use serde::Deserialize;
+use tendermint::block::Height;
+use tendermint::Hash;
+use tendermint_rpc::endpoint::{block, status, tx, validators};
+use tendermint_rpc::{Client, HttpClient, Paging, Response};
+use tokio::time::{Duration, Instant};
+
+use crate::client::CosmWasmClient;
+use crate::error::{ClientError, TxErrorKind};
+use crate::transactions::{SignedTx, TxHash};
+
+/// Most mempool transactions returned by the `unconfirmed_txs` endpoint
+const UNCONFIRMED_TXS_LIMIT: u32 = 100;
+
+/// Tendermint RPC client with its URL, cloned clients share their connections
+#[derive(Clone)]
+pub(crate) struct RpcClient {
+    pub(crate) url: String,
+    pub(crate) client: HttpClient,
+    /// http queries the endpoints `HttpClient` doesn't support, e.g. `unconfirmed_txs`
+    http: reqwest::Client,
+}
+
+impl RpcClient {
+    pub(crate) fn new(url: &str) -> Result<Self, ClientError> {
+        let client = HttpClient::new(url)
+            .map_err(|e| ClientError::invalid(format!("RPC URL {}", url), e))?;
+        Ok(Self {
+            url: url.trim_end_matches('/').to_string(),
+            client,
+            http: reqwest::Client::new(),
+        })
+    }
+}
+
+/// Transactions in the mempool of the RPC node, not yet included in a block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnconfirmedTxs {
+    /// total is the number of transactions in the mempool, `txs` holds the first
+    /// 100 of them at most
+    pub total: u64,
+    pub total_bytes: u64,
+    pub txs: Vec<SignedTx>,
+}
+
+/// Whether a transaction is waiting in the mempool, see `CosmWasmClient::tx_in_mempool`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MempoolStatus {
+    /// The transaction is in the mempool
+    Pending,
+    /// The transaction isn't in the mempool, if it isn't found by `tx` either it
+    /// was dropped and can be resubmitted
+    Missing,
+    /// The mempool holds more transactions than the endpoint returns and the
+    /// transaction isn't among the returned ones
+    Unknown,
+}
+
+#[derive(Deserialize)]
+struct UnconfirmedTxsResponse {
+    #[serde(with = "tendermint::serializers::from_str")]
+    total: u64,
+    #[serde(with = "tendermint::serializers::from_str")]
+    total_bytes: u64,
+    #[serde(with = "tendermint::serializers::txs")]
+    txs: Vec<Vec<u8>>,
+}
+
+impl Response for UnconfirmedTxsResponse {}
+
+/// Tendermint RPC queries of the endpoint set by `CosmWasmClient::with_rpc`
+impl CosmWasmClient {
+    fn rpc_client(&self) -> Result<&HttpClient, ClientError> {
+        self.rpc
+            .as_ref()
+            .map(|rpc| &rpc.client)
+            .ok_or_else(|| ClientError::not_found("Tendermint RPC endpoint"))
+    }
+
+    /// Returns the node info and latest block of the RPC node
+    pub async fn status(&self) -> Result<status::Response, ClientError> {
+        self.rpc_client()?
+            .status()
+            .await
+            .map_err(|e| ClientError::rpc("fetch node status", e))
+    }
+
+    pub async fn block(&self, height: u64) -> Result<block::Response, ClientError> {
+        let height =
+            Height::try_from(height).map_err(|e| ClientError::invalid("block height", e))?;
+        self.rpc_client()?
+            .block(height)
+            .await
+            .map_err(|e| ClientError::rpc(format!("fetch block {}", height), e))
+    }
+
+    /// Returns the whole validator set at the height
+    pub async fn validators(&self, height: u64) -> Result<validators::Response, ClientError> {
+        let height =
+            Height::try_from(height).map_err(|e| ClientError::invalid("block height", e))?;
+        self.rpc_client()?
+            .validators(height, Paging::All)
+            .await
+            .map_err(|e| ClientError::rpc(format!("fetch validators at {}", height), e))
+    }
+
+    /// Returns the tx with its result and events by hash, in either case
+    pub async fn tx(&self, hash: &str) -> Result<tx::Response, ClientError> {
+        let tx_hash: TxHash = hash.parse()?;
+        let hash = Hash::from_hex_upper(tendermint::hash::Algorithm::Sha256, tx_hash.as_str())
+            .map_err(|e| ClientError::invalid("tx hash", e))?;
+        self.rpc_client()?
+            .tx(hash, false)
+            .await
+            .map_err(|e| ClientError::rpc(format!("fetch tx {}", tx_hash.as_str()), e))
+    }
+
+    /// Returns the transactions waiting in the mempool of the RPC node
+    pub async fn unconfirmed_txs(&self) -> Result<UnconfirmedTxs, ClientError> {
+        let rpc = self
+            .rpc
+            .as_ref()
+            .ok_or_else(|| ClientError::not_found("Tendermint RPC endpoint"))?;
+        let body = rpc
+            .http
+            .get(format!("{}/unconfirmed_txs", rpc.url))
+            .query(&[("limit", UNCONFIRMED_TXS_LIMIT)])
+            .send()
+            .await
+            .and_then(|resp| resp.error_for_status())
+            .map_err(|e| ClientError::connection(&rpc.url, e))?
+            .bytes()
+            .await
+            .map_err(|e| ClientError::connection(&rpc.url, e))?;
+        let resp = UnconfirmedTxsResponse::from_string(body)
+            .map_err(|e| ClientError::rpc("fetch unconfirmed txs", e))?;
+
+        Ok(UnconfirmedTxs {
+            total: resp.total,
+            total_bytes: resp.total_bytes,
+            txs: resp.txs.into_iter().map(SignedTx::from_bytes).collect(),
+        })
+    }
+
+    /// Returns whether the tx is waiting in the mempool of the RPC node. Only the
+    /// first 100 mempool transactions can be fetched, so a tx missing from a fuller
+    /// mempool is reported as `MempoolStatus::Unknown`.
+    pub async fn tx_in_mempool(&self, hash: &str) -> Result<MempoolStatus, ClientError> {
+        let tx_hash: TxHash = hash.parse()?;
+        let unconfirmed = self.unconfirmed_txs().await?;
+
+        if unconfirmed
+            .txs
+            .iter()
+            .any(|signed_tx| signed_tx.tx_hash == tx_hash)
+        {
+            Ok(MempoolStatus::Pending)
+        } else if unconfirmed.total > unconfirmed.txs.len() as u64 {
+            tracing::debug!(
+                "Checked {} of {} mempool transactions for {}",
+                unconfirmed.txs.len(),
+                unconfirmed.total,
+                tx_hash
+            );
+            Ok(MempoolStatus::Unknown)
+        } else {
+            Ok(MempoolStatus::Missing)
+        }
+    }
+
+    /// Runs the gRPC query method at `path`, e.g. `/cosmos.auth.v1beta1.Query/Account`,
+    /// through the ABCI query of the RPC endpoint, at `height` or the latest height
+    pub(crate) async fn abci_query<Req, Resp>(
+        &self,
+        path: &str,
+        request: &Req,
+        height: Option<u64>,
+        action: &str,
+    ) -> Result<Resp, ClientError>
+    where
+        Req: prost::Message,
+        Resp: prost::Message + Default,
+    {
+        let query_height = height
+            .map(Height::try_from)
+            .transpose()
+            .map_err(|e| ClientError::invalid("block height", e))?;
+        let resp = self
+            .rpc_client()?
+            .abci_query(
+                Some(path.to_string()),
+                request.encode_to_vec(),
+                query_height,
+                false,
+            )
+            .await
+            .map_err(|e| ClientError::rpc(action, e).classify_pruned(height))?;
+        if resp.code.is_err() {
+            return Err(ClientError::grpc(
+                action,
+                abci_status(&resp.codespace, resp.code.value(), resp.log),
+            )
+            .classify_pruned(height));
+        }
+
+        Resp::decode(resp.value.as_slice())
+            .map_err(|e| ClientError::serialization(format!("decode {} response", path), e))
+    }
+}
+
+/// Returns the status the gRPC endpoint answers a failed query with, so errors
+/// don't depend on the transport
+fn abci_status(codespace: &str, code: u32, log: String) -> tonic::Status {
+    let code = match (codespace, code) {
+        (TxErrorKind::SDK_CODESPACE, 22) | (TxErrorKind::WASM_CODESPACE, 8) => {
+            tonic::Code::NotFound
+        }
+        (TxErrorKind::SDK_CODESPACE, 18) => tonic::Code::InvalidArgument,
+        _ => tonic::Code::Unknown,
+    };
+    tonic::Status::new(code, log)
+}
+
+/// Tendermint RPC endpoint with its health
+struct RpcEndpoint {
+    url: String,
+    client: HttpClient,
+    /// consecutive_failures is reset on the first successful request
+    consecutive_failures: u32,
+    total_failures: u64,
+}
+
+/// Ordered list of Tendermint RPC endpoints, the first one being the primary.
+/// Requests go to the active endpoint, which rotates to the next one after
+/// too many consecutive failures.
+pub(crate) struct RpcEndpoints {
+    endpoints: Vec<RpcEndpoint>,
+    active: usize,
+    /// failed_over_at is when the primary was last abandoned
+    failed_over_at: Option<Instant>,
+}
+
+impl RpcEndpoints {
+    pub(crate) fn new(primary_url: &str) -> Result<Self, ClientError> {
+        Ok(Self::with_primary(RpcClient::new(primary_url)?))
+    }
+
+    /// Uses an existing client as the primary endpoint, e.g. the one of a `CosmWasmClient`
+    pub(crate) fn with_primary(primary: RpcClient) -> Self {
+        let mut endpoints = Self {
+            endpoints: Vec::new(),
+            active: 0,
+            failed_over_at: None,
+        };
+        endpoints.push(primary);
+        endpoints
+    }
+
+    pub(crate) fn add(&mut self, url: &str) -> Result<(), ClientError> {
+        self.push(RpcClient::new(url)?);
+        Ok(())
+    }
+
+    fn push(&mut self, rpc_client: RpcClient) {
+        self.endpoints.push(RpcEndpoint {
+            url: rpc_client.url,
+            client: rpc_client.client,
+            consecutive_failures: 0,
+            total_failures: 0,
+        });
+    }
+
+    /// Returns the client of the active endpoint
+    pub(crate) fn client(&self) -> &HttpClient {
+        &self.endpoints[self.active].client
+    }
+
+    pub(crate) fn active_url(&self) -> &str {
+        &self.endpoints[self.active].url
+    }
+
+    pub(crate) fn record_success(&mut self) {
+        self.endpoints[self.active].consecutive_failures = 0;
+    }
+
+    /// Records a failed request and rotates to the next endpoint once the active
+    /// one failed `failover_threshold` times in a row
+    pub(crate) fn record_failure(&mut self, failover_threshold: u32) {
+        let endpoint_count = self.endpoints.len();
+        let endpoint = &mut self.endpoints[self.active];
+        endpoint.consecutive_failures += 1;
+        endpoint.total_failures += 1;
+        if endpoint_count < 2 || endpoint.consecutive_failures < failover_threshold {
+            return;
+        }
+
+        let failed = self.active;
+        self.active = (self.active + 1) % endpoint_count;
+        // give the new endpoint a fresh start
+        self.endpoints[self.active].consecutive_failures = 0;
+        if failed == 0 {
+            self.failed_over_at = Some(Instant::now());
+        }
+        tracing::warn!(
+            "RPC endpoint {} failed {} times in a row ({} total), failing over to {}",
+            self.endpoints[failed].url,
+            self.endpoints[failed].consecutive_failures,
+            self.endpoints[failed].total_failures,
+            self.active_url()
+        );
+    }
+
+    /// Switches back to the primary endpoint once `retry_interval` has passed since
+    /// failing over, if it's still unhealthy it fails over again
+    pub(crate) fn retry_primary(&mut self, retry_interval: Duration) {
+        if self.active == 0 {
+            return;
+        }
+        if self
+            .failed_over_at
+            .is_some_and(|failed_over_at| failed_over_at.elapsed() >= retry_interval)
+        {
+            tracing::info!("Retrying primary RPC endpoint {}", self.endpoints[0].url);
+            self.active = 0;
+            self.endpoints[0].consecutive_failures = 0;
+            self.failed_over_at = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::readonly_client;
+
+    #[tokio::test]
+    async fn test_failover() {
+        let mut endpoints = RpcEndpoints::new("http://primary:26657").unwrap();
+        endpoints.add("http://backup:26657").unwrap();
+
+        endpoints.record_failure(2);
+        endpoints.record_success();
+        endpoints.record_failure(2);
+        assert_eq!(endpoints.active_url(), "http://primary:26657");
+
+        endpoints.record_failure(2);
+        assert_eq!(endpoints.active_url(), "http://backup:26657");
+
+        endpoints.retry_primary(Duration::from_secs(60));
+        assert_eq!(endpoints.active_url(), "http://backup:26657");
+        endpoints.retry_primary(Duration::ZERO);
+        assert_eq!(endpoints.active_url(), "http://primary:26657");
+    }
+
+    #[tokio::test]
+    async fn test_client_rpc() {
+        let client = readonly_client("http://localhost:9090");
+        assert!(matches!(
+            client.status().await,
+            Err(ClientError::NotFound { .. })
+        ));
+
+        let client = client.with_rpc("http://localhost:26657").unwrap();
+        assert!(matches!(
+            client.tx("not a hash").await,
+            Err(ClientError::Invalid { .. })
+        ));
+    }
+
+    /// Answers a single JSON-RPC request with the result, returning the raw request
+    async fn serve_rpc(result: serde_json::Value) -> (String, tokio::task::JoinHandle<String>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = vec![];
+            let mut buf = [0; 4096];
+            let body = loop {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            line.to_lowercase()
+                                .strip_prefix("content-length: ")
+                                .map(str::to_string)
+                        })
+                        .map_or(0, |length| length.parse().unwrap());
+                    if body.len() >= length {
+                        break body.to_string();
+                    }
+                }
+            };
+
+            // GET requests have no body to take the id from
+            let id = serde_json::from_str::<serde_json::Value>(&body)
+                .map_or(serde_json::json!(-1), |body| body["id"].clone());
+            let response = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "result": result,
+            })
+            .to_string();
+            stream
+                .write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        response.len(),
+                        response
+                    )
+                    .as_bytes(),
+                )
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn test_abci_query_fallback() {
+        use base64::Engine;
+        use cosmos_sdk_proto::cosmwasm::wasm::v1::QuerySmartContractStateResponse;
+        use prost::Message;
+
+        let value = QuerySmartContractStateResponse {
+            data: br#"{"count":3}"#.to_vec(),
+        }
+        .encode_to_vec();
+        let (url, server) = serve_rpc(serde_json::json!({ "response": {
+            "code": 0,
+            "log": "",
+            "index": "0",
+            "value": base64::engine::general_purpose::STANDARD.encode(value),
+            "height": "10",
+        }}))
+        .await;
+
+        // nothing listens on the gRPC port
+        let client = readonly_client("http://127.0.0.1:1")
+            .with_rpc(&url)
+            .unwrap();
+
+        let resp: serde_json::Value = client
+            .query_smart(
+                "bbn1qwlgtx52gsdu7dtp0cekka5zehdl0uj3fhp9acg325fvgs8jdzksawp5cw",
+                &serde_json::json!({ "count": {} }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp, serde_json::json!({ "count": 3 }));
+
+        let request = server.await.unwrap();
+        let request: serde_json::Value =
+            serde_json::from_str(request.split_once("\r\n\r\n").unwrap().1).unwrap();
+        assert_eq!(request["method"], "abci_query");
+        assert_eq!(
+            request["params"]["path"],
+            "/cosmwasm.wasm.v1.Query/SmartContractState"
+        );
+
+        assert_eq!(
+            abci_status(TxErrorKind::SDK_CODESPACE, 22, "not found".to_string()).code(),
+            tonic::Code::NotFound
+        );
+    }
+
+    #[tokio::test]
+    async fn test_abci_query_pruned() {
+        let (url, server) = serve_rpc(serde_json::json!({ "response": {
+            "code": 26,
+            "codespace": "sdk",
+            "log": "failed to load state at height 5; version does not exist (latest height: 100): invalid height",
+            "index": "0",
+            "height": "5",
+        }}))
+        .await;
+
+        // nothing listens on the gRPC port, the raw query falls back to ABCI
+        let client = readonly_client("http://127.0.0.1:1")
+            .with_rpc(&url)
+            .unwrap();
+        let error = client
+            .query_raw_at_height(
+                "bbn1qwlgtx52gsdu7dtp0cekka5zehdl0uj3fhp9acg325fvgs8jdzksawp5cw",
+                b"config",
+                5,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ClientError::Pruned { height: 5 }));
+
+        let request = server.await.unwrap();
+        let request: serde_json::Value =
+            serde_json::from_str(request.split_once("\r\n\r\n").unwrap().1).unwrap();
+        assert_eq!(
+            request["params"]["path"],
+            "/cosmwasm.wasm.v1.Query/RawContractState"
+        );
+        assert_eq!(request["params"]["height"], "5");
+    }
+
+    #[tokio::test]
+    async fn test_unconfirmed_txs() {
+        use base64::Engine;
+
+        let pending = SignedTx::from_bytes(b"pending tx".to_vec());
+        let (url, server) = serve_rpc(serde_json::json!({
+            "n_txs": "1",
+            "total": "1",
+            "total_bytes": "10",
+            "txs": [base64::engine::general_purpose::STANDARD.encode(&pending.tx_bytes)],
+        }))
+        .await;
+
+        let client = readonly_client("http://localhost:9090")
+            .with_rpc(&url)
+            .unwrap();
+
+        assert_eq!(
+            client
+                .tx_in_mempool(&pending.tx_hash.as_str().to_lowercase())
+                .await
+                .unwrap(),
+            MempoolStatus::Pending
+        );
+        assert!(server
+            .await
+            .unwrap()
+            .starts_with("GET /unconfirmed_txs?limit=100 HTTP/1.1"));
+
+        // the mempool holds more txs than returned
+        let (url, _server) = serve_rpc(serde_json::json!({
+            "n_txs": "1",
+            "total": "150",
+            "total_bytes": "1500",
+            "txs": [base64::engine::general_purpose::STANDARD.encode(&pending.tx_bytes)],
+        }))
+        .await;
+        let client = client.with_rpc(&url).unwrap();
+        let other = SignedTx::from_bytes(b"other tx".to_vec());
+        assert_eq!(
+            client.tx_in_mempool(other.tx_hash.as_str()).await.unwrap(),
+            MempoolStatus::Unknown
+        );
+
+        let (url, _server) = serve_rpc(serde_json::json!({
+            "n_txs": "0",
+            "total": "0",
+            "total_bytes": "0",
+            "txs": null,
+        }))
+        .await;
+        let client = client.with_rpc(&url).unwrap();
+        let unconfirmed = client.unconfirmed_txs().await.unwrap();
+        assert_eq!(unconfirmed.total, 0);
+        assert!(unconfirmed.txs.is_empty());
+
+        let (url, _server) = serve_rpc(serde_json::json!({
+            "n_txs": "0",
+            "total": "0",
+            "total_bytes": "0",
+            "txs": null,
+        }))
+        .await;
+        let client = client.with_rpc(&url).unwrap();
+        assert_eq!(
+            client.tx_in_mempool(other.tx_hash.as_str()).await.unwrap(),
+            MempoolStatus::Missing
+        );
+    }
+}
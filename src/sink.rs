@@ -0,0 +1,89 @@
+use anyhow::anyhow;
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+
+use crate::events::BlockEvents;
+
+/// Receives the events of the blocks processed by an `EventListener`, e.g. to
+/// write them to a database or a message bus without a separate consumer task
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    /// Delivers the events of a block, waiting while the sink is full
+    async fn send(&self, block_events: BlockEvents) -> anyhow::Result<()>;
+
+    /// Delivers the events of a block if the sink has room for them right away,
+    /// otherwise returns them back
+    async fn try_send(&self, block_events: BlockEvents) -> anyhow::Result<Option<BlockEvents>> {
+        self.send(block_events).await?;
+        Ok(None)
+    }
+
+    /// Returns the maximum and the currently free capacity of the sink's buffer,
+    /// `None` if it doesn't buffer events. Used for the listener's high-water mark.
+    fn capacity(&self) -> Option<(usize, usize)> {
+        None
+    }
+}
+
+#[async_trait]
+impl EventSink for mpsc::Sender<BlockEvents> {
+    async fn send(&self, block_events: BlockEvents) -> anyhow::Result<()> {
+        mpsc::Sender::send(self, block_events)
+            .await
+            .map_err(|e| anyhow!("Failed to send block events: {}", e))
+    }
+
+    async fn try_send(&self, block_events: BlockEvents) -> anyhow::Result<Option<BlockEvents>> {
+        match mpsc::Sender::try_send(self, block_events) {
+            Ok(()) => Ok(None),
+            Err(mpsc::error::TrySendError::Full(block_events)) => Ok(Some(block_events)),
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                Err(anyhow!("Failed to send block events: channel closed"))
+            }
+        }
+    }
+
+    fn capacity(&self) -> Option<(usize, usize)> {
+        Some((self.max_capacity(), mpsc::Sender::capacity(self)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_channel_sink() {
+        let (sender, mut receiver) = mpsc::channel(1);
+        let sink: Box<dyn EventSink> = Box::new(sender);
+
+        assert!(sink
+            .try_send(BlockEvents {
+                height: 1,
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(sink.capacity(), Some((1, 0)));
+        let returned = sink
+            .try_send(BlockEvents {
+                height: 2,
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(returned.height, 2);
+
+        assert_eq!(receiver.recv().await.unwrap().height, 1);
+        drop(receiver);
+        assert!(sink
+            .send(BlockEvents {
+                height: 3,
+                ..Default::default()
+            })
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,175 @@
+use cosmos_sdk_proto::cosmos::slashing::v1beta1::{
+    query_client::QueryClient as SlashingQueryClient, QuerySigningInfoRequest, ValidatorSigningInfo,
+};
+pub use cosmos_sdk_proto::cosmos::staking::v1beta1::BondStatus;
+use cosmos_sdk_proto::cosmos::staking::v1beta1::QueryValidatorRequest;
+use cosmrs::{crypto::PublicKey, tendermint, AccountId, Any};
+
+use crate::client::CosmWasmClient;
+use crate::error::ClientError;
+
+/// Liveness record of a validator kept by the slashing module
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningInfo {
+    pub consensus_address: String,
+    pub start_height: i64,
+    /// missed_blocks is the number of blocks missed in the current signing window
+    pub missed_blocks: i64,
+    /// jailed_until is the unix timestamp in seconds the validator may unjail at,
+    /// `None` if it has never been jailed
+    pub jailed_until: Option<i64>,
+    /// tombstoned validators were slashed for double signing and can't unjail
+    pub tombstoned: bool,
+}
+
+impl From<ValidatorSigningInfo> for SigningInfo {
+    fn from(info: ValidatorSigningInfo) -> Self {
+        Self {
+            consensus_address: info.address,
+            start_height: info.start_height,
+            missed_blocks: info.missed_blocks_counter,
+            jailed_until: info
+                .jailed_until
+                .map(|time| time.seconds)
+                .filter(|seconds| *seconds > 0),
+            tombstoned: info.tombstoned,
+        }
+    }
+}
+
+/// Staking and slashing status of a validator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatorStatus {
+    pub operator_address: String,
+    pub consensus_address: String,
+    pub status: BondStatus,
+    pub jailed: bool,
+    /// signing_info is `None` for validators that have never been bonded
+    pub signing_info: Option<SigningInfo>,
+}
+
+/// Returns the consensus address, e.g. `bbnvalcons1...`, of a validator consensus
+/// public key, given the operator address prefix, e.g. `bbnvaloper`
+pub fn consensus_address(pubkey: &Any, operator_prefix: &str) -> Result<String, ClientError> {
+    let pubkey =
+        PublicKey::try_from(pubkey).map_err(|e| ClientError::invalid("consensus pubkey", e))?;
+    let id = tendermint::account::Id::from(tendermint::PublicKey::from(pubkey));
+    let prefix = format!(
+        "{}valcons",
+        operator_prefix
+            .strip_suffix("valoper")
+            .unwrap_or(operator_prefix)
+    );
+    AccountId::new(&prefix, id.as_bytes())
+        .map(|address| address.to_string())
+        .map_err(|e| ClientError::invalid("consensus address", e))
+}
+
+impl CosmWasmClient {
+    /// Returns the signing info of a validator by consensus address, `None` if the
+    /// validator has never been bonded
+    pub async fn query_signing_info(
+        &self,
+        consensus_address: &str,
+    ) -> Result<Option<SigningInfo>, ClientError> {
+        let mut client = SlashingQueryClient::connect(self.grpc_url.clone())
+            .await
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))?;
+
+        let result = client
+            .signing_info(QuerySigningInfoRequest {
+                cons_address: consensus_address.to_string(),
+            })
+            .await;
+        match result {
+            Ok(resp) => Ok(resp.into_inner().val_signing_info.map(SigningInfo::from)),
+            Err(status) if status.code() == tonic::Code::NotFound => Ok(None),
+            Err(status) => Err(ClientError::grpc("query signing info", status)),
+        }
+    }
+
+    /// Returns the status of a validator by operator address, e.g. to alert when an
+    /// operator backing peg-outs is jailed
+    pub async fn query_validator_status(
+        &self,
+        operator_address: &str,
+    ) -> Result<ValidatorStatus, ClientError> {
+        let validator = self
+            .staking_query_client()
+            .await?
+            .validator(QueryValidatorRequest {
+                validator_addr: operator_address.to_string(),
+            })
+            .await
+            .map_err(|e| ClientError::grpc("query validator", e))?
+            .into_inner()
+            .validator
+            .ok_or_else(|| ClientError::not_found(format!("validator {}", operator_address)))?;
+
+        let operator = operator_address
+            .parse::<AccountId>()
+            .map_err(|e| ClientError::invalid("operator address", e))?;
+        let pubkey = validator
+            .consensus_pubkey
+            .ok_or_else(|| ClientError::not_found("validator consensus pubkey"))?;
+        let consensus_address = consensus_address(&pubkey, operator.prefix())?;
+        let signing_info = self.query_signing_info(&consensus_address).await?;
+
+        Ok(ValidatorStatus {
+            operator_address: validator.operator_address,
+            consensus_address,
+            status: BondStatus::try_from(validator.status).unwrap_or_default(),
+            jailed: validator.jailed,
+            signing_info,
+        })
+    }
+
+    /// Returns whether the validator is jailed, by operator address
+    pub async fn is_jailed(&self, operator_address: &str) -> Result<bool, ClientError> {
+        Ok(self.query_validator_status(operator_address).await?.jailed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmos_sdk_proto::Timestamp;
+
+    #[test]
+    fn test_signing_info() {
+        let pubkey = PublicKey::from_json(
+            r#"{"@type":"/cosmos.crypto.ed25519.PubKey","key":"ebj9Tr3rZpHYQ7e0w1qXBMgmsK1S0EwyM5mqQxiBh4I="}"#,
+        )
+        .unwrap()
+        .to_any()
+        .unwrap();
+        let address = consensus_address(&pubkey, "bbnvaloper").unwrap();
+        assert!(address.starts_with("bbnvalcons1"));
+        assert_eq!(consensus_address(&pubkey, "bbn").unwrap(), address);
+
+        let info = SigningInfo::from(ValidatorSigningInfo {
+            address: address.clone(),
+            start_height: 100,
+            missed_blocks_counter: 3,
+            jailed_until: Some(Timestamp {
+                seconds: 0,
+                nanos: 0,
+            }),
+            ..Default::default()
+        });
+        assert_eq!(info.jailed_until, None);
+        assert_eq!(info.missed_blocks, 3);
+
+        let info = SigningInfo::from(ValidatorSigningInfo {
+            address,
+            jailed_until: Some(Timestamp {
+                seconds: 1_700_000_000,
+                nanos: 0,
+            }),
+            tombstoned: true,
+            ..Default::default()
+        });
+        assert_eq!(info.jailed_until, Some(1_700_000_000));
+        assert!(info.tombstoned);
+    }
+}
@@ -0,0 +1,170 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use async_trait::async_trait;
+
+use crate::events::BlockEvents;
+
+/// Persistent FIFO queue holding the block events a slow consumer couldn't take yet
+#[async_trait]
+pub trait SpillQueue: Send + Sync {
+    async fn push(&self, block_events: &BlockEvents) -> anyhow::Result<()>;
+
+    /// Returns the oldest queued block events without removing them
+    async fn peek(&self) -> anyhow::Result<Option<BlockEvents>>;
+
+    /// Removes the oldest queued block events
+    async fn pop(&self) -> anyhow::Result<()>;
+
+    /// Removes the queued block events from `height` onwards, e.g. after a reorg
+    async fn remove_from(&self, height: u64) -> anyhow::Result<()>;
+}
+
+/// Stores every spilled block as a JSON file named by its height
+#[derive(Debug, Clone)]
+pub struct FileSpillQueue {
+    dir: PathBuf,
+}
+
+impl FileSpillQueue {
+    pub fn new(dir: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create spill directory {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    /// Returns the path of the oldest spilled block, heights are zero padded so
+    /// file names sort by height
+    async fn oldest(&self) -> anyhow::Result<Option<PathBuf>> {
+        let mut entries = tokio::fs::read_dir(&self.dir)
+            .await
+            .with_context(|| format!("Failed to read spill directory {}", self.dir.display()))?;
+
+        let mut oldest: Option<PathBuf> = None;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json")
+                && oldest.as_ref().is_none_or(|oldest| path < *oldest)
+            {
+                oldest = Some(path);
+            }
+        }
+        Ok(oldest)
+    }
+}
+
+#[async_trait]
+impl SpillQueue for FileSpillQueue {
+    async fn push(&self, block_events: &BlockEvents) -> anyhow::Result<()> {
+        let content =
+            serde_json::to_vec(block_events).context("Failed to serialize block events")?;
+        let path = self.dir.join(format!("{:020}.json", block_events.height));
+
+        // write to a temporary file first so a crash never leaves a truncated block
+        let tmp_path = path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, content)
+            .await
+            .with_context(|| format!("Failed to write spill file {}", tmp_path.display()))?;
+        tokio::fs::rename(&tmp_path, &path)
+            .await
+            .with_context(|| format!("Failed to write spill file {}", path.display()))
+    }
+
+    async fn peek(&self) -> anyhow::Result<Option<BlockEvents>> {
+        let Some(path) = self.oldest().await? else {
+            return Ok(None);
+        };
+        let content = tokio::fs::read(&path)
+            .await
+            .with_context(|| format!("Failed to read spill file {}", path.display()))?;
+        let block_events = serde_json::from_slice(&content)
+            .with_context(|| format!("Invalid spill file {}", path.display()))?;
+        Ok(Some(block_events))
+    }
+
+    async fn pop(&self) -> anyhow::Result<()> {
+        if let Some(path) = self.oldest().await? {
+            tokio::fs::remove_file(&path)
+                .await
+                .with_context(|| format!("Failed to remove spill file {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    async fn remove_from(&self, height: u64) -> anyhow::Result<()> {
+        let mut entries = tokio::fs::read_dir(&self.dir)
+            .await
+            .with_context(|| format!("Failed to read spill directory {}", self.dir.display()))?;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let spilled_height = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok());
+            if spilled_height.is_some_and(|h| h >= height) {
+                tokio::fs::remove_file(&path)
+                    .await
+                    .with_context(|| format!("Failed to remove spill file {}", path.display()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_spill_queue() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = FileSpillQueue::new(dir.path().join("spill")).unwrap();
+
+        assert!(queue.peek().await.unwrap().is_none());
+        queue
+            .push(&BlockEvents {
+                height: 9,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        queue
+            .push(&BlockEvents {
+                height: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(queue.peek().await.unwrap().unwrap().height, 9);
+        queue.pop().await.unwrap();
+        assert_eq!(queue.peek().await.unwrap().unwrap().height, 10);
+        queue.pop().await.unwrap();
+        assert!(queue.peek().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_file_spill_queue_remove_from() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = FileSpillQueue::new(dir.path()).unwrap();
+        for height in [9, 10, 11] {
+            queue
+                .push(&BlockEvents {
+                    height,
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+
+        queue.remove_from(10).await.unwrap();
+        assert_eq!(queue.peek().await.unwrap().unwrap().height, 9);
+        queue.pop().await.unwrap();
+        assert!(queue.peek().await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,323 @@
+use cosmos_sdk_proto::cosmos::base::query::v1beta1::PageRequest;
+use cosmos_sdk_proto::cosmos::staking::v1beta1::{
+    query_client::QueryClient as StakingQueryClient, DelegationResponse, QueryDelegationRequest,
+    QueryDelegatorDelegationsRequest, QueryDelegatorUnbondingDelegationsRequest,
+    UnbondingDelegation,
+};
+#[cfg(feature = "wallet")]
+use cosmrs::staking::{MsgBeginRedelegate, MsgDelegate, MsgUndelegate};
+#[cfg(feature = "wallet")]
+use cosmrs::tx::Msg;
+use cosmrs::AccountId;
+#[cfg(feature = "wallet")]
+use cosmrs::{Any, Coin, Denom};
+use std::str::FromStr;
+
+use crate::client::CosmWasmClient;
+use crate::error::ClientError;
+
+/// Delegations queried per page
+const DELEGATIONS_PAGE_LIMIT: u64 = 100;
+
+/// Stake of a delegator with a validator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delegation {
+    pub delegator: String,
+    pub validator: String,
+    /// amount is the delegated balance in the staking denom
+    pub amount: u128,
+    pub denom: String,
+}
+
+impl TryFrom<DelegationResponse> for Delegation {
+    type Error = ClientError;
+
+    fn try_from(response: DelegationResponse) -> Result<Self, ClientError> {
+        let delegation = response
+            .delegation
+            .ok_or_else(|| ClientError::not_found("delegation"))?;
+        let balance = response
+            .balance
+            .ok_or_else(|| ClientError::not_found("delegation balance"))?;
+
+        Ok(Self {
+            delegator: delegation.delegator_address,
+            validator: delegation.validator_address,
+            amount: balance
+                .amount
+                .parse()
+                .map_err(|e| ClientError::invalid("delegation balance", e))?,
+            denom: balance.denom,
+        })
+    }
+}
+
+/// Stake being unbonded from a validator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unbonding {
+    pub validator: String,
+    pub creation_height: i64,
+    /// completion_time is the unix timestamp in seconds the stake is released at
+    pub completion_time: i64,
+    /// amount is the balance still unbonding, in the staking denom
+    pub amount: u128,
+}
+
+fn unbondings(unbonding: UnbondingDelegation) -> Result<Vec<Unbonding>, ClientError> {
+    unbonding
+        .entries
+        .into_iter()
+        .map(|entry| {
+            Ok(Unbonding {
+                validator: unbonding.validator_address.clone(),
+                creation_height: entry.creation_height,
+                completion_time: entry.completion_time.map_or(0, |time| time.seconds),
+                amount: entry
+                    .balance
+                    .parse()
+                    .map_err(|e| ClientError::invalid("unbonding balance", e))?,
+            })
+        })
+        .collect()
+}
+
+impl CosmWasmClient {
+    pub(crate) async fn staking_query_client(
+        &self,
+    ) -> Result<StakingQueryClient<tonic::transport::Channel>, ClientError> {
+        StakingQueryClient::connect(self.grpc_url.clone())
+            .await
+            .map_err(|e| ClientError::connection(&self.grpc_url, e))
+    }
+
+    /// Returns the validator operator address of an account, e.g. `bbnvaloper1...`
+    /// for `bbn1...`, to query or maintain the self-delegation of an operator
+    pub fn validator_address(account: &str) -> Result<String, ClientError> {
+        let account_id =
+            AccountId::from_str(account).map_err(|e| ClientError::invalid("account address", e))?;
+        AccountId::new(
+            &format!("{}valoper", account_id.prefix()),
+            &account_id.to_bytes(),
+        )
+        .map(|valoper| valoper.to_string())
+        .map_err(|e| ClientError::invalid("validator address", e))
+    }
+
+    /// Returns the stake of the delegator with the validator, `None` if it has none
+    pub async fn query_delegation(
+        &self,
+        delegator: &str,
+        validator: &str,
+    ) -> Result<Option<Delegation>, ClientError> {
+        let result = self
+            .staking_query_client()
+            .await?
+            .delegation(QueryDelegationRequest {
+                delegator_addr: delegator.to_string(),
+                validator_addr: validator.to_string(),
+            })
+            .await;
+
+        match result {
+            Ok(resp) => resp
+                .into_inner()
+                .delegation_response
+                .map(Delegation::try_from)
+                .transpose(),
+            Err(status) if status.code() == tonic::Code::NotFound => Ok(None),
+            Err(status) => Err(ClientError::grpc("query delegation", status)),
+        }
+    }
+
+    /// Returns all delegations of the delegator
+    pub async fn query_delegations(&self, delegator: &str) -> Result<Vec<Delegation>, ClientError> {
+        let mut client = self.staking_query_client().await?;
+
+        let mut delegations = vec![];
+        let mut key = vec![];
+        loop {
+            let resp = client
+                .delegator_delegations(QueryDelegatorDelegationsRequest {
+                    delegator_addr: delegator.to_string(),
+                    pagination: Some(PageRequest {
+                        key,
+                        limit: DELEGATIONS_PAGE_LIMIT,
+                        ..Default::default()
+                    }),
+                })
+                .await
+                .map_err(|e| ClientError::grpc("query delegations", e))?
+                .into_inner();
+            for delegation in resp.delegation_responses {
+                delegations.push(delegation.try_into()?);
+            }
+
+            key = resp.pagination.map(|p| p.next_key).unwrap_or_default();
+            if key.is_empty() {
+                break;
+            }
+        }
+
+        Ok(delegations)
+    }
+
+    /// Returns the stake of the delegator still unbonding, by validator and entry
+    pub async fn query_unbondings(&self, delegator: &str) -> Result<Vec<Unbonding>, ClientError> {
+        let mut client = self.staking_query_client().await?;
+
+        let mut entries = vec![];
+        let mut key = vec![];
+        loop {
+            let resp = client
+                .delegator_unbonding_delegations(QueryDelegatorUnbondingDelegationsRequest {
+                    delegator_addr: delegator.to_string(),
+                    pagination: Some(PageRequest {
+                        key,
+                        limit: DELEGATIONS_PAGE_LIMIT,
+                        ..Default::default()
+                    }),
+                })
+                .await
+                .map_err(|e| ClientError::grpc("query unbonding delegations", e))?
+                .into_inner();
+            for unbonding in resp.unbonding_responses {
+                entries.extend(unbondings(unbonding)?);
+            }
+
+            key = resp.pagination.map(|p| p.next_key).unwrap_or_default();
+            if key.is_empty() {
+                break;
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+#[cfg(feature = "wallet")]
+impl CosmWasmClient {
+    /// Delegates the amount in `ChainConfig.denom` from the wallet to the validator
+    pub async fn delegate(&self, validator: &str, amount: u128) -> Result<String, ClientError> {
+        let msg = MsgDelegate {
+            delegator_address: self.signer()?.account_id.clone(),
+            validator_address: parse_validator(validator)?,
+            amount: self.staking_coin(amount)?,
+        };
+        tracing::info!("Delegating {} to {}", amount, validator);
+
+        self.build_and_broadcast_msgs(vec![staking_any(&msg)?])
+            .await
+    }
+
+    /// Starts unbonding the amount in `ChainConfig.denom` from the validator, it is
+    /// released after the unbonding period, see `query_unbondings`
+    pub async fn undelegate(&self, validator: &str, amount: u128) -> Result<String, ClientError> {
+        let msg = MsgUndelegate {
+            delegator_address: self.signer()?.account_id.clone(),
+            validator_address: parse_validator(validator)?,
+            amount: self.staking_coin(amount)?,
+        };
+        tracing::info!("Undelegating {} from {}", amount, validator);
+
+        self.build_and_broadcast_msgs(vec![staking_any(&msg)?])
+            .await
+    }
+
+    /// Moves the amount in `ChainConfig.denom` from one validator to another without
+    /// unbonding it
+    pub async fn redelegate(
+        &self,
+        src_validator: &str,
+        dst_validator: &str,
+        amount: u128,
+    ) -> Result<String, ClientError> {
+        let msg = MsgBeginRedelegate {
+            delegator_address: self.signer()?.account_id.clone(),
+            validator_src_address: parse_validator(src_validator)?,
+            validator_dst_address: parse_validator(dst_validator)?,
+            amount: self.staking_coin(amount)?,
+        };
+        tracing::info!(
+            "Redelegating {} from {} to {}",
+            amount,
+            src_validator,
+            dst_validator
+        );
+
+        self.build_and_broadcast_msgs(vec![staking_any(&msg)?])
+            .await
+    }
+
+    fn staking_coin(&self, amount: u128) -> Result<Coin, ClientError> {
+        Ok(Coin {
+            denom: Denom::from_str(&self.config.denom)
+                .map_err(|e| ClientError::invalid("staking denom", e))?,
+            amount,
+        })
+    }
+}
+
+#[cfg(feature = "wallet")]
+fn parse_validator(validator: &str) -> Result<AccountId, ClientError> {
+    AccountId::from_str(validator).map_err(|e| ClientError::invalid("validator address", e))
+}
+
+#[cfg(feature = "wallet")]
+fn staking_any(msg: &impl Msg) -> Result<Any, ClientError> {
+    msg.to_any()
+        .map_err(|e| ClientError::serialization("convert message to Any", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmos_sdk_proto::cosmos::base::v1beta1::Coin as ProtoCoin;
+    use cosmos_sdk_proto::cosmos::staking::v1beta1::{
+        Delegation as ProtoDelegation, UnbondingDelegationEntry,
+    };
+
+    #[test]
+    fn test_delegation_conversion() {
+        let delegator = "cosmos1hsk6jryyqjfhp5dhc55tc9jtckygx0eph6dd02";
+        let valoper = CosmWasmClient::validator_address(delegator).unwrap();
+        assert!(valoper.starts_with("cosmosvaloper1"));
+        assert!(CosmWasmClient::validator_address("invalid").is_err());
+
+        let response = DelegationResponse {
+            delegation: Some(ProtoDelegation {
+                delegator_address: delegator.to_string(),
+                validator_address: valoper.clone(),
+                shares: "1000000000000000000000".to_string(),
+            }),
+            balance: Some(ProtoCoin {
+                denom: "ubbn".to_string(),
+                amount: "1000".to_string(),
+            }),
+        };
+        let delegation = Delegation::try_from(response).unwrap();
+        assert_eq!(delegation.validator, valoper);
+        assert_eq!(delegation.amount, 1000);
+        assert!(Delegation::try_from(DelegationResponse::default()).is_err());
+
+        let unbonding = UnbondingDelegation {
+            delegator_address: delegator.to_string(),
+            validator_address: valoper.clone(),
+            entries: vec![
+                UnbondingDelegationEntry {
+                    creation_height: 10,
+                    balance: "300".to_string(),
+                    ..Default::default()
+                },
+                UnbondingDelegationEntry {
+                    creation_height: 20,
+                    balance: "200".to_string(),
+                    ..Default::default()
+                },
+            ],
+        };
+        let entries = unbondings(unbonding).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].creation_height, 20);
+        assert_eq!(entries[1].amount, 200);
+    }
+}
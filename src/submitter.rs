@@ -0,0 +1,242 @@
+use std::time::Duration;
+
+use cosmrs::Any;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+use crate::client::CosmWasmClient;
+use crate::error::{ClientError, TransactionError, TxErrorKind};
+use crate::logging;
+use crate::transactions::SignedTx;
+
+/// Default number of queued transactions before `submit` waits
+const DEFAULT_QUEUE_SIZE: usize = 100;
+/// Default time to wait for a broadcast transaction to be included in a block
+const DEFAULT_CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60);
+/// Default interval between `get_tx` polls while waiting for confirmation
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone)]
+pub struct TxSubmitterConfig {
+    /// queue_size is the capacity of the request channel
+    pub queue_size: usize,
+    /// confirmation_timeout is how long to wait for a transaction to be included
+    pub confirmation_timeout: Duration,
+    /// poll_interval is the interval between confirmation polls
+    pub poll_interval: Duration,
+}
+
+impl Default for TxSubmitterConfig {
+    fn default() -> Self {
+        Self {
+            queue_size: DEFAULT_QUEUE_SIZE,
+            confirmation_timeout: DEFAULT_CONFIRMATION_TIMEOUT,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+}
+
+/// Outcome of a confirmed transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxResult {
+    pub tx_hash: String,
+    /// height is the block height the transaction was included in
+    pub height: i64,
+    /// code is the ABCI result code, 0 on success
+    pub code: u32,
+    /// codespace is the module that returned `code`, empty on success
+    pub codespace: String,
+    pub gas_used: i64,
+    pub raw_log: String,
+}
+
+struct TxRequest {
+    msgs: Vec<Any>,
+    reply: oneshot::Sender<Result<TxResult, ClientError>>,
+}
+
+/// Cloneable handle used to queue transactions on a running `TxSubmitter`
+#[derive(Clone)]
+pub struct TxSubmitterHandle {
+    sender: mpsc::Sender<TxRequest>,
+}
+
+impl TxSubmitterHandle {
+    /// Queues a transaction with the given messages and waits until it is confirmed
+    pub async fn submit(&self, msgs: Vec<Any>) -> Result<TxResult, ClientError> {
+        let (reply, result) = oneshot::channel();
+        self.sender
+            .send(TxRequest { msgs, reply })
+            .await
+            .map_err(|_| stopped())?;
+
+        result.await.map_err(|_| stopped())?
+    }
+}
+
+fn stopped() -> ClientError {
+    ClientError::Stopped {
+        what: "Transaction submitter".to_string(),
+    }
+}
+
+/// Background task signing and broadcasting queued transactions one at a time.
+/// The account sequence is tracked locally so consecutive transactions never race,
+/// and confirmations are awaited concurrently without blocking the queue.
+pub struct TxSubmitter {
+    client: CosmWasmClient,
+    config: TxSubmitterConfig,
+    receiver: mpsc::Receiver<TxRequest>,
+    account_number: u64,
+    sequence: Option<u64>,
+}
+
+impl TxSubmitter {
+    /// Starts the submitter task, it stops once all handles are dropped
+    pub fn spawn(
+        client: CosmWasmClient,
+        config: TxSubmitterConfig,
+    ) -> (TxSubmitterHandle, JoinHandle<()>) {
+        let (sender, receiver) = mpsc::channel(config.queue_size);
+        let submitter = Self {
+            client,
+            config,
+            receiver,
+            account_number: 0,
+            sequence: None,
+        };
+
+        (TxSubmitterHandle { sender }, tokio::spawn(submitter.run()))
+    }
+
+    async fn run(mut self) {
+        while let Some(request) = self.receiver.recv().await {
+            match self.sign_and_broadcast(request.msgs).await {
+                Ok(signed_tx) => {
+                    let client = self.client.clone();
+                    let config = self.config.clone();
+                    tokio::spawn(async move {
+                        let result = wait_for_confirmation(&client, &config, &signed_tx).await;
+                        let _ = request.reply.send(result);
+                    });
+                }
+                Err(e) => {
+                    let _ = request.reply.send(Err(e));
+                }
+            }
+        }
+
+        tracing::info!("Transaction submitter stopped");
+    }
+
+    async fn sign_and_broadcast(&mut self, msgs: Vec<Any>) -> Result<SignedTx, ClientError> {
+        let mut retried = false;
+
+        loop {
+            let sequence = match self.sequence {
+                Some(sequence) => sequence,
+                None => self.refresh_sequence().await?,
+            };
+
+            let fee = self.client.fee_for_gas(self.client.config.gas_limit)?;
+            let tx_bytes = self
+                .client
+                .build_tx_with_sequence(msgs.clone(), fee, self.account_number, sequence)?
+                .to_bytes()
+                .map_err(|e| ClientError::serialization("serialize transaction", e))?;
+            let signed_tx = SignedTx::from_bytes(tx_bytes);
+
+            let tx_response = match self.client.broadcast_tx(signed_tx.tx_bytes.clone()).await {
+                Ok(response) => response,
+                Err(e) => {
+                    // the node may have accepted the tx and its sequence
+                    self.sequence = None;
+                    return Err(ClientError::broadcast_failed(&signed_tx.tx_hash, e));
+                }
+            }
+            .tx_response
+            .ok_or_else(|| ClientError::not_found("transaction response"))?;
+
+            let kind = match tx_response.code {
+                0 => None,
+                code => Some(TxErrorKind::from_abci(&tx_response.codespace, code)),
+            };
+            match kind {
+                None | Some(TxErrorKind::TxInMempoolCache) => {
+                    tracing::info!(
+                        "Transaction {} broadcast with sequence {}",
+                        signed_tx.tx_hash,
+                        sequence
+                    );
+                    self.sequence = Some(sequence + 1);
+                    return Ok(signed_tx);
+                }
+                // another signer used the account, resync once
+                Some(TxErrorKind::WrongSequence) if !retried => {
+                    tracing::warn!(
+                        "Sequence {} rejected, refreshing account: {}",
+                        sequence,
+                        logging::text(&tx_response.raw_log)
+                    );
+                    self.sequence = None;
+                    retried = true;
+                }
+                _ => {
+                    // CheckTx failures don't consume the sequence, but resync to be safe
+                    self.sequence = None;
+                    return Err(ClientError::TxFailed(Box::new(
+                        TransactionError::from(tx_response).or_tx_hash(&signed_tx.tx_hash),
+                    )));
+                }
+            }
+        }
+    }
+
+    async fn refresh_sequence(&mut self) -> Result<u64, ClientError> {
+        let account = self
+            .client
+            .get_account_info(self.client.signer()?.account_id.to_string())
+            .await?;
+        self.account_number = account.account_number;
+        self.sequence = Some(account.sequence);
+
+        Ok(account.sequence)
+    }
+}
+
+async fn wait_for_confirmation(
+    client: &CosmWasmClient,
+    config: &TxSubmitterConfig,
+    signed_tx: &SignedTx,
+) -> Result<TxResult, ClientError> {
+    let deadline = tokio::time::Instant::now() + config.confirmation_timeout;
+
+    loop {
+        // the node returns an error until the tx is included in a block
+        if let Ok(resp) = client.get_tx(signed_tx.tx_hash.as_str()).await {
+            if let Some(tx_response) = resp.tx_response {
+                if tx_response.code != 0 {
+                    return Err(ClientError::TxFailed(Box::new(
+                        TransactionError::from(tx_response).or_tx_hash(&signed_tx.tx_hash),
+                    )));
+                }
+                return Ok(TxResult {
+                    tx_hash: tx_response.txhash,
+                    height: tx_response.height,
+                    code: tx_response.code,
+                    codespace: tx_response.codespace,
+                    gas_used: tx_response.gas_used,
+                    raw_log: tx_response.raw_log,
+                });
+            }
+        }
+
+        if tokio::time::Instant::now() >= deadline {
+            return Err(ClientError::Timeout {
+                what: format!("Confirmation of transaction {}", signed_tx.tx_hash),
+                timeout: config.confirmation_timeout,
+            });
+        }
+        tokio::time::sleep(config.poll_interval).await;
+    }
+}
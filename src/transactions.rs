@@ -1,14 +1,164 @@
+#[cfg(feature = "wallet")]
+use crate::btc_address::validate_btc_address;
+#[cfg(feature = "wallet")]
 use crate::client::CosmWasmClient;
-use anyhow::Context;
+use crate::error::ClientError;
+#[cfg(feature = "wallet")]
+use crate::error::{TransactionError, TxErrorKind};
+#[cfg(all(feature = "wallet", feature = "babylon"))]
+use crate::generated::babylon::btclightclient::v1::MsgInsertHeaders;
+#[cfg(feature = "wallet")]
+use crate::logging;
+use crate::version::BridgeSchema;
+#[cfg(feature = "wallet")]
+use cosmos_sdk_proto::cosmos::base::abci::v1beta1::TxResponse;
+#[cfg(feature = "wallet")]
 use cosmos_sdk_proto::traits::Message;
+#[cfg(feature = "wallet")]
 use cosmrs::cosmwasm::{MsgExecuteContract, MsgInstantiateContract};
+#[cfg(feature = "wallet")]
 use cosmrs::tx::{BodyBuilder, Fee, Msg, Raw, SignDoc, SignerInfo};
-use cosmrs::{Any, Coin, Denom};
+#[cfg(feature = "wallet")]
+use cosmrs::{AccountId, Any};
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::Addr;
 use cosmwasm_std::Uint128;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::str::FromStr;
+#[cfg(feature = "wallet")]
+use std::time::{Duration, Instant};
+
+/// Default time a cached account sequence is trusted, see `CosmWasmClient::account_cache_ttl`
+#[cfg(feature = "wallet")]
+pub(crate) const DEFAULT_ACCOUNT_CACHE_TTL: Duration = Duration::from_secs(10);
+
+/// Type URL of the Babylon btclightclient MsgInsertHeaders message
+#[cfg(all(feature = "wallet", feature = "babylon"))]
+const MSG_INSERT_HEADERS_TYPE_URL: &str = "/babylon.btclightclient.v1.MsgInsertHeaders";
+/// Length of a serialized BTC block header
+#[cfg(all(feature = "wallet", feature = "babylon"))]
+const BTC_HEADER_LEN: usize = 80;
+
+/// Cosmos tx hash, the uppercase hex sha256 of the tx bytes as reported by the
+/// node, explorers, `get_tx` and `tx_search`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TxHash(String);
+
+impl TxHash {
+    pub fn from_tx_bytes(tx_bytes: &[u8]) -> Self {
+        Self(hex::encode_upper(Sha256::digest(tx_bytes)))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for TxHash {
+    type Err = ClientError;
+
+    /// Parses a hex tx hash in either case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 64 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ClientError::invalid("tx hash", s));
+        }
+        Ok(Self(s.to_ascii_uppercase()))
+    }
+}
+
+impl TryFrom<String> for TxHash {
+    type Error = ClientError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<TxHash> for String {
+    fn from(hash: TxHash) -> Self {
+        hash.0
+    }
+}
+
+impl std::fmt::Display for TxHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for TxHash {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Signed transaction bytes together with their hash
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedTx {
+    pub tx_hash: TxHash,
+    /// tx_bytes are the signed, protobuf encoded TxRaw bytes
+    pub tx_bytes: Vec<u8>,
+}
+
+impl SignedTx {
+    pub fn from_bytes(tx_bytes: Vec<u8>) -> Self {
+        let tx_hash = TxHash::from_tx_bytes(&tx_bytes);
+        Self { tx_hash, tx_bytes }
+    }
+}
+
+/// Account number and next sequence of the signer, so consecutive transactions
+/// don't query the account. Shared between clones of a client.
+#[cfg(feature = "wallet")]
+#[derive(Debug, Default)]
+pub(crate) struct AccountCache {
+    address: String,
+    /// account_number never changes once the account exists
+    account_number: u64,
+    /// sequence is the next unused sequence, with when it was fetched from the node
+    sequence: Option<(u64, Instant)>,
+}
+
+#[cfg(feature = "wallet")]
+impl AccountCache {
+    /// Returns the account number and the cached sequence if fetched less than
+    /// `ttl` ago
+    fn get(&self, address: &str, ttl: Duration) -> Option<(u64, u64)> {
+        let (sequence, fetched_at) = self.sequence?;
+        (self.address == address && fetched_at.elapsed() < ttl)
+            .then_some((self.account_number, sequence))
+    }
+
+    /// Same as `get`, reserving the sequence for the caller
+    fn reserve(&mut self, address: &str, ttl: Duration) -> Option<(u64, u64)> {
+        let reserved = self.get(address, ttl)?;
+        if let Some((sequence, _)) = self.sequence.as_mut() {
+            *sequence += 1;
+        }
+        Some(reserved)
+    }
+
+    /// Caches a fetched account, unless a concurrent transaction already cached and
+    /// reserved a later sequence
+    fn update(&mut self, address: &str, account_number: u64, sequence: u64) {
+        if self.address == address && self.sequence.is_some_and(|(cached, _)| cached >= sequence) {
+            return;
+        }
+        *self = Self {
+            address: address.to_string(),
+            account_number,
+            sequence: Some((sequence, Instant::now())),
+        };
+    }
+
+    /// Drops the sequence after a failed broadcast or a reserved sequence that is
+    /// never broadcast, it's fetched again by the next transaction
+    pub(crate) fn invalidate(&mut self) {
+        self.sequence = None;
+    }
+}
 
 #[cw_serde]
 pub struct Operator {
@@ -45,8 +195,9 @@ impl Default for InstantiateMsg {
     }
 }
 
+/// Execute messages of the bridge contract `0.1.x`, see `version::BridgeSchema`
 #[cw_serde]
-pub enum ExecuteMsg {
+pub enum ExecuteMsgV1 {
     /// PegIn is the message for peg in requests
     PegIn {
         /// sender_btc_pk is the Bitcoin public key of the sender
@@ -73,7 +224,7 @@ pub enum ExecuteMsg {
         /// btc_address is the Bitcoin address for receiving the
         /// pegged out $BTC
         btc_address: String,
-        /// fee_rate is the fee rate of the peg out transaction
+        /// fee_rate is the fee rate of the peg out transaction in sat/vB
         fee_rate: u32,
         /// amount is the amount of $bBTC to peg out
         amount: Uint128,
@@ -82,8 +233,220 @@ pub enum ExecuteMsg {
         operator_btc_pk: String,
         // TODO: more fields
     },
+    /// UpdateOperators replaces the operator set, admin only
+    UpdateOperators {
+        /// operators is the new list of operators
+        operators: Vec<Operator>,
+    },
+    /// UpdateParams updates the bridge parameters, admin only.
+    /// Unset fields are left unchanged.
+    UpdateParams {
+        /// btc_confirmation_depth is the number of blocks to confirm on BTC
+        btc_confirmation_depth: Option<u32>,
+    },
+    /// Pause rejects all peg ins and peg outs until unpaused, admin only
+    Pause {},
+    /// Unpause resumes peg ins and peg outs, admin only
+    Unpause {},
+}
+
+/// Serializes a contract message to the bytes sent on chain as the `msg` of
+/// `MsgExecuteContract`, `MsgInstantiateContract` and `MsgMigrateContract`: compact
+/// JSON with the fields in declaration order and no trailing newline.
+///
+/// The bytes of every `ExecuteMsg` and `InstantiateMsg` variant are pinned by golden
+/// tests, so contracts and audit trails comparing the msg recorded on chain can rely
+/// on them across crate versions. Reordering or renaming fields is a breaking change.
+pub fn contract_msg_bytes<T: Serialize + ?Sized>(msg: &T) -> Result<Vec<u8>, ClientError> {
+    serde_json::to_vec(msg).map_err(|e| ClientError::serialization("serialize message", e))
+}
+
+/// Draft of the execute messages planned for the bridge contract `0.2.x`, see
+/// `version::BridgeSchema::V2`. Same as `ExecuteMsgV1` except that peg ins also
+/// carry the txid of the peg in tx. Not checked against a released contract.
+#[cw_serde]
+pub enum ExecuteMsgV2 {
+    /// PegIn is the message for peg in requests
+    PegIn {
+        /// sender_btc_pk is the Bitcoin public key of the sender
+        /// The BTC PK is in the compressed format (33 bytes)
+        sender_btc_pk: String,
+        /// receiver_address is the Cosmos address of the receiver
+        /// who receives the $bBTC tokens
+        receiver_address: Addr,
+        /// amount is the amount of $BTC to peg in
+        amount: Uint128,
+        /// btc_block_hash is the block hash of the Bitcoin block
+        /// that contains the peg out transaction
+        btc_block_hash: String,
+        /// pegin_tx is the peg in transaction in hex format
+        pegin_tx: String,
+        /// pegin_txid is the txid of the peg in transaction in display order,
+        /// used by the contract to deduplicate peg ins
+        pegin_txid: String,
+        /// pegin_tx_idx is the index of the peg in transaction in the Bitcoin block
+        pegin_tx_idx: u32,
+        /// pegin_tx_merkle_proof is the merkle proof of the peg in transaction in hex format
+        pegin_tx_merkle_proof: Vec<String>,
+    },
+    /// PegOut is the message for peg out requests
+    PegOut {
+        /// btc_address is the Bitcoin address for receiving the
+        /// pegged out $BTC
+        btc_address: String,
+        /// fee_rate is the fee rate of the peg out transaction in sat/vB
+        fee_rate: u32,
+        /// amount is the amount of $bBTC to peg out
+        amount: Uint128,
+        /// operator_btc_pk is the Bitcoin public key of the operator
+        /// The BTC PK is in the compressed format (33 bytes)
+        operator_btc_pk: String,
+    },
+    /// UpdateOperators replaces the operator set, admin only
+    UpdateOperators {
+        /// operators is the new list of operators
+        operators: Vec<Operator>,
+    },
+    /// UpdateParams updates the bridge parameters, admin only.
+    /// Unset fields are left unchanged.
+    UpdateParams {
+        /// btc_confirmation_depth is the number of blocks to confirm on BTC
+        btc_confirmation_depth: Option<u32>,
+    },
+    /// Pause rejects all peg ins and peg outs until unpaused, admin only
+    Pause {},
+    /// Unpause resumes peg ins and peg outs, admin only
+    Unpause {},
+}
+
+/// Execute messages of the oldest supported bridge contract, which the client
+/// sends unless the handshake detected a newer schema
+pub type ExecuteMsg = ExecuteMsgV1;
+
+impl TryFrom<ExecuteMsgV1> for ExecuteMsgV2 {
+    type Error = ClientError;
+
+    /// Upgrades the message, computing the txid of peg ins from the peg in tx
+    fn try_from(msg: ExecuteMsgV1) -> Result<Self, Self::Error> {
+        Ok(match msg {
+            ExecuteMsgV1::PegIn {
+                sender_btc_pk,
+                receiver_address,
+                amount,
+                btc_block_hash,
+                pegin_tx,
+                pegin_tx_idx,
+                pegin_tx_merkle_proof,
+            } => {
+                let tx_bytes =
+                    hex::decode(&pegin_tx).map_err(|e| ClientError::invalid("peg in tx hex", e))?;
+                ExecuteMsgV2::PegIn {
+                    sender_btc_pk,
+                    receiver_address,
+                    amount,
+                    btc_block_hash,
+                    pegin_txid: compute_txid(&tx_bytes)?,
+                    pegin_tx,
+                    pegin_tx_idx,
+                    pegin_tx_merkle_proof,
+                }
+            }
+            ExecuteMsgV1::PegOut {
+                btc_address,
+                fee_rate,
+                amount,
+                operator_btc_pk,
+            } => ExecuteMsgV2::PegOut {
+                btc_address,
+                fee_rate,
+                amount,
+                operator_btc_pk,
+            },
+            ExecuteMsgV1::UpdateOperators { operators } => {
+                ExecuteMsgV2::UpdateOperators { operators }
+            }
+            ExecuteMsgV1::UpdateParams {
+                btc_confirmation_depth,
+            } => ExecuteMsgV2::UpdateParams {
+                btc_confirmation_depth,
+            },
+            ExecuteMsgV1::Pause {} => ExecuteMsgV2::Pause {},
+            ExecuteMsgV1::Unpause {} => ExecuteMsgV2::Unpause {},
+        })
+    }
+}
+
+/// Bridge execute message in the schema of a given contract version, serialized
+/// as the inner message
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum VersionedExecuteMsg {
+    V1(ExecuteMsgV1),
+    V2(ExecuteMsgV2),
 }
 
+impl VersionedExecuteMsg {
+    /// Converts the message to the given schema
+    pub fn new(msg: ExecuteMsgV1, schema: BridgeSchema) -> Result<Self, ClientError> {
+        match schema {
+            BridgeSchema::V1 => Ok(Self::V1(msg)),
+            BridgeSchema::V2 => Ok(Self::V2(msg.try_into()?)),
+        }
+    }
+}
+
+/// Peg in request submitted as part of a batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PegInRequest {
+    /// sender_btc_pk is the compressed Bitcoin public key of the sender
+    pub sender_btc_pk: String,
+    /// recipient is the Cosmos address receiving the $bBTC tokens
+    pub recipient: String,
+    pub amount: u128,
+    /// block_hash is the hash of the Bitcoin block containing the peg in transaction
+    pub block_hash: String,
+    /// pegin_tx is the peg in transaction in hex format
+    pub pegin_tx: String,
+    /// pegin_tx_idx is the index of the peg in transaction in the Bitcoin block
+    pub pegin_tx_idx: u32,
+    /// pegin_tx_merkle_proof is the merkle proof of the peg in transaction in hex format
+    pub pegin_tx_merkle_proof: Vec<String>,
+}
+
+impl From<PegInRequest> for ExecuteMsg {
+    fn from(req: PegInRequest) -> Self {
+        ExecuteMsg::PegIn {
+            sender_btc_pk: req.sender_btc_pk,
+            receiver_address: Addr::unchecked(req.recipient),
+            amount: Uint128::from(req.amount),
+            btc_block_hash: req.block_hash,
+            pegin_tx: req.pegin_tx,
+            pegin_tx_idx: req.pegin_tx_idx,
+            pegin_tx_merkle_proof: req.pegin_tx_merkle_proof,
+        }
+    }
+}
+
+/// Result of a batch peg in transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PegInBatch {
+    pub tx_hash: String,
+    /// requests are the submitted requests with the msg_index of their message in the tx,
+    /// matching `PegInEvent::msg_index`
+    pub requests: Vec<(u32, PegInRequest)>,
+}
+
+impl PegInBatch {
+    /// Returns the request submitted at the given msg_index
+    pub fn request(&self, msg_index: u32) -> Option<&PegInRequest> {
+        self.requests
+            .iter()
+            .find(|(index, _)| *index == msg_index)
+            .map(|(_, req)| req)
+    }
+}
+
+#[cfg(feature = "wallet")]
 impl CosmWasmClient {
     /// Instantiates a new contract with the given code ID
     pub async fn instantiate(
@@ -92,7 +455,7 @@ impl CosmWasmClient {
         denom: &str,
         operators: Vec<Operator>,
         label: &str,
-    ) -> anyhow::Result<String> {
+    ) -> Result<String, ClientError> {
         let msg = InstantiateMsg {
             cw20_code_id: 0,
             btc_confirmation_depth: 6,
@@ -104,6 +467,7 @@ impl CosmWasmClient {
     }
 
     /// Mints tokens to the specified recipient
+    #[allow(clippy::too_many_arguments)]
     pub async fn peg_in(
         &self,
         sender_btc_pk: &str,
@@ -113,28 +477,100 @@ impl CosmWasmClient {
         pegin_tx: &str,
         pegin_tx_idx: u32,
         pegin_tx_merkle_proof: Vec<String>,
-    ) -> anyhow::Result<String> {
-        let msg = ExecuteMsg::PegIn {
+    ) -> Result<String, ClientError> {
+        let req = PegInRequest {
             sender_btc_pk: sender_btc_pk.to_string(),
-            receiver_address: Addr::unchecked(recipient),
-            amount: Uint128::from(amount),
-            btc_block_hash: block_hash.to_string(),
+            recipient: recipient.to_string(),
+            amount,
+            block_hash: block_hash.to_string(),
             pegin_tx: pegin_tx.to_string(),
             pegin_tx_idx,
             pegin_tx_merkle_proof,
         };
+        self.precheck_peg_in(&req).await?;
+
+        self.execute_contract(&self.bridge_msg(ExecuteMsg::from(req))?)
+            .await
+    }
+
+    /// Submits multiple peg ins in a single transaction, one `PegIn` message per request.
+    /// The gas grows with the number of peg ins, so it's simulated rather than taken
+    /// from the configured gas limit.
+    pub async fn peg_in_batch(
+        &self,
+        requests: Vec<PegInRequest>,
+    ) -> Result<PegInBatch, ClientError> {
+        let msgs = self.peg_in_batch_msgs(&requests).await?;
+        tracing::debug!("Submitting {} peg ins in one transaction", msgs.len());
+
+        let fee = self.estimate_fee(msgs.clone()).await?;
+        let signed_tx = self.sign_msgs_with_fee(msgs, fee).await?;
+        let tx_hash = self.broadcast_signed_tx(&signed_tx).await?;
+
+        Ok(PegInBatch {
+            tx_hash,
+            requests: (0u32..).zip(requests).collect(),
+        })
+    }
+
+    /// Returns the `PegIn` messages of a batch, in the order of the requests
+    pub(crate) async fn peg_in_batch_msgs(
+        &self,
+        requests: &[PegInRequest],
+    ) -> Result<Vec<Any>, ClientError> {
+        if requests.is_empty() {
+            return Err(ClientError::invalid(
+                "peg in batch",
+                "no requests to submit",
+            ));
+        }
+        for req in requests {
+            self.precheck_peg_in(req).await?;
+        }
+
+        let contract = self
+            .contract
+            .clone()
+            .ok_or_else(|| ClientError::not_found("contract address"))?;
+
+        requests
+            .iter()
+            .map(|req| {
+                let msg = self.bridge_msg(ExecuteMsg::from(req.clone()))?;
+                self.execute_msg(contract.clone(), &msg)
+            })
+            .collect()
+    }
+
+    /// Fails with `ClientError::AlreadyPegged` if precheck is enabled and the
+    /// contract has already processed the peg in tx
+    async fn precheck_peg_in(&self, req: &PegInRequest) -> Result<(), ClientError> {
+        if !self.peg_in_precheck {
+            return Ok(());
+        }
+
+        let tx_bytes =
+            hex::decode(&req.pegin_tx).map_err(|e| ClientError::invalid("peg in tx hex", e))?;
+        let txid = compute_txid(&tx_bytes)?;
+        if self.is_peg_in_processed(&txid).await? {
+            tracing::warn!("Skipping peg in tx {}, already processed", txid);
+            return Err(ClientError::AlreadyPegged { txid });
+        }
 
-        self.execute_contract(&msg).await
+        Ok(())
     }
 
-    /// Burns the specified amount of tokens
+    /// Burns the specified amount of tokens, `fee_rate` is the BTC fee rate
+    /// in sat/vB the operator should use for the peg out transaction
     pub async fn peg_out(
         &self,
         btc_address: &str,
         fee_rate: u32,
         amount: u128,
         operator_btc_pk: &str,
-    ) -> anyhow::Result<String> {
+    ) -> Result<String, ClientError> {
+        validate_btc_address(btc_address, self.btc_network)?;
+
         let msg = ExecuteMsg::PegOut {
             btc_address: btc_address.to_string(),
             fee_rate,
@@ -142,7 +578,79 @@ impl CosmWasmClient {
             operator_btc_pk: operator_btc_pk.to_string(),
         };
 
-        self.execute_contract(&msg).await
+        self.execute_contract(&self.bridge_msg(msg)?).await
+    }
+
+    /// Replaces the bridge operator set
+    pub async fn update_operators(&self, operators: Vec<Operator>) -> Result<String, ClientError> {
+        self.execute_contract(&self.bridge_msg(ExecuteMsg::UpdateOperators { operators })?)
+            .await
+    }
+
+    /// Updates the bridge parameters, leaving unset fields unchanged
+    pub async fn update_params(
+        &self,
+        btc_confirmation_depth: Option<u32>,
+    ) -> Result<String, ClientError> {
+        self.execute_contract(&self.bridge_msg(ExecuteMsg::UpdateParams {
+            btc_confirmation_depth,
+        })?)
+        .await
+    }
+
+    /// Pauses peg ins and peg outs
+    pub async fn pause(&self) -> Result<String, ClientError> {
+        self.execute_contract(&self.bridge_msg(ExecuteMsg::Pause {})?)
+            .await
+    }
+
+    /// Resumes peg ins and peg outs
+    pub async fn unpause(&self) -> Result<String, ClientError> {
+        self.execute_contract(&self.bridge_msg(ExecuteMsg::Unpause {})?)
+            .await
+    }
+
+    /// Returns the bridge message in the schema of the contract, see `bridge_schema`
+    fn bridge_msg(&self, msg: ExecuteMsg) -> Result<VersionedExecuteMsg, ClientError> {
+        VersionedExecuteMsg::new(msg, self.bridge_schema())
+    }
+
+    /// Fails unless the checksum of the bytecode stored under `code_id` is the
+    /// expected hex sha256, e.g. as printed by `sha256sum` for the optimized wasm
+    pub async fn verify_code_checksum(
+        &self,
+        code_id: u64,
+        expected_checksum: &str,
+    ) -> Result<(), ClientError> {
+        let expected = hex::decode(expected_checksum.trim_start_matches("0x"))
+            .map_err(|e| ClientError::invalid("expected code checksum", e))?;
+        let checksum = self.query_code_checksum(code_id).await?;
+
+        if checksum != expected {
+            return Err(ClientError::invalid(
+                format!("checksum of code {}", code_id),
+                format!(
+                    "{} doesn't match the expected {}",
+                    hex::encode(checksum),
+                    hex::encode(expected)
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Instantiates the code like `initiate_contract` once `verify_code_checksum`
+    /// confirmed the bytecode is the expected one
+    pub async fn initiate_verified_contract<T: Serialize>(
+        &self,
+        code_id: u64,
+        expected_checksum: &str,
+        msg: &T,
+        label: &str,
+    ) -> Result<String, ClientError> {
+        self.verify_code_checksum(code_id, expected_checksum)
+            .await?;
+        self.initiate_contract(code_id, msg, label).await
     }
 
     pub async fn initiate_contract<T: Serialize>(
@@ -150,14 +658,18 @@ impl CosmWasmClient {
         code_id: u64,
         msg: &T,
         label: &str,
-    ) -> anyhow::Result<String> {
-        let msg_bytes = serde_json::to_vec(msg)
-            .map_err(anyhow::Error::from)
-            .context("Failed to serialize message")?;
+    ) -> Result<String, ClientError> {
+        let wallet = self.signer()?;
+        let msg_bytes = contract_msg_bytes(msg)?;
+        tracing::debug!(
+            "Instantiating code {} with msg: {}",
+            code_id,
+            logging::text(&String::from_utf8_lossy(&msg_bytes))
+        );
 
         let instantiate_msg = MsgInstantiateContract {
-            sender: self.wallet.account_id.clone(),
-            admin: Some(self.wallet.account_id.clone()),
+            sender: wallet.account_id.clone(),
+            admin: Some(wallet.account_id.clone()),
             code_id,
             label: Some(label.to_string()),
             msg: msg_bytes,
@@ -167,90 +679,671 @@ impl CosmWasmClient {
         self.build_and_broadcast_tx(
             instantiate_msg
                 .to_any()
-                .map_err(|e| anyhow::anyhow!("Failed to convert message to Any: {}", e))?,
+                .map_err(|e| ClientError::serialization("convert message to Any", e))?,
         )
         .await
     }
 
     /// Build and broadcasts a transaction with the given message
-    pub async fn execute_contract<T: Serialize>(&self, msg: &T) -> anyhow::Result<String> {
-        let msg_bytes = serde_json::to_vec(msg)
-            .map_err(anyhow::Error::from)
-            .context("Failed to serialize message")?;
-
+    pub async fn execute_contract<T: Serialize>(&self, msg: &T) -> Result<String, ClientError> {
         let contract = self
             .contract
             .clone()
-            .ok_or_else(|| anyhow::anyhow!("No contract address found"))?;
+            .ok_or_else(|| ClientError::not_found("contract address"))?;
+
+        self.execute(contract, msg).await
+    }
+
+    /// Build and broadcasts a transaction executing the given message on any contract,
+    /// e.g. a CW20 or CW721 token contract
+    pub async fn execute_contract_at<T: Serialize>(
+        &self,
+        contract: &str,
+        msg: &T,
+    ) -> Result<String, ClientError> {
+        let contract = AccountId::from_str(contract)
+            .map_err(|e| ClientError::invalid(format!("contract address {}", contract), e))?;
+
+        self.execute(contract, msg).await
+    }
+
+    async fn execute<T: Serialize>(
+        &self,
+        contract: AccountId,
+        msg: &T,
+    ) -> Result<String, ClientError> {
+        let msg = self.execute_msg(contract, msg)?;
+        self.build_and_broadcast_msgs(vec![msg]).await
+    }
+
+    pub(crate) fn execute_msg<T: Serialize>(
+        &self,
+        contract: AccountId,
+        msg: &T,
+    ) -> Result<Any, ClientError> {
+        let msg_bytes = contract_msg_bytes(msg)?;
+        tracing::debug!(
+            "Executing contract {} with msg: {}",
+            contract,
+            logging::text(&String::from_utf8_lossy(&msg_bytes))
+        );
 
         let execute_msg = MsgExecuteContract {
-            sender: self.wallet.account_id.clone(),
-            contract: contract,
+            sender: self.signer()?.account_id.clone(),
+            contract,
             msg: msg_bytes,
             funds: vec![],
         };
 
-        self.build_and_broadcast_tx(
-            execute_msg
-                .to_any()
-                .map_err(|e| anyhow::anyhow!("Failed to convert message to Any: {}", e))?,
-        )
-        .await
+        execute_msg
+            .to_any()
+            .map_err(|e| ClientError::serialization("convert message to Any", e))
     }
 
-    async fn build_and_broadcast_tx<M>(&self, msg: M) -> anyhow::Result<String>
+    async fn build_and_broadcast_tx<M>(&self, msg: M) -> Result<String, ClientError>
     where
         M: Message + Into<Any>,
     {
-        let tx_raw = self.build_tx(msg).await?;
+        self.build_and_broadcast_msgs(vec![msg.into()]).await
+    }
+
+    pub(crate) async fn build_and_broadcast_msgs(
+        &self,
+        msgs: Vec<Any>,
+    ) -> Result<String, ClientError> {
+        let signed_tx = self.sign_msgs(msgs).await?;
+        self.broadcast_signed_tx(&signed_tx).await
+    }
+
+    /// Builds and signs a transaction without broadcasting it, so the exact bytes
+    /// can be stored and re-broadcast later
+    pub async fn sign_tx<M>(&self, msg: M) -> Result<SignedTx, ClientError>
+    where
+        M: Message + Into<Any>,
+    {
+        self.sign_msgs(vec![msg.into()]).await
+    }
+
+    /// Same as `sign_tx` for a transaction carrying multiple messages
+    pub async fn sign_msgs(&self, msgs: Vec<Any>) -> Result<SignedTx, ClientError> {
+        let fee = self.fee_for_gas(self.config.gas_limit)?;
+        self.sign_msgs_with_fee(msgs, fee).await
+    }
+
+    /// Same as `sign_msgs` with an explicit fee, e.g. one returned by `estimate_fee`
+    pub async fn sign_msgs_with_fee(
+        &self,
+        msgs: Vec<Any>,
+        fee: Fee,
+    ) -> Result<SignedTx, ClientError> {
+        let tx_raw = self.build_tx_with_fee(msgs, fee).await?;
 
         let tx_bytes = tx_raw
             .to_bytes()
-            .map_err(|e| anyhow::anyhow!("Failed to serialize transaction: {}", e))?;
+            .map_err(|e| ClientError::serialization("serialize transaction", e))
+            .inspect_err(|_| self.invalidate_account_cache())?;
+
+        Ok(SignedTx::from_bytes(tx_bytes))
+    }
 
-        let response = self.broadcast_tx(tx_bytes).await?;
+    /// Re-submits previously signed tx bytes unchanged, e.g. when a node claims not
+    /// to know the tx. The hash stays the same and no sequence is consumed.
+    pub async fn rebroadcast(&self, tx_bytes: &[u8]) -> Result<String, ClientError> {
+        let signed_tx = SignedTx::from_bytes(tx_bytes.to_vec());
+        tracing::info!("Re-broadcasting transaction {}", signed_tx.tx_hash);
+
+        self.broadcast_signed_tx(&signed_tx).await
+    }
+
+    /// Broadcasts a signed transaction and waits until it is included, failing with
+    /// `ClientError::TxFailed` if its execution failed. If the broadcast request
+    /// itself fails, the transaction is still awaited in case the node received it.
+    pub async fn broadcast_and_wait(
+        &self,
+        signed_tx: &SignedTx,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> Result<TxResponse, ClientError> {
+        match self.broadcast_signed_tx(signed_tx).await {
+            Ok(_) => {}
+            Err(ClientError::BroadcastFailed { tx_hash, source }) => {
+                tracing::warn!(
+                    "Broadcast of transaction {} failed, checking whether it landed: {}",
+                    tx_hash,
+                    source
+                );
+            }
+            Err(e) => return Err(e),
+        }
+
+        self.wait_for_tx(signed_tx.tx_hash.as_str(), timeout, poll_interval)
+            .await
+            .map_err(|e| match e {
+                ClientError::TxFailed(tx_error) => {
+                    ClientError::TxFailed(Box::new(tx_error.or_tx_hash(&signed_tx.tx_hash)))
+                }
+                e => e,
+            })
+    }
+
+    pub(crate) async fn broadcast_signed_tx(
+        &self,
+        signed_tx: &SignedTx,
+    ) -> Result<String, ClientError> {
+        // the cached sequence may be wrong or consumed, refetch it
+        self.try_broadcast_signed_tx(signed_tx)
+            .await
+            .inspect_err(|_| self.invalidate_account_cache())
+    }
+
+    /// Drops the cached sequence, it's fetched again by the next transaction. Call it
+    /// when a transaction returned by `build_tx` or `sign_tx` is never broadcast, so
+    /// its reserved sequence doesn't leave a gap.
+    pub fn invalidate_account_cache(&self) {
+        self.account_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .invalidate();
+    }
+
+    async fn try_broadcast_signed_tx(&self, signed_tx: &SignedTx) -> Result<String, ClientError> {
+        // the hash is known before broadcasting, so a dropped connection doesn't
+        // lose track of a transaction the node may have accepted
+        let response = self
+            .broadcast_tx(signed_tx.tx_bytes.clone())
+            .await
+            .map_err(|e| ClientError::broadcast_failed(&signed_tx.tx_hash, e))?;
         let tx_response = response
             .tx_response
-            .ok_or_else(|| anyhow::anyhow!("Transaction response is empty"))?;
+            .ok_or_else(|| ClientError::not_found("transaction response"))?;
+
+        // identical bytes already in the mempool count as a successful broadcast
+        if TxErrorKind::from_abci(&tx_response.codespace, tx_response.code)
+            == TxErrorKind::TxInMempoolCache
+        {
+            tracing::info!("Transaction {} already in mempool", signed_tx.tx_hash);
+            return Ok(signed_tx.tx_hash.to_string());
+        }
 
         if tx_response.code != 0 {
-            return Err(anyhow::anyhow!(
-                "Transaction failed: {}",
-                tx_response.raw_log
-            ));
+            tracing::warn!(
+                "Transaction {} failed with code {}: {}",
+                tx_response.txhash,
+                tx_response.code,
+                logging::text(&tx_response.raw_log)
+            );
+            return Err(ClientError::TxFailed(Box::new(
+                TransactionError::from(tx_response).or_tx_hash(&signed_tx.tx_hash),
+            )));
         }
 
+        if !tx_response
+            .txhash
+            .eq_ignore_ascii_case(signed_tx.tx_hash.as_str())
+        {
+            tracing::warn!(
+                "Node reported tx hash {} for transaction {}",
+                tx_response.txhash,
+                signed_tx.tx_hash
+            );
+        }
+
+        tracing::info!("Transaction broadcast: {}", tx_response.txhash);
         Ok(tx_response.txhash)
     }
 
     /// Builds and signs a transaction with the given message
-    pub async fn build_tx<M>(&self, msg: M) -> anyhow::Result<Raw>
+    pub async fn build_tx<M>(&self, msg: M) -> Result<Raw, ClientError>
     where
         M: Message + Into<Any>,
     {
-        let account = self
-            .get_account_info(self.wallet.account_id.to_string())
-            .await?;
-        let account_number = account.account_number;
-        let sequence = account.sequence;
+        self.build_tx_msgs(vec![msg.into()]).await
+    }
+
+    /// Builds and signs a transaction with the given messages, executed in order
+    pub async fn build_tx_msgs(&self, msgs: Vec<Any>) -> Result<Raw, ClientError> {
+        let fee = self.fee_for_gas(self.config.gas_limit)?;
+        self.build_tx_with_fee(msgs, fee).await
+    }
 
-        let chain_id = self.config.chain_id.parse().context("Invalid chain ID")?;
+    /// Builds and signs a transaction with the given messages and an explicit fee,
+    /// e.g. one returned by `estimate_fee`. The transaction takes the next sequence,
+    /// see `invalidate_account_cache` if it's never broadcast.
+    pub async fn build_tx_with_fee(&self, msgs: Vec<Any>, fee: Fee) -> Result<Raw, ClientError> {
+        let (account_number, sequence) = self.next_sequence(true).await?;
+
+        self.build_tx_with_sequence(msgs, fee, account_number, sequence)
+            .inspect_err(|_| self.invalidate_account_cache())
+    }
 
-        let fee = Coin {
-            amount: self.config.fee_amount,
-            denom: Denom::from_str(&self.config.denom)
-                .map_err(|e| anyhow::anyhow!("Invalid denom: {}", e))?,
+    /// Returns the account number and the next sequence of the signer, from the
+    /// account cache while it's fresh. With `reserve`, the sequence is taken for a
+    /// new transaction, otherwise it's only used e.g. to simulate one.
+    pub(crate) async fn next_sequence(&self, reserve: bool) -> Result<(u64, u64), ClientError> {
+        let address = self.signer()?.account_id.to_string();
+        let ttl = self.account_cache_ttl;
+        let take = |cache: &mut AccountCache| {
+            if reserve {
+                cache.reserve(&address, ttl)
+            } else {
+                cache.get(&address, ttl)
+            }
         };
-        let fee = Fee::from_amount_and_gas(fee, self.config.gas_limit);
+        if let Some(account) =
+            take(&mut self.account_cache.lock().unwrap_or_else(|e| e.into_inner()))
+        {
+            return Ok(account);
+        }
 
-        let tx_body = BodyBuilder::new().msg(msg).finish();
+        let account = self.get_account_info(address.clone()).await?;
+        let mut cache = self.account_cache.lock().unwrap_or_else(|e| e.into_inner());
+        cache.update(&address, account.account_number, account.sequence);
+        Ok(take(&mut cache).unwrap_or((account.account_number, account.sequence)))
+    }
 
-        let auth_info = SignerInfo::single_direct(Some(self.wallet.public_key.clone()), sequence)
-            .auth_info(fee);
+    /// Builds and signs a transaction without querying the account, for callers
+    /// tracking the sequence themselves
+    pub fn build_tx_with_sequence(
+        &self,
+        msgs: Vec<Any>,
+        fee: Fee,
+        account_number: u64,
+        sequence: u64,
+    ) -> Result<Raw, ClientError> {
+        let wallet = self.signer()?;
+        let chain_id = self
+            .config
+            .chain_id
+            .parse()
+            .map_err(|e| ClientError::invalid("chain ID", e))?;
+
+        let mut body = BodyBuilder::new();
+        body.msgs(msgs);
+        for option in &self.extension_options {
+            body.extension_option(option.clone());
+        }
+        for option in &self.non_critical_extension_options {
+            body.non_critical_extension_option(option.clone());
+        }
+        let tx_body = body.finish();
+
+        let auth_info = SignerInfo::single_direct(Some(wallet.public_key), sequence).auth_info(fee);
 
         let sign_doc = SignDoc::new(&tx_body, &auth_info, &chain_id, account_number)
-            .map_err(|e| anyhow::anyhow!("Failed to create sign doc: {}", e))?;
+            .map_err(|e| ClientError::wallet("create sign doc", e))?;
 
-        self.wallet.sign(sign_doc)
+        wallet.sign(sign_doc)
+    }
+}
+
+#[cfg(all(feature = "wallet", feature = "babylon"))]
+impl CosmWasmClient {
+    /// Relays BTC headers to the Babylon btclightclient module. Headers are hex encoded
+    /// 80-byte block headers, ordered from lowest to highest height.
+    pub async fn insert_headers(&self, headers: &[String]) -> Result<String, ClientError> {
+        let msg = insert_headers_msg(self.signer()?.account_id.as_ref(), headers)?;
+        tracing::debug!("Inserting {} BTC headers", headers.len());
+
+        self.build_and_broadcast_tx(Any {
+            type_url: MSG_INSERT_HEADERS_TYPE_URL.to_string(),
+            value: msg.encode_to_vec(),
+        })
+        .await
+    }
+}
+
+/// Returns the txid of a serialized BTC transaction in the usual display order.
+/// Witness data is stripped, so the txid of segwit transactions is also correct.
+pub fn compute_txid(tx: &[u8]) -> Result<String, ClientError> {
+    let mut reader = TxReader { tx, pos: 0 };
+    let segwit = tx.len() > 6 && tx[4] == 0 && tx[5] != 0;
+
+    let mut stripped = reader.take(4)?.to_vec();
+    if segwit {
+        reader.take(2)?;
+    }
+
+    let body_start = reader.pos;
+    let input_count = reader.varint()?;
+    for _ in 0..input_count {
+        reader.take(36)?;
+        let script_len = reader.varint()?;
+        reader.take(script_len)?;
+        reader.take(4)?;
+    }
+    let output_count = reader.varint()?;
+    for _ in 0..output_count {
+        reader.take(8)?;
+        let script_len = reader.varint()?;
+        reader.take(script_len)?;
+    }
+    stripped.extend_from_slice(&tx[body_start..reader.pos]);
+
+    if segwit {
+        for _ in 0..input_count {
+            let items = reader.varint()?;
+            for _ in 0..items {
+                let item_len = reader.varint()?;
+                reader.take(item_len)?;
+            }
+        }
+    }
+    stripped.extend_from_slice(reader.take(4)?);
+    if reader.pos != tx.len() {
+        return Err(ClientError::invalid(
+            "BTC transaction",
+            "trailing bytes after the lock time",
+        ));
+    }
+
+    let mut txid = Sha256::digest(Sha256::digest(&stripped)).to_vec();
+    txid.reverse();
+    Ok(hex::encode(txid))
+}
+
+struct TxReader<'a> {
+    tx: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> TxReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ClientError> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.tx.len())
+            .ok_or_else(|| ClientError::invalid("BTC transaction", "truncated"))?;
+        let bytes = &self.tx[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ClientError> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn varint(&mut self) -> Result<usize, ClientError> {
+        let value = match self.take(1)?[0] {
+            0xfd => u16::from_le_bytes(self.array()?) as u64,
+            0xfe => u32::from_le_bytes(self.array()?) as u64,
+            0xff => u64::from_le_bytes(self.array()?),
+            n => n as u64,
+        };
+        usize::try_from(value)
+            .map_err(|_| ClientError::invalid("BTC transaction", "varint out of range"))
+    }
+}
+
+#[cfg(all(feature = "wallet", feature = "babylon"))]
+fn insert_headers_msg(signer: &str, headers: &[String]) -> Result<MsgInsertHeaders, ClientError> {
+    if headers.is_empty() {
+        return Err(ClientError::invalid("headers", "no headers to insert"));
+    }
+
+    let headers = headers
+        .iter()
+        .map(|header| {
+            let bytes = hex::decode(header).map_err(|e| ClientError::invalid("header hex", e))?;
+            if bytes.len() != BTC_HEADER_LEN {
+                return Err(ClientError::invalid(
+                    "header length",
+                    format!("expected {} bytes, got {}", BTC_HEADER_LEN, bytes.len()),
+                ));
+            }
+            Ok(bytes)
+        })
+        .collect::<Result<Vec<_>, ClientError>>()?;
+
+    Ok(MsgInsertHeaders {
+        signer: signer.to_string(),
+        headers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signed_tx_hash() {
+        let signed_tx = SignedTx::from_bytes(b"abc".to_vec());
+        assert_eq!(
+            signed_tx.tx_hash.as_str(),
+            "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD"
+        );
+        assert_eq!(SignedTx::from_bytes(b"abc".to_vec()), signed_tx);
+
+        let parsed: TxHash = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+            .parse()
+            .unwrap();
+        assert_eq!(parsed, signed_tx.tx_hash);
+        assert!("ba78".parse::<TxHash>().is_err());
+    }
+
+    #[cfg(feature = "wallet")]
+    #[test]
+    fn test_account_cache() {
+        let ttl = Duration::from_secs(60);
+        let mut cache = AccountCache::default();
+        assert_eq!(cache.reserve("bbn1a", ttl), None);
+
+        cache.update("bbn1a", 7, 3);
+        assert_eq!(cache.get("bbn1a", ttl), Some((7, 3)));
+        assert_eq!(cache.reserve("bbn1a", ttl), Some((7, 3)));
+        assert_eq!(cache.reserve("bbn1a", ttl), Some((7, 4)));
+        assert_eq!(cache.reserve("bbn1b", ttl), None);
+
+        // a concurrent fetch doesn't rewind the reserved sequences
+        cache.update("bbn1a", 7, 3);
+        assert_eq!(cache.reserve("bbn1a", ttl), Some((7, 5)));
+
+        cache.invalidate();
+        assert_eq!(cache.reserve("bbn1a", ttl), None);
+        cache.update("bbn1a", 7, 4);
+        assert_eq!(cache.reserve("bbn1a", Duration::ZERO), None);
+    }
+
+    #[cfg(feature = "wallet")]
+    #[test]
+    fn test_extension_options() {
+        let option = Any {
+            type_url: "/ethermint.types.v1.ExtensionOptionDynamicFeeTx".to_string(),
+            value: vec![1, 2, 3],
+        };
+        let client = crate::client::tests::test_client("http://localhost:9090")
+            .with_extension_option(option.clone());
+
+        let fee = client.fee_for_gas(200_000).unwrap();
+        let tx_bytes = client
+            .build_tx_with_sequence(vec![], fee, 7, 3)
+            .unwrap()
+            .to_bytes()
+            .unwrap();
+        let tx = cosmrs::Tx::from_bytes(&tx_bytes).unwrap();
+        assert_eq!(tx.body.extension_options, vec![option]);
+        assert!(tx.body.non_critical_extension_options.is_empty());
+    }
+
+    #[cfg(all(feature = "wallet", feature = "babylon"))]
+    #[test]
+    fn test_insert_headers_msg() {
+        let header = "0".repeat(BTC_HEADER_LEN * 2);
+        let msg = insert_headers_msg("bbn1signer", &[header.clone(), header]).unwrap();
+        assert_eq!(msg.signer, "bbn1signer");
+        assert_eq!(msg.headers.len(), 2);
+        assert_eq!(msg.headers[0].len(), BTC_HEADER_LEN);
+
+        assert!(insert_headers_msg("bbn1signer", &[]).is_err());
+        assert!(insert_headers_msg("bbn1signer", &["00".to_string()]).is_err());
+        assert!(insert_headers_msg("bbn1signer", &["zz".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_peg_in_batch_request() {
+        let req = PegInRequest {
+            sender_btc_pk: "02ab".to_string(),
+            recipient: "bbn1recipient".to_string(),
+            amount: 1000,
+            block_hash: "00ff".to_string(),
+            pegin_tx: "0100".to_string(),
+            pegin_tx_idx: 1,
+            pegin_tx_merkle_proof: vec![],
+        };
+        let batch = PegInBatch {
+            tx_hash: "HASH".to_string(),
+            requests: (0u32..).zip(vec![req.clone(), req.clone()]).collect(),
+        };
+        assert_eq!(batch.request(1), Some(&req));
+        assert_eq!(batch.request(2), None);
+
+        match ExecuteMsg::from(req) {
+            ExecuteMsg::PegIn { amount, .. } => assert_eq!(amount, Uint128::new(1000)),
+            _ => panic!("expected PegIn"),
+        }
+    }
+
+    #[test]
+    fn test_compute_txid() {
+        // BTC genesis coinbase
+        let body = "010000000000000000000000000000000000000000000000000000000000000000ffffffff4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac";
+        let txid = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+
+        let legacy = hex::decode(format!("01000000{}00000000", body)).unwrap();
+        assert_eq!(compute_txid(&legacy).unwrap(), txid);
+
+        // the same tx with a single witness item keeps its txid
+        let segwit = hex::decode(format!("010000000001{}0101ab00000000", body)).unwrap();
+        assert_eq!(compute_txid(&segwit).unwrap(), txid);
+
+        assert!(compute_txid(&legacy[..legacy.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_versioned_execute_msg() {
+        let pause = VersionedExecuteMsg::new(ExecuteMsg::Pause {}, BridgeSchema::V2).unwrap();
+        assert_eq!(
+            String::from_utf8(contract_msg_bytes(&pause).unwrap()).unwrap(),
+            r#"{"pause":{}}"#
+        );
+
+        // BTC genesis coinbase
+        let pegin_tx = "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000";
+        let peg_in = ExecuteMsg::PegIn {
+            sender_btc_pk: "02bb".to_string(),
+            receiver_address: Addr::unchecked("bbn1receiver"),
+            amount: Uint128::new(1000),
+            btc_block_hash: "00ff".to_string(),
+            pegin_tx: pegin_tx.to_string(),
+            pegin_tx_idx: 0,
+            pegin_tx_merkle_proof: vec![],
+        };
+        match VersionedExecuteMsg::new(peg_in.clone(), BridgeSchema::V2).unwrap() {
+            VersionedExecuteMsg::V2(ExecuteMsgV2::PegIn { pegin_txid, .. }) => assert_eq!(
+                pegin_txid,
+                "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b"
+            ),
+            msg => panic!("expected a V2 PegIn, got {:?}", msg),
+        }
+        assert_eq!(
+            VersionedExecuteMsg::new(peg_in.clone(), BridgeSchema::V1).unwrap(),
+            VersionedExecuteMsg::V1(peg_in)
+        );
+
+        let invalid = ExecuteMsg::PegIn {
+            sender_btc_pk: "02bb".to_string(),
+            receiver_address: Addr::unchecked("bbn1receiver"),
+            amount: Uint128::new(1000),
+            btc_block_hash: "00ff".to_string(),
+            pegin_tx: "zz".to_string(),
+            pegin_tx_idx: 0,
+            pegin_tx_merkle_proof: vec![],
+        };
+        assert!(VersionedExecuteMsg::new(invalid, BridgeSchema::V2).is_err());
+    }
+
+    #[test]
+    fn test_admin_msg_format() {
+        assert_eq!(
+            serde_json::to_string(&ExecuteMsg::Pause {}).unwrap(),
+            r#"{"pause":{}}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&ExecuteMsg::UpdateParams {
+                btc_confirmation_depth: Some(6)
+            })
+            .unwrap(),
+            r#"{"update_params":{"btc_confirmation_depth":6}}"#
+        );
+    }
+
+    #[test]
+    fn test_contract_msg_golden_bytes() {
+        let operator = Operator {
+            btc_pk: "02aa".to_string(),
+            address: Addr::unchecked("bbn1operator"),
+        };
+        let golden: Vec<(Vec<u8>, &str)> = vec![
+            (
+                contract_msg_bytes(&InstantiateMsg {
+                    cw20_code_id: 1,
+                    denom: "bbtc".to_string(),
+                    btc_confirmation_depth: 6,
+                    operators: vec![operator.clone()],
+                })
+                .unwrap(),
+                r#"{"cw20_code_id":1,"denom":"bbtc","btc_confirmation_depth":6,"operators":[{"btc_pk":"02aa","address":"bbn1operator"}]}"#,
+            ),
+            (
+                contract_msg_bytes(&ExecuteMsg::PegIn {
+                    sender_btc_pk: "02bb".to_string(),
+                    receiver_address: Addr::unchecked("bbn1receiver"),
+                    amount: Uint128::new(1000),
+                    btc_block_hash: "00ff".to_string(),
+                    pegin_tx: "0100".to_string(),
+                    pegin_tx_idx: 2,
+                    pegin_tx_merkle_proof: vec!["ab".to_string(), "cd".to_string()],
+                })
+                .unwrap(),
+                r#"{"peg_in":{"sender_btc_pk":"02bb","receiver_address":"bbn1receiver","amount":"1000","btc_block_hash":"00ff","pegin_tx":"0100","pegin_tx_idx":2,"pegin_tx_merkle_proof":["ab","cd"]}}"#,
+            ),
+            (
+                contract_msg_bytes(&ExecuteMsg::PegOut {
+                    btc_address: "tb1qaddress".to_string(),
+                    fee_rate: 10,
+                    amount: Uint128::new(500),
+                    operator_btc_pk: "02aa".to_string(),
+                })
+                .unwrap(),
+                r#"{"peg_out":{"btc_address":"tb1qaddress","fee_rate":10,"amount":"500","operator_btc_pk":"02aa"}}"#,
+            ),
+            (
+                contract_msg_bytes(&ExecuteMsg::UpdateOperators {
+                    operators: vec![operator],
+                })
+                .unwrap(),
+                r#"{"update_operators":{"operators":[{"btc_pk":"02aa","address":"bbn1operator"}]}}"#,
+            ),
+            (
+                contract_msg_bytes(&ExecuteMsg::UpdateParams {
+                    btc_confirmation_depth: None,
+                })
+                .unwrap(),
+                r#"{"update_params":{"btc_confirmation_depth":null}}"#,
+            ),
+            (
+                contract_msg_bytes(&ExecuteMsg::Pause {}).unwrap(),
+                r#"{"pause":{}}"#,
+            ),
+            (
+                contract_msg_bytes(&ExecuteMsg::Unpause {}).unwrap(),
+                r#"{"unpause":{}}"#,
+            ),
+        ];
+
+        for (bytes, expected) in golden {
+            assert_eq!(String::from_utf8(bytes).unwrap(), expected);
+        }
     }
 }
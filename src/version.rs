@@ -0,0 +1,213 @@
+use cosmwasm_schema::cw_serde;
+use semver::{Version, VersionReq};
+
+use crate::client::CosmWasmClient;
+use crate::error::ClientError;
+
+/// Bridge contract versions whose message schema is supported, see `BridgeSchema`
+pub const SUPPORTED_BRIDGE_VERSIONS: &str = ">=0.1.0, <0.2.0";
+
+/// cw2 storage key holding the contract name and version
+const CW2_CONTRACT_INFO_KEY: &[u8] = b"contract_info";
+
+/// Contract name and version as stored by cw2
+#[cw_serde]
+pub struct ContractVersion {
+    /// contract is the crate name of the contract, e.g. `crates.io:bridge`
+    pub contract: String,
+    /// version is the semver version of the contract
+    pub version: String,
+}
+
+/// Message schema spoken by a bridge contract version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum BridgeSchema {
+    /// `transactions::ExecuteMsgV1`, contracts `0.1.x`
+    #[default]
+    V1,
+    /// `transactions::ExecuteMsgV2`, a draft of the schema planned for contracts
+    /// `0.2.x` that isn't checked against a released contract yet. It's never
+    /// detected by `handshake`, only selected explicitly with `with_bridge_schema`.
+    V2,
+}
+
+impl BridgeSchema {
+    /// Returns the contract versions speaking this schema, or planned to for a draft
+    pub fn versions(self) -> &'static str {
+        match self {
+            Self::V1 => ">=0.1.0, <0.2.0",
+            Self::V2 => ">=0.2.0, <0.3.0",
+        }
+    }
+
+    /// Returns the schema of the given contract version, `None` if unsupported
+    pub fn from_version(version: &str) -> Result<Option<Self>, ClientError> {
+        let version = parse_version(version)?;
+
+        Ok([Self::V1].into_iter().find(|schema| {
+            VersionReq::parse(schema.versions())
+                .expect("valid schema version range")
+                .matches(&version)
+        }))
+    }
+}
+
+/// What to do when the contract version is not supported by this crate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HandshakeMode {
+    /// Return an error
+    #[default]
+    Enforce,
+    /// Log a warning and continue
+    Warn,
+    /// Don't query the contract version at all
+    Skip,
+}
+
+/// Returns true if the given contract version is supported by this crate
+pub fn is_supported_version(version: &str) -> Result<bool, ClientError> {
+    let version = parse_version(version)?;
+    let supported =
+        VersionReq::parse(SUPPORTED_BRIDGE_VERSIONS).expect("valid supported version range");
+
+    Ok(supported.matches(&version))
+}
+
+fn parse_version(version: &str) -> Result<Version, ClientError> {
+    Version::parse(version.trim_start_matches('v'))
+        .map_err(|e| ClientError::invalid(format!("contract version {}", version), e))
+}
+
+impl CosmWasmClient {
+    /// Returns the cw2 version info of the bridge contract
+    pub async fn query_contract_version(&self) -> Result<ContractVersion, ClientError> {
+        let contract = self
+            .contract
+            .as_ref()
+            .ok_or_else(|| ClientError::not_found("contract address"))?;
+
+        let data = self
+            .query_raw(contract.as_ref(), CW2_CONTRACT_INFO_KEY)
+            .await?
+            .ok_or_else(|| ClientError::not_found(format!("cw2 version info of {}", contract)))?;
+
+        serde_json::from_slice(&data)
+            .map_err(|e| ClientError::serialization("deserialize contract version", e))
+    }
+
+    /// Returns the message schema of the bridge contract from its cw2 version,
+    /// `None` if the version is not supported by this crate
+    pub async fn query_bridge_schema(&self) -> Result<Option<BridgeSchema>, ClientError> {
+        let version = self.query_contract_version().await?;
+        BridgeSchema::from_version(&version.version)
+    }
+
+    /// Returns the message schema used for bridge transactions, `BridgeSchema::V1`
+    /// until detected by `handshake` or set with `with_bridge_schema`
+    pub fn bridge_schema(&self) -> BridgeSchema {
+        *self.bridge_schema.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Sets the message schema of the bridge contract, e.g. with `HandshakeMode::Skip`
+    pub fn with_bridge_schema(self, schema: BridgeSchema) -> Self {
+        *self
+            .bridge_schema
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = schema;
+        self
+    }
+
+    /// Checks that the bridge contract speaks a message schema supported by this crate
+    /// and switches the bridge transactions of the client and its clones to it.
+    /// Should be called once at startup, before submitting any transaction. With
+    /// `HandshakeMode::Warn` a missing or unparseable cw2 version only logs a warning
+    /// and keeps the current schema.
+    pub async fn handshake(
+        &self,
+        mode: HandshakeMode,
+    ) -> Result<Option<ContractVersion>, ClientError> {
+        if mode == HandshakeMode::Skip {
+            return Ok(None);
+        }
+
+        let detected = self.query_contract_version().await.and_then(|version| {
+            BridgeSchema::from_version(&version.version).map(|schema| (version, schema))
+        });
+        let (version, schema) = match (mode, detected) {
+            // a missing or unreadable cw2 entry is only fatal when enforcing
+            (
+                HandshakeMode::Warn,
+                Err(
+                    e @ (ClientError::NotFound { .. }
+                    | ClientError::Invalid { .. }
+                    | ClientError::Serialization { .. }),
+                ),
+            ) => {
+                tracing::warn!(
+                    "Failed to read the bridge contract version, keeping schema {:?}: {}",
+                    self.bridge_schema(),
+                    e
+                );
+                return Ok(None);
+            }
+            (_, detected) => detected?,
+        };
+
+        if let Some(schema) = schema {
+            tracing::info!(
+                "Bridge contract {} {} is supported, using schema {:?}",
+                version.contract,
+                version.version,
+                schema
+            );
+            *self
+                .bridge_schema
+                .write()
+                .unwrap_or_else(|e| e.into_inner()) = schema;
+            return Ok(Some(version));
+        }
+
+        match mode {
+            HandshakeMode::Enforce => Err(ClientError::invalid(
+                "bridge contract version",
+                format!(
+                    "{} {} is unsupported, supported: {}",
+                    version.contract, version.version, SUPPORTED_BRIDGE_VERSIONS
+                ),
+            )),
+            _ => {
+                tracing::warn!(
+                    "Bridge contract {} {} is outside of the supported range {}",
+                    version.contract,
+                    version.version,
+                    SUPPORTED_BRIDGE_VERSIONS
+                );
+                Ok(Some(version))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_supported_version() {
+        assert!(is_supported_version("0.1.0").unwrap());
+        assert!(is_supported_version("v0.1.3").unwrap());
+        assert!(!is_supported_version("0.2.0").unwrap());
+        assert!(!is_supported_version("1.0.0").unwrap());
+        assert!(is_supported_version("not-a-version").is_err());
+    }
+
+    #[test]
+    fn test_bridge_schema_from_version() {
+        assert_eq!(
+            BridgeSchema::from_version("0.1.4").unwrap(),
+            Some(BridgeSchema::V1)
+        );
+        // the draft V2 schema is never detected
+        assert_eq!(BridgeSchema::from_version("v0.2.0").unwrap(), None);
+    }
+}
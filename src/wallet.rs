@@ -1,10 +1,11 @@
-use anyhow::Context;
 use cosmrs::{
     crypto::{secp256k1::SigningKey, PublicKey},
     tx::{Raw, SignDoc},
     AccountId,
 };
 
+use crate::error::ClientError;
+
 #[derive(Debug, Clone)]
 pub struct Wallet {
     pub private_key: Vec<u8>,
@@ -13,16 +14,17 @@ pub struct Wallet {
 }
 
 impl Wallet {
-    pub fn new(private_key: &str, account_prefix: &str) -> anyhow::Result<Self> {
-        let private_key = hex::decode(private_key).context("Invalid private key hex format")?;
+    pub fn new(private_key: &str, account_prefix: &str) -> Result<Self, ClientError> {
+        let private_key = hex::decode(private_key)
+            .map_err(|e| ClientError::wallet("decode private key hex", e))?;
 
         let signing_key = SigningKey::from_slice(&private_key)
-            .map_err(|e| anyhow::anyhow!("Failed to parse signing key: {e}"))?;
+            .map_err(|e| ClientError::wallet("parse signing key", e))?;
 
         let public_key = signing_key.public_key();
         let account_id = public_key
             .account_id(account_prefix)
-            .map_err(|e| anyhow::anyhow!("Failed to generate account ID: {e}"))?;
+            .map_err(|e| ClientError::wallet("generate account ID", e))?;
 
         Ok(Self {
             private_key,
@@ -31,12 +33,12 @@ impl Wallet {
         })
     }
 
-    pub fn sign(&self, sign_doc: SignDoc) -> anyhow::Result<Raw> {
+    pub fn sign(&self, sign_doc: SignDoc) -> Result<Raw, ClientError> {
         let signing_key = SigningKey::from_slice(&self.private_key)
-            .map_err(|e| anyhow::anyhow!("Failed to parse signing key: {e}"))?;
+            .map_err(|e| ClientError::wallet("parse signing key", e))?;
 
         sign_doc
             .sign(&signing_key)
-            .map_err(|e| anyhow::anyhow!("Failed to sign transaction: {e}"))
+            .map_err(|e| ClientError::wallet("sign transaction", e))
     }
 }
@@ -0,0 +1,100 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::client::{CosmWasmApi, CosmWasmClient};
+use crate::error::ClientError;
+use crate::wallet::Wallet;
+
+/// Clients signing with different funded wallets on the same chain, handed out
+/// round-robin so many transactions can be in flight at once without waiting on
+/// the sequence of a single account
+pub struct WalletPool {
+    clients: Vec<CosmWasmClient>,
+    next: AtomicUsize,
+}
+
+impl WalletPool {
+    /// Creates a client per hex private key, sharing the endpoints and settings of
+    /// `client`
+    pub fn new<I, S>(client: &CosmWasmClient, private_keys: I) -> Result<Self, ClientError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let wallets = private_keys
+            .into_iter()
+            .map(|private_key| Wallet::new(private_key.as_ref(), &client.config.account_prefix))
+            .collect::<Result<Vec<_>, ClientError>>()?;
+        Self::from_wallets(client, wallets)
+    }
+
+    pub fn from_wallets(
+        client: &CosmWasmClient,
+        wallets: Vec<Wallet>,
+    ) -> Result<Self, ClientError> {
+        if wallets.is_empty() {
+            return Err(ClientError::invalid("wallet pool", "no wallets"));
+        }
+        let mut clients: Vec<CosmWasmClient> = Vec::with_capacity(wallets.len());
+        for wallet in wallets {
+            if clients
+                .iter()
+                .any(|client| client.sender() == wallet.account_id.as_ref())
+            {
+                return Err(ClientError::invalid(
+                    "wallet pool",
+                    format!("duplicate wallet {}", wallet.account_id),
+                ));
+            }
+            clients.push(client.clone().with_wallet(wallet));
+        }
+
+        Ok(Self {
+            clients,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    /// Returns the client of the next wallet in turn
+    pub fn next(&self) -> &CosmWasmClient {
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.clients.len();
+        &self.clients[index]
+    }
+
+    pub fn clients(&self) -> &[CosmWasmClient] {
+        &self.clients
+    }
+
+    pub fn addresses(&self) -> Vec<String> {
+        self.clients.iter().map(|client| client.sender()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::readonly_client;
+
+    #[test]
+    fn test_wallet_pool() {
+        let client = readonly_client("http://localhost:9090");
+
+        let pool = WalletPool::new(&client, ["01".repeat(32), "02".repeat(32)]).unwrap();
+        let addresses = pool.addresses();
+        assert_eq!(pool.len(), 2);
+        assert_ne!(addresses[0], addresses[1]);
+        assert_eq!(pool.next().sender(), addresses[0]);
+        assert_eq!(pool.next().sender(), addresses[1]);
+        assert_eq!(pool.next().sender(), addresses[0]);
+
+        assert!(WalletPool::new(&client, Vec::<String>::new()).is_err());
+        assert!(WalletPool::new(&client, ["01".repeat(32), "01".repeat(32)]).is_err());
+    }
+}
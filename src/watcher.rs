@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::client::{CosmWasmApi, CosmWasmClient};
+use crate::error::ClientError;
+
+/// Default interval between two polls of the watched state
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(6);
+/// Default number of changes buffered by `StateWatcher::spawn` before polling waits
+const DEFAULT_CHANGE_BUFFER: usize = 100;
+
+/// Contract state read by a `StateWatcher`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchedState {
+    /// Raw value stored under the key, e.g. a cw-storage-plus `Item`
+    Raw(Vec<u8>),
+    /// Smart query, compared by its JSON response
+    Smart(serde_json::Value),
+}
+
+/// Change of a watched state between two polls
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateChange {
+    /// name identifies the watched state, as given to `watch_raw` or `watch_smart`
+    pub name: String,
+    /// previous is the value at the previous poll, `None` if the raw key was unset
+    pub previous: Option<Vec<u8>>,
+    /// current is the new value, `None` if the raw key has been removed
+    pub current: Option<Vec<u8>>,
+}
+
+impl StateChange {
+    /// Deserializes the previous JSON value
+    pub fn previous_as<T: DeserializeOwned>(&self) -> Result<Option<T>, ClientError> {
+        decode(&self.name, self.previous.as_deref())
+    }
+
+    /// Deserializes the current JSON value
+    pub fn current_as<T: DeserializeOwned>(&self) -> Result<Option<T>, ClientError> {
+        decode(&self.name, self.current.as_deref())
+    }
+}
+
+fn decode<T: DeserializeOwned>(name: &str, value: Option<&[u8]>) -> Result<Option<T>, ClientError> {
+    value
+        .map(|value| {
+            serde_json::from_slice(value).map_err(|e| {
+                ClientError::serialization(format!("deserialize watched state {}", name), e)
+            })
+        })
+        .transpose()
+}
+
+/// Polls selected raw keys and smart queries of a contract and reports the values
+/// that changed since the previous poll, for state that isn't reflected in events.
+/// The first poll records the initial values without reporting them.
+pub struct StateWatcher<A: CosmWasmApi> {
+    client: A,
+    contract: String,
+    targets: Vec<(String, WatchedState)>,
+    poll_interval: Duration,
+    /// values are the values read at the last poll by target name
+    values: HashMap<String, Option<Vec<u8>>>,
+    initialized: bool,
+}
+
+impl<A: CosmWasmApi> StateWatcher<A> {
+    pub fn new(client: A, contract: &str) -> Self {
+        Self {
+            client,
+            contract: contract.to_string(),
+            targets: vec![],
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            values: HashMap::new(),
+            initialized: false,
+        }
+    }
+
+    /// Watches the raw value stored under `key` in the contract storage. Fails if
+    /// `name` is already watched.
+    pub fn watch_raw(self, name: &str, key: &[u8]) -> Result<Self, ClientError> {
+        self.watch(name, WatchedState::Raw(key.to_vec()))
+    }
+
+    /// Watches the response of the smart query `msg`. Fails if `name` is already watched.
+    pub fn watch_smart<M: Serialize>(self, name: &str, msg: &M) -> Result<Self, ClientError> {
+        let msg = serde_json::to_value(msg)
+            .map_err(|e| ClientError::serialization("serialize query message", e))?;
+        self.watch(name, WatchedState::Smart(msg))
+    }
+
+    fn watch(mut self, name: &str, state: WatchedState) -> Result<Self, ClientError> {
+        if self.targets.iter().any(|(watched, _)| watched == name) {
+            return Err(ClientError::invalid(
+                format!("watched state {}", name),
+                "the name is already watched",
+            ));
+        }
+        self.targets.push((name.to_string(), state));
+        Ok(self)
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Reads every watched state once and returns the changes since the previous
+    /// poll, in the order the states are watched. Nothing is recorded if a read fails.
+    pub async fn poll(&mut self) -> Result<Vec<StateChange>, ClientError> {
+        let mut values = HashMap::with_capacity(self.targets.len());
+        for (name, state) in &self.targets {
+            let value =
+                match state {
+                    WatchedState::Raw(key) => self.client.query_raw(&self.contract, key).await?,
+                    WatchedState::Smart(msg) => {
+                        let response = self.client.query_json(&self.contract, msg.clone()).await?;
+                        Some(serde_json::to_vec(&response).map_err(|e| {
+                            ClientError::serialization("serialize query response", e)
+                        })?)
+                    }
+                };
+            values.insert(name.clone(), value);
+        }
+
+        let changes = if self.initialized {
+            self.targets
+                .iter()
+                .filter_map(|(name, _)| {
+                    let previous = self.values.get(name).cloned().flatten();
+                    let current = values.get(name).cloned().flatten();
+                    (previous != current).then(|| StateChange {
+                        name: name.clone(),
+                        previous,
+                        current,
+                    })
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+        self.values = values;
+        self.initialized = true;
+
+        Ok(changes)
+    }
+
+    /// Polls every `poll_interval` and sends the changes until the receiver is
+    /// dropped. Failed polls are logged and retried at the next interval.
+    pub async fn run(mut self, sender: mpsc::Sender<StateChange>) {
+        let mut ticker = tokio::time::interval(self.poll_interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = sender.closed() => break,
+            }
+
+            match self.poll().await {
+                Ok(changes) => {
+                    for change in changes {
+                        tracing::debug!(
+                            "Watched state {} of {} changed",
+                            change.name,
+                            self.contract
+                        );
+                        if sender.send(change).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to poll the state of {}: {}", self.contract, e),
+            }
+        }
+
+        tracing::info!("State watcher of {} stopped", self.contract);
+    }
+}
+
+impl<A: CosmWasmApi + 'static> StateWatcher<A> {
+    /// Starts the watcher task and returns the receiver of the changes, the task
+    /// stops once the receiver is dropped
+    pub fn spawn(self) -> (mpsc::Receiver<StateChange>, JoinHandle<()>) {
+        let (sender, receiver) = mpsc::channel(DEFAULT_CHANGE_BUFFER);
+        (receiver, tokio::spawn(self.run(sender)))
+    }
+}
+
+impl CosmWasmClient {
+    /// Returns a watcher of the bridge contract state, see `StateWatcher`
+    pub fn state_watcher(&self) -> Result<StateWatcher<CosmWasmClient>, ClientError> {
+        let contract = self
+            .contract
+            .as_ref()
+            .ok_or_else(|| ClientError::not_found("contract address"))?
+            .to_string();
+
+        Ok(StateWatcher::new(self.clone(), &contract))
+    }
+}
+
+#[cfg(all(test, feature = "babylon", feature = "wallet"))]
+mod tests {
+    use super::*;
+    use crate::mock::MockCosmWasmClient;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_state_watcher() {
+        let mock = MockCosmWasmClient::new("bbn1sender");
+        mock.set_raw_state("bbn1contract", b"paused", b"false");
+        mock.set_query_response(
+            "bbn1contract",
+            json!({ "operators": {} }),
+            json!({ "operators": ["02aa"] }),
+        );
+        let mut watcher = StateWatcher::new(mock.clone(), "bbn1contract")
+            .watch_raw("paused", b"paused")
+            .unwrap()
+            .watch_raw("params", b"params")
+            .unwrap()
+            .watch_smart("operators", &json!({ "operators": {} }))
+            .unwrap();
+        // a name identifies one watched state
+        assert!(StateWatcher::new(mock.clone(), "bbn1contract")
+            .watch_raw("paused", b"paused")
+            .unwrap()
+            .watch_smart("paused", &json!({ "paused": {} }))
+            .is_err());
+
+        assert!(watcher.poll().await.unwrap().is_empty());
+        assert!(watcher.poll().await.unwrap().is_empty());
+
+        mock.set_raw_state("bbn1contract", b"paused", b"true");
+        mock.set_raw_state("bbn1contract", b"params", b"{\"depth\":6}");
+        mock.set_query_response(
+            "bbn1contract",
+            json!({ "operators": {} }),
+            json!({ "operators": ["02aa", "02bb"] }),
+        );
+        let changes = watcher.poll().await.unwrap();
+        assert_eq!(
+            changes.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+            vec!["paused", "params", "operators"]
+        );
+        assert_eq!(changes[0].previous_as::<bool>().unwrap(), Some(false));
+        assert_eq!(changes[0].current_as::<bool>().unwrap(), Some(true));
+        assert_eq!(changes[1].previous, None);
+        assert_eq!(
+            changes[2].current_as::<serde_json::Value>().unwrap(),
+            Some(json!({ "operators": ["02aa", "02bb"] }))
+        );
+
+        assert!(watcher.poll().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,293 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::error::ClientError;
+use crate::events::{BackoffPolicy, BlockEvents};
+use crate::sink::EventSink;
+
+/// Header carrying the unix timestamp in seconds the request was signed at
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+/// Header carrying `sha256=<hex HMAC-SHA256 of "<timestamp>.<body>">`
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+/// Header carrying the height of the delivered block
+pub const HEIGHT_HEADER: &str = "X-Webhook-Height";
+
+/// Delivery settings of a `WebhookSink`
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// max_retries is the number of retries after the first failed attempt
+    /// before the block is reported as failed to the listener
+    pub max_retries: u32,
+    pub backoff: BackoffPolicy,
+    /// timeout is the timeout of a single request
+    pub timeout: Duration,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            backoff: BackoffPolicy::default(),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Outcome of a failed delivery attempt
+enum Attempt {
+    /// The request may succeed if retried, e.g. after a 5xx or a timeout
+    Retry(anyhow::Error),
+    /// The endpoint rejected the request, e.g. with a 4xx
+    Fail(anyhow::Error),
+}
+
+/// POSTs the events of every block as JSON to a URL, so backends in any language
+/// can consume them without talking to the node. Failed requests are retried with
+/// backoff, except for client errors other than 408 and 429.
+///
+/// With a secret, every request is signed: the `X-Webhook-Signature` header is
+/// `sha256=` followed by the hex HMAC-SHA256 of `<timestamp>.<body>`, keyed with the
+/// secret, where timestamp is the `X-Webhook-Timestamp` header. Receivers should
+/// recompute it and reject stale timestamps.
+pub struct WebhookSink {
+    http: reqwest::Client,
+    url: String,
+    secret: Option<Vec<u8>>,
+    headers: Vec<(String, String)>,
+    config: WebhookConfig,
+}
+
+impl WebhookSink {
+    pub fn new(url: &str) -> Result<Self, ClientError> {
+        reqwest::Url::parse(url).map_err(|e| ClientError::invalid("webhook URL", e))?;
+
+        Ok(Self {
+            http: reqwest::Client::new(),
+            url: url.to_string(),
+            secret: None,
+            headers: vec![],
+            config: WebhookConfig::default(),
+        })
+    }
+
+    /// Signs the requests with the shared secret
+    pub fn with_secret(mut self, secret: impl AsRef<[u8]>) -> Self {
+        self.secret = Some(secret.as_ref().to_vec());
+        self
+    }
+
+    /// Adds a header to every request, e.g. an `Authorization` token
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_config(mut self, config: WebhookConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    async fn post(&self, height: u64, body: &[u8]) -> Result<(), Attempt> {
+        let mut request = self
+            .http
+            .post(&self.url)
+            .timeout(self.config.timeout)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(HEIGHT_HEADER, height.to_string());
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        if let Some(secret) = &self.secret {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            request = request
+                .header(TIMESTAMP_HEADER, timestamp.to_string())
+                .header(SIGNATURE_HEADER, sign(secret, timestamp, body));
+        }
+
+        let response = request
+            .body(body.to_vec())
+            .send()
+            .await
+            .map_err(|e| Attempt::Retry(anyhow!("Failed to post to webhook: {}", e)))?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let error = anyhow!("Webhook responded with {}", status);
+        if status.is_client_error()
+            && status != reqwest::StatusCode::REQUEST_TIMEOUT
+            && status != reqwest::StatusCode::TOO_MANY_REQUESTS
+        {
+            Err(Attempt::Fail(error))
+        } else {
+            Err(Attempt::Retry(error))
+        }
+    }
+}
+
+/// Returns the signature header value of a body signed at the given unix timestamp
+pub fn sign(secret: &[u8], timestamp: u64, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[async_trait]
+impl EventSink for WebhookSink {
+    async fn send(&self, block_events: BlockEvents) -> anyhow::Result<()> {
+        let body = serde_json::to_vec(&block_events).context("Failed to serialize block events")?;
+
+        let mut failures = 0;
+        loop {
+            let error = match self.post(block_events.height, &body).await {
+                Ok(()) => return Ok(()),
+                Err(Attempt::Fail(error)) => return Err(error),
+                Err(Attempt::Retry(error)) => error,
+            };
+            failures += 1;
+            if failures > self.config.max_retries {
+                return Err(error.context(format!(
+                    "Giving up on block {} after {} attempts",
+                    block_events.height, failures
+                )));
+            }
+
+            let delay = self.config.backoff.delay(failures);
+            tracing::warn!(
+                "Webhook delivery of block {} failed, retrying in {:?}: {}",
+                block_events.height,
+                delay,
+                error
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Answers the requests with the given statuses in turn, returning the raw requests
+    async fn serve(statuses: Vec<u16>) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/events", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let mut requests = vec![];
+            for status in statuses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = vec![];
+                let mut buf = [0; 4096];
+                loop {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|line| {
+                                line.to_lowercase()
+                                    .strip_prefix("content-length: ")
+                                    .map(str::to_string)
+                            })
+                            .map_or(0, |length| length.parse().unwrap());
+                        if body.len() >= length {
+                            break;
+                        }
+                    }
+                }
+                stream
+                    .write_all(
+                        format!(
+                            "HTTP/1.1 {} Status\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                            status
+                        )
+                        .as_bytes(),
+                    )
+                    .await
+                    .unwrap();
+                requests.push(String::from_utf8(request).unwrap());
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn test_webhook_sink() {
+        let config = WebhookConfig {
+            max_retries: 1,
+            backoff: BackoffPolicy::fixed(Duration::from_millis(10)),
+            ..Default::default()
+        };
+
+        let (url, server) = serve(vec![503, 200]).await;
+        let sink = WebhookSink::new(&url)
+            .unwrap()
+            .with_secret("secret")
+            .with_config(config.clone());
+        sink.send(BlockEvents {
+            height: 7,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let requests = server.await.unwrap();
+        assert_eq!(requests.len(), 2);
+
+        let request = &requests[1];
+        let header = |name: &str| {
+            request
+                .lines()
+                .find_map(|line| {
+                    let (key, value) = line.split_once(": ")?;
+                    key.eq_ignore_ascii_case(name).then(|| value.to_string())
+                })
+                .unwrap()
+        };
+        let body = request.split_once("\r\n\r\n").unwrap().1;
+        assert_eq!(header(HEIGHT_HEADER), "7");
+        let timestamp: u64 = header(TIMESTAMP_HEADER).parse().unwrap();
+        assert_eq!(
+            header(SIGNATURE_HEADER),
+            sign(b"secret", timestamp, body.as_bytes())
+        );
+        assert_eq!(serde_json::from_str::<BlockEvents>(body).unwrap().height, 7);
+
+        // client errors aren't retried
+        let (url, server) = serve(vec![400]).await;
+        let sink = WebhookSink::new(&url).unwrap().with_config(config.clone());
+        assert!(sink
+            .send(BlockEvents {
+                height: 8,
+                ..Default::default()
+            })
+            .await
+            .is_err());
+        assert_eq!(server.await.unwrap().len(), 1);
+
+        // server errors are retried up to max_retries
+        let (url, server) = serve(vec![500, 500]).await;
+        let sink = WebhookSink::new(&url).unwrap().with_config(config);
+        assert!(sink
+            .send(BlockEvents {
+                height: 9,
+                ..Default::default()
+            })
+            .await
+            .is_err());
+        assert_eq!(server.await.unwrap().len(), 2);
+    }
+}
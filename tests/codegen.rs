@@ -0,0 +1,41 @@
+//! Compiles the bindings generated from `codegen/bridge.json` and checks they are
+//! up to date, regenerate them with
+//! `cargo run --example schema_codegen -- tests/codegen/bridge.json tests/codegen/bridge.rs`
+#![cfg(feature = "wallet")]
+
+use cosmwasm_client_rs::codegen::ContractSchema;
+
+#[allow(dead_code)]
+mod bridge {
+    include!("codegen/bridge.rs");
+}
+
+#[test]
+fn test_generated_bindings() {
+    let code = ContractSchema::load("tests/codegen/bridge.json")
+        .unwrap()
+        .generate()
+        .unwrap();
+    assert_eq!(code, include_str!("codegen/bridge.rs"));
+
+    // keywords are escaped in Rust and keep their names in JSON
+    let msg = bridge::ExecuteMsg::PegOut {
+        btc_address: "bc1q".to_string(),
+        fee_rate: 10,
+        amount: 1000u128.into(),
+        r#type: "fast".to_string(),
+        super_: None,
+    };
+    assert_eq!(
+        serde_json::to_string(&msg).unwrap(),
+        r#"{"peg_out":{"btc_address":"bc1q","fee_rate":10,"amount":"1000","type":"fast","super":null}}"#
+    );
+    assert_eq!(
+        serde_json::to_string(&bridge::ExecuteMsg::Self_ { self_: true }).unwrap(),
+        r#"{"self":{"Self":true}}"#
+    );
+    let operator: bridge::Operator =
+        serde_json::from_str(r#"{"btc_pk":"02aa","address":"bbn1operator","crate":"bridge"}"#)
+            .unwrap();
+    assert_eq!(operator.crate_, "bridge");
+}
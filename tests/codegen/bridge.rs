@@ -0,0 +1,85 @@
+// @generated by cosmwasm-client-rs from the crates.io:bridge 0.1.0 schema, do not edit
+
+use cosmwasm_schema::cw_serde;
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Pause rejects all peg ins and peg outs until unpaused
+    Pause,
+    PegOut {
+        btc_address: String,
+        fee_rate: u32,
+        amount: cosmwasm_std::Uint128,
+        r#type: String,
+        #[serde(rename = "super")]
+        super_: Option<String>,
+    },
+    #[serde(rename = "self")]
+    Self_ {
+        #[serde(rename = "Self")]
+        self_: bool,
+    },
+}
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub denom: String,
+    pub cw20_code_id: u64,
+    pub operators: Vec<Operator>,
+}
+
+#[cw_serde]
+pub struct Operator {
+    pub btc_pk: String,
+    pub address: cosmwasm_std::Addr,
+    #[serde(rename = "crate")]
+    pub crate_: String,
+}
+
+#[cw_serde]
+pub enum QueryMsg {
+    Operators {},
+    Crate {},
+}
+
+/// Typed client of the `crates.io:bridge` contract
+pub struct BridgeClient<'a> {
+    client: &'a ::cosmwasm_client_rs::CosmWasmClient,
+    contract: String,
+}
+
+impl<'a> BridgeClient<'a> {
+    pub fn new(client: &'a ::cosmwasm_client_rs::CosmWasmClient, contract: impl Into<String>) -> Self {
+        Self { client, contract: contract.into() }
+    }
+
+    pub fn contract(&self) -> &str {
+        &self.contract
+    }
+
+    /// Pause rejects all peg ins and peg outs until unpaused
+    pub async fn pause(&self) -> Result<String, ::cosmwasm_client_rs::ClientError> {
+        let msg = ExecuteMsg::Pause;
+        self.client.execute_contract_at(&self.contract, &msg).await
+    }
+
+    pub async fn peg_out(&self, btc_address: String, fee_rate: u32, amount: cosmwasm_std::Uint128, r#type: String, super_: Option<String>) -> Result<String, ::cosmwasm_client_rs::ClientError> {
+        let msg = ExecuteMsg::PegOut { btc_address, fee_rate, amount, r#type, super_ };
+        self.client.execute_contract_at(&self.contract, &msg).await
+    }
+
+    pub async fn self_(&self, self_: bool) -> Result<String, ::cosmwasm_client_rs::ClientError> {
+        let msg = ExecuteMsg::Self_ { self_ };
+        self.client.execute_contract_at(&self.contract, &msg).await
+    }
+
+    pub async fn query_operators(&self) -> Result<Vec<Operator>, ::cosmwasm_client_rs::ClientError> {
+        let msg = QueryMsg::Operators {};
+        self.client.query_smart(&self.contract, &msg).await
+    }
+
+    pub async fn query_crate_(&self) -> Result<String, ::cosmwasm_client_rs::ClientError> {
+        let msg = QueryMsg::Crate {};
+        self.client.query_smart(&self.contract, &msg).await
+    }
+}